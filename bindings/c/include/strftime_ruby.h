@@ -0,0 +1,74 @@
+#ifndef STRFTIME_RUBY_H
+#define STRFTIME_RUBY_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A null pointer was passed with a non-zero length. */
+#define STRFTIME_RUBY_ERROR_NULL_POINTER (-1)
+/* The time has invalid values, or its time zone name is not valid UTF-8. */
+#define STRFTIME_RUBY_ERROR_INVALID_TIME (-2)
+/* The format string is ended by an unterminated directive. */
+#define STRFTIME_RUBY_ERROR_INVALID_FORMAT_STRING (-3)
+/* The formatted string is too large. */
+#define STRFTIME_RUBY_ERROR_FORMATTED_STRING_TOO_LARGE (-4)
+/* The format string has too many directives. */
+#define STRFTIME_RUBY_ERROR_TOO_MANY_DIRECTIVES (-5)
+/* The buffer is too small for the formatted string. */
+#define STRFTIME_RUBY_ERROR_WRITE_ZERO (-6)
+/* Any other formatting error. */
+#define STRFTIME_RUBY_ERROR_OTHER (-7)
+
+/* Broken-down time, with a field for each method of the `Time` trait. */
+typedef struct StrftimeTm {
+    /* Year, including the century. */
+    int32_t year;
+    /* Month of the year in 1..=12. */
+    uint8_t month;
+    /* Day of the month in 1..=31. */
+    uint8_t day;
+    /* Hour of the day in 0..=23. */
+    uint8_t hour;
+    /* Minute of the hour in 0..=59. */
+    uint8_t minute;
+    /* Second of the minute in 0..=60. */
+    uint8_t second;
+    /* Day of the week in 0..=6, with Sunday == 0. */
+    uint8_t day_of_week;
+    /* Day of the year in 1..=366. */
+    uint16_t day_of_year;
+    /* Number of nanoseconds in 0..=999999999. */
+    uint32_t nanoseconds;
+    /* Offset in seconds between the time zone and UTC. */
+    int32_t utc_offset;
+    /* Number of seconds since the Epoch. */
+    int64_t to_int;
+    /* Whether the time zone is UTC. */
+    bool is_utc;
+    /* UTF-8 name of the time zone, which is not nul-terminated. Can be NULL if
+     * time_zone_len is zero. */
+    const char *time_zone;
+    /* Length in bytes of the time zone name. */
+    size_t time_zone_len;
+} StrftimeTm;
+
+/*
+ * Format a broken-down time with the specified format byte string, writing in
+ * the provided buffer.
+ *
+ * Returns the number of written bytes, or a negative STRFTIME_RUBY_ERROR_*
+ * error code. The output is not nul-terminated.
+ */
+ptrdiff_t strftime_ruby_format(uint8_t *buf, size_t len, const uint8_t *fmt,
+                               size_t fmt_len, const StrftimeTm *tm);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* STRFTIME_RUBY_H */
@@ -0,0 +1,238 @@
+#![deny(unsafe_op_in_unsafe_fn)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![warn(missing_docs)]
+#![warn(rust_2018_idioms)]
+
+//! C bindings for the Ruby `Time#strftime` compatible formatter in the
+//! [`strftime`] crate.
+//!
+//! The library exposes a single `strftime_ruby_format` function, declared in
+//! `include/strftime_ruby.h`, which produces byte-identical output to Ruby's
+//! `Time#strftime`. The time is passed as a [`StrftimeTm`] struct holding the
+//! values of the [`Time`] trait methods.
+//!
+//! ```c
+//! #include "strftime_ruby.h"
+//!
+//! StrftimeTm tm = {
+//!     .year = 1970, .month = 1, .day = 1, .day_of_week = 4, .day_of_year = 1,
+//!     .is_utc = true, .time_zone = "UTC", .time_zone_len = 3,
+//! };
+//! uint8_t buf[64];
+//! ptrdiff_t len = strftime_ruby_format(buf, sizeof(buf), (const uint8_t *)"%F", 2, &tm);
+//! // => buf = "1970-01-01", len = 10
+//! ```
+
+use core::ffi::c_char;
+use core::slice;
+
+use ::strftime::{Error, Time};
+
+/// A null pointer was passed with a non-zero length.
+pub const STRFTIME_RUBY_ERROR_NULL_POINTER: isize = -1;
+/// The time has invalid values, or its time zone name is not valid UTF-8.
+pub const STRFTIME_RUBY_ERROR_INVALID_TIME: isize = -2;
+/// The format string is ended by an unterminated directive.
+pub const STRFTIME_RUBY_ERROR_INVALID_FORMAT_STRING: isize = -3;
+/// The formatted string is too large.
+pub const STRFTIME_RUBY_ERROR_FORMATTED_STRING_TOO_LARGE: isize = -4;
+/// The format string has too many directives.
+pub const STRFTIME_RUBY_ERROR_TOO_MANY_DIRECTIVES: isize = -5;
+/// The buffer is too small for the formatted string.
+pub const STRFTIME_RUBY_ERROR_WRITE_ZERO: isize = -6;
+/// Any other formatting error.
+pub const STRFTIME_RUBY_ERROR_OTHER: isize = -7;
+
+/// Broken-down time passed to [`strftime_ruby_format`], with a field for each
+/// method of the [`Time`] trait.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct StrftimeTm {
+    /// Year, including the century.
+    pub year: i32,
+    /// Month of the year in `1..=12`.
+    pub month: u8,
+    /// Day of the month in `1..=31`.
+    pub day: u8,
+    /// Hour of the day in `0..=23`.
+    pub hour: u8,
+    /// Minute of the hour in `0..=59`.
+    pub minute: u8,
+    /// Second of the minute in `0..=60`.
+    pub second: u8,
+    /// Day of the week in `0..=6`, with `Sunday == 0`.
+    pub day_of_week: u8,
+    /// Day of the year in `1..=366`.
+    pub day_of_year: u16,
+    /// Number of nanoseconds in `0..=999_999_999`.
+    pub nanoseconds: u32,
+    /// Offset in seconds between the time zone and UTC.
+    pub utc_offset: i32,
+    /// Number of seconds since the Epoch.
+    pub to_int: i64,
+    /// Whether the time zone is UTC.
+    pub is_utc: bool,
+    /// UTF-8 name of the time zone, which is not nul-terminated. Can be null
+    /// if `time_zone_len` is zero.
+    pub time_zone: *const c_char,
+    /// Length in bytes of the time zone name.
+    pub time_zone_len: usize,
+}
+
+/// A [`StrftimeTm`] with its time zone name validated as UTF-8.
+#[derive(Debug)]
+struct Tm<'a> {
+    /// Broken-down time.
+    inner: &'a StrftimeTm,
+    /// Time zone name.
+    time_zone: &'a str,
+}
+
+impl Time for Tm<'_> {
+    fn year(&self) -> i32 {
+        self.inner.year
+    }
+
+    fn month(&self) -> u8 {
+        self.inner.month
+    }
+
+    fn day(&self) -> u8 {
+        self.inner.day
+    }
+
+    fn hour(&self) -> u8 {
+        self.inner.hour
+    }
+
+    fn minute(&self) -> u8 {
+        self.inner.minute
+    }
+
+    fn second(&self) -> u8 {
+        self.inner.second
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.inner.nanoseconds
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.inner.day_of_week
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.inner.day_of_year
+    }
+
+    fn to_int(&self) -> i64 {
+        self.inner.to_int
+    }
+
+    fn is_utc(&self) -> bool {
+        self.inner.is_utc
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.inner.utc_offset
+    }
+
+    fn time_zone(&self) -> &str {
+        self.time_zone
+    }
+}
+
+/// Returns the error code of an [`Error`].
+fn error_code(err: &Error) -> isize {
+    match err {
+        Error::InvalidTime | Error::InvalidUtcOffset { .. } => STRFTIME_RUBY_ERROR_INVALID_TIME,
+        Error::InvalidFormatString | Error::UnterminatedDirective { .. } => {
+            STRFTIME_RUBY_ERROR_INVALID_FORMAT_STRING
+        }
+        Error::FormattedStringTooLarge { .. } => STRFTIME_RUBY_ERROR_FORMATTED_STRING_TOO_LARGE,
+        Error::TooManyDirectives { .. } => STRFTIME_RUBY_ERROR_TOO_MANY_DIRECTIVES,
+        Error::WriteZero => STRFTIME_RUBY_ERROR_WRITE_ZERO,
+        _ => STRFTIME_RUBY_ERROR_OTHER,
+    }
+}
+
+/// Constructs a slice from a pointer and a length, allowing a null pointer
+/// for an empty slice.
+///
+/// # Safety
+///
+/// If `len` is not zero, `ptr` must be valid for reads of `len` bytes for the
+/// lifetime `'a`.
+unsafe fn slice_from_raw<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        // SAFETY: the pointer is non-null and valid for reads of `len` bytes
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+}
+
+/// Format a broken-down time with the specified format byte string, writing
+/// in the provided buffer.
+///
+/// Returns the number of written bytes, or a negative `STRFTIME_RUBY_ERROR_*`
+/// error code. The output is not nul-terminated.
+///
+/// # Safety
+///
+/// - `buf` must be valid for writes of `len` bytes, or `len` must be zero.
+/// - `fmt` must be valid for reads of `fmt_len` bytes, or `fmt_len` must be
+///   zero.
+/// - `tm` must be null or point to a valid `StrftimeTm`, whose `time_zone`
+///   field must be valid for reads of `time_zone_len` bytes, or
+///   `time_zone_len` must be zero.
+/// - The buffer must not overlap with the format string or the time.
+#[no_mangle]
+pub unsafe extern "C" fn strftime_ruby_format(
+    buf: *mut u8,
+    len: usize,
+    fmt: *const u8,
+    fmt_len: usize,
+    tm: *const StrftimeTm,
+) -> isize {
+    // SAFETY: `tm` is null or points to a valid `StrftimeTm`
+    let Some(tm) = (unsafe { tm.as_ref() }) else {
+        return STRFTIME_RUBY_ERROR_NULL_POINTER;
+    };
+    // SAFETY: the pointers are valid for reads of their lengths
+    let (Some(format), Some(time_zone)) = (unsafe {
+        (
+            slice_from_raw(fmt, fmt_len),
+            slice_from_raw(tm.time_zone.cast(), tm.time_zone_len),
+        )
+    }) else {
+        return STRFTIME_RUBY_ERROR_NULL_POINTER;
+    };
+    let buf = if len == 0 {
+        &mut []
+    } else if buf.is_null() {
+        return STRFTIME_RUBY_ERROR_NULL_POINTER;
+    } else {
+        // SAFETY: the pointer is non-null, valid for writes of `len` bytes and
+        // does not overlap with the other arguments
+        unsafe { slice::from_raw_parts_mut(buf, len) }
+    };
+
+    let Ok(time_zone) = core::str::from_utf8(time_zone) else {
+        return STRFTIME_RUBY_ERROR_INVALID_TIME;
+    };
+    let time = Tm {
+        inner: tm,
+        time_zone,
+    };
+
+    match ::strftime::buffered::strftime_len(&time, format, buf) {
+        // The written length is at most `len`, which fits in an `isize` for a
+        // valid buffer
+        Ok(written) => isize::try_from(written).unwrap_or(STRFTIME_RUBY_ERROR_OTHER),
+        Err(err) => error_code(&err),
+    }
+}
@@ -0,0 +1,127 @@
+#![forbid(unsafe_code)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![allow(clippy::cast_possible_truncation)]
+#![warn(missing_docs)]
+#![warn(rust_2018_idioms)]
+
+//! Node.js bindings for the Ruby `Time#strftime` compatible formatter in the
+//! [`strftime`] crate.
+//!
+//! The native module exposes a single `strftime` function, which produces
+//! byte-identical output to Ruby's `Time#strftime`.
+//!
+//! ```js
+//! const { strftime } = require("strftime-ruby");
+//!
+//! strftime("%F %T.%L %z", 1_000_000_000_123, 7200);
+//! // => '2001-09-09 03:46:40.123 +0200'
+//! ```
+
+use ::strftime::{DateTime, Time};
+use napi::{Error, Result};
+use napi_derive::napi;
+
+/// A [`DateTime`] with a custom time zone name.
+#[derive(Debug)]
+struct ZonedTime {
+    /// Date and time.
+    inner: DateTime,
+    /// Time zone name.
+    time_zone: Option<String>,
+}
+
+impl Time for ZonedTime {
+    fn year(&self) -> i32 {
+        self.inner.year()
+    }
+
+    fn month(&self) -> u8 {
+        self.inner.month()
+    }
+
+    fn day(&self) -> u8 {
+        self.inner.day()
+    }
+
+    fn hour(&self) -> u8 {
+        self.inner.hour()
+    }
+
+    fn minute(&self) -> u8 {
+        self.inner.minute()
+    }
+
+    fn second(&self) -> u8 {
+        self.inner.second()
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.inner.nanoseconds()
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.inner.day_of_week()
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.inner.day_of_year()
+    }
+
+    fn to_int(&self) -> i64 {
+        self.inner.to_int()
+    }
+
+    fn is_utc(&self) -> bool {
+        self.inner.is_utc()
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.inner.utc_offset()
+    }
+
+    fn time_zone(&self) -> &str {
+        match self.time_zone {
+            Some(ref time_zone) => time_zone,
+            None => self.inner.time_zone(),
+        }
+    }
+}
+
+/// Format a Unix timestamp in milliseconds with a Ruby `Time#strftime` format
+/// string.
+///
+/// `epochMs` is the number of milliseconds since `1970-01-01 00:00:00 UTC`, as
+/// returned by `Date.now()`. When `offset` is `undefined`, the time is in UTC,
+/// otherwise it has a fixed offset in seconds from UTC. `tzName` overrides the
+/// time zone name used by the `%Z` directive.
+///
+/// # Errors
+///
+/// Throws an error if the time is out of range or if the format string is
+/// invalid.
+#[napi(js_name = "strftime")]
+#[allow(clippy::needless_pass_by_value)]
+pub fn js_strftime(
+    format: String,
+    epoch_ms: i64,
+    offset: Option<i32>,
+    tz_name: Option<String>,
+) -> Result<String> {
+    let seconds = epoch_ms.div_euclid(1000);
+    // The remainder is in `0..1000`, so the nanoseconds fit in a `u32`
+    let nanoseconds = (epoch_ms.rem_euclid(1000) * 1_000_000) as u32;
+
+    let inner = match offset {
+        None => DateTime::from_timestamp(seconds, nanoseconds),
+        Some(offset) => DateTime::from_timestamp_with_offset(seconds, nanoseconds, offset),
+    };
+    let inner = inner.ok_or_else(|| Error::from_reason("time out of range"))?;
+
+    let time = ZonedTime {
+        inner,
+        time_zone: tz_name,
+    };
+
+    ::strftime::string::strftime(&time, &format).map_err(|err| Error::from_reason(err.to_string()))
+}
@@ -0,0 +1,123 @@
+#![forbid(unsafe_code)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![warn(missing_docs)]
+#![warn(rust_2018_idioms)]
+
+//! Python bindings for the Ruby `Time#strftime` compatible formatter in the
+//! [`strftime`] crate.
+//!
+//! The `strftime_ruby` Python module exposes a single `strftime` function,
+//! which produces byte-identical output to Ruby's `Time#strftime`.
+//!
+//! ```python
+//! import strftime_ruby
+//!
+//! strftime_ruby.strftime("%F %T %z", 1_000_000_000, 7200)
+//! # => '2001-09-09 03:46:40 +0200'
+//! ```
+
+use ::strftime::{DateTime, Time};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// A [`DateTime`] with a custom time zone name.
+#[derive(Debug)]
+struct ZonedTime<'a> {
+    /// Date and time.
+    inner: DateTime,
+    /// Time zone name.
+    time_zone: Option<&'a str>,
+}
+
+impl Time for ZonedTime<'_> {
+    fn year(&self) -> i32 {
+        self.inner.year()
+    }
+
+    fn month(&self) -> u8 {
+        self.inner.month()
+    }
+
+    fn day(&self) -> u8 {
+        self.inner.day()
+    }
+
+    fn hour(&self) -> u8 {
+        self.inner.hour()
+    }
+
+    fn minute(&self) -> u8 {
+        self.inner.minute()
+    }
+
+    fn second(&self) -> u8 {
+        self.inner.second()
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.inner.nanoseconds()
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.inner.day_of_week()
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.inner.day_of_year()
+    }
+
+    fn to_int(&self) -> i64 {
+        self.inner.to_int()
+    }
+
+    fn is_utc(&self) -> bool {
+        self.inner.is_utc()
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.inner.utc_offset()
+    }
+
+    fn time_zone(&self) -> &str {
+        self.time_zone.unwrap_or_else(|| self.inner.time_zone())
+    }
+}
+
+/// Format a Unix timestamp with a Ruby `Time#strftime` format string.
+///
+/// `epoch` is the number of seconds since `1970-01-01 00:00:00 UTC` and
+/// `nanoseconds` the sub-second part. When `offset` is `None`, the time is in
+/// UTC, otherwise it has a fixed offset in seconds from UTC. `tz_name`
+/// overrides the time zone name used by the `%Z` directive.
+#[pyfunction]
+#[pyo3(name = "strftime")]
+#[pyo3(signature = (format, epoch, offset=None, tz_name=None, nanoseconds=0))]
+fn py_strftime(
+    format: &str,
+    epoch: i64,
+    offset: Option<i32>,
+    tz_name: Option<&str>,
+    nanoseconds: u32,
+) -> PyResult<String> {
+    let inner = match offset {
+        None => DateTime::from_timestamp(epoch, nanoseconds),
+        Some(offset) => DateTime::from_timestamp_with_offset(epoch, nanoseconds, offset),
+    };
+    let inner = inner.ok_or_else(|| PyValueError::new_err("time out of range"))?;
+
+    let time = ZonedTime {
+        inner,
+        time_zone: tz_name,
+    };
+
+    ::strftime::string::strftime(&time, format)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Python module definition.
+#[pymodule]
+fn strftime_ruby(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(py_strftime, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,528 @@
+//! A linter for `strftime` format strings.
+//!
+//! Format strings are read from the command line arguments or, if there are
+//! none, from the lines of the standard input. For each format string, the
+//! linter reports parse errors, lint warnings, the directive breakdown and the
+//! worst-case size of the formatted output.
+//!
+//! The process exits with a non-zero status if a format string has errors, or
+//! warnings when `--deny-warnings` is passed, which makes it suitable to
+//! validate format strings stored in configuration files in CI.
+//!
+//! ```console
+//! $ cargo run --example strftime-lint -- '%Y-%m-%d %^H:%M'
+//! $ cat formats.txt | cargo run --example strftime-lint -- --deny-warnings
+//! ```
+
+use std::env;
+use std::io::{self, BufRead};
+use std::ops::Range;
+use std::process;
+
+use strftime::DateTime;
+
+/// Maximum value of a directive width, which must fit in a `c_int`.
+const MAX_WIDTH: usize = i32::MAX as usize;
+
+/// Worst-case size in bytes of a formatted directive or literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Size {
+    /// Bounded size.
+    Bounded(usize),
+    /// Unbounded size, depending on the time zone name.
+    Unbounded,
+}
+
+impl Size {
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Bounded(a), Self::Bounded(b)) => Self::Bounded(a.saturating_add(b)),
+            _ => Self::Unbounded,
+        }
+    }
+}
+
+/// Description of a conversion specifier.
+struct SpecInfo {
+    /// Conversion specifier, including the colons of `%z` variants.
+    spec: &'static str,
+    /// Human-readable description.
+    description: &'static str,
+    /// Worst-case size in bytes without width, or `None` if unbounded.
+    max_len: Option<usize>,
+    /// Whether the case flags change the output.
+    has_case: bool,
+    /// Whether the width is used even with the `-` flag.
+    width_with_left_padding: bool,
+}
+
+const fn spec(
+    spec: &'static str,
+    description: &'static str,
+    max_len: usize,
+    has_case: bool,
+    width_with_left_padding: bool,
+) -> SpecInfo {
+    SpecInfo {
+        spec,
+        description,
+        max_len: Some(max_len),
+        has_case,
+        width_with_left_padding,
+    }
+}
+
+/// All the supported conversion specifiers.
+///
+/// The sizes assume any `i32` year, `i64` timestamp and `i32` UTC offset
+/// accepted by the `Time` trait.
+const SPECS: &[SpecInfo] = &[
+    spec("Y", "year with century", 11, false, false),
+    spec("C", "year divided by 100", 9, false, false),
+    spec("y", "year modulo 100", 2, false, false),
+    spec("m", "month of the year", 2, false, false),
+    spec("B", "full month name", 9, true, false),
+    spec("b", "abbreviated month name", 3, true, false),
+    spec("h", "abbreviated month name", 3, true, false),
+    spec("d", "day of the month, zero-padded", 2, false, false),
+    spec("e", "day of the month, blank-padded", 2, false, false),
+    spec("j", "day of the year", 3, false, false),
+    spec(
+        "H",
+        "hour of the day (24-hour clock), zero-padded",
+        2,
+        false,
+        false,
+    ),
+    spec(
+        "k",
+        "hour of the day (24-hour clock), blank-padded",
+        2,
+        false,
+        false,
+    ),
+    spec(
+        "I",
+        "hour of the day (12-hour clock), zero-padded",
+        2,
+        false,
+        false,
+    ),
+    spec(
+        "l",
+        "hour of the day (12-hour clock), blank-padded",
+        2,
+        false,
+        false,
+    ),
+    spec("P", "lowercase meridian indicator", 2, true, false),
+    spec("p", "uppercase meridian indicator", 2, true, false),
+    spec("M", "minute of the hour", 2, false, false),
+    spec("S", "second of the minute", 2, false, false),
+    spec(
+        "L",
+        "milliseconds, width sets the number of digits",
+        3,
+        false,
+        true,
+    ),
+    spec(
+        "N",
+        "fractional seconds, width sets the number of digits",
+        9,
+        false,
+        true,
+    ),
+    spec("z", "UTC offset as +hhmm", 9, false, true),
+    spec(":z", "UTC offset as +hh:mm", 10, false, true),
+    spec("::z", "UTC offset as +hh:mm:ss", 13, false, true),
+    spec(":::z", "UTC offset as +hh[:mm[:ss]]", 13, false, true),
+    SpecInfo {
+        spec: "Z",
+        description: "time zone name",
+        max_len: None,
+        has_case: true,
+        width_with_left_padding: false,
+    },
+    spec("A", "full weekday name", 9, true, false),
+    spec("a", "abbreviated weekday name", 3, true, false),
+    spec("u", "day of the week from Monday (1..=7)", 1, false, false),
+    spec("w", "day of the week from Sunday (0..=6)", 1, false, false),
+    spec("G", "ISO 8601 week-based year", 11, false, false),
+    spec("g", "ISO 8601 week-based year modulo 100", 2, false, false),
+    spec("V", "ISO 8601 week number", 2, false, false),
+    spec("U", "week number from Sunday", 2, false, false),
+    spec("W", "week number from Monday", 2, false, false),
+    spec("s", "seconds since the Unix epoch", 20, false, false),
+    spec("n", "newline", 1, false, false),
+    spec("t", "tab", 1, false, false),
+    spec("%", "literal percent sign", 1, false, false),
+    spec("c", "date and time (%a %b %e %H:%M:%S %Y)", 31, true, true),
+    spec("D", "date (%m/%d/%y)", 8, false, true),
+    spec("x", "date (%m/%d/%y)", 8, false, true),
+    spec("F", "ISO 8601 date (%Y-%m-%d)", 17, false, true),
+    spec("v", "VMS date (%e-%^b-%4Y)", 18, false, true),
+    spec("r", "12-hour time (%I:%M:%S %p)", 11, false, true),
+    spec("R", "24-hour time (%H:%M)", 5, false, true),
+    spec("T", "24-hour time (%H:%M:%S)", 8, false, true),
+    spec("X", "24-hour time (%H:%M:%S)", 8, false, true),
+];
+
+/// A parsed directive.
+struct Directive<'a> {
+    /// Byte range of the directive in the format string.
+    span: Range<usize>,
+    /// Text of the directive.
+    text: &'a str,
+    /// Width, if any.
+    width: Option<usize>,
+    /// Specifier description.
+    info: &'static SpecInfo,
+}
+
+/// A diagnostic with its byte range in the format string.
+struct Diagnostic {
+    /// Byte range in the format string.
+    span: Range<usize>,
+    /// Message.
+    message: String,
+}
+
+/// Result of linting a format string.
+#[derive(Default)]
+struct Report<'a> {
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+    directives: Vec<Directive<'a>>,
+    literal_len: usize,
+}
+
+/// Lint a format string.
+fn lint(format: &str) -> Report<'_> {
+    let bytes = format.as_bytes();
+    let mut report = Report::default();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'%' {
+            report.literal_len += 1;
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        pos += 1;
+
+        // Flags
+        let flags_start = pos;
+        while let Some(b'-' | b'_' | b'0' | b'^' | b'#') = bytes.get(pos) {
+            pos += 1;
+        }
+        let flags = &bytes[flags_start..pos];
+
+        // Width
+        let width_start = pos;
+        while bytes.get(pos).map_or(false, u8::is_ascii_digit) {
+            pos += 1;
+        }
+        let width = if pos == width_start {
+            None
+        } else {
+            match format[width_start..pos].parse::<usize>() {
+                Ok(width) if width <= MAX_WIDTH => Some(width),
+                _ => {
+                    report.warnings.push(Diagnostic {
+                        span: start..pos,
+                        message: "width does not fit in a c_int, the directive is written verbatim"
+                            .to_string(),
+                    });
+                    report.literal_len += pos - start;
+                    continue;
+                }
+            }
+        };
+
+        // POSIX locale extensions
+        if let [ext @ (b'E' | b'O'), spec, ..] = bytes[pos..] {
+            let specs: &[u8] = if ext == b'E' {
+                b"CXYcxy"
+            } else {
+                b"HIMSUVWdeklmuwy"
+            };
+            if specs.contains(&spec) {
+                report.warnings.push(Diagnostic {
+                    span: pos..pos + 1,
+                    message: format!("locale modifier `{}` is ignored", char::from(ext)),
+                });
+                pos += 1;
+            }
+        }
+
+        // Conversion specifier
+        let spec_start = pos;
+        while let Some(b':') = bytes.get(pos) {
+            pos += 1;
+        }
+        let colons = pos - spec_start;
+
+        let spec = if colons == 0 {
+            match format[pos..].chars().next() {
+                Some(ch) => {
+                    pos += ch.len_utf8();
+                    &format[spec_start..pos]
+                }
+                None => {
+                    report.errors.push(Diagnostic {
+                        span: start..pos,
+                        message: "unterminated directive at the end of the format string"
+                            .to_string(),
+                    });
+                    break;
+                }
+            }
+        } else if bytes.get(pos) == Some(&b'z') {
+            pos += 1;
+            &format[spec_start..pos]
+        } else {
+            ""
+        };
+
+        let info = match SPECS.iter().find(|info| info.spec == spec) {
+            Some(info) if !spec.is_empty() => info,
+            _ => {
+                report.warnings.push(Diagnostic {
+                    span: start..pos,
+                    message: format!(
+                        "unknown directive `{}` is written verbatim",
+                        &format[start..pos]
+                    ),
+                });
+                report.literal_len += pos - start;
+                continue;
+            }
+        };
+
+        lint_flags(&mut report, start..pos, flags, width, info);
+
+        report.directives.push(Directive {
+            span: start..pos,
+            text: &format[start..pos],
+            width,
+            info,
+        });
+    }
+
+    report
+}
+
+/// Lint the flags of a directive.
+fn lint_flags(
+    report: &mut Report<'_>,
+    span: Range<usize>,
+    flags: &[u8],
+    width: Option<usize>,
+    info: &SpecInfo,
+) {
+    let has = |flag| flags.contains(&flag);
+
+    for flag in [b'^', b'#'] {
+        if has(flag) && !info.has_case {
+            report.warnings.push(Diagnostic {
+                span: span.clone(),
+                message: format!(
+                    "flag `{}` has no effect on `%{}`",
+                    char::from(flag),
+                    info.spec
+                ),
+            });
+        }
+    }
+
+    let padding_flags = flags.iter().filter(|&&x| matches!(x, b'_' | b'0')).count();
+    if has(b'-') && padding_flags > 0 {
+        report.warnings.push(Diagnostic {
+            span: span.clone(),
+            message: "flag `-` overrides the other padding flags".to_string(),
+        });
+    } else if padding_flags > 1 {
+        report.warnings.push(Diagnostic {
+            span: span.clone(),
+            message: "only the last padding flag is used".to_string(),
+        });
+    }
+
+    if has(b'-') && width.is_some() && !info.width_with_left_padding {
+        report.warnings.push(Diagnostic {
+            span,
+            message: "width is ignored because of the `-` flag".to_string(),
+        });
+    }
+}
+
+/// Compute the worst-case output size of a format string.
+fn worst_case_size(report: &Report<'_>) -> Size {
+    report
+        .directives
+        .iter()
+        .map(|directive| match directive.info.max_len {
+            Some(len) => Size::Bounded(len.max(directive.width.unwrap_or(0))),
+            None => Size::Unbounded,
+        })
+        .fold(Size::Bounded(report.literal_len), Size::add)
+}
+
+/// Print a diagnostic, underlining its span in the format string.
+fn print_diagnostic(level: &str, format: &str, diagnostic: &Diagnostic) {
+    let Range { start, end } = diagnostic.span;
+    println!("  {level}: {} (bytes {start}..{end})", diagnostic.message);
+    println!("    {format}");
+    let offset = format[..start].chars().count();
+    let len = format[start..end].chars().count().max(1);
+    println!("    {}{}", " ".repeat(offset), "^".repeat(len));
+}
+
+/// Lint a format string and print the report. Returns the number of errors
+/// and warnings.
+fn check(format: &str) -> (usize, usize) {
+    let report = lint(format);
+
+    println!("format: {format:?}");
+
+    // The crate is the source of truth for parse errors
+    let time = DateTime::from_timestamp(0, 0).expect("Unix epoch should be a valid time");
+    let mut sample = String::new();
+    let result = strftime::fmt::strftime(&time, format, &mut sample);
+    debug_assert_eq!(result.is_err(), !report.errors.is_empty());
+
+    for error in &report.errors {
+        print_diagnostic("error", format, error);
+    }
+    for warning in &report.warnings {
+        print_diagnostic("warning", format, warning);
+    }
+
+    if !report.directives.is_empty() {
+        println!("  directives:");
+        for directive in &report.directives {
+            let Range { start, end } = directive.span;
+            println!(
+                "    {start:>4}..{end:<4} {:<10} {}",
+                directive.text, directive.info.description
+            );
+        }
+    }
+
+    if report.errors.is_empty() {
+        match worst_case_size(&report) {
+            Size::Bounded(size) => println!("  worst-case output size: {size} bytes"),
+            Size::Unbounded => {
+                println!("  worst-case output size: unbounded (depends on the time zone name)");
+            }
+        }
+        println!("  sample (Unix epoch): {sample:?}");
+    }
+    println!();
+
+    (report.errors.len(), report.warnings.len())
+}
+
+fn main() {
+    let mut deny_warnings = false;
+    let mut formats = Vec::new();
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--deny-warnings" => deny_warnings = true,
+            "-h" | "--help" => {
+                println!("Usage: strftime-lint [--deny-warnings] [FORMAT]...");
+                println!();
+                println!("Lint strftime format strings given as arguments, or read from stdin.");
+                return;
+            }
+            _ => formats.push(arg),
+        }
+    }
+
+    if formats.is_empty() {
+        for line in io::stdin().lock().lines() {
+            match line {
+                Ok(line) if line.is_empty() => {}
+                Ok(line) => formats.push(line),
+                Err(err) => {
+                    eprintln!("strftime-lint: failed to read stdin: {err}");
+                    process::exit(2);
+                }
+            }
+        }
+    }
+
+    let (mut errors, mut warnings) = (0, 0);
+    for format in &formats {
+        let (e, w) = check(format);
+        errors += e;
+        warnings += w;
+    }
+
+    println!(
+        "{} format(s) checked: {errors} error(s), {warnings} warning(s)",
+        formats.len()
+    );
+
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_valid() {
+        let report = lint("%Y-%m-%dT%H:%M:%S%:z");
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.directives.len(), 7);
+        assert_eq!(worst_case_size(&report), Size::Bounded(11 + 2 * 5 + 10 + 5));
+    }
+
+    #[test]
+    fn test_lint_unterminated() {
+        let report = lint("%Y-%_");
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].span, 3..5);
+    }
+
+    #[test]
+    fn test_lint_warnings() {
+        let report = lint("%^d %-5m %_0H %EY %Q %99999999999d");
+        let spans: Vec<_> = report.warnings.iter().map(|w| w.span.clone()).collect();
+        assert_eq!(spans, [0..3, 4..8, 9..13, 15..16, 18..20, 21..33]);
+    }
+
+    #[test]
+    fn test_worst_case_size() {
+        assert_eq!(worst_case_size(&lint("%100N")), Size::Bounded(100));
+        assert_eq!(worst_case_size(&lint("abc%n")), Size::Bounded(4));
+        assert_eq!(worst_case_size(&lint("%Z")), Size::Unbounded);
+    }
+
+    #[test]
+    fn test_lint_matches_crate() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+        for format in [
+            "%",
+            "%-",
+            "%::",
+            "%:::",
+            "%::::z",
+            "%%%",
+            "%E",
+            "%2147483648",
+            "%10",
+        ] {
+            let result = strftime::fmt::strftime(&time, format, &mut String::new());
+            assert_eq!(result.is_err(), !lint(format).errors.is_empty(), "{format}");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+#![no_main]
+
+mod mock;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use mock::MockTime;
+use strftime::explain::Item;
+use strftime::{Dialect, Error, FormatOptions, OffsetPolicy};
+
+#[derive(Debug, Arbitrary)]
+struct Options<'a> {
+    max_directives: u16,
+    extensions: bool,
+    offset_policy: u8,
+    lenient_trailing_percent: bool,
+    strict_directives: bool,
+    strict_widths: bool,
+    error_context: bool,
+    digit_separator: &'a str,
+    aliases: Vec<(u8, &'a str)>,
+}
+
+impl<'a> Options<'a> {
+    fn to_format_options(&'a self) -> FormatOptions<'a> {
+        let dialect = if self.extensions {
+            Dialect::Extensions
+        } else {
+            Dialect::Ruby
+        };
+        let offset_policy = match self.offset_policy % 3 {
+            0 => OffsetPolicy::Allow,
+            1 => OffsetPolicy::Reject,
+            _ => OffsetPolicy::Clamp,
+        };
+
+        let mut options = FormatOptions::new()
+            .with_max_directives(self.max_directives.into())
+            .with_dialect(dialect)
+            .with_offset_policy(offset_policy)
+            .with_digit_separator(self.digit_separator)
+            .with_aliases(&self.aliases);
+        if self.lenient_trailing_percent {
+            options = options.with_lenient_trailing_percent();
+        }
+        if self.strict_directives {
+            options = options.with_strict_directives();
+        }
+        if self.strict_widths {
+            options = options.with_strict_widths();
+        }
+        if self.error_context {
+            options = options.with_error_context();
+        }
+        options
+    }
+}
+
+fuzz_target!(|data: (MockTime, Options, &[u8])| {
+    let (time, options, format) = data;
+    let format_options = options.to_format_options();
+
+    let mut buf = [0u8; 1024];
+    let result = strftime::buffered::strftime_with_options(&time, format, &mut buf, &format_options);
+
+    let err = match result {
+        Ok(output) => {
+            assert!(output.len() <= format.len().saturating_mul(512 * 1024));
+
+            // Without aliases, every directive of the format string is counted
+            // once, and the Ruby directives are a subset of the extensions.
+            if options.aliases.is_empty() && !options.extensions {
+                let directives = strftime::explain(format)
+                    .filter(|item| matches!(item, Ok(Item::Directive(_))))
+                    .count();
+                assert!(directives <= usize::from(options.max_directives));
+            }
+            return;
+        }
+        Err(err) => err,
+    };
+
+    let err = match err {
+        Error::Directive { source, .. } => {
+            assert!(options.error_context);
+            *source
+        }
+        err => err,
+    };
+
+    match err {
+        Error::TooManyDirectives { limit } => {
+            assert_eq!(limit, usize::from(options.max_directives));
+        }
+        Error::UnterminatedDirective { .. } => assert!(!options.lenient_trailing_percent),
+        Error::UnknownDirective => assert!(options.strict_directives),
+        Error::WidthTooLarge { .. } => assert!(options.strict_widths),
+        Error::InvalidUtcOffset { .. } => assert_eq!(options.offset_policy % 3, 1),
+        _ => {}
+    }
+});
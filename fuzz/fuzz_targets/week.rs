@@ -0,0 +1,76 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use strftime::test_util::{iso_8601_year_and_week_number, week_number};
+
+fn is_leap_year(year: i64) -> bool {
+    year % 400 == 0 || (year % 4 == 0 && year % 100 != 0)
+}
+
+fn days_in_year(year: i64) -> i64 {
+    if is_leap_year(year) {
+        366
+    } else {
+        365
+    }
+}
+
+/// Count the days on or before `year_day` in `0..=365` falling on `start`,
+/// where `week_day` is the day of the week of `year_day`.
+fn reference_week_number(week_day: i64, year_day: i64, start: i64) -> i64 {
+    (0..=year_day)
+        .filter(|day| (week_day - (year_day - day)).rem_euclid(7) == start)
+        .count() as i64
+}
+
+/// Returns the number of ISO 8601 weeks of a year starting on `first_day`.
+fn iso_weeks_in_year(year: i64, first_day: i64) -> i64 {
+    // A year has 53 weeks if it starts on a Thursday, or on a Wednesday for
+    // leap years
+    if first_day == 4 || (first_day == 3 && is_leap_year(year)) {
+        53
+    } else {
+        52
+    }
+}
+
+fn reference_iso_8601_year_and_week_number(year: i64, week_day: i64, year_day: i64) -> (i64, i64) {
+    let first_day = (week_day - year_day).rem_euclid(7);
+    let iso_week_day = (week_day + 6) % 7 + 1;
+    let week = (year_day + 1 - iso_week_day + 10) / 7;
+
+    if week < 1 {
+        let previous_first_day = (first_day - days_in_year(year - 1)).rem_euclid(7);
+        (year - 1, iso_weeks_in_year(year - 1, previous_first_day))
+    } else if week > iso_weeks_in_year(year, first_day) {
+        (year + 1, 1)
+    } else {
+        (year, week)
+    }
+}
+
+fuzz_target!(|data: (i64, u8, u16)| {
+    let (year, week_day, year_day) = data;
+
+    // The previous and next years must exist
+    if year == i64::MIN || year == i64::MAX {
+        return;
+    }
+
+    let week_day = i64::from(week_day % 7);
+    let year_day = i64::from(year_day) % days_in_year(year);
+
+    for (monday_start, start) in [(false, 0), (true, 1)] {
+        let week = week_number(week_day, year_day + 1, monday_start);
+        assert!((0..=53).contains(&week));
+        assert_eq!(week, reference_week_number(week_day, year_day, start));
+    }
+
+    let (iso_year, iso_week) = iso_8601_year_and_week_number(year, week_day, year_day + 1);
+    assert!((year - 1..=year + 1).contains(&iso_year));
+    assert!((1..=53).contains(&iso_week));
+    assert_eq!(
+        (iso_year, iso_week),
+        reference_iso_8601_year_and_week_number(year, week_day, year_day)
+    );
+});
@@ -0,0 +1,539 @@
+#![forbid(unsafe_code)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![warn(clippy::cargo)]
+#![warn(missing_docs)]
+#![warn(rust_2018_idioms)]
+#![warn(unused_qualifications)]
+
+//! Procedural macros for the [`strftime-ruby`] crate.
+//!
+//! This crate is re-exported by `strftime-ruby` when its **derive** feature is
+//! enabled and should not be used directly.
+//!
+//! [`strftime-ruby`]: https://docs.rs/strftime-ruby
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Error, Fields, ItemStruct, LitStr, Member};
+
+/// Implement [`Display`] for a newtype wrapping a `Time` implementation,
+/// formatting it with a `strftime` format string.
+///
+/// The format string is validated at compile time. The optional `debug`
+/// argument also implements [`Debug`] with the same format string.
+///
+/// The struct must have exactly one field, which implements `Time`. Since
+/// [`Display`] cannot report errors, formatting an invalid time returns a
+/// [`core::fmt::Error`].
+///
+/// # Examples
+///
+/// ```
+/// use strftime::strftime_display;
+/// use strftime::test_util::FixedTime;
+///
+/// #[strftime_display(format = "%Y-%m-%dT%H:%M:%S%:z", debug)]
+/// struct Iso8601(FixedTime);
+///
+/// let time = Iso8601(FixedTime::UNIX_EPOCH);
+/// assert_eq!(time.to_string(), "1970-01-01T00:00:00+00:00");
+/// assert_eq!(format!("{time:?}"), "1970-01-01T00:00:00+00:00");
+/// ```
+///
+/// [`Display`]: core::fmt::Display
+/// [`Debug`]: core::fmt::Debug
+#[proc_macro_attribute]
+pub fn strftime_display(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut format = None;
+    let mut debug = false;
+
+    let args_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("format") {
+            let value: LitStr = meta.value()?.parse()?;
+            if let Err(message) = validate(value.value().as_bytes()) {
+                return Err(Error::new(value.span(), message));
+            }
+            format = Some(value);
+            Ok(())
+        } else if meta.path.is_ident("debug") {
+            debug = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported strftime_display argument"))
+        }
+    });
+    parse_macro_input!(args with args_parser);
+
+    let item = parse_macro_input!(input as ItemStruct);
+
+    match expand(&item, format, debug) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generate the formatting trait implementations for the struct.
+fn expand(
+    item: &ItemStruct,
+    format: Option<LitStr>,
+    debug: bool,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let format = format.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "missing format string, expected `format = \"...\"`",
+        )
+    })?;
+
+    let member = match &item.fields {
+        Fields::Named(fields) if fields.named.len() == 1 => {
+            let field = &fields.named[0];
+            Member::Named(
+                field
+                    .ident
+                    .clone()
+                    .expect("named field should have an ident"),
+            )
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Member::Unnamed(0.into()),
+        _ => {
+            return Err(Error::new(
+                item.fields.span(),
+                "strftime_display requires a struct with exactly one field",
+            ))
+        }
+    };
+
+    let ident = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let display = quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::strftime::fmt::strftime(&self.#member, #format, f).map_err(|_| ::core::fmt::Error)
+            }
+        }
+    };
+
+    let debug = debug.then(|| {
+        quote! {
+            impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Display::fmt(self, f)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #item
+        #display
+        #debug
+    })
+}
+
+/// Parse a `strftime` format string at compile time into a
+/// [`strftime::Format`].
+///
+/// The expansion is a constant expression, so it can initialize a `static` or
+/// a `const` item. Unlike [`Format::parse`], which copies the invalid
+/// directives verbatim to the output, an unknown directive or a directive
+/// with a too large width is a compile error, like an unterminated directive.
+///
+/// The `%L` and `%N` directives require the **subsec** feature of
+/// `strftime-ruby`.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::{strftime_format, Format};
+/// use strftime::test_util::FixedTime;
+///
+/// static ISO_8601: Format = strftime_format!("%Y-%m-%dT%H:%M:%S%:z");
+///
+/// let mut buf = String::new();
+/// ISO_8601.fmt(&FixedTime::UNIX_EPOCH, &mut buf).unwrap();
+/// assert_eq!(buf, "1970-01-01T00:00:00+00:00");
+/// ```
+///
+/// An unknown directive does not compile:
+///
+/// ```compile_fail
+/// let format = strftime::strftime_format!("%Q");
+/// ```
+///
+/// [`strftime::Format`]: https://docs.rs/strftime-ruby/latest/strftime/struct.Format.html
+/// [`Format::parse`]: https://docs.rs/strftime-ruby/latest/strftime/struct.Format.html#method.parse
+#[proc_macro]
+pub fn strftime_format(input: TokenStream) -> TokenStream {
+    let format = parse_macro_input!(input as LitStr);
+
+    match compile(&format) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generate the constant expression of the parsed format string.
+fn compile(format: &LitStr) -> Result<proc_macro2::TokenStream, Error> {
+    let value = format.value();
+    let pieces = parse(value.as_bytes()).map_err(|message| Error::new(format.span(), message))?;
+
+    let items = pieces
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Literal(text) => {
+                let text = syn::LitByteStr::new(text, format.span());
+                Ok(quote! { ::strftime::__FormatItem::__literal(#text) })
+            }
+            Piece::Invalid {
+                offset,
+                text,
+                reason,
+            } => Err(Error::new(
+                format.span(),
+                format!(
+                    "invalid format string: {reason} `{}` at byte offset {offset}, use `%%` for a literal `%`",
+                    String::from_utf8_lossy(text)
+                ),
+            )),
+            Piece::Directive {
+                spec,
+                flags,
+                width,
+                padding,
+            } => {
+                let spec = syn::Ident::new(spec, Span::call_site());
+                let padding = syn::Ident::new(padding, Span::call_site());
+                let width = if let Some(width) = width {
+                    quote! { ::core::option::Option::Some(#width as usize) }
+                } else {
+                    quote! { ::core::option::Option::None }
+                };
+                Ok(quote! {
+                    ::strftime::__FormatItem::__directive(
+                        ::strftime::pieces::Spec::#spec,
+                        #flags,
+                        #width,
+                        ::strftime::pieces::Padding::#padding,
+                    )
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let len = value.len();
+
+    Ok(quote! {
+        {
+            const ITEMS: &[::strftime::__FormatItem] = &[#(#items),*];
+            ::strftime::Format::__from_static(ITEMS, #len)
+        }
+    })
+}
+
+/// Piece of a format string.
+#[derive(Debug, PartialEq, Eq)]
+enum Piece<'a> {
+    /// Text which is copied verbatim to the output.
+    Literal(&'a [u8]),
+    /// Invalid directive, which is copied verbatim to the output.
+    Invalid {
+        /// Byte offset of the directive in the format string.
+        offset: usize,
+        /// Text of the directive.
+        text: &'a [u8],
+        /// Reason of the directive being invalid.
+        reason: &'static str,
+    },
+    /// Formatting directive.
+    Directive {
+        /// Name of the `Spec` variant.
+        spec: &'static str,
+        /// Bits of the formatting flags.
+        flags: u8,
+        /// Optional width.
+        width: Option<u64>,
+        /// Name of the `Padding` variant.
+        padding: &'static str,
+    },
+}
+
+/// Formatting specifiers without colons, sorted by character.
+const SPECS: &[(u8, &str)] = &[
+    (b'%', "Percent"),
+    (b'A', "WeekDayName"),
+    (b'B', "MonthName"),
+    (b'C', "YearDiv100"),
+    (b'D', "CombinationDate"),
+    (b'F', "CombinationIso8601"),
+    (b'G', "YearIso8601"),
+    (b'H', "Hour24hZero"),
+    (b'I', "Hour12hZero"),
+    (b'L', "MilliSecond"),
+    (b'M', "Minute"),
+    (b'N', "FractionalSecond"),
+    (b'P', "MeridianLower"),
+    (b'R', "CombinationHourMinute24h"),
+    (b'S', "Second"),
+    (b'T', "CombinationTime24h"),
+    (b'U', "WeekNumberFromSunday"),
+    (b'V', "WeekNumberIso8601"),
+    (b'W', "WeekNumberFromMonday"),
+    (b'X', "CombinationTime24h"),
+    (b'Y', "Year4Digits"),
+    (b'Z', "TimeZoneName"),
+    (b'a', "WeekDayNameAbbr"),
+    (b'b', "MonthNameAbbr"),
+    (b'c', "CombinationDateTime"),
+    (b'd', "MonthDayZero"),
+    (b'e', "MonthDaySpace"),
+    (b'g', "YearIso8601Rem100"),
+    (b'h', "MonthNameAbbr"),
+    (b'j', "YearDay"),
+    (b'k', "Hour24hSpace"),
+    (b'l', "Hour12hSpace"),
+    (b'm', "Month"),
+    (b'n', "Newline"),
+    (b'p', "MeridianUpper"),
+    (b'r', "CombinationTime12h"),
+    (b's', "SecondsSinceEpoch"),
+    (b't', "Tabulation"),
+    (b'u', "WeekDayFrom1"),
+    (b'v', "CombinationVmsDate"),
+    (b'w', "WeekDayFrom0"),
+    (b'x', "CombinationDate"),
+    (b'y', "YearRem100"),
+    (b'z', "TimeZoneOffsetHourMinute"),
+];
+
+/// Parse a format string with the directives of the Ruby dialect.
+///
+/// This mirrors the format string parsing of the `strftime-ruby` crate, which
+/// cannot be used from this crate without creating a dependency cycle.
+/// Returns an error if the format string is ended by an unterminated
+/// directive.
+fn parse(format: &[u8]) -> Result<Vec<Piece<'_>>, String> {
+    /// Maximum value of a directive width.
+    const MAX_WIDTH: u64 = i32::MAX as u64;
+
+    let mut pieces = Vec::new();
+    let mut cursor = format;
+
+    while let Some(position) = cursor.iter().position(|&x| x == b'%') {
+        if position > 0 {
+            pieces.push(Piece::Literal(&cursor[..position]));
+        }
+
+        let directive_start = format.len() - cursor.len() + position;
+        let directive = &cursor[position..];
+        cursor = &cursor[position + 1..];
+
+        // Flags, where the left padding overrides the previous padding
+        let mut flags = 0;
+        let mut padding = "Left";
+        while let Some(&flag) = cursor.first() {
+            match flag {
+                b'-' => {
+                    flags |= 1 << 0;
+                    padding = "Left";
+                }
+                b'_' => padding = "Spaces",
+                b'0' => padding = "Zeros",
+                b'^' => flags |= 1 << 2,
+                b'#' => flags |= 1 << 1,
+                _ => break,
+            }
+            cursor = &cursor[1..];
+        }
+
+        // Width, which makes the directive invalid when it is too large
+        let digits = cursor.iter().take_while(|x| x.is_ascii_digit()).count();
+        let width = cursor[..digits].iter().try_fold(0_u64, |width, &digit| {
+            let width = width * 10 + u64::from(digit - b'0');
+            (width <= MAX_WIDTH).then(|| width)
+        });
+        cursor = &cursor[digits..];
+
+        let text = |cursor: &[u8]| &directive[..directive.len() - cursor.len()];
+
+        let width = if let Some(width) = width {
+            (digits > 0).then(|| width)
+        } else {
+            pieces.push(Piece::Invalid {
+                offset: directive_start,
+                text: text(cursor),
+                reason: "width too large",
+            });
+            continue;
+        };
+
+        // POSIX locale extensions
+        if let [ext, spec, ..] = cursor {
+            let is_extension = match ext {
+                b'E' => b"CXYcxy".contains(spec),
+                b'O' => b"HIMSUVWdeklmuwy".contains(spec),
+                _ => false,
+            };
+            if is_extension {
+                cursor = &cursor[1..];
+            }
+        }
+
+        // Conversion specifier
+        let colons = cursor.iter().take_while(|&&x| x == b':').count();
+        cursor = &cursor[colons..];
+
+        let spec = if colons == 0 {
+            match cursor.split_first() {
+                Some((&spec, remaining)) => {
+                    cursor = remaining;
+                    SPECS
+                        .binary_search_by_key(&spec, |&(c, _)| c)
+                        .ok()
+                        .map(|index| SPECS[index].1)
+                }
+                None => {
+                    return Err(format!(
+                        "invalid format string: unterminated directive at byte offset {directive_start}"
+                    ))
+                }
+            }
+        } else if let Some(remaining) = cursor.strip_prefix(b"z") {
+            cursor = remaining;
+            match colons {
+                1 => Some("TimeZoneOffsetHourMinuteColon"),
+                2 => Some("TimeZoneOffsetHourMinuteSecondColon"),
+                3 => Some("TimeZoneOffsetColonMinimal"),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        pieces.push(match spec {
+            Some(spec) => Piece::Directive {
+                spec,
+                flags,
+                width,
+                padding,
+            },
+            None => Piece::Invalid {
+                offset: directive_start,
+                text: text(cursor),
+                reason: "unknown directive",
+            },
+        });
+    }
+
+    if !cursor.is_empty() {
+        pieces.push(Piece::Literal(cursor));
+    }
+
+    Ok(pieces)
+}
+
+/// Check that a format string is not ended by an unterminated directive.
+fn validate(format: &[u8]) -> Result<(), String> {
+    parse(format).map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, parse, validate, Piece};
+    use proc_macro2::Span;
+    use syn::LitStr;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(b"").is_ok());
+        assert!(validate(b"%Y-%m-%dT%H:%M:%S%:z").is_ok());
+        assert!(validate(b"%%").is_ok());
+        assert!(validate(b"%Q").is_ok());
+        assert!(validate(b"%::").is_ok());
+        assert!(validate(b"%-_0^#10Ey").is_ok());
+        assert!(validate(b"%2147483648").is_ok());
+        assert!(validate(b"%E").is_ok());
+
+        assert!(validate(b"%").is_err());
+        assert!(validate(b"%Y%").is_err());
+        assert!(validate(b"%%%").is_err());
+        assert!(validate(b"%-_0^#").is_err());
+        assert!(validate(b"%2147483647").is_err());
+
+        assert_eq!(
+            validate(b"%Y-%").unwrap_err(),
+            "invalid format string: unterminated directive at byte offset 3"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(b"at %-_0^#10Ey%Q%::z%").unwrap_err(),
+            "invalid format string: unterminated directive at byte offset 19"
+        );
+        assert_eq!(
+            parse(b"at %_^#10Ey %Q%::z").unwrap(),
+            [
+                Piece::Literal(b"at "),
+                Piece::Directive {
+                    spec: "YearRem100",
+                    flags: 0b110,
+                    width: Some(10),
+                    padding: "Spaces",
+                },
+                Piece::Literal(b" "),
+                Piece::Invalid {
+                    offset: 12,
+                    text: b"%Q",
+                    reason: "unknown directive",
+                },
+                Piece::Directive {
+                    spec: "TimeZoneOffsetHourMinuteSecondColon",
+                    flags: 0,
+                    width: None,
+                    padding: "Left",
+                },
+            ]
+        );
+        assert_eq!(
+            parse(b"%_-2147483648d%::::z").unwrap(),
+            [
+                Piece::Invalid {
+                    offset: 0,
+                    text: b"%_-2147483648",
+                    reason: "width too large",
+                },
+                Piece::Literal(b"d"),
+                Piece::Invalid {
+                    offset: 14,
+                    text: b"%::::z",
+                    reason: "unknown directive",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_error() {
+        let compile = |format| compile(&LitStr::new(format, Span::call_site()));
+
+        assert!(compile("%Y-%m-%d %%").is_ok());
+        assert_eq!(
+            compile("%Y %Q").unwrap_err().to_string(),
+            "invalid format string: unknown directive `%Q` at byte offset 3, use `%%` for a literal `%`"
+        );
+        assert_eq!(
+            compile("%2147483648d").unwrap_err().to_string(),
+            "invalid format string: width too large `%2147483648` at byte offset 0, use `%%` for a literal `%`"
+        );
+        assert_eq!(
+            compile("%Y %").unwrap_err().to_string(),
+            "invalid format string: unterminated directive at byte offset 3"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use strftime::strftime_display;
+use strftime::test_util::FixedTime;
+
+#[strftime_display(format = "%Y-%m-%dT%H:%M:%S%:z")]
+struct Iso8601(FixedTime);
+
+#[strftime_display(format = "%a %b %e %T %Z %Y", debug)]
+struct Date<T: strftime::Time> {
+    time: T,
+}
+
+#[test]
+fn test_display() {
+    let time = Iso8601(FixedTime::LEAP_DAY);
+    assert_eq!(time.to_string(), "2024-02-29T13:14:15+01:00");
+}
+
+#[test]
+fn test_display_named_field_and_debug() {
+    let time = Date {
+        time: FixedTime::LEAP_DAY,
+    };
+    assert_eq!(time.to_string(), "Thu Feb 29 13:14:15 CET 2024");
+    assert_eq!(format!("{time:?}"), "Thu Feb 29 13:14:15 CET 2024");
+}
+
+#[test]
+fn test_display_invalid_time() {
+    use std::fmt::Write;
+
+    let time = Iso8601(FixedTime {
+        month: 13,
+        ..FixedTime::UNIX_EPOCH
+    });
+    assert!(write!(String::new(), "{time}").is_err());
+}
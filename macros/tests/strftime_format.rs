@@ -0,0 +1,32 @@
+use strftime::test_util::FixedTime;
+use strftime::{strftime_format, Format};
+
+static ISO_8601: Format = strftime_format!("%Y-%m-%dT%H:%M:%S%:z");
+
+const FLAGS: Format = strftime_format!("%-d|%_5m|%010Y|%^B|%#p|%Ey|%::z|%%");
+
+#[test]
+fn test_static_format() {
+    let mut buf = String::new();
+    ISO_8601.fmt(&FixedTime::LEAP_DAY, &mut buf).unwrap();
+    assert_eq!(buf, "2024-02-29T13:14:15+01:00");
+}
+
+#[test]
+fn test_same_as_parse() {
+    assert_eq!(ISO_8601, Format::parse("%Y-%m-%dT%H:%M:%S%:z").unwrap());
+    assert_eq!(
+        FLAGS,
+        Format::parse("%-d|%_5m|%010Y|%^B|%#p|%Ey|%::z|%%").unwrap()
+    );
+    assert_eq!(strftime_format!(""), Format::parse("").unwrap());
+    assert_eq!(strftime_format!("text"), Format::parse("text").unwrap());
+    assert_eq!(strftime_format!("%N %3L"), Format::parse("%N %3L").unwrap());
+}
+
+#[test]
+fn test_required_fields() {
+    let fields = FLAGS.required_fields();
+    assert!(fields.day() && fields.month() && fields.year() && fields.hour());
+    assert!(!fields.minute());
+}
@@ -0,0 +1,216 @@
+//! Provides `strftime` implementations for [Apache Arrow] timestamp arrays,
+//! writing to newly allocated string arrays.
+//!
+//! [Apache Arrow]: https://arrow.apache.org/
+
+use arrow_array::builder::GenericStringBuilder;
+use arrow_array::types::ArrowTimestampType;
+use arrow_array::{
+    GenericStringArray, LargeStringArray, OffsetSizeTrait, PrimitiveArray, StringArray,
+};
+use arrow_schema::TimeUnit;
+
+use crate::{DateTime, Error};
+
+/// Format an Arrow array of timestamps with the specified UTF-8 format string,
+/// writing to a newly allocated [`StringArray`].
+///
+/// The timestamps are interpreted according to the time unit of the array as
+/// the elapsed time since `1970-01-01 00:00:00 UTC`. They are formatted in UTC
+/// when `utc_offset` is [`None`], or with the provided fixed offset in seconds
+/// from UTC otherwise. Null values are preserved.
+///
+/// See the [crate-level documentation](crate) for a complete description of
+/// possible format specifiers.
+///
+/// # Allocations
+///
+/// This `strftime` implementation writes all formatted values into a single
+/// [`GenericStringBuilder`], which is allocated once with a capacity
+/// estimated from the length of the array and of the format string.
+///
+/// # Examples
+///
+/// ```
+/// use arrow_array::{Array, TimestampMillisecondArray};
+/// use strftime::arrow::strftime;
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let array = TimestampMillisecondArray::from(vec![Some(0), None, Some(1_000_000_000_123)]);
+///
+/// let formatted = strftime(&array, None, "%F %T.%L")?;
+/// assert_eq!(formatted.value(0), "1970-01-01 00:00:00.000");
+/// assert!(formatted.is_null(1));
+/// assert_eq!(formatted.value(2), "2001-09-09 01:46:40.123");
+///
+/// let formatted = strftime(&array, Some(-3600), "%F %T %:z")?;
+/// assert_eq!(formatted.value(0), "1969-12-31 23:00:00 -01:00");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Can produce an [`Error`] when the formatting fails. In particular, returns
+/// [`Error::InvalidTime`] if a timestamp is out of range or if the offset is
+/// not strictly between `-86400` and `86400`.
+pub fn strftime<T: ArrowTimestampType>(
+    array: &PrimitiveArray<T>,
+    utc_offset: Option<i32>,
+    format: &str,
+) -> Result<StringArray, Error> {
+    strftime_generic(array, utc_offset, format)
+}
+
+/// Format an Arrow array of timestamps with the specified UTF-8 format string,
+/// writing to a newly allocated [`LargeStringArray`].
+///
+/// This function is identical to [`strftime`], but uses 64-bit offsets for the
+/// resulting string array.
+///
+/// # Examples
+///
+/// ```
+/// use arrow_array::TimestampSecondArray;
+/// use strftime::arrow::strftime_large;
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let array = TimestampSecondArray::from(vec![86_400]);
+///
+/// let formatted = strftime_large(&array, None, "%c")?;
+/// assert_eq!(formatted.value(0), "Fri Jan  2 00:00:00 1970");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Can produce an [`Error`] when the formatting fails. In particular, returns
+/// [`Error::InvalidTime`] if a timestamp is out of range or if the offset is
+/// not strictly between `-86400` and `86400`.
+pub fn strftime_large<T: ArrowTimestampType>(
+    array: &PrimitiveArray<T>,
+    utc_offset: Option<i32>,
+    format: &str,
+) -> Result<LargeStringArray, Error> {
+    strftime_generic(array, utc_offset, format)
+}
+
+/// Format an Arrow array of timestamps into a string array with the provided
+/// offset size.
+fn strftime_generic<T: ArrowTimestampType, O: OffsetSizeTrait>(
+    array: &PrimitiveArray<T>,
+    utc_offset: Option<i32>,
+    format: &str,
+) -> Result<GenericStringArray<O>, Error> {
+    let data_capacity = array.len().saturating_mul(format.len());
+    let mut builder = GenericStringBuilder::<O>::with_capacity(array.len(), data_capacity);
+
+    for value in array {
+        match value {
+            None => builder.append_null(),
+            Some(value) => {
+                let time = to_date_time(value, T::UNIT, utc_offset).ok_or(Error::InvalidTime)?;
+                crate::fmt::strftime(&time, format, &mut builder)?;
+                // Finish the current string
+                builder.append_value("");
+            }
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+/// Convert a timestamp with the provided time unit to a `DateTime`.
+fn to_date_time(value: i64, unit: TimeUnit, utc_offset: Option<i32>) -> Option<DateTime> {
+    let (seconds, nanoseconds) = match unit {
+        TimeUnit::Second => (value, 0),
+        TimeUnit::Millisecond => (value.div_euclid(1_000), value.rem_euclid(1_000) * 1_000_000),
+        TimeUnit::Microsecond => (
+            value.div_euclid(1_000_000),
+            value.rem_euclid(1_000_000) * 1_000,
+        ),
+        TimeUnit::Nanosecond => (
+            value.div_euclid(1_000_000_000),
+            value.rem_euclid(1_000_000_000),
+        ),
+    };
+
+    let nanoseconds = u32::try_from(nanoseconds).ok()?;
+    match utc_offset {
+        None => DateTime::from_timestamp(seconds, nanoseconds),
+        Some(utc_offset) => DateTime::from_timestamp_with_offset(seconds, nanoseconds, utc_offset),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use arrow_array::{
+        Array, TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+        TimestampSecondArray,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_strftime_units() {
+        let array = TimestampSecondArray::from(vec![-1]);
+        let formatted = strftime(&array, None, "%F %T.%N").unwrap();
+        assert_eq!(formatted.value(0), "1969-12-31 23:59:59.000000000");
+
+        let array = TimestampMillisecondArray::from(vec![-1]);
+        let formatted = strftime(&array, None, "%F %T.%N").unwrap();
+        assert_eq!(formatted.value(0), "1969-12-31 23:59:59.999000000");
+
+        let array = TimestampMicrosecondArray::from(vec![-1]);
+        let formatted = strftime(&array, None, "%F %T.%N").unwrap();
+        assert_eq!(formatted.value(0), "1969-12-31 23:59:59.999999000");
+
+        let array = TimestampNanosecondArray::from(vec![-1]);
+        let formatted = strftime(&array, None, "%F %T.%N").unwrap();
+        assert_eq!(formatted.value(0), "1969-12-31 23:59:59.999999999");
+    }
+
+    #[test]
+    fn test_strftime_time_zone() {
+        let array = TimestampSecondArray::from(vec![0]);
+
+        let formatted = strftime(&array, None, "%z %Z").unwrap();
+        assert_eq!(formatted.value(0), "+0000 UTC");
+
+        let formatted = strftime(&array, Some(0), "%z %Z").unwrap();
+        assert_eq!(formatted.value(0), "+0000 ");
+    }
+
+    #[test]
+    fn test_strftime_nulls() {
+        let array = TimestampSecondArray::from(vec![None, Some(0), None]);
+        let formatted = strftime_large(&array, None, "%Y").unwrap();
+
+        assert_eq!(formatted.len(), 3);
+        assert!(formatted.is_null(0));
+        assert_eq!(formatted.value(1), "1970");
+        assert!(formatted.is_null(2));
+    }
+
+    #[test]
+    fn test_strftime_errors() {
+        let array = TimestampSecondArray::from(vec![i64::MAX]);
+        assert!(matches!(
+            strftime(&array, None, "%Y"),
+            Err(Error::InvalidTime)
+        ));
+
+        let array = TimestampSecondArray::from(vec![0]);
+        assert!(matches!(
+            strftime(&array, Some(86_400), "%Y"),
+            Err(Error::InvalidTime)
+        ));
+        assert!(matches!(
+            strftime(&array, None, "%"),
+            Err(Error::UnterminatedDirective { .. })
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+//! Construction of a [`DateTime`] from the BCD-encoded registers of a
+//! real-time clock.
+
+use crate::calendar::{civil_from_days, days_from_civil, SECONDS_PER_DAY};
+use crate::DateTime;
+
+/// Flag of the hours register selecting the 12-hour mode.
+const HOURS_12H: u8 = 0x40;
+
+/// Flag of the hours register selecting PM in 12-hour mode.
+const HOURS_PM: u8 = 0x20;
+
+/// Flag of the month register set when the year overflows from 99 to 00.
+const MONTH_CENTURY: u8 = 0x80;
+
+/// Decode a packed BCD byte, returning [`None`] if a digit is not in `0..=9`.
+const fn decode_bcd(byte: u8) -> Option<u8> {
+    let (tens, units) = (byte >> 4, byte & 0x0f);
+    if tens > 9 || units > 9 {
+        None
+    } else {
+        Some(tens * 10 + units)
+    }
+}
+
+/// Decode a packed BCD byte and check if it is in `min..=max`.
+const fn decode_bcd_in(byte: u8, min: u8, max: u8) -> Option<u8> {
+    match decode_bcd(byte) {
+        Some(value) if value >= min && value <= max => Some(value),
+        _ => None,
+    }
+}
+
+impl DateTime {
+    /// Construct a new UTC `DateTime` from the raw BCD-encoded registers of a
+    /// real-time clock, like the DS3231 or the PCF8563.
+    ///
+    /// The registers are decoded as follows:
+    ///
+    /// - The high bit of the `seconds` and `minutes` registers, used as a
+    ///   status flag by some chips, is ignored.
+    /// - The `hours` register is in 24-hour mode, unless bit 6 is set. In
+    ///   12-hour mode, bit 5 is set for PM.
+    /// - The high bit of the `month` register is the century flag, which adds
+    ///   100 years.
+    /// - The two-digit `year` register is added to `base_year`, which is
+    ///   usually `2000`.
+    ///
+    /// The day of the week and the day of the year are derived from the date,
+    /// so the day of the week register of the chip is not needed.
+    ///
+    /// Returns [`None`] if a register is not valid BCD, if a field is out of
+    /// range or if the date does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime;
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// // Registers read from the chip: 2024-02-29 11:30:45 PM in 12-hour mode
+    /// let [seconds, minutes, hours, day, month, year] = [0x45, 0x30, 0x71, 0x29, 0x02, 0x24];
+    ///
+    /// let time = DateTime::from_bcd_registers(seconds, minutes, hours, day, month, year, 2000).unwrap();
+    ///
+    /// let mut buf = [0u8; 32];
+    /// assert_eq!(strftime(&time, b"%F %T %a %j", &mut buf)?, b"2024-02-29 23:30:45 Thu 060");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn from_bcd_registers(
+        seconds: u8,
+        minutes: u8,
+        hours: u8,
+        day: u8,
+        month: u8,
+        year: u8,
+        base_year: i32,
+    ) -> Option<Self> {
+        let second = match decode_bcd_in(seconds & 0x7f, 0, 59) {
+            Some(second) => second,
+            None => return None,
+        };
+        let minute = match decode_bcd_in(minutes & 0x7f, 0, 59) {
+            Some(minute) => minute,
+            None => return None,
+        };
+        let hour = if hours & HOURS_12H == 0 {
+            match decode_bcd_in(hours & 0x3f, 0, 23) {
+                Some(hour) => hour,
+                None => return None,
+            }
+        } else {
+            let pm = hours & HOURS_PM != 0;
+            match decode_bcd_in(hours & 0x1f, 1, 12) {
+                Some(12) if pm => 12,
+                Some(12) => 0,
+                Some(hour) if pm => hour + 12,
+                Some(hour) => hour,
+                None => return None,
+            }
+        };
+        let day = match decode_bcd_in(day & 0x3f, 1, 31) {
+            Some(day) => day,
+            None => return None,
+        };
+        let century = if month & MONTH_CENTURY == 0 { 0 } else { 100 };
+        let month = match decode_bcd_in(month & 0x1f, 1, 12) {
+            Some(month) => month,
+            None => return None,
+        };
+        let year = match decode_bcd(year) {
+            Some(year) => base_year as i64 + century + year as i64,
+            None => return None,
+        };
+
+        // Check that the day exists in the month
+        let days = days_from_civil(year, month, day);
+        let (_, actual_month, _) = civil_from_days(days);
+        if actual_month != month {
+            return None;
+        }
+
+        let seconds_of_day = hour as i64 * 3600 + minute as i64 * 60 + second as i64;
+        Self::from_timestamp(days * SECONDS_PER_DAY + seconds_of_day, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Time;
+
+    #[test]
+    fn test_decode_bcd() {
+        assert_eq!(decode_bcd(0x00), Some(0));
+        assert_eq!(decode_bcd(0x59), Some(59));
+        assert_eq!(decode_bcd(0x99), Some(99));
+        assert_eq!(decode_bcd(0x0a), None);
+        assert_eq!(decode_bcd(0xa0), None);
+    }
+
+    #[test]
+    fn test_from_bcd_registers() {
+        let time = DateTime::from_bcd_registers(0x59, 0x59, 0x23, 0x31, 0x12, 0x99, 2000).unwrap();
+        assert_eq!(time.year(), 2099);
+        assert_eq!(time.month(), 12);
+        assert_eq!(time.day(), 31);
+        assert_eq!(time.hour(), 23);
+        assert_eq!(time.minute(), 59);
+        assert_eq!(time.second(), 59);
+        assert_eq!(time.day_of_week(), 4);
+        assert_eq!(time.day_of_year(), 365);
+        assert!(time.is_utc());
+
+        // Status flags and century flag
+        let time = DateTime::from_bcd_registers(0x80, 0x80, 0x00, 0x01, 0x81, 0x00, 2000).unwrap();
+        assert_eq!(time.year(), 2100);
+        assert_eq!(time.to_int(), 4_102_444_800);
+
+        // 12-hour mode
+        let hour =
+            |hours| DateTime::from_bcd_registers(0, 0, hours, 1, 1, 0, 2000).map(|t| t.hour());
+        assert_eq!(hour(0x52), Some(0));
+        assert_eq!(hour(0x41), Some(1));
+        assert_eq!(hour(0x72), Some(12));
+        assert_eq!(hour(0x61), Some(13));
+        assert_eq!(hour(0x71), Some(23));
+        assert_eq!(hour(0x40), None);
+        assert_eq!(hour(0x53), None);
+        assert_eq!(hour(0x24), None);
+    }
+
+    #[test]
+    fn test_from_bcd_registers_invalid() {
+        assert!(DateTime::from_bcd_registers(0x60, 0, 0, 1, 1, 0, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0x1a, 0, 0, 1, 1, 0, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0x60, 0, 1, 1, 0, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0, 0, 0x00, 1, 0, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0, 0, 1, 0x13, 0, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0, 0, 1, 1, 0xa0, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0, 0, 0x30, 0x02, 0x24, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0, 0, 0x29, 0x02, 0x23, 2000).is_none());
+        assert!(DateTime::from_bcd_registers(0, 0, 0, 0x29, 0x02, 0x24, 2000).is_some());
+    }
+}
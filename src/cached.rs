@@ -0,0 +1,242 @@
+//! A [`Time`] adapter memoizing the values of another implementation.
+
+use core::cell::Cell;
+
+use crate::Time;
+
+/// A [`Time`] implementation which lazily computes and memoizes the values of
+/// the wrapped implementation.
+///
+/// Each accessor of the wrapped implementation is called at most once, even
+/// if the format string references the same field multiple times. This is
+/// useful when the wrapped implementation is expensive, for example when it
+/// looks up the time zone database or converts a timestamp to a civil date on
+/// every call.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::buffered::strftime;
+/// use strftime::{CachedTime, Time};
+///
+/// // Not shown: create a time implementation with the year 1970
+/// // let time = ...;
+/// # include!("mock.rs.in");
+/// # fn main() -> Result<(), strftime::Error> {
+/// # let time = MockTime { year: 1970, month: 1, day: 1, day_of_week: 4, day_of_year: 1, ..Default::default() };
+/// assert_eq!(time.year(), 1970);
+///
+/// let cached = CachedTime::new(&time);
+///
+/// let mut buf = [0u8; 32];
+/// assert_eq!(strftime(&cached, b"%Y %C %y %G", &mut buf)?, b"1970 19 70 1970");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachedTime<'a, T: ?Sized> {
+    /// Wrapped time implementation.
+    inner: &'a T,
+    year: Cell<Option<i32>>,
+    month: Cell<Option<u8>>,
+    day: Cell<Option<u8>>,
+    hour: Cell<Option<u8>>,
+    minute: Cell<Option<u8>>,
+    second: Cell<Option<u8>>,
+    nanoseconds: Cell<Option<u32>>,
+    day_of_week: Cell<Option<u8>>,
+    day_of_year: Cell<Option<u16>>,
+    to_int: Cell<Option<i64>>,
+    is_utc: Cell<Option<bool>>,
+    utc_offset: Cell<Option<i32>>,
+    time_zone: Cell<Option<&'a str>>,
+}
+
+impl<'a, T: Time + ?Sized> CachedTime<'a, T> {
+    /// Construct a new `CachedTime` wrapping a _time_ implementation.
+    #[must_use]
+    pub const fn new(inner: &'a T) -> Self {
+        Self {
+            inner,
+            year: Cell::new(None),
+            month: Cell::new(None),
+            day: Cell::new(None),
+            hour: Cell::new(None),
+            minute: Cell::new(None),
+            second: Cell::new(None),
+            nanoseconds: Cell::new(None),
+            day_of_week: Cell::new(None),
+            day_of_year: Cell::new(None),
+            to_int: Cell::new(None),
+            is_utc: Cell::new(None),
+            utc_offset: Cell::new(None),
+            time_zone: Cell::new(None),
+        }
+    }
+
+    /// Returns the wrapped _time_ implementation.
+    #[must_use]
+    pub const fn get_ref(&self) -> &'a T {
+        self.inner
+    }
+}
+
+/// Returns the cached value, computing and caching it if needed.
+fn cached<V: Copy>(cell: &Cell<Option<V>>, compute: impl FnOnce() -> V) -> V {
+    if let Some(value) = cell.get() {
+        return value;
+    }
+    let value = compute();
+    cell.set(Some(value));
+    value
+}
+
+impl<T: Time + ?Sized> Time for CachedTime<'_, T> {
+    fn year(&self) -> i32 {
+        cached(&self.year, || self.inner.year())
+    }
+
+    fn month(&self) -> u8 {
+        cached(&self.month, || self.inner.month())
+    }
+
+    fn day(&self) -> u8 {
+        cached(&self.day, || self.inner.day())
+    }
+
+    fn hour(&self) -> u8 {
+        cached(&self.hour, || self.inner.hour())
+    }
+
+    fn minute(&self) -> u8 {
+        cached(&self.minute, || self.inner.minute())
+    }
+
+    fn second(&self) -> u8 {
+        cached(&self.second, || self.inner.second())
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        cached(&self.nanoseconds, || self.inner.nanoseconds())
+    }
+
+    fn day_of_week(&self) -> u8 {
+        cached(&self.day_of_week, || self.inner.day_of_week())
+    }
+
+    fn day_of_year(&self) -> u16 {
+        cached(&self.day_of_year, || self.inner.day_of_year())
+    }
+
+    fn to_int(&self) -> i64 {
+        cached(&self.to_int, || self.inner.to_int())
+    }
+
+    fn is_utc(&self) -> bool {
+        cached(&self.is_utc, || self.inner.is_utc())
+    }
+
+    fn utc_offset(&self) -> i32 {
+        cached(&self.utc_offset, || self.inner.utc_offset())
+    }
+
+    fn time_zone(&self) -> &str {
+        cached(&self.time_zone, || self.inner.time_zone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::buffered::strftime;
+
+    /// A time implementation counting the calls to its accessors.
+    #[derive(Default)]
+    struct CountingTime {
+        calls: Cell<usize>,
+    }
+
+    impl CountingTime {
+        fn call<V>(&self, value: V) -> V {
+            self.calls.set(self.calls.get() + 1);
+            value
+        }
+    }
+
+    impl Time for CountingTime {
+        fn year(&self) -> i32 {
+            self.call(1970)
+        }
+        fn month(&self) -> u8 {
+            self.call(1)
+        }
+        fn day(&self) -> u8 {
+            self.call(1)
+        }
+        fn hour(&self) -> u8 {
+            self.call(0)
+        }
+        fn minute(&self) -> u8 {
+            self.call(0)
+        }
+        fn second(&self) -> u8 {
+            self.call(0)
+        }
+        fn nanoseconds(&self) -> u32 {
+            self.call(0)
+        }
+        fn day_of_week(&self) -> u8 {
+            self.call(4)
+        }
+        fn day_of_year(&self) -> u16 {
+            self.call(1)
+        }
+        fn to_int(&self) -> i64 {
+            self.call(0)
+        }
+        fn is_utc(&self) -> bool {
+            self.call(true)
+        }
+        fn utc_offset(&self) -> i32 {
+            self.call(0)
+        }
+        fn time_zone(&self) -> &str {
+            self.call("UTC")
+        }
+    }
+
+    #[test]
+    fn test_cached_time() {
+        #[cfg(feature = "subsec")]
+        const FORMAT: &[u8] = b"%c %F %T %s %N %z %Z %j %G %V %U %a %c %F %T %s %N %z %Z";
+        #[cfg(feature = "subsec")]
+        const EXPECTED: &[u8] = b"Thu Jan  1 00:00:00 1970 1970-01-01 00:00:00 0 000000000 \
+            +0000 UTC 001 1970 01 00 Thu Thu Jan  1 00:00:00 1970 1970-01-01 00:00:00 0 \
+            000000000 +0000 UTC";
+        #[cfg(not(feature = "subsec"))]
+        const FORMAT: &[u8] = b"%c %F %T %s %z %Z %j %G %V %U %a %c %F %T %s %z %Z";
+        #[cfg(not(feature = "subsec"))]
+        const EXPECTED: &[u8] = b"Thu Jan  1 00:00:00 1970 1970-01-01 00:00:00 0 \
+            +0000 UTC 001 1970 01 00 Thu Thu Jan  1 00:00:00 1970 1970-01-01 00:00:00 0 \
+            +0000 UTC";
+
+        let time = CountingTime::default();
+        let mut buf = [0u8; 256];
+
+        assert_eq!(strftime(&time, FORMAT, &mut buf).unwrap(), EXPECTED);
+        assert!(time.calls.get() > 13);
+
+        let time = CountingTime::default();
+        let cached = CachedTime::new(&time);
+
+        assert_eq!(strftime(&cached, FORMAT, &mut buf).unwrap(), EXPECTED);
+        assert!(time.calls.get() <= 13);
+
+        let calls = time.calls.get();
+        assert_eq!(strftime(&cached, FORMAT, &mut buf).unwrap(), EXPECTED);
+        assert_eq!(time.calls.get(), calls);
+        assert!(core::ptr::eq(cached.get_ref(), &time));
+    }
+}
@@ -0,0 +1,135 @@
+//! Proleptic Gregorian calendar computations.
+//!
+//! The conversions between days and civil dates are based on the algorithms
+//! described in <https://howardhinnant.github.io/date_algorithms.html>.
+
+// All the casted values are non-negative by construction.
+#![allow(clippy::cast_sign_loss)]
+
+/// Number of seconds in a day.
+pub(crate) const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Number of days between `0000-03-01` and `1970-01-01`.
+const DAYS_FROM_0000_03_01_TO_1970_01_01: i64 = 719_468;
+
+/// Number of days in a 400-year era.
+const DAYS_PER_ERA: i64 = 146_097;
+
+/// Check if a year is a leap year.
+pub(crate) const fn is_leap_year(year: i64) -> bool {
+    year % 400 == 0 || (year % 4 == 0 && year % 100 != 0)
+}
+
+/// Compute the number of days since `1970-01-01` of a civil date.
+///
+/// ## Inputs
+///
+/// * `year`: Year.
+/// * `month`: Month of the year in `1..=12`.
+/// * `day`: Day of the month in `1..=31`.
+///
+pub(crate) const fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * DAYS_PER_ERA + day_of_era - DAYS_FROM_0000_03_01_TO_1970_01_01
+}
+
+/// Compute the civil date `(year, month, day)` from the number of days since
+/// `1970-01-01`.
+pub(crate) const fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + DAYS_FROM_0000_03_01_TO_1970_01_01;
+    let era = days.div_euclid(DAYS_PER_ERA);
+    let day_of_era = days.rem_euclid(DAYS_PER_ERA);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u8;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u8;
+    let year = year_of_era + era * 400;
+    let year = if month <= 2 { year + 1 } else { year };
+    (year, month, day)
+}
+
+/// Compute the day of the week from Sunday in `0..=6` from the number of days
+/// since `1970-01-01`.
+pub(crate) const fn day_of_week(days: i64) -> u8 {
+    // 1970-01-01 is a Thursday
+    (days + 4).rem_euclid(7) as u8
+}
+
+/// Compute the day of the year in `1..=366` of a civil date.
+pub(crate) const fn day_of_year(year: i64, month: u8, day: u8) -> u16 {
+    (days_from_civil(year, month, day) - days_from_civil(year, 1, 1) + 1) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_leap_year() {
+        assert!(is_leap_year(2000));
+        assert!(!is_leap_year(2001));
+        assert!(is_leap_year(2004));
+        assert!(!is_leap_year(2100));
+        assert!(!is_leap_year(2200));
+        assert!(!is_leap_year(2300));
+        assert!(is_leap_year(2400));
+        assert!(is_leap_year(-4));
+        assert!(!is_leap_year(-100));
+        assert!(is_leap_year(-400));
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(0, 3, 1), -719_468);
+        assert_eq!(days_from_civil(-1, 12, 31), -719_529);
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-719_468), (0, 3, 1));
+        assert_eq!(civil_from_days(-719_529), (-1, 12, 31));
+    }
+
+    #[test]
+    fn test_civil_round_trip() {
+        for days in -1_000_000..1_000_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert!((1..=12).contains(&month));
+            assert!((1..=31).contains(&day));
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn test_day_of_week() {
+        assert_eq!(day_of_week(0), 4);
+        assert_eq!(day_of_week(3), 0);
+        assert_eq!(day_of_week(-1), 3);
+        assert_eq!(day_of_week(-4), 0);
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        assert_eq!(day_of_year(2001, 1, 1), 1);
+        assert_eq!(day_of_year(2001, 12, 31), 365);
+        assert_eq!(day_of_year(2004, 12, 31), 366);
+        assert_eq!(day_of_year(2004, 3, 1), 61);
+    }
+}
@@ -0,0 +1,190 @@
+//! [`Time`] implementations for the date and time types of the [`chrono`]
+//! crate.
+//!
+//! [`chrono`]: https://docs.rs/chrono
+
+use ::chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Timelike, Utc};
+
+use crate::Time;
+
+/// Number of nanoseconds in a second.
+const NANOS_PER_SECOND: u32 = 1_000_000_000;
+
+/// Implements the [`Time`] trait for a type implementing the [`Datelike`] and
+/// [`Timelike`] traits of `chrono`, with the given methods depending on the
+/// time zone.
+///
+/// A leap second is represented in `chrono` by a number of nanoseconds greater
+/// than one second, and is formatted as the second `60`.
+macro_rules! impl_time {
+    ($ty:ty { $($time_zone_methods:tt)* }) => {
+        #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+        impl Time for $ty {
+            fn year(&self) -> i32 {
+                Datelike::year(self)
+            }
+
+            // The values returned by `chrono` are in range of the narrower
+            // integer types.
+            #[allow(clippy::cast_possible_truncation)]
+            fn month(&self) -> u8 {
+                Datelike::month(self) as u8
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn day(&self) -> u8 {
+                Datelike::day(self) as u8
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn hour(&self) -> u8 {
+                Timelike::hour(self) as u8
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn minute(&self) -> u8 {
+                Timelike::minute(self) as u8
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn second(&self) -> u8 {
+                let leap_second = Timelike::nanosecond(self) >= NANOS_PER_SECOND;
+                Timelike::second(self) as u8 + u8::from(leap_second)
+            }
+
+            #[cfg(feature = "subsec")]
+            fn nanoseconds(&self) -> u32 {
+                Timelike::nanosecond(self) % NANOS_PER_SECOND
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn day_of_week(&self) -> u8 {
+                self.weekday().num_days_from_sunday() as u8
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn day_of_year(&self) -> u16 {
+                self.ordinal() as u16
+            }
+
+            $($time_zone_methods)*
+        }
+    };
+}
+
+// A time in UTC, with the `UTC` time zone name like Ruby.
+impl_time!(DateTime<Utc> {
+    fn to_int(&self) -> i64 {
+        self.timestamp()
+    }
+
+    fn is_utc(&self) -> bool {
+        true
+    }
+
+    fn utc_offset(&self) -> i32 {
+        0
+    }
+
+    #[allow(clippy::unnecessary_literal_bound)]
+    fn time_zone(&self) -> &str {
+        "UTC"
+    }
+});
+
+// A time with a fixed offset has no time zone name in Ruby, and is not in UTC
+// even if the offset is zero.
+impl_time!(DateTime<FixedOffset> {
+    fn to_int(&self) -> i64 {
+        self.timestamp()
+    }
+
+    fn is_utc(&self) -> bool {
+        false
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.offset().local_minus_utc()
+    }
+
+    #[allow(clippy::unnecessary_literal_bound)]
+    fn time_zone(&self) -> &str {
+        ""
+    }
+});
+
+// A naive time is formatted as a time in UTC. The time zone directives can be
+// rejected with `NaivePolicy::Reject`.
+impl_time!(NaiveDateTime {
+    fn to_int(&self) -> i64 {
+        self.and_utc().timestamp()
+    }
+
+    fn is_utc(&self) -> bool {
+        true
+    }
+
+    fn utc_offset(&self) -> i32 {
+        0
+    }
+
+    #[allow(clippy::unnecessary_literal_bound)]
+    fn time_zone(&self) -> &str {
+        "UTC"
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use ::chrono::{NaiveDate, TimeZone};
+
+    use super::*;
+    use crate::buffered::strftime;
+
+    #[test]
+    fn test_chrono_utc() {
+        let time = Utc.with_ymd_and_hms(2001, 9, 9, 1, 46, 40).unwrap();
+
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            strftime(&time, b"%a %F %T %j %z %Z %s", &mut buf).unwrap(),
+            b"Sun 2001-09-09 01:46:40 252 +0000 UTC 1000000000"
+        );
+    }
+
+    #[test]
+    fn test_chrono_fixed_offset() {
+        let offset = FixedOffset::west_opt(5 * 3600 + 30 * 60).unwrap();
+        let time = offset.with_ymd_and_hms(-1, 12, 31, 23, 59, 59).unwrap();
+
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            strftime(&time, b"%u %F %T %:z [%Z]", &mut buf).unwrap(),
+            b"5 -0001-12-31 23:59:59 -05:30 []"
+        );
+
+        let time = time.with_timezone(&FixedOffset::east_opt(0).unwrap());
+        assert!(!time.is_utc());
+        assert_eq!(Time::to_int(&time), time.timestamp());
+    }
+
+    #[test]
+    fn test_chrono_naive() {
+        let time = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_123_456_789)
+            .unwrap();
+
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            strftime(&time, b"%F %T %Z", &mut buf).unwrap(),
+            b"2016-12-31 23:59:60 UTC"
+        );
+        #[cfg(feature = "subsec")]
+        assert_eq!(strftime(&time, b"%N", &mut buf).unwrap(), b"123456789");
+
+        let options = crate::FormatOptions::new().with_naive_policy(crate::NaivePolicy::Reject);
+        let result = crate::buffered::strftime_with_options(&time, b"%Z", &mut buf, &options);
+        assert!(matches!(result, Err(crate::Error::MissingTimeZone)));
+    }
+}
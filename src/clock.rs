@@ -0,0 +1,167 @@
+//! Clock sources for formatting the current time.
+//!
+//! The [`Now`] trait abstracts over the source of the current time, so that
+//! `no_std` firmware can plug in a real-time clock or a HAL timer, while
+//! [`SystemClock`] uses the system time when `std` is available.
+//!
+//! # Examples
+//!
+//! ```
+//! use strftime::clock::{format_now, Now};
+//! use strftime::DateTime;
+//!
+//! /// A clock reading a real-time clock peripheral.
+//! struct Rtc;
+//!
+//! impl Now for Rtc {
+//!     type Time = DateTime;
+//!
+//!     fn now(&self) -> DateTime {
+//!         // Not shown: read the number of seconds since the Unix epoch
+//!         let seconds = 1_000_000_000;
+//!         DateTime::from_timestamp(seconds, 0).unwrap()
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), strftime::Error> {
+//! let mut buf = [0u8; 32];
+//! assert_eq!(format_now(&Rtc, b"%F %T", &mut buf)?, b"2001-09-09 01:46:40");
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "std")]
+use crate::DateTime;
+use crate::{buffered, Error, Time};
+
+/// A source of the current time.
+pub trait Now {
+    /// _Time_ implementation returned by the clock.
+    type Time: Time;
+
+    /// Returns the current time.
+    fn now(&self) -> Self::Time;
+}
+
+impl<T: Now + ?Sized> Now for &T {
+    type Time = T::Time;
+
+    fn now(&self) -> Self::Time {
+        (**self).now()
+    }
+}
+
+/// Format the current time of a clock with the specified format byte string,
+/// writing in the provided buffer and returning the written subslice.
+///
+/// See the [crate-level documentation](crate) for a complete description of
+/// possible format specifiers.
+///
+/// # Allocations
+///
+/// This function makes no heap allocations and is usable in a `no_std`
+/// context.
+///
+/// # Errors
+///
+/// Can produce an [`Error`] when the formatting fails.
+pub fn format_now<'a>(
+    clock: &impl Now,
+    format: &[u8],
+    buf: &'a mut [u8],
+) -> Result<&'a mut [u8], Error> {
+    buffered::strftime(&clock.now(), format, buf)
+}
+
+/// A clock returning the current system time in UTC.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::clock::{Now, SystemClock};
+/// use strftime::Time;
+///
+/// let now = SystemClock.now();
+/// assert!(now.year() >= 2022);
+/// assert!(now.is_utc());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Now for SystemClock {
+    type Time = DateTime;
+
+    /// Returns the current system time in UTC.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system time is out of range of a [`DateTime`].
+    fn now(&self) -> DateTime {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let (seconds, nanoseconds) = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => (
+                i64::try_from(duration.as_secs()).ok(),
+                duration.subsec_nanos(),
+            ),
+            // Negative durations are rounded towards negative infinity
+            Err(err) => {
+                let duration = err.duration();
+                let seconds = i64::try_from(duration.as_secs()).ok();
+                match duration.subsec_nanos() {
+                    0 => (seconds.map(|seconds| -seconds), 0),
+                    nanoseconds => (
+                        seconds.map(|seconds| -seconds - 1),
+                        1_000_000_000 - nanoseconds,
+                    ),
+                }
+            }
+        };
+
+        seconds
+            .and_then(|seconds| DateTime::from_timestamp(seconds, nanoseconds))
+            .expect("system time should be in range of a DateTime")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DateTime;
+
+    struct FixedClock(i64);
+
+    impl Now for FixedClock {
+        type Time = DateTime;
+
+        fn now(&self) -> DateTime {
+            DateTime::from_timestamp(self.0, 0).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_format_now() {
+        let clock = FixedClock(86_400);
+
+        let mut buf = [0u8; 16];
+        assert_eq!(format_now(&clock, b"%F", &mut buf).unwrap(), b"1970-01-02");
+        assert_eq!(format_now(&&clock, b"%s", &mut buf).unwrap(), b"86400");
+
+        let mut buf = [0u8; 4];
+        assert!(matches!(
+            format_now(&clock, b"%F", &mut buf),
+            Err(Error::WriteZero)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_system_clock() {
+        let now = SystemClock.now();
+        assert!(now.year() >= 2022);
+        assert!(now.is_utc());
+    }
+}
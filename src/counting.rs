@@ -0,0 +1,101 @@
+//! A writer discarding its output, counting the written bytes.
+
+use core::fmt;
+
+/// A writer discarding the formatted output, counting the written bytes.
+///
+/// `CountingWriter` implements [`core::fmt::Write`], and [`std::io::Write`]
+/// when the `std` feature is enabled, so it can be passed to any `strftime`
+/// function to learn the exact length of the formatted string before
+/// allocating a buffer. See also [`count::strftime_len`].
+///
+/// [`count::strftime_len`]: crate::count::strftime_len
+///
+/// # Examples
+///
+/// ```
+/// use strftime::fmt::strftime;
+/// use strftime::{CountingWriter, DateTime};
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = DateTime::from_timestamp(0, 0).unwrap();
+///
+/// let mut counter = CountingWriter::new();
+/// strftime(&time, "%F %T", &mut counter)?;
+/// assert_eq!(counter.count(), 19);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CountingWriter {
+    /// Number of written bytes.
+    count: usize,
+}
+
+impl CountingWriter {
+    /// Construct a new `CountingWriter` with a count of zero.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    /// Returns the number of written bytes.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Resets the number of written bytes to zero.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+impl fmt::Write for CountingWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.count = self.count.saturating_add(s.len());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::io::Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count = self.count.saturating_add(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DateTime;
+
+    #[test]
+    fn test_counting_writer_fmt() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+
+        let mut counter = CountingWriter::new();
+        crate::fmt::strftime(&time, "%F", &mut counter).unwrap();
+        crate::fmt::strftime(&time, " %c", &mut counter).unwrap();
+        assert_eq!(counter.count(), 35);
+
+        counter.reset();
+        assert_eq!(counter, CountingWriter::default());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_counting_writer_io() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+
+        let mut counter = CountingWriter::new();
+        crate::io::strftime(&time, b"\xff%Y", &mut counter).unwrap();
+        assert_eq!(counter.count(), 5);
+    }
+}
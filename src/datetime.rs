@@ -0,0 +1,268 @@
+//! A [`Time`] implementation computed from a Unix timestamp.
+
+// All the casted values are non-negative by construction.
+#![allow(clippy::cast_sign_loss)]
+
+use crate::calendar::{civil_from_days, day_of_week, day_of_year, SECONDS_PER_DAY};
+use crate::Time;
+
+/// A date and time with a fixed UTC offset, computed from a Unix timestamp in
+/// the proleptic Gregorian calendar.
+///
+/// This type implements [`Time`] and can be used with all the `strftime`
+/// functions in this crate.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::buffered::strftime;
+/// use strftime::DateTime;
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = DateTime::from_timestamp(1_000_000_000, 0).unwrap();
+///
+/// let mut buf = [0u8; 32];
+/// assert_eq!(strftime(&time, b"%F %T %Z", &mut buf)?, b"2001-09-09 01:46:40 UTC");
+///
+/// let time = DateTime::from_timestamp_with_offset(1_000_000_000, 0, 7200).unwrap();
+/// assert_eq!(strftime(&time, b"%F %T %z", &mut buf)?, b"2001-09-09 03:46:40 +0200");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    /// Year.
+    year: i32,
+    /// Month of the year in `1..=12`.
+    month: u8,
+    /// Day of the month in `1..=31`.
+    day: u8,
+    /// Hour of the day in `0..=23`.
+    hour: u8,
+    /// Minute of the hour in `0..=59`.
+    minute: u8,
+    /// Second of the minute in `0..=59`.
+    second: u8,
+    /// Number of nanoseconds in `0..=999_999_999`.
+    nanoseconds: u32,
+    /// Day of the week from Sunday in `0..=6`.
+    day_of_week: u8,
+    /// Day of the year in `1..=366`.
+    day_of_year: u16,
+    /// Number of seconds since `1970-01-01 00:00:00 UTC`.
+    timestamp: i64,
+    /// Whether the time zone is UTC.
+    is_utc: bool,
+    /// Offset in seconds from UTC.
+    utc_offset: i32,
+}
+
+impl DateTime {
+    /// Construct a new UTC `DateTime` from the number of seconds since
+    /// `1970-01-01 00:00:00 UTC` and a number of nanoseconds.
+    ///
+    /// Returns [`None`] if the number of nanoseconds is not in
+    /// `0..=999_999_999` or if the year is out of range of an [`i32`].
+    #[must_use]
+    pub const fn from_timestamp(seconds: i64, nanoseconds: u32) -> Option<Self> {
+        Self::new(seconds, nanoseconds, 0, true)
+    }
+
+    /// Construct a new `DateTime` from the number of seconds since
+    /// `1970-01-01 00:00:00 UTC`, a number of nanoseconds and a fixed offset
+    /// in seconds from UTC.
+    ///
+    /// The resulting time is never considered to be in UTC, even if the offset
+    /// is zero, matching Ruby's behavior for times with a fixed offset.
+    ///
+    /// Returns [`None`] if the number of nanoseconds is not in
+    /// `0..=999_999_999`, if the offset is not strictly between `-86400` and
+    /// `86400` or if the year is out of range of an [`i32`].
+    #[must_use]
+    pub const fn from_timestamp_with_offset(
+        seconds: i64,
+        nanoseconds: u32,
+        utc_offset: i32,
+    ) -> Option<Self> {
+        if utc_offset <= -SECONDS_PER_DAY as i32 || utc_offset >= SECONDS_PER_DAY as i32 {
+            return None;
+        }
+        Self::new(seconds, nanoseconds, utc_offset, false)
+    }
+
+    /// Construct a new `DateTime` with the same number of nanoseconds and
+    /// UTC offset, from another number of seconds since
+    /// `1970-01-01 00:00:00 UTC`.
+    pub(crate) const fn with_timestamp(&self, seconds: i64) -> Option<Self> {
+        Self::new(seconds, self.nanoseconds, self.utc_offset, self.is_utc)
+    }
+
+    /// Construct a new `DateTime`.
+    const fn new(seconds: i64, nanoseconds: u32, utc_offset: i32, is_utc: bool) -> Option<Self> {
+        if nanoseconds > 999_999_999 {
+            return None;
+        }
+
+        let local_seconds = match seconds.checked_add(utc_offset as i64) {
+            Some(local_seconds) => local_seconds,
+            None => return None,
+        };
+
+        let days = local_seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = local_seconds.rem_euclid(SECONDS_PER_DAY);
+
+        let (year, month, day) = civil_from_days(days);
+        if year < i32::MIN as i64 || year > i32::MAX as i64 {
+            return None;
+        }
+
+        Some(Self {
+            year: year as i32,
+            month,
+            day,
+            hour: (seconds_of_day / 3600) as u8,
+            minute: (seconds_of_day / 60 % 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+            nanoseconds,
+            day_of_week: day_of_week(days),
+            day_of_year: day_of_year(year, month, day),
+            timestamp: seconds,
+            is_utc,
+            utc_offset,
+        })
+    }
+}
+
+impl Time for DateTime {
+    fn year(&self) -> i32 {
+        self.year
+    }
+
+    fn month(&self) -> u8 {
+        self.month
+    }
+
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    fn second(&self) -> u8 {
+        self.second
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.nanoseconds
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.day_of_week
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.day_of_year
+    }
+
+    fn to_int(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn is_utc(&self) -> bool {
+        self.is_utc
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    fn time_zone(&self) -> &str {
+        if self.is_utc {
+            "UTC"
+        } else {
+            ""
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_timestamp() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+        assert_eq!(time.year(), 1970);
+        assert_eq!(time.month(), 1);
+        assert_eq!(time.day(), 1);
+        assert_eq!(time.hour(), 0);
+        assert_eq!(time.minute(), 0);
+        assert_eq!(time.second(), 0);
+        assert_eq!(time.nanoseconds(), 0);
+        assert_eq!(time.day_of_week(), 4);
+        assert_eq!(time.day_of_year(), 1);
+        assert_eq!(time.to_int(), 0);
+        assert!(time.is_utc());
+        assert_eq!(time.utc_offset(), 0);
+        assert_eq!(time.time_zone(), "UTC");
+
+        let time = DateTime::from_timestamp(-1, 999_999_999).unwrap();
+        assert_eq!(time.year(), 1969);
+        assert_eq!(time.month(), 12);
+        assert_eq!(time.day(), 31);
+        assert_eq!(time.hour(), 23);
+        assert_eq!(time.minute(), 59);
+        assert_eq!(time.second(), 59);
+        assert_eq!(time.nanoseconds(), 999_999_999);
+        assert_eq!(time.day_of_week(), 3);
+        assert_eq!(time.day_of_year(), 365);
+        assert_eq!(time.to_int(), -1);
+
+        let time = DateTime::from_timestamp(951_825_600, 0).unwrap();
+        assert_eq!(time.year(), 2000);
+        assert_eq!(time.month(), 2);
+        assert_eq!(time.day(), 29);
+        assert_eq!(time.hour(), 12);
+        assert_eq!(time.day_of_week(), 2);
+        assert_eq!(time.day_of_year(), 60);
+    }
+
+    #[test]
+    fn test_from_timestamp_with_offset() {
+        let time = DateTime::from_timestamp_with_offset(0, 0, -3600).unwrap();
+        assert_eq!(time.year(), 1969);
+        assert_eq!(time.month(), 12);
+        assert_eq!(time.day(), 31);
+        assert_eq!(time.hour(), 23);
+        assert_eq!(time.to_int(), 0);
+        assert!(!time.is_utc());
+        assert_eq!(time.utc_offset(), -3600);
+        assert_eq!(time.time_zone(), "");
+
+        let time = DateTime::from_timestamp_with_offset(0, 0, 0).unwrap();
+        assert!(!time.is_utc());
+
+        assert!(DateTime::from_timestamp_with_offset(0, 0, 86_399).is_some());
+        assert!(DateTime::from_timestamp_with_offset(0, 0, -86_399).is_some());
+        assert!(DateTime::from_timestamp_with_offset(0, 0, 86_400).is_none());
+        assert!(DateTime::from_timestamp_with_offset(0, 0, -86_400).is_none());
+        assert!(DateTime::from_timestamp_with_offset(i64::MAX, 0, 1).is_none());
+    }
+
+    #[test]
+    fn test_from_timestamp_out_of_range() {
+        assert!(DateTime::from_timestamp(0, 1_000_000_000).is_none());
+        assert!(DateTime::from_timestamp(i64::MAX, 0).is_none());
+        assert!(DateTime::from_timestamp(i64::MIN, 0).is_none());
+
+        let time = DateTime::from_timestamp(67_767_976_233_532_799, 0).unwrap();
+        assert_eq!(time.year(), i32::MAX);
+        assert!(DateTime::from_timestamp(67_767_976_233_532_800, 0).is_none());
+    }
+}
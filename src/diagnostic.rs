@@ -0,0 +1,266 @@
+//! Rendering of format errors for command line tools and logs.
+//!
+//! A [`Diagnostic`] prints an [`Error`] with the format string which caused
+//! it, a caret pointing at the offending directive and a short hint:
+//!
+//! ```text
+//! error: unterminated directive `%` at byte 6
+//!   |
+//!   | %Y-%m-%
+//!   |       ^ write %% for a literal percent
+//! ```
+//!
+//! The directive is located for unterminated directives, and for errors
+//! wrapped in an [`Error::Directive`] with
+//! [`FormatOptions::with_error_context`].
+//!
+//! [`FormatOptions::with_error_context`]: crate::FormatOptions::with_error_context
+
+use core::fmt;
+use core::ops::Range;
+use std::string::String;
+
+use crate::Error;
+
+/// A format error rendered with its format string.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::diagnostic::Diagnostic;
+/// use strftime::string::strftime_with_options;
+/// use strftime::{FormatOptions, Time};
+///
+/// // Not shown: create a time implementation with the year 1970
+/// // let time = ...;
+/// # include!("mock.rs.in");
+/// # fn main() {
+/// # let time = MockTime { year: 1970, ..Default::default() };
+/// assert_eq!(time.year(), 1970);
+///
+/// let format = "Date: %Y-%m-%d";
+/// let options = FormatOptions::new().with_error_context();
+/// let err = strftime_with_options(&time, format, &options).unwrap_err();
+///
+/// let diagnostic = Diagnostic::new(format, &err);
+/// assert_eq!(diagnostic.span(), Some(9..11));
+/// assert_eq!(
+///     diagnostic.to_string(),
+///     "error: invalid time\n  \
+///        |\n  \
+///        | Date: %Y-%m-%d\n  \
+///        |          ^^ a field of the time is out of range"
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a> {
+    /// Format string which caused the error.
+    format: &'a [u8],
+    /// Error returned when formatting.
+    error: &'a Error,
+}
+
+impl<'a> Diagnostic<'a> {
+    /// Construct a new `Diagnostic` for an error returned when formatting a
+    /// time with the provided format string.
+    #[must_use]
+    pub fn new<F: AsRef<[u8]> + ?Sized>(format: &'a F, error: &'a Error) -> Self {
+        Self {
+            format: format.as_ref(),
+            error,
+        }
+    }
+
+    /// Returns the byte range of the offending directive in the format
+    /// string, if it is known.
+    ///
+    /// The range is only known for an unterminated directive and for an
+    /// [`Error::Directive`] of the format string. It is not known for an
+    /// error in the format string of an alias or a named format.
+    #[must_use]
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self.error {
+            Error::UnterminatedDirective {
+                position, snippet, ..
+            } => {
+                // The unterminated directive ends the format string
+                let text = self.format.get(*position..)?;
+                let matches = if snippet.is_truncated() {
+                    text.starts_with(snippet.as_bytes())
+                } else {
+                    text == snippet.as_bytes()
+                };
+                matches.then(|| *position..self.format.len())
+            }
+            Error::Directive {
+                directive,
+                position,
+                ..
+            } => {
+                let end = position.checked_add(directive.len())?;
+                match self.format.get(*position..end) {
+                    Some(text) if text == directive.as_bytes() => Some(*position..end),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the error which is not wrapped in an [`Error::Directive`].
+    fn source_error(&self) -> &'a Error {
+        match self.error {
+            Error::Directive { source, .. } => source,
+            error => error,
+        }
+    }
+
+    /// Returns a short hint to fix the error, if any.
+    fn hint(&self) -> Option<&'static str> {
+        match self.source_error() {
+            Error::UnterminatedDirective { .. } => Some("write %% for a literal percent"),
+            Error::InvalidTime => Some("a field of the time is out of range"),
+            Error::InvalidUtcOffset { .. } => {
+                Some("the UTC offset is rejected by the offset policy")
+            }
+            Error::MissingTimeZone => {
+                Some("the time has no time zone; configure a naive policy to format it")
+            }
+            Error::UnknownDirective => Some("write %% for a literal percent"),
+            #[cfg(feature = "std")]
+            Error::NonUnicodeFormatString => {
+                Some("the format string must be valid Unicode on this platform")
+            }
+            Error::WidthTooLarge { .. } => Some("reduce the width of the directive"),
+            Error::MissingWidthArgument => Some("add a width argument for each `*` width"),
+            Error::FormattedStringTooLarge { .. } => Some("reduce the width of the directives"),
+            Error::TooManyDirectives { .. } => {
+                Some("reduce the number of directives or raise the limit")
+            }
+            Error::WriteZero => Some("the buffer is too small for the formatted string"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error: {}\n  |\n  | ", self.source_error())?;
+
+        // Replace control characters, so the caret stays aligned
+        let format = String::from_utf8_lossy(self.format);
+        for c in format.chars() {
+            let c = if c.is_control() { ' ' } else { c };
+            fmt::Write::write_char(f, c)?;
+        }
+
+        match (self.span(), self.hint()) {
+            (Some(span), hint) => {
+                let column = String::from_utf8_lossy(&self.format[..span.start])
+                    .chars()
+                    .count();
+                let width = String::from_utf8_lossy(&self.format[span]).chars().count();
+                write!(f, "\n  | {:column$}{:^<width$}", "", "")?;
+                if let Some(hint) = hint {
+                    write!(f, " {hint}")?;
+                }
+                Ok(())
+            }
+            (None, Some(hint)) => write!(f, "\n  = hint: {hint}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::boxed::Box;
+    use std::string::ToString;
+
+    use super::*;
+    use crate::Snippet;
+
+    #[test]
+    fn test_unterminated_directive() {
+        let err = Error::UnterminatedDirective {
+            position: 6,
+            snippet: Snippet::new(b"%"),
+        };
+
+        let diagnostic = Diagnostic::new("%Y-%m-%", &err);
+        assert_eq!(diagnostic.span(), Some(6..7));
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: unterminated directive `%` at byte 6\n  |\n  | %Y-%m-%\n  |       ^ \
+             write %% for a literal percent"
+        );
+
+        let err = Error::UnterminatedDirective {
+            position: 3,
+            snippet: Snippet::new(b"%-10"),
+        };
+
+        let diagnostic = Diagnostic::new("\u{e9}\t%-10", &err);
+        assert_eq!(diagnostic.span(), Some(3..7));
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: unterminated directive `%-10` at byte 3\n  |\n  | \u{e9} %-10\n  |   ^^^^ \
+             write %% for a literal percent"
+        );
+
+        // The directive is not in the format string, like for an alias
+        assert_eq!(Diagnostic::new("%Y", &err).span(), None);
+        assert_eq!(Diagnostic::new("%-10Y", &err).span(), None);
+
+        // A truncated snippet is a prefix of the directive
+        let format = "%Y %00000000000000000000";
+        let err = Error::UnterminatedDirective {
+            position: 3,
+            snippet: Snippet::new(&format.as_bytes()[3..]),
+        };
+        assert_eq!(Diagnostic::new(format, &err).span(), Some(3..24));
+    }
+
+    #[test]
+    fn test_directive() {
+        let err = Error::Directive {
+            directive: "%-d".to_string(),
+            position: 3,
+            source: Box::new(Error::WriteZero),
+        };
+
+        let diagnostic = Diagnostic::new("%m/%-d", &err);
+        assert_eq!(diagnostic.span(), Some(3..6));
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: failed to write the whole buffer\n  |\n  | %m/%-d\n  |    ^^^ \
+             the buffer is too small for the formatted string"
+        );
+
+        // The directive is not in the format string, like for an alias
+        let diagnostic = Diagnostic::new("%m/%q", &err);
+        assert_eq!(diagnostic.span(), None);
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: failed to write the whole buffer\n  |\n  | %m/%q\n  \
+             = hint: the buffer is too small for the formatted string"
+        );
+
+        let err = Error::Directive {
+            directive: "%d".to_string(),
+            position: usize::MAX,
+            source: Box::new(Error::InvalidTime),
+        };
+        assert_eq!(Diagnostic::new("%d", &err).span(), None);
+    }
+
+    #[test]
+    fn test_without_span() {
+        let err = Error::FmtError(fmt::Error);
+        assert_eq!(
+            Diagnostic::new("%Y", &err).to_string(),
+            "error: formatter error\n  |\n  | %Y"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! An extension trait adding `strftime` methods to [`Time`] implementations.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::{Error, Time};
+
+/// Extension methods formatting a [`Time`] implementation.
+///
+/// This trait is implemented for all the [`Time`] implementations, so the
+/// `strftime` functions can be called with the method syntax at call sites.
+///
+/// See the [crate-level documentation](crate) for a complete description of
+/// possible format specifiers.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::{Time, TimeExt};
+///
+/// // Not shown: create a time implementation with the year 1970
+/// // let time = ...;
+/// # include!("mock.rs.in");
+/// # fn main() -> Result<(), strftime::Error> {
+/// # let time = MockTime { year: 1970, month: 1, day: 1, ..Default::default() };
+/// assert_eq!(time.year(), 1970);
+///
+/// let mut buf = [0u8; 8];
+/// assert_eq!(time.strftime_into(&mut buf, b"%Y")?, b"1970");
+///
+/// # #[cfg(feature = "alloc")]
+/// assert_eq!(time.strftime("%F %T")?, "1970-01-01 00:00:00");
+/// # Ok(())
+/// # }
+/// ```
+pub trait TimeExt: Time {
+    /// Format this _time_ with the specified UTF-8 format string, writing to
+    /// a newly allocated [`String`].
+    ///
+    /// This method is equivalent to [`string::strftime`].
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    ///
+    /// [`string::strftime`]: crate::string::strftime
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn strftime(&self, format: &str) -> Result<String, Error>;
+
+    /// Format this _time_ with the specified format byte string, writing in
+    /// the provided buffer and returning the written subslice.
+    ///
+    /// This method is equivalent to [`buffered::strftime`], and makes no heap
+    /// allocations.
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    ///
+    /// [`buffered::strftime`]: crate::buffered::strftime
+    fn strftime_into<'a>(&self, buf: &'a mut [u8], format: &[u8]) -> Result<&'a mut [u8], Error>;
+}
+
+impl<T: Time + ?Sized> TimeExt for T {
+    #[cfg(feature = "alloc")]
+    fn strftime(&self, format: &str) -> Result<String, Error> {
+        crate::string::strftime(self, format)
+    }
+
+    fn strftime_into<'a>(&self, buf: &'a mut [u8], format: &[u8]) -> Result<&'a mut [u8], Error> {
+        crate::buffered::strftime(self, format, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DateTime;
+
+    #[test]
+    fn test_time_ext() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+
+        let mut buf = [0u8; 10];
+        assert_eq!(time.strftime_into(&mut buf, b"%F").unwrap(), b"1970-01-01");
+        assert!(matches!(
+            time.strftime_into(&mut buf, b"%F %T"),
+            Err(Error::WriteZero)
+        ));
+
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(time.strftime("%F %T").unwrap(), "1970-01-01 00:00:00");
+            assert!(matches!(
+                time.strftime("%Y %"),
+                Err(Error::UnterminatedDirective { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Exact size of a formatted string, computed without formatting it.
+
+use super::check::CheckedTime;
+use super::pieces::{Item, Pieces};
+use super::{year_width, CountWrite, Flags, Padding, Spec, TimeFormatter, DAYS, MONTHS};
+use crate::{Dialect, Error, Time};
+
+/// Returns the exact length of a formatted string.
+///
+/// The length of the directives with no flags, width and padding, like `%F`
+/// or `%H`, is computed arithmetically from the fields of the _time_. The
+/// formatter is run without output if the format string has another
+/// directive, or if the formatting would fail.
+pub(crate) fn required_capacity(
+    time: &(impl Time + ?Sized),
+    format: &[u8],
+) -> Result<usize, Error> {
+    if let Some(len) = computed_len(time, format) {
+        return Ok(len);
+    }
+
+    let mut counter = CountWrite::default();
+    TimeFormatter::new(time, format).fmt(&mut counter)?;
+    Ok(counter.count())
+}
+
+/// Computes the length of a formatted string, returning `None` if the format
+/// string has a directive whose length is not computed, or if the formatting
+/// would fail.
+fn computed_len(time: &(impl CheckedTime + ?Sized), format: &[u8]) -> Option<usize> {
+    let mut len = 0_usize;
+
+    for item in Pieces::new(format, Dialect::Ruby) {
+        len += match item.ok()? {
+            Item::Literal(literal) => literal.len(),
+            Item::Directive {
+                spec,
+                flags,
+                width,
+                padding,
+            } => {
+                let is_plain =
+                    flags == Flags::default() && width.is_none() && padding == Padding::Left;
+                if !is_plain {
+                    return None;
+                }
+                directive_len(spec, time)?
+            }
+        };
+    }
+
+    Some(len)
+}
+
+/// Returns the width of the string representation of a number.
+fn num_width(value: i64) -> usize {
+    let sign_width = usize::from(value < 0);
+    let mut n = 1;
+    let mut value = value.unsigned_abs();
+    while value >= 10 {
+        value /= 10;
+        n += 1;
+    }
+    sign_width + n
+}
+
+/// Returns the width of a zero-padded year, with at least 4 digits plus the
+/// possible negative sign.
+fn year4_width(year: i32) -> usize {
+    let default_width = if year < 0 { 5 } else { 4 };
+    year_width(year).max(default_width)
+}
+
+/// Computes the length of a directive with no flags, width and padding,
+/// returning `None` if it is not computed or if the formatting would fail.
+fn directive_len(spec: Spec, time: &(impl CheckedTime + ?Sized)) -> Option<usize> {
+    let len = match spec {
+        Spec::Year4Digits => year4_width(time.year()),
+        Spec::YearDiv100 => num_width(time.year().div_euclid(100).into()).max(2),
+        Spec::YearRem100 => 2,
+        Spec::Month => time.month().ok().and(Some(2))?,
+        Spec::MonthNameAbbr => time.month().ok().and(Some(3))?,
+        Spec::MonthName => MONTHS[usize::from(time.month().ok()? - 1)].len(),
+        Spec::MonthDayZero | Spec::MonthDaySpace => time.day().ok().and(Some(2))?,
+        Spec::YearDay => time.day_of_year().ok().and(Some(3))?,
+        Spec::Hour24hZero
+        | Spec::Hour24hSpace
+        | Spec::Hour12hZero
+        | Spec::Hour12hSpace
+        | Spec::MeridianLower
+        | Spec::MeridianUpper => time.hour().ok().and(Some(2))?,
+        Spec::Minute => time.minute().ok().and(Some(2))?,
+        Spec::Second => time.second().ok().and(Some(2))?,
+        #[cfg(feature = "subsec")]
+        Spec::MilliSecond => time.nanoseconds().ok().and(Some(3))?,
+        #[cfg(feature = "subsec")]
+        Spec::FractionalSecond => time.nanoseconds().ok().and(Some(9))?,
+        Spec::TimeZoneName => time.time_zone().ok()?.len(),
+        Spec::WeekDayName => DAYS[usize::from(time.day_of_week().ok()?)].len(),
+        Spec::WeekDayNameAbbr => time.day_of_week().ok().and(Some(3))?,
+        Spec::WeekDayFrom1 | Spec::WeekDayFrom0 => time.day_of_week().ok().and(Some(1))?,
+        Spec::WeekNumberIso8601 | Spec::WeekNumberFromSunday | Spec::WeekNumberFromMonday => {
+            time.day_of_week().ok()?;
+            time.day_of_year().ok()?;
+            2
+        }
+        Spec::SecondsSinceEpoch => num_width(time.to_int()),
+        Spec::Newline | Spec::Tabulation | Spec::Percent => 1,
+        Spec::CombinationDateTime => {
+            time.day_of_week().ok()?;
+            time.month().ok()?;
+            time.day().ok()?;
+            time.hour().ok()?;
+            time.minute().ok()?;
+            time.second().ok()?;
+            "www mmm dd HH:MM:SS ".len() + year4_width(time.year())
+        }
+        Spec::CombinationDate => {
+            time.month().ok()?;
+            time.day().ok()?;
+            "mm/dd/yy".len()
+        }
+        Spec::CombinationIso8601 => {
+            time.month().ok()?;
+            time.day().ok()?;
+            year4_width(time.year()) + "-mm-dd".len()
+        }
+        Spec::CombinationTime12h | Spec::CombinationHourMinute24h | Spec::CombinationTime24h => {
+            time.hour().ok()?;
+            time.minute().ok()?;
+            match spec {
+                Spec::CombinationTime12h => {
+                    time.second().ok()?;
+                    "HH:MM:SS PM".len()
+                }
+                Spec::CombinationHourMinute24h => "HH:MM".len(),
+                _ => {
+                    time.second().ok()?;
+                    "HH:MM:SS".len()
+                }
+            }
+        }
+        _ => return None,
+    };
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    include!("../mock.rs.in");
+
+    #[test]
+    fn test_num_width() {
+        assert_eq!(num_width(0), 1);
+        assert_eq!(num_width(9), 1);
+        assert_eq!(num_width(10), 2);
+        assert_eq!(num_width(-1), 2);
+        assert_eq!(num_width(i64::MIN), 20);
+        assert_eq!(num_width(i64::MAX), 19);
+    }
+
+    #[test]
+    fn test_required_capacity() {
+        let formats: &[&[u8]] = &[
+            b"",
+            b"literal \xff",
+            b"%Y %C %y %m %B %b %h %d %e %j",
+            b"%H %k %I %l %P %p %M %S",
+            #[cfg(feature = "subsec")]
+            b"%L %N %3N",
+            b"%Z %A %a %u %w %U %W %V %s %n%t%%",
+            b"%c|%D|%x|%F|%r|%R|%T|%X",
+            b"%v %G %g %z %:z %10Y %-m %^B %_5d",
+            b"%q %E %O %:",
+        ];
+        let times = [
+            MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 0, "UTC"),
+            MockTime::new(
+                -1,
+                9,
+                30,
+                12,
+                5,
+                60,
+                1,
+                0,
+                273,
+                -62_000_000_000,
+                false,
+                3600,
+                "",
+            ),
+            MockTime::new(
+                -12_345,
+                5,
+                7,
+                23,
+                59,
+                59,
+                999_999_999,
+                3,
+                127,
+                i64::MIN,
+                false,
+                -45_000,
+                "CEST",
+            ),
+            MockTime::new(
+                i32::MAX,
+                12,
+                31,
+                11,
+                1,
+                2,
+                3,
+                6,
+                366,
+                i64::MAX,
+                false,
+                0,
+                "Pacific",
+            ),
+        ];
+
+        for time in &times {
+            for &format in formats {
+                let expected = crate::count::strftime_len(time, format).unwrap();
+                assert_eq!(
+                    required_capacity(time, format).unwrap(),
+                    expected,
+                    "{format:?}"
+                );
+            }
+        }
+
+        let time = &times[0];
+        assert!(computed_len(time, b"%c %F %T %s %Z").is_some());
+        assert!(computed_len(time, b"%10Y").is_none());
+        assert!(computed_len(time, b"%z").is_none());
+    }
+
+    #[test]
+    fn test_required_capacity_error() {
+        let time = MockTime {
+            month: 13,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            required_capacity(&time, b"%B"),
+            Err(Error::InvalidTime)
+        ));
+        assert!(matches!(
+            required_capacity(&time, b"%Y %"),
+            Err(Error::UnterminatedDirective { .. })
+        ));
+        assert_eq!(required_capacity(&time, b"%Y").unwrap(), 4);
+    }
+}
@@ -1,6 +1,6 @@
 //! Checks for a time implementation.
 
-use crate::{Error, Time};
+use crate::{Error, OffsetPolicy, Time, MAX_UTC_OFFSET};
 
 /// Wrapper trait for validating a time implementation.
 pub(crate) trait CheckedTime {
@@ -17,6 +17,7 @@ pub(crate) trait CheckedTime {
     /// Checks if the second of the minute is in `0..=60`.
     fn second(&self) -> Result<u8, Error>;
     /// Checks if the number of nanoseconds is in `0..=999_999_999`.
+    #[cfg(feature = "subsec")]
     fn nanoseconds(&self) -> Result<u32, Error>;
     /// Checks if the day of the week is in `0..=6`.
     fn day_of_week(&self) -> Result<u8, Error>;
@@ -26,13 +27,13 @@ pub(crate) trait CheckedTime {
     fn to_int(&self) -> i64;
     /// No checks.
     fn is_utc(&self) -> bool;
-    /// No checks.
-    fn utc_offset(&self) -> i32;
+    /// Checks the UTC offset against the [`OffsetPolicy`], if any.
+    fn utc_offset(&self) -> Result<i32, Error>;
     /// Checks if the name of the time zone is valid ASCII.
     fn time_zone(&self) -> Result<&str, Error>;
 }
 
-impl<T: Time> CheckedTime for T {
+impl<T: Time + ?Sized> CheckedTime for T {
     fn year(&self) -> i32 {
         self.year()
     }
@@ -72,6 +73,7 @@ impl<T: Time> CheckedTime for T {
         }
     }
 
+    #[cfg(feature = "subsec")]
     fn nanoseconds(&self) -> Result<u32, Error> {
         match self.nanoseconds() {
             nanoseconds @ 0..=999_999_999 => Ok(nanoseconds),
@@ -101,8 +103,8 @@ impl<T: Time> CheckedTime for T {
         self.is_utc()
     }
 
-    fn utc_offset(&self) -> i32 {
-        self.utc_offset()
+    fn utc_offset(&self) -> Result<i32, Error> {
+        Ok(self.utc_offset())
     }
 
     fn time_zone(&self) -> Result<&str, Error> {
@@ -113,6 +115,89 @@ impl<T: Time> CheckedTime for T {
     }
 }
 
+/// Time implementation wrapper applying an [`OffsetPolicy`] to the UTC offset.
+pub(crate) struct OffsetCheckedTime<'a, T: ?Sized> {
+    /// Wrapped time implementation.
+    inner: &'a T,
+    /// Policy for invalid UTC offsets.
+    policy: OffsetPolicy,
+}
+
+impl<'a, T: CheckedTime + ?Sized> OffsetCheckedTime<'a, T> {
+    /// Construct a new `OffsetCheckedTime` wrapper.
+    pub(crate) const fn new(inner: &'a T, policy: OffsetPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl<T: CheckedTime + ?Sized> CheckedTime for OffsetCheckedTime<'_, T> {
+    fn year(&self) -> i32 {
+        self.inner.year()
+    }
+
+    fn month(&self) -> Result<u8, Error> {
+        self.inner.month()
+    }
+
+    fn day(&self) -> Result<u8, Error> {
+        self.inner.day()
+    }
+
+    fn hour(&self) -> Result<u8, Error> {
+        self.inner.hour()
+    }
+
+    fn minute(&self) -> Result<u8, Error> {
+        self.inner.minute()
+    }
+
+    fn second(&self) -> Result<u8, Error> {
+        self.inner.second()
+    }
+
+    #[cfg(feature = "subsec")]
+    fn nanoseconds(&self) -> Result<u32, Error> {
+        self.inner.nanoseconds()
+    }
+
+    fn day_of_week(&self) -> Result<u8, Error> {
+        self.inner.day_of_week()
+    }
+
+    fn day_of_year(&self) -> Result<u16, Error> {
+        self.inner.day_of_year()
+    }
+
+    fn to_int(&self) -> i64 {
+        self.inner.to_int()
+    }
+
+    fn is_utc(&self) -> bool {
+        self.inner.is_utc()
+    }
+
+    fn utc_offset(&self) -> Result<i32, Error> {
+        let utc_offset = self.inner.utc_offset()?;
+        let is_utc = self.inner.is_utc();
+
+        let is_valid = (-MAX_UTC_OFFSET..=MAX_UTC_OFFSET).contains(&utc_offset)
+            && !(is_utc && utc_offset != 0);
+
+        match self.policy {
+            OffsetPolicy::Reject if !is_valid => {
+                Err(Error::InvalidUtcOffset { utc_offset, is_utc })
+            }
+            OffsetPolicy::Clamp if is_utc => Ok(0),
+            OffsetPolicy::Clamp => Ok(utc_offset.clamp(-MAX_UTC_OFFSET, MAX_UTC_OFFSET)),
+            _ => Ok(utc_offset),
+        }
+    }
+
+    fn time_zone(&self) -> Result<&str, Error> {
+        self.inner.time_zone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +225,7 @@ mod tests {
         check(true, &CheckedTime::hour(&times[0]));
         check(true, &CheckedTime::minute(&times[0]));
         check(true, &CheckedTime::second(&times[0]));
+        #[cfg(feature = "subsec")]
         check(true, &CheckedTime::nanoseconds(&times[0]));
         check(true, &CheckedTime::day_of_week(&times[0]));
         check(true, &CheckedTime::day_of_year(&times[0]));
@@ -150,9 +236,46 @@ mod tests {
         check(false, &CheckedTime::hour(&times[1]));
         check(false, &CheckedTime::minute(&times[1]));
         check(false, &CheckedTime::second(&times[1]));
+        #[cfg(feature = "subsec")]
         check(false, &CheckedTime::nanoseconds(&times[1]));
         check(false, &CheckedTime::day_of_week(&times[1]));
         check(false, &CheckedTime::day_of_year(&times[1]));
         check(false, &CheckedTime::time_zone(&times[1]));
     }
+
+    #[test]
+    fn test_offset_checked_time() {
+        #[rustfmt::skip]
+        let times = [
+            MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, MAX_UTC_OFFSET, ""),
+            MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, -MAX_UTC_OFFSET - 1, ""),
+            MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 3600, "UTC"),
+        ];
+
+        let offset = |time, policy| OffsetCheckedTime::new(time, policy).utc_offset();
+
+        assert_eq!(offset(&times[0], OffsetPolicy::Allow).unwrap(), 93_600);
+        assert_eq!(offset(&times[1], OffsetPolicy::Allow).unwrap(), -93_601);
+        assert_eq!(offset(&times[2], OffsetPolicy::Allow).unwrap(), 3600);
+
+        assert_eq!(offset(&times[0], OffsetPolicy::Reject).unwrap(), 93_600);
+        assert!(matches!(
+            offset(&times[1], OffsetPolicy::Reject),
+            Err(Error::InvalidUtcOffset {
+                utc_offset: -93_601,
+                is_utc: false
+            })
+        ));
+        assert!(matches!(
+            offset(&times[2], OffsetPolicy::Reject),
+            Err(Error::InvalidUtcOffset {
+                utc_offset: 3600,
+                is_utc: true
+            })
+        ));
+
+        assert_eq!(offset(&times[0], OffsetPolicy::Clamp).unwrap(), 93_600);
+        assert_eq!(offset(&times[1], OffsetPolicy::Clamp).unwrap(), -93_600);
+        assert_eq!(offset(&times[2], OffsetPolicy::Clamp).unwrap(), 0);
+    }
 }
@@ -0,0 +1,1908 @@
+//! Format strings parsed once and formatted many times.
+
+use alloc::borrow::{Borrow, Cow};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use super::strptime::NUMERIC_SPECS;
+use super::utils::{Cursor, SizeLimiter};
+use super::write::{FmtWrite, Write};
+use super::{parse_spec, Flag, Flags, Padding, Piece, Spec, TimeFormatter};
+use crate::{Dialect, Error, FormatOptions, Snippet, Time};
+
+/// Item of a parsed format string.
+///
+/// This type is public for the expansion of the `strftime_format!` macro.
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Item(Kind);
+
+/// Kind of an item of a parsed format string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Kind {
+    /// Literal text, including the invalid directives.
+    Literal(Cow<'static, [u8]>),
+    /// Formatting directive.
+    Directive(Piece),
+}
+
+impl Item {
+    /// Construct a literal item, for the expansion of the `strftime_format!`
+    /// macro.
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn __literal(text: &'static [u8]) -> Self {
+        Self(Kind::Literal(Cow::Borrowed(text)))
+    }
+
+    /// Construct a directive item, for the expansion of the
+    /// `strftime_format!` macro.
+    ///
+    /// The flags are the bits of the [`Flag`](super::Flag) values.
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn __directive(
+        spec: Spec,
+        flags: u8,
+        width: Option<usize>,
+        padding: Padding,
+    ) -> Self {
+        Self(Kind::Directive(Piece {
+            width,
+            padding,
+            flags: Flags(flags),
+            spec,
+        }))
+    }
+}
+
+/// Methods of a [`Time`] implementation which are called when formatting a
+/// [`Format`], returned by [`Format::required_fields`].
+///
+/// Time implementations computing some fields lazily can skip the fields
+/// which are not required by the format string.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::Format;
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let fields = Format::parse("%Y-%m-%dT%H:%M:%S%z")?.required_fields();
+///
+/// assert!(fields.year() && fields.utc_offset());
+/// assert!(!fields.nanoseconds() && !fields.time_zone());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequiredFields(u16);
+
+impl RequiredFields {
+    /// [`Time::year`].
+    const YEAR: u16 = 1 << 0;
+    /// [`Time::month`].
+    const MONTH: u16 = 1 << 1;
+    /// [`Time::day`].
+    const DAY: u16 = 1 << 2;
+    /// [`Time::hour`].
+    const HOUR: u16 = 1 << 3;
+    /// [`Time::minute`].
+    const MINUTE: u16 = 1 << 4;
+    /// [`Time::second`].
+    const SECOND: u16 = 1 << 5;
+    /// [`Time::nanoseconds`].
+    const NANOSECONDS: u16 = 1 << 6;
+    /// [`Time::day_of_week`].
+    const DAY_OF_WEEK: u16 = 1 << 7;
+    /// [`Time::day_of_year`].
+    const DAY_OF_YEAR: u16 = 1 << 8;
+    /// [`Time::to_int`].
+    const TO_INT: u16 = 1 << 9;
+    /// [`Time::is_utc`].
+    const IS_UTC: u16 = 1 << 10;
+    /// [`Time::utc_offset`].
+    const UTC_OFFSET: u16 = 1 << 11;
+    /// [`Time::time_zone`].
+    const TIME_ZONE: u16 = 1 << 12;
+    /// ISO 8601 week-based year or week number.
+    const ISO_8601_WEEK: u16 = 1 << 13;
+
+    /// Returns the fields required by a formatting specifier.
+    const fn of(spec: Spec) -> u16 {
+        const TIME: u16 = RequiredFields::HOUR | RequiredFields::MINUTE | RequiredFields::SECOND;
+        const WEEK: u16 = RequiredFields::DAY_OF_WEEK | RequiredFields::DAY_OF_YEAR;
+
+        match spec {
+            Spec::Year4Digits | Spec::YearDiv100 | Spec::YearRem100 => Self::YEAR,
+            Spec::Month | Spec::MonthName | Spec::MonthNameAbbr => Self::MONTH,
+            Spec::MonthDayZero | Spec::MonthDaySpace => Self::DAY,
+            Spec::YearDay => Self::DAY_OF_YEAR,
+            Spec::Hour24hZero
+            | Spec::Hour24hSpace
+            | Spec::Hour12hZero
+            | Spec::Hour12hSpace
+            | Spec::MeridianLower
+            | Spec::MeridianUpper => Self::HOUR,
+            Spec::DayPeriod => TIME,
+            Spec::Minute => Self::MINUTE,
+            Spec::Second => Self::SECOND,
+            #[cfg(feature = "subsec")]
+            Spec::MilliSecond | Spec::FractionalSecond => Self::NANOSECONDS,
+            Spec::TimeZoneOffsetHourMinute
+            | Spec::TimeZoneOffsetHourMinuteColon
+            | Spec::TimeZoneOffsetHourMinuteSecondColon
+            | Spec::TimeZoneOffsetColonMinimal
+            | Spec::TimeZoneOffsetHourMinuteSecond => Self::UTC_OFFSET | Self::IS_UTC,
+            Spec::TimeZoneName => Self::TIME_ZONE,
+            Spec::WeekDayName | Spec::WeekDayNameAbbr | Spec::WeekDayFrom1 | Spec::WeekDayFrom0 => {
+                Self::DAY_OF_WEEK
+            }
+            Spec::YearIso8601 | Spec::YearIso8601Rem100 | Spec::WeekNumberIso8601 => {
+                Self::YEAR | WEEK | Self::ISO_8601_WEEK
+            }
+            Spec::WeekNumberFromSunday | Spec::WeekNumberFromMonday => WEEK,
+            Spec::SecondsSinceEpoch => Self::TO_INT,
+            #[cfg(feature = "subsec")]
+            Spec::SecondsSinceEpochFractional => Self::TO_INT | Self::NANOSECONDS,
+            Spec::Newline | Spec::Tabulation | Spec::Percent => 0,
+            Spec::CombinationDateTime => {
+                Self::YEAR | Self::MONTH | Self::DAY | Self::DAY_OF_WEEK | TIME
+            }
+            Spec::CombinationDate | Spec::CombinationIso8601 | Spec::CombinationVmsDate => {
+                Self::YEAR | Self::MONTH | Self::DAY
+            }
+            Spec::CombinationTime12h | Spec::CombinationTime24h => TIME,
+            Spec::CombinationHourMinute24h => Self::HOUR | Self::MINUTE,
+        }
+    }
+
+    /// Checks if a field is required.
+    const fn contains(self, field: u16) -> bool {
+        self.0 & field != 0
+    }
+
+    /// Returns `true` if [`Time::year`] is required.
+    #[must_use]
+    pub const fn year(self) -> bool {
+        self.contains(Self::YEAR)
+    }
+
+    /// Returns `true` if [`Time::month`] is required.
+    #[must_use]
+    pub const fn month(self) -> bool {
+        self.contains(Self::MONTH)
+    }
+
+    /// Returns `true` if [`Time::day`] is required.
+    #[must_use]
+    pub const fn day(self) -> bool {
+        self.contains(Self::DAY)
+    }
+
+    /// Returns `true` if [`Time::hour`] is required.
+    #[must_use]
+    pub const fn hour(self) -> bool {
+        self.contains(Self::HOUR)
+    }
+
+    /// Returns `true` if [`Time::minute`] is required.
+    #[must_use]
+    pub const fn minute(self) -> bool {
+        self.contains(Self::MINUTE)
+    }
+
+    /// Returns `true` if [`Time::second`] is required.
+    #[must_use]
+    pub const fn second(self) -> bool {
+        self.contains(Self::SECOND)
+    }
+
+    /// Returns `true` if [`Time::nanoseconds`] is required.
+    #[must_use]
+    pub const fn nanoseconds(self) -> bool {
+        self.contains(Self::NANOSECONDS)
+    }
+
+    /// Returns `true` if [`Time::day_of_week`] is required.
+    #[must_use]
+    pub const fn day_of_week(self) -> bool {
+        self.contains(Self::DAY_OF_WEEK)
+    }
+
+    /// Returns `true` if [`Time::day_of_year`] is required.
+    #[must_use]
+    pub const fn day_of_year(self) -> bool {
+        self.contains(Self::DAY_OF_YEAR)
+    }
+
+    /// Returns `true` if [`Time::to_int`] is required.
+    #[must_use]
+    pub const fn to_int(self) -> bool {
+        self.contains(Self::TO_INT)
+    }
+
+    /// Returns `true` if [`Time::is_utc`] is required.
+    #[must_use]
+    pub const fn is_utc(self) -> bool {
+        self.contains(Self::IS_UTC)
+    }
+
+    /// Returns `true` if [`Time::utc_offset`] is required.
+    #[must_use]
+    pub const fn utc_offset(self) -> bool {
+        self.contains(Self::UTC_OFFSET)
+    }
+
+    /// Returns `true` if [`Time::time_zone`] is required.
+    #[must_use]
+    pub const fn time_zone(self) -> bool {
+        self.contains(Self::TIME_ZONE)
+    }
+
+    /// Returns `true` if the ISO 8601 week-based year or week number is
+    /// formatted, which is computed from [`Time::year`],
+    /// [`Time::day_of_week`] and [`Time::day_of_year`].
+    #[must_use]
+    pub const fn iso_8601_week(self) -> bool {
+        self.contains(Self::ISO_8601_WEEK)
+    }
+
+    /// Returns `true` if no field is required.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A format string parsed once, which can be formatted many times without
+/// parsing it again.
+///
+/// The format string is parsed with the default options, so the directives
+/// are the ones of [`Dialect::Ruby`].
+///
+/// # Examples
+///
+/// ```
+/// use strftime::{DateTime, Format};
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let format = Format::parse("%Y-%m-%dT%H:%M:%S%z")?;
+///
+/// let mut buf = String::new();
+/// for seconds in [0, 86_400] {
+///     let time = DateTime::from_timestamp(seconds, 0).unwrap();
+///     format.fmt(&time, &mut buf)?;
+///     buf.push('\n');
+/// }
+/// assert_eq!(buf, "1970-01-01T00:00:00+0000\n1970-01-02T00:00:00+0000\n");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Two formats are equal if they have the same items, so the format strings
+/// `"%b"` and `"%h"` give equal formats.
+///
+/// With the **serde** feature, a `Format` is serialized as its format string,
+/// which is parsed again when deserialized.
+#[derive(Debug, Clone)]
+pub struct Format {
+    /// Items of the format string.
+    items: Cow<'static, [Item]>,
+    /// Length of the format string.
+    len: usize,
+}
+
+impl Format {
+    /// Parse a format string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnterminatedDirective`] if the format string is ended
+    /// by an unterminated directive.
+    pub fn parse<F: AsRef<[u8]> + ?Sized>(format: &F) -> Result<Self, Error> {
+        let format = format.as_ref();
+
+        let mut items = Vec::new();
+        let mut literal = Vec::new();
+
+        let mut cursor = Cursor::new(format);
+
+        loop {
+            literal.extend_from_slice(cursor.read_until(|&x| x == b'%'));
+
+            let remaining_before = cursor.remaining();
+
+            // Read the '%' character
+            if cursor.next().is_none() {
+                break;
+            }
+
+            let piece = parse_spec(&mut cursor, Dialect::Ruby).map_err(|_| {
+                Error::UnterminatedDirective {
+                    position: format.len() - remaining_before.len(),
+                    snippet: Snippet::new(remaining_before),
+                }
+            })?;
+
+            if let Some(piece) = piece {
+                if !literal.is_empty() {
+                    items.push(Item(Kind::Literal(Cow::Owned(literal.split_off(0)))));
+                }
+                items.push(Item(Kind::Directive(piece)));
+            } else {
+                // Invalid directives are written verbatim
+                let len = remaining_before.len() - cursor.remaining().len();
+                literal.extend_from_slice(&remaining_before[..len]);
+            }
+        }
+
+        if !literal.is_empty() {
+            items.push(Item(Kind::Literal(Cow::Owned(literal))));
+        }
+
+        Ok(Self {
+            items: Cow::Owned(items),
+            len: format.len(),
+        })
+    }
+
+    /// Returns a builder of a format from its directives and literal text.
+    ///
+    /// The built format has the same items as the format parsed from the
+    /// equivalent format string, without having to escape the literal text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::pieces::Padding;
+    /// use strftime::Format;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let format = Format::builder()
+    ///     .year4()
+    ///     .literal("-")
+    ///     .month()
+    ///     .literal("-")
+    ///     .day()
+    ///     .literal(" at 100%: ")
+    ///     .hour24()
+    ///     .padding(Padding::Spaces)
+    ///     .build()?;
+    ///
+    /// assert_eq!(format, Format::parse("%Y-%m-%d at 100%%: %_H")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> FormatBuilder {
+        FormatBuilder::default()
+    }
+
+    /// Returns the concatenation of this format and `other`, without parsing
+    /// their format strings again.
+    ///
+    /// The concatenated format is equal to the format parsed from the
+    /// concatenation of the format strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::Format;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let prefix = Format::parse("[%F ")?;
+    /// let suffix = Format::parse("%T]")?;
+    ///
+    /// assert_eq!(prefix.concat(&suffix), Format::parse("[%F %T]")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn concat(&self, other: &Self) -> Self {
+        let mut format = self.clone();
+        format.append(other);
+        format
+    }
+
+    /// Returns the concatenation of `formats`, separated by the literal text
+    /// `separator`, without parsing their format strings again.
+    ///
+    /// Like with [`FormatBuilder::literal`], the separator is copied verbatim
+    /// to the output, so a `'%'` character in the separator is not a
+    /// directive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::{DateTime, Format};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let fragments = [Format::parse("%F")?, Format::parse("%T")?, Format::parse("%z")?];
+    /// let format = Format::join(&fragments, " | ");
+    ///
+    /// assert_eq!(format, Format::parse("%F | %T | %z")?);
+    ///
+    /// let time = DateTime::from_timestamp(0, 0).unwrap();
+    /// let mut buf = [0u8; 32];
+    /// assert_eq!(format.fmt_buffered(&time, &mut buf)?, b"1970-01-01 | 00:00:00 | +0000");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn join<S: AsRef<[u8]> + ?Sized>(formats: &[Self], separator: &S) -> Self {
+        let separator = Self::from_literal(separator.as_ref());
+
+        let mut joined = Self::from_literal(b"");
+        for (index, format) in formats.iter().enumerate() {
+            if index > 0 {
+                joined.append(&separator);
+            }
+            joined.append(format);
+        }
+        joined
+    }
+
+    /// Append the items of `other`, merging adjacent literal text like when
+    /// parsing a format string.
+    fn append(&mut self, other: &Self) {
+        let items = self.items.to_mut();
+        for item in other.items.iter() {
+            if let (Some(Item(Kind::Literal(last))), Item(Kind::Literal(literal))) =
+                (items.last_mut(), item)
+            {
+                last.to_mut().extend_from_slice(literal);
+            } else {
+                items.push(item.clone());
+            }
+        }
+        self.len = self.len.saturating_add(other.len);
+    }
+
+    /// Construct a format copying `text` verbatim to the output.
+    fn from_literal(text: &[u8]) -> Self {
+        let builder = FormatBuilder::default().literal(text);
+
+        let mut items = builder.items;
+        if !builder.literal.is_empty() {
+            items.push(Item(Kind::Literal(Cow::Owned(builder.literal))));
+        }
+
+        // Each '%' character is a `%%` directive in the format string
+        let percents = items
+            .iter()
+            .filter(|item| matches!(item, Item(Kind::Directive(_))))
+            .count();
+
+        Self {
+            items: Cow::Owned(items),
+            len: text.len() + percents,
+        }
+    }
+
+    /// Construct a `Format` from static items, for the expansion of the
+    /// `strftime_format!` macro.
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn __from_static(items: &'static [Item], len: usize) -> Self {
+        Self {
+            items: Cow::Borrowed(items),
+            len,
+        }
+    }
+
+    /// Returns the methods of a [`Time`] implementation which are called when
+    /// formatting with this format string.
+    #[must_use]
+    pub fn required_fields(&self) -> RequiredFields {
+        let fields = self.items.iter().fold(0, |fields, item| match item {
+            Item(Kind::Literal(_)) => fields,
+            Item(Kind::Directive(piece)) => fields | RequiredFields::of(piece.spec),
+        });
+        RequiredFields(fields)
+    }
+
+    /// Returns `true` if parsing the output of this format with the same
+    /// format string recovers the formatted instant unambiguously.
+    ///
+    /// The format must have the number of seconds since the Epoch `%s`, or a
+    /// complete date, time and UTC offset:
+    ///
+    /// - A year with century, from `%Y` or from `%C` and `%y`, with the month
+    ///   and the day of the month, or with the day of the year `%j`, or with a
+    ///   week number `%U` or `%W` and a day of the week. An ISO 8601 week `%V`
+    ///   needs the ISO 8601 week-based year `%G` and a day of the week.
+    /// - An hour of the 24-hour clock, or an hour of the 12-hour clock with a
+    ///   meridian indicator, with the minute and the second.
+    /// - A UTC offset from `%z`, `%:z`, `%::z` or `%:::z`. A time zone
+    ///   abbreviation from `%Z` is ambiguous, like `CST` which is used for
+    ///   several time zones.
+    ///
+    /// Lossy directives like `%y` without `%C` are flagged by the missing
+    /// fields. A directive without padding like `%-H`, or `%s`, followed by a
+    /// number is ambiguous, since the parser can't know where its digits end.
+    ///
+    /// The instant is recovered to the second. The years outside of
+    /// `0..=9999` and the UTC offsets with seconds formatted by `%z` or `%:z`
+    /// are not checked.
+    ///
+    /// See [`ParsedFields`](crate::strptime::ParsedFields) for the parsing of
+    /// a time from the fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::Format;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// assert!(Format::parse("%FT%T%:z")?.is_round_trippable());
+    /// assert!(Format::parse("%s")?.is_round_trippable());
+    /// assert!(Format::parse("%G-W%V-%u %I:%M:%S %p %z")?.is_round_trippable());
+    ///
+    /// // The year is lossy
+    /// assert!(!Format::parse("%D %T %z")?.is_round_trippable());
+    /// // The time zone abbreviation is ambiguous
+    /// assert!(!Format::parse("%F %T %Z")?.is_round_trippable());
+    /// // The seconds are missing
+    /// assert!(!Format::parse("%F %R %z")?.is_round_trippable());
+    /// // The unpadded hour is followed by a number
+    /// assert!(!Format::parse("%F %-H%M%S %z")?.is_round_trippable());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn is_round_trippable(&self) -> bool {
+        let mut fields = 0;
+        let mut is_unpadded_number = false;
+
+        for item in self.items.iter() {
+            let (starts_with_number, spec) = match item {
+                Item(Kind::Literal(literal)) => (
+                    matches!(literal.first(), Some(x) if x.is_ascii_digit()),
+                    None,
+                ),
+                Item(Kind::Directive(piece)) => {
+                    let is_numeric = spec_text(piece.spec)
+                        .bytes()
+                        .last()
+                        .map_or(false, |x| NUMERIC_SPECS.contains(&x));
+                    (is_numeric, Some((piece, is_numeric)))
+                }
+            };
+
+            if is_unpadded_number && starts_with_number {
+                return false;
+            }
+
+            is_unpadded_number = match spec {
+                Some((piece, is_numeric)) => {
+                    fields |= RecoveredFields::of(piece.spec);
+                    is_numeric
+                        && (piece.flags.contains(Flag::LeftPadding)
+                            || piece.spec == Spec::SecondsSinceEpoch)
+                }
+                None => false,
+            };
+        }
+
+        RecoveredFields::recover_instant(fields)
+    }
+
+    /// Returns an upper bound of the length in bytes of the output of this
+    /// format string.
+    ///
+    /// The bound holds for all the values of the [`Time`] methods, like the
+    /// years of 11 characters or the UTC offsets of more than 99 hours, and
+    /// takes the widths of the directives into account. The only unbounded
+    /// value is the time zone name of `%Z`, whose length is taken from
+    /// `time_hint`, so the bound holds for the times with a time zone name no
+    /// longer than the one of `time_hint`.
+    ///
+    /// A buffer of this length never fails with [`Error::WriteZero`] when
+    /// formatting with [`fmt_buffered`](Self::fmt_buffered).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::{DateTime, Format};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let format = Format::parse("%F %T %Z")?;
+    /// let time = DateTime::from_timestamp(0, 0).unwrap();
+    ///
+    /// let mut buf = vec![0u8; format.max_len(&time)];
+    /// assert_eq!(format.fmt_buffered(&time, &mut buf)?, b"1970-01-01 00:00:00 UTC");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_len(&self, time_hint: &(impl Time + ?Sized)) -> usize {
+        let time_zone_len = time_hint.time_zone().len();
+
+        self.items.iter().fold(0, |len, item| {
+            let item_len = match item {
+                Item(Kind::Literal(literal)) => literal.len(),
+                Item(Kind::Directive(piece)) => piece_max_len(piece, time_zone_len),
+            };
+            len.saturating_add(item_len)
+        })
+    }
+
+    /// Format a _time_ implementation, writing to the provided
+    /// [`core::fmt::Write`] object.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFormatString`] if the literal text of the format
+    /// string is not valid UTF-8, which is possible for a format parsed from
+    /// bytes. Can produce an [`Error`] when the formatting fails.
+    pub fn fmt(&self, time: &(impl Time + ?Sized), buf: &mut dyn fmt::Write) -> Result<(), Error> {
+        self.check_utf8()?;
+        self.fmt_to(time, &mut FmtWrite::new(buf))
+    }
+
+    /// Format a slice of _time_ implementations, writing the formatted strings
+    /// separated by `separator` to the provided [`core::fmt::Write`] object.
+    ///
+    /// The format is checked once for all the _times_, which makes this
+    /// method faster than calling [`fmt`](Self::fmt) in a loop for large
+    /// exports.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::{DateTime, Format};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let format = Format::parse("%F")?;
+    /// let times = [0, 86_400].map(|seconds| DateTime::from_timestamp(seconds, 0).unwrap());
+    ///
+    /// let mut buf = String::new();
+    /// format.format_many(&times, "\n", &mut buf)?;
+    /// assert_eq!(buf, "1970-01-01\n1970-01-02");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFormatString`] if the literal text of the format
+    /// string is not valid UTF-8. Can produce an [`Error`] when the formatting
+    /// fails, in which case the _times_ before the failing one are already
+    /// written.
+    pub fn format_many<T: Time>(
+        &self,
+        times: &[T],
+        separator: &str,
+        buf: &mut dyn fmt::Write,
+    ) -> Result<(), Error> {
+        self.fmt_joined::<T, _>(times, separator, buf)
+    }
+
+    /// Format an iterator of _time_ implementations, writing the formatted
+    /// strings separated by `separator` to the provided [`core::fmt::Write`]
+    /// object.
+    ///
+    /// See [`format_many`](Self::format_many).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::{DateTime, Format};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let format = Format::parse("%s")?;
+    /// let times = (0..3).map(|seconds| DateTime::from_timestamp(seconds, 0).unwrap());
+    ///
+    /// let mut buf = String::new();
+    /// format.format_iter(times, ",", &mut buf)?;
+    /// assert_eq!(buf, "0,1,2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFormatString`] if the literal text of the format
+    /// string is not valid UTF-8. Can produce an [`Error`] when the formatting
+    /// fails, in which case the _times_ before the failing one are already
+    /// written.
+    pub fn format_iter<I>(
+        &self,
+        times: I,
+        separator: &str,
+        buf: &mut dyn fmt::Write,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: Time,
+    {
+        self.fmt_joined::<I::Item, _>(times, separator, buf)
+    }
+
+    /// Format a _time_ implementation, writing in the provided buffer and
+    /// returning the written subslice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::{DateTime, Format};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let format = Format::parse(b"%F")?;
+    /// let time = DateTime::from_timestamp(0, 0).unwrap();
+    ///
+    /// let mut buf = [0u8; 16];
+    /// assert_eq!(format.fmt_buffered(&time, &mut buf)?, b"1970-01-01");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn fmt_buffered<'a>(
+        &self,
+        time: &(impl Time + ?Sized),
+        buf: &'a mut [u8],
+    ) -> Result<&'a mut [u8], Error> {
+        let len = buf.len();
+
+        let mut cursor = &mut buf[..];
+        self.fmt_to(time, &mut cursor)?;
+        let remaining_len = cursor.len();
+
+        Ok(&mut buf[..len - remaining_len])
+    }
+
+    /// Format a _time_ implementation, writing to the provided
+    /// [`std::io::Write`] object.
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn fmt_io(
+        &self,
+        time: &(impl Time + ?Sized),
+        buf: &mut dyn std::io::Write,
+    ) -> Result<(), Error> {
+        let mut writer = super::IoWrite::new(buf);
+        let result = self.fmt_to(time, &mut writer);
+        // Write the buffered output even on error, like an unbuffered writer
+        result.and(writer.flush())
+    }
+
+    /// Returns the canonical format string of this format, which is parsed to
+    /// an equal format.
+    ///
+    /// The directives are written with their flags and width, using a single
+    /// canonical conversion specifier, so `%h` is written as `%b` and the
+    /// ignored `E` and `O` modifiers are removed. Invalid directives are kept
+    /// verbatim in the literal text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::Format;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let format = Format::parse("%-_10d %h %Ey %% %q")?;
+    /// assert_eq!(format.to_format_string()?, "%-_10d %b %y %% %q");
+    /// assert_eq!(format.to_string(), "%-_10d %b %y %% %q");
+    ///
+    /// assert_eq!(Format::parse(&format.to_format_string()?)?, format);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFormatString`] if the format string is not
+    /// valid UTF-8, which is possible for a format parsed from bytes, and
+    /// [`Error::OutOfMemory`] if allocating the string fails.
+    pub fn to_format_string(&self) -> Result<String, Error> {
+        let format = self.to_format_bytes()?;
+        String::from_utf8(format).map_err(|_| Error::InvalidFormatString)
+    }
+
+    /// Returns the canonical format string of this format as bytes, which is
+    /// parsed to an equal format.
+    ///
+    /// See [`to_format_string`](Self::to_format_string).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfMemory`] if allocating the byte string fails.
+    pub fn to_format_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut format = Vec::new();
+        self.write_format_string(&mut format)?;
+        Ok(format)
+    }
+
+    /// Write a format string which is parsed to the same items.
+    ///
+    /// Invalid directives are kept verbatim in the literal text, and are
+    /// parsed again as literal text.
+    fn write_format_string(&self, f: &mut dyn Write) -> Result<(), Error> {
+        for item in &*self.items {
+            match item {
+                Item(Kind::Literal(literal)) => f.write_all(literal)?,
+                Item(Kind::Directive(piece)) => {
+                    f.write_all(b"%")?;
+                    if piece.flags.contains(Flag::LeftPadding) {
+                        f.write_all(b"-")?;
+                    }
+                    match piece.padding {
+                        Padding::Left => {}
+                        Padding::Spaces => f.write_all(b"_")?,
+                        Padding::Zeros => f.write_all(b"0")?,
+                    }
+                    if piece.flags.contains(Flag::UpperCase) {
+                        f.write_all(b"^")?;
+                    }
+                    if piece.flags.contains(Flag::ChangeCase) {
+                        f.write_all(b"#")?;
+                    }
+                    if let Some(width) = piece.width {
+                        write!(f, "{width}")?;
+                    }
+                    f.write_all(spec_text(piece.spec).as_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the literal text of the format string is valid UTF-8.
+    fn check_utf8(&self) -> Result<(), Error> {
+        let is_utf8 = self.items.iter().all(|item| match item {
+            Item(Kind::Literal(literal)) => core::str::from_utf8(literal).is_ok(),
+            Item(Kind::Directive(_)) => true,
+        });
+        if is_utf8 {
+            Ok(())
+        } else {
+            Err(Error::InvalidFormatString)
+        }
+    }
+
+    /// Format _time_ implementations separated by `separator`, checking the
+    /// format string once.
+    fn fmt_joined<T, B>(
+        &self,
+        times: impl IntoIterator<Item = B>,
+        separator: &str,
+        buf: &mut dyn fmt::Write,
+    ) -> Result<(), Error>
+    where
+        T: Time + ?Sized,
+        B: Borrow<T>,
+    {
+        self.check_utf8()?;
+
+        let mut writer = FmtWrite::new(buf);
+        for (index, time) in times.into_iter().enumerate() {
+            if index > 0 {
+                writer.write_all(separator.as_bytes())?;
+            }
+            self.fmt_to(time.borrow(), &mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Format a _time_ implementation with the items of the format string.
+    fn fmt_to(&self, time: &(impl Time + ?Sized), buf: &mut dyn Write) -> Result<(), Error> {
+        const DEFAULT_OPTIONS: FormatOptions<'static> = FormatOptions::new();
+
+        // Use the same size limit as for the format string
+        let size_limit = self.len.saturating_mul(512 * 1024);
+        let mut f = SizeLimiter::new(buf, size_limit);
+
+        for item in &*self.items {
+            match item {
+                Item(Kind::Literal(literal)) => f.write_all(literal)?,
+                Item(Kind::Directive(piece)) => {
+                    TimeFormatter::fmt_piece(&mut f, time, piece, &DEFAULT_OPTIONS)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Define the builder methods adding a directive.
+macro_rules! directive_methods {
+    ($($(#[$attr:meta])* $name:ident => $spec:ident,)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(self) -> Self {
+                self.directive(Spec::$spec)
+            }
+        )*
+    };
+}
+
+/// Builder of a [`Format`], created by [`Format::builder`].
+///
+/// The flags, width and padding methods modify the last added directive, and
+/// have no effect if literal text was added after it.
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct FormatBuilder {
+    /// Items of the format string.
+    items: Vec<Item>,
+    /// Literal text added after the items.
+    literal: Vec<u8>,
+}
+
+impl FormatBuilder {
+    /// Add literal text, which is copied verbatim to the output.
+    pub fn literal<T: AsRef<[u8]> + ?Sized>(mut self, text: &T) -> Self {
+        // A '%' character is added as a `%%` directive, like when parsing the
+        // escaped format string
+        let mut parts = text.as_ref().split(|&x| x == b'%');
+        if let Some(part) = parts.next() {
+            self.literal.extend_from_slice(part);
+        }
+        for part in parts {
+            self = self.directive(Spec::Percent);
+            self.literal.extend_from_slice(part);
+        }
+        self
+    }
+
+    /// Add a directive with a formatting specifier.
+    ///
+    /// The specifiers of [`Dialect::Extensions`] make [`build`](Self::build)
+    /// fail, since a [`Format`] uses the directives of [`Dialect::Ruby`].
+    pub fn directive(mut self, spec: Spec) -> Self {
+        if !self.literal.is_empty() {
+            let literal = core::mem::take(&mut self.literal);
+            self.items.push(Item(Kind::Literal(Cow::Owned(literal))));
+        }
+        let piece = Piece::new(None, Padding::Left, Flags::default(), spec);
+        self.items.push(Item(Kind::Directive(piece)));
+        self
+    }
+
+    /// Set a flag of the last directive.
+    ///
+    /// Like the `-` flag in a format string, [`Flag::LeftPadding`] also resets
+    /// the padding. The flags of [`Dialect::Extensions`] make
+    /// [`build`](Self::build) fail.
+    pub fn flag(mut self, flag: Flag) -> Self {
+        if let Some(piece) = self.last_directive() {
+            piece.flags.set(flag);
+            if flag == Flag::LeftPadding {
+                piece.padding = Padding::Left;
+            }
+        }
+        self
+    }
+
+    /// Set the width of the last directive.
+    ///
+    /// A width greater than `i32::MAX` makes [`build`](Self::build) fail.
+    pub fn width(mut self, width: usize) -> Self {
+        if let Some(piece) = self.last_directive() {
+            piece.width = Some(width);
+        }
+        self
+    }
+
+    /// Set the padding of the last directive.
+    pub fn padding(mut self, padding: Padding) -> Self {
+        if let Some(piece) = self.last_directive() {
+            piece.padding = padding;
+        }
+        self
+    }
+
+    directive_methods! {
+        /// Add a `%Y` directive: year with century.
+        year4 => Year4Digits,
+        /// Add a `%C` directive: year divided by 100.
+        century => YearDiv100,
+        /// Add a `%y` directive: year modulo 100.
+        year2 => YearRem100,
+        /// Add a `%m` directive: month of the year, zero-padded.
+        month => Month,
+        /// Add a `%B` directive: full month name.
+        month_name => MonthName,
+        /// Add a `%b` directive: abbreviated month name.
+        month_name_abbr => MonthNameAbbr,
+        /// Add a `%d` directive: day of the month, zero-padded.
+        day => MonthDayZero,
+        /// Add a `%e` directive: day of the month, blank-padded.
+        day_space => MonthDaySpace,
+        /// Add a `%j` directive: day of the year, zero-padded.
+        year_day => YearDay,
+        /// Add a `%H` directive: hour of the day (24-hour clock), zero-padded.
+        hour24 => Hour24hZero,
+        /// Add a `%k` directive: hour of the day (24-hour clock),
+        /// blank-padded.
+        hour24_space => Hour24hSpace,
+        /// Add a `%I` directive: hour of the day (12-hour clock), zero-padded.
+        hour12 => Hour12hZero,
+        /// Add a `%l` directive: hour of the day (12-hour clock),
+        /// blank-padded.
+        hour12_space => Hour12hSpace,
+        /// Add a `%P` directive: meridian indicator, lowercase.
+        meridian_lower => MeridianLower,
+        /// Add a `%p` directive: meridian indicator, uppercase.
+        meridian_upper => MeridianUpper,
+        /// Add a `%M` directive: minute of the hour.
+        minute => Minute,
+        /// Add a `%S` directive: second of the minute.
+        second => Second,
+        /// Add a `%L` directive: millisecond of the second.
+        #[cfg(feature = "subsec")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "subsec")))]
+        millisecond => MilliSecond,
+        /// Add a `%N` directive: fractional seconds digits.
+        #[cfg(feature = "subsec")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "subsec")))]
+        fractional_second => FractionalSecond,
+        /// Add a `%z` directive: UTC offset as `+hhmm`.
+        utc_offset => TimeZoneOffsetHourMinute,
+        /// Add a `%:z` directive: UTC offset as `+hh:mm`.
+        utc_offset_colon => TimeZoneOffsetHourMinuteColon,
+        /// Add a `%::z` directive: UTC offset as `+hh:mm:ss`.
+        utc_offset_seconds_colon => TimeZoneOffsetHourMinuteSecondColon,
+        /// Add a `%:::z` directive: shortest UTC offset with colons.
+        utc_offset_minimal => TimeZoneOffsetColonMinimal,
+        /// Add a `%Z` directive: time zone name.
+        time_zone => TimeZoneName,
+        /// Add a `%A` directive: full weekday name.
+        week_day_name => WeekDayName,
+        /// Add a `%a` directive: abbreviated weekday name.
+        week_day_name_abbr => WeekDayNameAbbr,
+        /// Add a `%u` directive: day of the week, from Monday as 1.
+        week_day_from_1 => WeekDayFrom1,
+        /// Add a `%w` directive: day of the week, from Sunday as 0.
+        week_day_from_0 => WeekDayFrom0,
+        /// Add a `%G` directive: ISO 8601 week-based year.
+        iso_8601_year => YearIso8601,
+        /// Add a `%g` directive: ISO 8601 week-based year modulo 100.
+        iso_8601_year2 => YearIso8601Rem100,
+        /// Add a `%V` directive: ISO 8601 week number.
+        iso_8601_week => WeekNumberIso8601,
+        /// Add a `%U` directive: week number, from the first Sunday.
+        week_from_sunday => WeekNumberFromSunday,
+        /// Add a `%W` directive: week number, from the first Monday.
+        week_from_monday => WeekNumberFromMonday,
+        /// Add a `%s` directive: number of seconds since the Unix epoch.
+        seconds_since_epoch => SecondsSinceEpoch,
+        /// Add a `%n` directive: newline character.
+        newline => Newline,
+        /// Add a `%t` directive: tab character.
+        tab => Tabulation,
+        /// Add a `%c` directive: date and time.
+        date_time => CombinationDateTime,
+        /// Add a `%D` directive: date as `%m/%d/%y`.
+        date => CombinationDate,
+        /// Add a `%F` directive: ISO 8601 date as `%Y-%m-%d`.
+        iso_8601_date => CombinationIso8601,
+        /// Add a `%v` directive: VMS date as `%e-%^b-%4Y`.
+        vms_date => CombinationVmsDate,
+        /// Add a `%r` directive: 12-hour time as `%I:%M:%S %p`.
+        time12 => CombinationTime12h,
+        /// Add a `%R` directive: 24-hour time as `%H:%M`.
+        hour_minute => CombinationHourMinute24h,
+        /// Add a `%T` directive: 24-hour time as `%H:%M:%S`.
+        time24 => CombinationTime24h,
+    }
+
+    /// Build the format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFormatString`] if a directive is not supported
+    /// by [`Dialect::Ruby`] or has a width greater than `i32::MAX`.
+    pub fn build(self) -> Result<Format, Error> {
+        let mut format = Format {
+            items: Cow::Owned(self.items),
+            len: 0,
+        };
+        if !self.literal.is_empty() {
+            let mut items = format.items.into_owned();
+            items.push(Item(Kind::Literal(Cow::Owned(self.literal))));
+            format.items = Cow::Owned(items);
+        }
+
+        // Parse the format string of the items, which also validates them
+        let mut format_string = Vec::new();
+        format.write_format_string(&mut format_string)?;
+        let parsed = Format::parse(&format_string)?;
+
+        if parsed == format {
+            Ok(parsed)
+        } else {
+            Err(Error::InvalidFormatString)
+        }
+    }
+
+    /// Returns the last added directive, if no literal text was added after
+    /// it.
+    fn last_directive(&mut self) -> Option<&mut Piece> {
+        if !self.literal.is_empty() {
+            return None;
+        }
+        match self.items.last_mut() {
+            Some(Item(Kind::Directive(piece))) => Some(piece),
+            _ => None,
+        }
+    }
+}
+
+/// Fields of a time recovered by parsing the output of a directive, used by
+/// [`Format::is_round_trippable`].
+struct RecoveredFields;
+
+impl RecoveredFields {
+    const YEAR: u32 = 1 << 0;
+    const CENTURY: u32 = 1 << 1;
+    const YEAR_REM_100: u32 = 1 << 2;
+    const MONTH: u32 = 1 << 3;
+    const DAY: u32 = 1 << 4;
+    const DAY_OF_YEAR: u32 = 1 << 5;
+    const HOUR_24H: u32 = 1 << 6;
+    const HOUR_12H: u32 = 1 << 7;
+    const MERIDIAN: u32 = 1 << 8;
+    const MINUTE: u32 = 1 << 9;
+    const SECOND: u32 = 1 << 10;
+    const DAY_OF_WEEK: u32 = 1 << 11;
+    const ISO_8601_YEAR: u32 = 1 << 12;
+    const ISO_8601_YEAR_REM_100: u32 = 1 << 13;
+    const ISO_8601_WEEK: u32 = 1 << 14;
+    const WEEK_FROM_SUNDAY: u32 = 1 << 15;
+    const WEEK_FROM_MONDAY: u32 = 1 << 16;
+    const SECONDS_SINCE_EPOCH: u32 = 1 << 17;
+    const UTC_OFFSET: u32 = 1 << 18;
+
+    /// Returns the fields recovered by parsing the output of a formatting
+    /// specifier.
+    const fn of(spec: Spec) -> u32 {
+        const TIME_24H: u32 =
+            RecoveredFields::HOUR_24H | RecoveredFields::MINUTE | RecoveredFields::SECOND;
+
+        match spec {
+            Spec::Year4Digits => Self::YEAR,
+            Spec::YearDiv100 => Self::CENTURY,
+            Spec::YearRem100 => Self::YEAR_REM_100,
+            Spec::Month | Spec::MonthName | Spec::MonthNameAbbr => Self::MONTH,
+            Spec::MonthDayZero | Spec::MonthDaySpace => Self::DAY,
+            Spec::YearDay => Self::DAY_OF_YEAR,
+            Spec::Hour24hZero | Spec::Hour24hSpace => Self::HOUR_24H,
+            Spec::Hour12hZero | Spec::Hour12hSpace => Self::HOUR_12H,
+            Spec::MeridianLower | Spec::MeridianUpper => Self::MERIDIAN,
+            Spec::Minute => Self::MINUTE,
+            Spec::Second => Self::SECOND,
+            Spec::TimeZoneOffsetHourMinute
+            | Spec::TimeZoneOffsetHourMinuteColon
+            | Spec::TimeZoneOffsetHourMinuteSecondColon
+            | Spec::TimeZoneOffsetColonMinimal
+            | Spec::TimeZoneOffsetHourMinuteSecond => Self::UTC_OFFSET,
+            Spec::WeekDayName | Spec::WeekDayNameAbbr | Spec::WeekDayFrom1 | Spec::WeekDayFrom0 => {
+                Self::DAY_OF_WEEK
+            }
+            Spec::YearIso8601 => Self::ISO_8601_YEAR,
+            Spec::YearIso8601Rem100 => Self::ISO_8601_YEAR_REM_100,
+            Spec::WeekNumberIso8601 => Self::ISO_8601_WEEK,
+            Spec::WeekNumberFromSunday => Self::WEEK_FROM_SUNDAY,
+            Spec::WeekNumberFromMonday => Self::WEEK_FROM_MONDAY,
+            Spec::SecondsSinceEpoch => Self::SECONDS_SINCE_EPOCH,
+            Spec::CombinationDateTime => {
+                Self::YEAR | Self::MONTH | Self::DAY | Self::DAY_OF_WEEK | TIME_24H
+            }
+            Spec::CombinationDate => Self::MONTH | Self::DAY | Self::YEAR_REM_100,
+            Spec::CombinationIso8601 | Spec::CombinationVmsDate => {
+                Self::YEAR | Self::MONTH | Self::DAY
+            }
+            Spec::CombinationTime12h => {
+                Self::HOUR_12H | Self::MERIDIAN | Self::MINUTE | Self::SECOND
+            }
+            Spec::CombinationHourMinute24h => Self::HOUR_24H | Self::MINUTE,
+            Spec::CombinationTime24h => TIME_24H,
+            Spec::TimeZoneName | Spec::DayPeriod => 0,
+            Spec::Newline | Spec::Tabulation | Spec::Percent => 0,
+            // The instant is recovered to the second
+            #[cfg(feature = "subsec")]
+            Spec::MilliSecond | Spec::FractionalSecond | Spec::SecondsSinceEpochFractional => 0,
+        }
+    }
+
+    /// Checks if all the fields are recovered.
+    const fn contains(fields: u32, expected: u32) -> bool {
+        fields & expected == expected
+    }
+
+    /// Checks if the recovered fields give the instant, following the
+    /// precedence of the fields when converting
+    /// [`ParsedFields`](crate::strptime::ParsedFields) to a time.
+    const fn recover_instant(fields: u32) -> bool {
+        if Self::contains(fields, Self::SECONDS_SINCE_EPOCH) {
+            return true;
+        }
+
+        let year = Self::contains(fields, Self::YEAR)
+            || Self::contains(fields, Self::CENTURY | Self::YEAR_REM_100);
+        let iso_8601_year = Self::contains(fields, Self::ISO_8601_YEAR)
+            || Self::contains(fields, Self::CENTURY | Self::ISO_8601_YEAR_REM_100);
+        let day_of_week = Self::contains(fields, Self::DAY_OF_WEEK);
+
+        let date = if Self::contains(fields, Self::MONTH | Self::DAY)
+            || Self::contains(fields, Self::DAY_OF_YEAR)
+        {
+            year
+        } else if Self::contains(fields, Self::ISO_8601_WEEK) {
+            iso_8601_year && day_of_week
+        } else if Self::contains(fields, Self::WEEK_FROM_MONDAY)
+            || Self::contains(fields, Self::WEEK_FROM_SUNDAY)
+        {
+            year && day_of_week
+        } else {
+            false
+        };
+
+        let hour = if Self::contains(fields, Self::HOUR_12H) {
+            Self::contains(fields, Self::MERIDIAN)
+        } else {
+            Self::contains(fields, Self::HOUR_24H)
+        };
+        let time = hour && Self::contains(fields, Self::MINUTE | Self::SECOND);
+
+        date && time && Self::contains(fields, Self::UTC_OFFSET)
+    }
+}
+
+/// Write the canonical format string, replacing the invalid UTF-8 sequences
+/// with `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// See [`Format::to_format_string`].
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_format_string(&mut LossyWrite(f))
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// Writer to a [`fmt::Formatter`], replacing the invalid UTF-8 sequences with
+/// `U+FFFD REPLACEMENT CHARACTER`.
+struct LossyWrite<'a, 'f>(&'a mut fmt::Formatter<'f>);
+
+impl Write for LossyWrite<'_, '_> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        let mut remaining = data;
+        loop {
+            match core::str::from_utf8(remaining) {
+                Ok(valid) => {
+                    self.0.write_str(valid)?;
+                    return Ok(data.len());
+                }
+                Err(err) => {
+                    let (valid, invalid) = remaining.split_at(err.valid_up_to());
+                    // The valid prefix was just checked
+                    self.0
+                        .write_str(core::str::from_utf8(valid).unwrap_or_default())?;
+                    self.0.write_str("\u{FFFD}")?;
+                    let invalid_len = err.error_len().unwrap_or(invalid.len());
+                    remaining = &invalid[invalid_len..];
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for Format {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl Eq for Format {}
+
+impl Hash for Format {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash(state);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl serde::Serialize for Format {
+    /// Serialize the format string, as a string if it is valid UTF-8 and as
+    /// bytes otherwise.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let format = self.to_format_bytes().map_err(serde::ser::Error::custom)?;
+
+        match core::str::from_utf8(&format) {
+            Ok(format) => serializer.serialize_str(format),
+            Err(_) => serializer.serialize_bytes(&format),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de> serde::Deserialize<'de> for Format {
+    /// Deserialize and parse a format string, from a string or bytes.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Visitor parsing a format string.
+        struct FormatVisitor;
+
+        impl serde::de::Visitor<'_> for FormatVisitor {
+            type Value = Format;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a strftime format string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, format: &str) -> Result<Format, E> {
+                self.visit_bytes(format.as_bytes())
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, format: &[u8]) -> Result<Format, E> {
+                Format::parse(format).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(FormatVisitor)
+    }
+}
+
+/// Returns an upper bound of the output length of a directive.
+fn piece_max_len(piece: &Piece, time_zone_len: usize) -> usize {
+    /// Maximum length of a year, like `-2147483648`.
+    const YEAR: usize = 11;
+    /// Maximum length of a UTC offset hour with its sign, like `-596523`.
+    const OFFSET_HOUR: usize = 7;
+
+    let width = piece.width.unwrap_or(0);
+
+    let len = match piece.spec {
+        Spec::Year4Digits | Spec::YearIso8601 => YEAR,
+        Spec::YearDiv100 => "-21474837".len(),
+        Spec::MonthName | Spec::WeekDayName => "September".len(),
+        Spec::MonthNameAbbr | Spec::WeekDayNameAbbr | Spec::YearDay => 3,
+        Spec::YearRem100
+        | Spec::Month
+        | Spec::MonthDayZero
+        | Spec::MonthDaySpace
+        | Spec::Hour24hZero
+        | Spec::Hour24hSpace
+        | Spec::Hour12hZero
+        | Spec::Hour12hSpace
+        | Spec::MeridianLower
+        | Spec::MeridianUpper
+        | Spec::Minute
+        | Spec::Second
+        | Spec::YearIso8601Rem100
+        | Spec::WeekNumberIso8601
+        | Spec::WeekNumberFromSunday
+        | Spec::WeekNumberFromMonday => 2,
+        Spec::DayPeriod => "in the afternoon".len(),
+        // The width is the number of digits
+        #[cfg(feature = "subsec")]
+        Spec::MilliSecond => return piece.width.unwrap_or(3),
+        #[cfg(feature = "subsec")]
+        Spec::FractionalSecond => return piece.width.unwrap_or(9),
+        #[cfg(feature = "subsec")]
+        Spec::SecondsSinceEpochFractional => {
+            return "-9223372036854775808."
+                .len()
+                .saturating_add(piece.width.unwrap_or(9));
+        }
+        Spec::TimeZoneOffsetHourMinute => OFFSET_HOUR + "mm".len(),
+        Spec::TimeZoneOffsetHourMinuteColon => OFFSET_HOUR + ":mm".len(),
+        Spec::TimeZoneOffsetHourMinuteSecond => OFFSET_HOUR + "mmss".len(),
+        Spec::TimeZoneOffsetHourMinuteSecondColon | Spec::TimeZoneOffsetColonMinimal => {
+            OFFSET_HOUR + ":mm:ss".len()
+        }
+        Spec::TimeZoneName => time_zone_len,
+        Spec::WeekDayFrom1
+        | Spec::WeekDayFrom0
+        | Spec::Newline
+        | Spec::Tabulation
+        | Spec::Percent => 1,
+        Spec::SecondsSinceEpoch => "-9223372036854775808".len(),
+        Spec::CombinationDateTime => "www mmm dd HH:MM:SS ".len() + YEAR,
+        Spec::CombinationDate => "mm/dd/yy".len(),
+        Spec::CombinationIso8601 => YEAR + "-mm-dd".len(),
+        Spec::CombinationVmsDate => "dd-mmm-".len() + YEAR,
+        Spec::CombinationTime12h => "HH:MM:SS PM".len(),
+        Spec::CombinationHourMinute24h => "HH:MM".len(),
+        Spec::CombinationTime24h => "HH:MM:SS".len(),
+    };
+
+    len.max(width)
+}
+
+/// Returns the text of a formatting specifier, after the `%` character and
+/// the flags and width of its directive.
+const fn spec_text(spec: Spec) -> &'static str {
+    match spec {
+        Spec::Year4Digits => "Y",
+        Spec::YearDiv100 => "C",
+        Spec::YearRem100 => "y",
+        Spec::Month => "m",
+        Spec::MonthName => "B",
+        Spec::MonthNameAbbr => "b",
+        Spec::MonthDayZero => "d",
+        Spec::MonthDaySpace => "e",
+        Spec::YearDay => "j",
+        Spec::Hour24hZero => "H",
+        Spec::Hour24hSpace => "k",
+        Spec::Hour12hZero => "I",
+        Spec::Hour12hSpace => "l",
+        Spec::MeridianLower => "P",
+        Spec::MeridianUpper => "p",
+        Spec::DayPeriod => ":p",
+        Spec::Minute => "M",
+        Spec::Second => "S",
+        #[cfg(feature = "subsec")]
+        Spec::MilliSecond => "L",
+        #[cfg(feature = "subsec")]
+        Spec::FractionalSecond => "N",
+        Spec::TimeZoneOffsetHourMinute => "z",
+        Spec::TimeZoneOffsetHourMinuteColon => ":z",
+        Spec::TimeZoneOffsetHourMinuteSecondColon => "::z",
+        Spec::TimeZoneOffsetColonMinimal => ":::z",
+        Spec::TimeZoneOffsetHourMinuteSecond => "o",
+        Spec::TimeZoneName => "Z",
+        Spec::WeekDayName => "A",
+        Spec::WeekDayNameAbbr => "a",
+        Spec::WeekDayFrom1 => "u",
+        Spec::WeekDayFrom0 => "w",
+        Spec::YearIso8601 => "G",
+        Spec::YearIso8601Rem100 => "g",
+        Spec::WeekNumberIso8601 => "V",
+        Spec::WeekNumberFromSunday => "U",
+        Spec::WeekNumberFromMonday => "W",
+        Spec::SecondsSinceEpoch => "s",
+        #[cfg(feature = "subsec")]
+        Spec::SecondsSinceEpochFractional => ":s",
+        Spec::Newline => "n",
+        Spec::Tabulation => "t",
+        Spec::Percent => "%",
+        Spec::CombinationDateTime => "c",
+        Spec::CombinationDate => "D",
+        Spec::CombinationIso8601 => "F",
+        Spec::CombinationVmsDate => "v",
+        Spec::CombinationTime12h => "r",
+        Spec::CombinationHourMinute24h => "R",
+        Spec::CombinationTime24h => "T",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::format::{TimeFormatter, POSSIBLE_SPECS};
+    use crate::DateTime;
+
+    include!("../mock.rs.in");
+
+    fn check(format: &[u8]) {
+        let time = DateTime::from_timestamp_with_offset(1_720_432_800, 123_456_789, -34_200);
+        let time = time.unwrap();
+
+        let mut expected = [0u8; 256];
+        let mut cursor = &mut expected[..];
+        TimeFormatter::new(&time, format).fmt(&mut cursor).unwrap();
+        let len = 256 - cursor.len();
+
+        let mut buf = [0u8; 256];
+        let compiled = Format::parse(format).unwrap();
+        assert_eq!(
+            compiled.fmt_buffered(&time, &mut buf).unwrap(),
+            &expected[..len]
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        check(b"");
+        check(b"%Y-%m-%dT%H:%M:%S%z");
+        check(b"%c %x %X %D %F %v %r %R %T");
+        check(b"%10A %^a %#B %-d %_5m %010Y %::z %Z %s %%");
+        check(b"%q %E %O %1000000000000000000000000Y %:");
+        check(b"\xff%j%U%W%G%V%g%u%w%C%y%n%t");
+        #[cfg(feature = "subsec")]
+        check(b"%L %N %3N %12N");
+    }
+
+    #[test]
+    fn test_format_non_utf8() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+        let format = Format::parse(b"%Y \xff").unwrap();
+
+        let mut buf = String::new();
+        assert!(matches!(
+            format.fmt(&time, &mut buf),
+            Err(Error::InvalidFormatString)
+        ));
+        assert!(buf.is_empty());
+
+        let mut buf = [0u8; 8];
+        assert_eq!(format.fmt_buffered(&time, &mut buf).unwrap(), b"1970 \xff");
+    }
+
+    #[test]
+    fn test_format_many() {
+        let format = Format::parse(b"%F").unwrap();
+        let times =
+            [0, 86_400, 172_800].map(|seconds| DateTime::from_timestamp(seconds, 0).unwrap());
+
+        let mut buf = String::new();
+        format.format_many(&times, ", ", &mut buf).unwrap();
+        assert_eq!(buf, "1970-01-01, 1970-01-02, 1970-01-03");
+
+        let mut buf = String::new();
+        format.format_iter(times, "", &mut buf).unwrap();
+        assert_eq!(buf, "1970-01-011970-01-021970-01-03");
+
+        let mut buf = String::new();
+        format.format_many::<DateTime>(&[], ",", &mut buf).unwrap();
+        assert!(buf.is_empty());
+
+        let format = Format::parse(b"%Y \xff").unwrap();
+        assert!(matches!(
+            format.format_many(&times, ",", &mut buf),
+            Err(Error::InvalidFormatString)
+        ));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_format_items() {
+        let format = Format::parse(b"at %q%H:%M").unwrap();
+        assert_eq!(format.items.len(), 4);
+        assert_eq!(format.items[0], Item::__literal(b"at %q"));
+        assert_eq!(format.items[2], Item::__literal(b":"));
+
+        assert!(Format::parse(b"").unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn test_required_fields() {
+        let fields = Format::parse(b"%c").unwrap().required_fields();
+        assert!(fields.year() && fields.month() && fields.day() && fields.day_of_week());
+        assert!(fields.hour() && fields.minute() && fields.second());
+        assert!(!fields.day_of_year() && !fields.iso_8601_week());
+        assert!(!fields.utc_offset() && !fields.is_utc() && !fields.time_zone());
+
+        let fields = Format::parse(b"%G-W%V %:z %Z %s")
+            .unwrap()
+            .required_fields();
+        assert!(fields.iso_8601_week() && fields.year() && fields.day_of_year());
+        assert!(fields.utc_offset() && fields.is_utc() && fields.time_zone());
+        assert!(fields.to_int() && !fields.nanoseconds() && !fields.month());
+
+        assert!(Format::parse(b"%% %n %q")
+            .unwrap()
+            .required_fields()
+            .is_empty());
+        assert_eq!(
+            Format::parse(b"").unwrap().required_fields(),
+            RequiredFields::default()
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let format = Format::builder()
+            .year4()
+            .literal("-")
+            .month()
+            .literal(b"-")
+            .day()
+            .build()
+            .unwrap();
+        assert_eq!(format, Format::parse("%Y-%m-%d").unwrap());
+        assert_eq!(format.len, "%Y-%m-%d".len());
+
+        let format = Format::builder()
+            .literal("100% ")
+            .literal("at %q: ")
+            .week_day_name()
+            .flag(Flag::UpperCase)
+            .width(10)
+            .day_space()
+            .padding(Padding::Zeros)
+            .flag(Flag::LeftPadding)
+            .utc_offset_seconds_colon()
+            .padding(Padding::Spaces)
+            .literal("%")
+            .build()
+            .unwrap();
+        assert_eq!(
+            format,
+            Format::parse("100%% at %%q: %^10A%0-e%_::z%%").unwrap()
+        );
+
+        // Modifiers after literal text have no effect
+        let format = Format::builder().minute().literal(":").width(3);
+        assert_eq!(format.build().unwrap(), Format::parse("%M:").unwrap());
+        assert_eq!(
+            Format::builder().build().unwrap(),
+            Format::parse("").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_error() {
+        let builder = Format::builder().directive(Spec::DayPeriod);
+        assert!(matches!(builder.build(), Err(Error::InvalidFormatString)));
+
+        let builder = Format::builder().month_name().flag(Flag::Truncate);
+        assert!(matches!(builder.build(), Err(Error::InvalidFormatString)));
+
+        let builder = Format::builder().year4().width(1 << 31);
+        assert!(matches!(builder.build(), Err(Error::InvalidFormatString)));
+        let builder = Format::builder().year4().width((1 << 31) - 1);
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_max_len() {
+        let times = [
+            MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 0, "UTC"),
+            MockTime::new(
+                i32::MIN,
+                9,
+                30,
+                23,
+                59,
+                60,
+                999_999_999,
+                3,
+                366,
+                i64::MIN,
+                false,
+                i32::MIN,
+                "Europe/Paris",
+            ),
+            MockTime::new(
+                i32::MAX,
+                12,
+                31,
+                12,
+                0,
+                0,
+                1,
+                6,
+                365,
+                i64::MAX,
+                false,
+                i32::MAX,
+                "",
+            ),
+        ];
+
+        let formats: &[&[u8]] = &[
+            b"%Y %C %y %G %g %V %U %W %j %A %a %B %b %u %w %n %t %%",
+            b"%c|%D|%F|%v|%r|%R|%T|%s",
+            b"%-d %_H %0k %l %I %p %P %^B %#a %Z %^Z",
+            b"%z %:z %::z %:::z %-z %_z %10z %_10:z %-12::z %3:::z",
+            b"%30c %30D %30F %30v %30r %30R %30T %30s %30Y %30Z %30A",
+            b"at %q %: %2147483648Y",
+            #[cfg(feature = "subsec")]
+            b"%L %N %3N %12N %100L %1N",
+        ];
+
+        for time in &times {
+            for &format in formats {
+                let format = Format::parse(format).unwrap();
+                let max_len = format.max_len(time);
+
+                let mut buf = [0u8; 1024];
+                let len = format.fmt_buffered(time, &mut buf).unwrap().len();
+                assert!(len <= max_len, "{len} > {max_len} for {format:?}");
+                assert!(format.fmt_buffered(time, &mut buf[..max_len]).is_ok());
+            }
+        }
+
+        let format = Format::parse(b"%1000Y").unwrap();
+        assert_eq!(format.max_len(&times[0]), 1000);
+        assert_eq!(Format::parse(b"").unwrap().max_len(&times[0]), 0);
+    }
+
+    #[test]
+    fn test_is_round_trippable() {
+        let formats: &[(&[u8], bool)] = &[
+            (b"%FT%T%z", true),
+            (b"%Y-%m-%d %H:%M:%S %::z", true),
+            (b"%c %:z", true),
+            (b"%v %r %:::z", true),
+            (b"%C%y%m%d%H%M%S%z", true),
+            (b"%C %D %T %z", true),
+            (b"%Y-%j %T %z", true),
+            (b"%G-W%V-%u %T %z", true),
+            (b"%C%g-W%V-%a %T %z", true),
+            (b"%Y %U %w %T %z", true),
+            (b"%Y %W %A %T %z", true),
+            (b"%s", true),
+            (b"%s.%N", true),
+            (b"%F %-H:%-M:%-S %z", true),
+            (b"%Y%m%d%H%M%S.%N%z", true),
+            (b"", false),
+            (b"%F", false),
+            (b"%T %z", false),
+            (b"%D %T %z", false),
+            (b"%y-%m-%d %T %z", false),
+            (b"%F %T", false),
+            (b"%F %T %Z", false),
+            (b"%F %R %z", false),
+            (b"%F %I:%M:%S %z", false),
+            (b"%Y-%m %T %z", false),
+            (b"%Y-W%V-%u %T %z", false),
+            (b"%G-W%V %T %z", false),
+            (b"%Y %U %T %z", false),
+            (b"%F %-H%M%S %z", false),
+            (b"%F %T %-m1 %z", false),
+            (b"%s1", false),
+        ];
+
+        for &(format, expected) in formats {
+            let compiled = Format::parse(format).unwrap();
+            assert_eq!(
+                compiled.is_round_trippable(),
+                expected,
+                "{:?}",
+                core::str::from_utf8(format)
+            );
+        }
+
+        // Without the subsec feature, `%N` is a literal
+        #[cfg(feature = "subsec")]
+        assert!(!Format::parse(b"%s%N").unwrap().is_round_trippable());
+    }
+
+    #[test]
+    fn test_concat() {
+        static ITEMS: [Item; 1] = [Item::__literal(b"at ")];
+
+        let formats: &[(&[u8], &[u8])] = &[
+            (b"", b""),
+            (b"%Y", b""),
+            (b"", b"%Y"),
+            (b"[%F ", b"%T]"),
+            (b"%Y", b"%m"),
+            (b"at ", b"noon"),
+            (b"100%%", b"d"),
+            (b"%q", b"%Y"),
+        ];
+
+        for &(a, b) in formats {
+            let concat = Format::parse(a).unwrap().concat(&Format::parse(b).unwrap());
+            assert_eq!(concat, Format::parse(&[a, b].concat()).unwrap());
+            assert_eq!(concat.len, a.len() + b.len());
+        }
+
+        // Static formats are copied on write
+        let format = Format::__from_static(&ITEMS, 3);
+        let concat = format.concat(&Format::parse(b"%T").unwrap());
+        assert_eq!(concat, Format::parse(b"at %T").unwrap());
+        assert_eq!(format, Format::parse(b"at ").unwrap());
+    }
+
+    #[test]
+    fn test_join() {
+        let formats = [b"%F".as_slice(), b"%T", b"", b"%z"].map(|x| Format::parse(x).unwrap());
+
+        let joined = Format::join(&formats, " ");
+        assert_eq!(joined, Format::parse(b"%F %T  %z").unwrap());
+        assert_eq!(joined.len, 9);
+
+        let joined = Format::join(&formats, "%");
+        assert_eq!(joined, Format::parse(b"%F%%%T%%%%%z").unwrap());
+        assert_eq!(joined.len, 12);
+
+        assert_eq!(Format::join(&formats[..1], "-"), formats[0]);
+        assert_eq!(Format::join(&[], "-"), Format::parse(b"").unwrap());
+    }
+
+    #[test]
+    fn test_format_error() {
+        assert!(matches!(
+            Format::parse(b"%Y%"),
+            Err(Error::UnterminatedDirective { position: 2, snippet, .. }) if snippet.as_bytes() == b"%"
+        ));
+        assert!(Format::parse(&b"%Y".repeat(5000)).is_ok());
+
+        let format = Format::parse(b"%Y").unwrap();
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+        assert!(matches!(
+            format.fmt_buffered(&time, &mut [0u8; 3]),
+            Err(Error::WriteZero)
+        ));
+    }
+
+    #[test]
+    fn test_write_format_string() {
+        let formats: &[(&[u8], &[u8])] = &[
+            (b"", b""),
+            (b"%Y-%m-%dT%H:%M:%S%:z", b"%Y-%m-%dT%H:%M:%S%:z"),
+            (
+                b"%-_10d %0^#B %-e %Ey %Oz %h %x %X",
+                b"%-_10d %0^#B %-e %y %Oz %b %D %T",
+            ),
+            (
+                b"at %q %:: %::::z %2147483648Y",
+                b"at %q %:: %::::z %2147483648Y",
+            ),
+            (b"%c %v %r %R %%%n%t\xff", b"%c %v %r %R %%%n%t\xff"),
+        ];
+
+        for &(format, expected) in formats {
+            let compiled = Format::parse(format).unwrap();
+            let mut buf = Vec::new();
+            compiled.write_format_string(&mut buf).unwrap();
+            assert_eq!(buf, expected);
+            assert_eq!(Format::parse(&buf).unwrap(), compiled);
+        }
+    }
+
+    #[test]
+    fn test_to_format_string() {
+        let format = Format::parse(b"%-_10d %h %Ey %% %q").unwrap();
+        assert_eq!(format.to_format_string().unwrap(), "%-_10d %b %y %% %q");
+        assert_eq!(format.to_string(), "%-_10d %b %y %% %q");
+
+        let format = Format::parse(b"%Y \xff\xfe%d \xe2\x82").unwrap();
+        assert!(matches!(
+            format.to_format_string(),
+            Err(Error::InvalidFormatString)
+        ));
+        assert_eq!(format.to_format_bytes().unwrap(), b"%Y \xff\xfe%d \xe2\x82");
+        assert_eq!(format.to_string(), "%Y \u{FFFD}\u{FFFD}%d \u{FFFD}");
+    }
+
+    #[test]
+    fn test_to_format_string_roundtrip() {
+        let specs = POSSIBLE_SPECS.iter().map(|&(x, _)| [x].to_vec());
+        let colon_specs = [":z", "::z", ":::z"].iter().map(|x| x.as_bytes().to_vec());
+        let flags = ["", "-", "_", "0", "^", "#", "-_", "_-", "0^#", "^#-0"];
+        let widths = ["", "1", "3", "12", "2147483647"];
+
+        for spec in specs.chain(colon_specs) {
+            for flags in flags {
+                for width in widths {
+                    let mut format = alloc::format!("at %{flags}{width}").into_bytes();
+                    format.extend_from_slice(&spec);
+                    format.extend_from_slice(b" %q");
+
+                    let parsed = Format::parse(&format).unwrap();
+                    let rendered = parsed.to_format_bytes().unwrap();
+                    assert_eq!(Format::parse(&rendered).unwrap(), parsed);
+                    assert_eq!(parsed.to_format_bytes().unwrap(), rendered);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        let format = Format::parse(b"%Y-%m-%d %H:%M").unwrap();
+        assert_tokens(&format, &[Token::Str("%Y-%m-%d %H:%M")]);
+        assert_de_tokens(&format, &[Token::Bytes(b"%Y-%m-%d %H:%M")]);
+
+        let format = Format::parse(b"%h \xff").unwrap();
+        assert_tokens(&format, &[Token::Bytes(b"%b \xff")]);
+
+        assert_de_tokens_error::<Format>(
+            &[Token::Str("%Y%")],
+            "unterminated directive `%` at byte 2",
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Conversion of parsed fields to the entries of the hash returned by Ruby's
+//! `Date._strptime`.
+
+use super::strptime::ParsedFields;
+
+/// Value of an entry of a [`DateHash`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value<'a> {
+    /// An integer, like the `year` entry.
+    Integer(i64),
+    /// A reduced fraction, for the `sec_fraction` entry.
+    Rational {
+        /// Numerator of the fraction.
+        numerator: i64,
+        /// Positive denominator of the fraction.
+        denominator: i64,
+    },
+    /// A string, for the `zone` and `leftover` entries.
+    String(&'a [u8]),
+}
+
+/// Keys of the entries of a [`DateHash`], in order.
+const KEYS: [&str; 18] = [
+    "year",
+    "mon",
+    "mday",
+    "yday",
+    "wday",
+    "cwyear",
+    "cweek",
+    "cwday",
+    "wnum0",
+    "wnum1",
+    "hour",
+    "min",
+    "sec",
+    "sec_fraction",
+    "seconds",
+    "zone",
+    "offset",
+    "leftover",
+];
+
+/// An iterator over the entries of the hash returned by Ruby's
+/// [`Date._strptime`] for the parsed fields, as `(key, value)` pairs.
+///
+/// The keys are the names of the symbols of the Ruby hash, like `"mon"` for
+/// the month. Only the entries of the parsed fields are returned.
+///
+/// Unlike Ruby, the `zone` entry is only set by `%Z`, and the `offset` entry
+/// is only set by the UTC offset directives, since time zone abbreviations
+/// are not resolved.
+///
+/// [`Date._strptime`]: <https://ruby-doc.org/stdlib-3.1.2/libdoc/date/rdoc/Date.html#method-c-_strptime>
+#[derive(Debug, Clone)]
+pub struct DateHash<'a> {
+    /// Parsed fields.
+    fields: ParsedFields<'a>,
+    /// Remaining input after the end of the format string.
+    leftover: &'a [u8],
+    /// Index of the next key.
+    index: usize,
+}
+
+impl<'a> DateHash<'a> {
+    /// Construct a new `DateHash` for the parsed fields and the remaining
+    /// input.
+    pub(crate) fn new(fields: ParsedFields<'a>, leftover: &'a [u8]) -> Self {
+        Self {
+            fields,
+            leftover,
+            index: 0,
+        }
+    }
+
+    /// Returns the value of an entry, if it is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{date_strptime, ParseOptions, Value};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let hash = date_strptime(b"2024-07-08", b"%F", &ParseOptions::new())?;
+    ///
+    /// assert_eq!(hash.get("mon"), Some(Value::Integer(7)));
+    /// assert_eq!(hash.get("hour"), None);
+    /// assert_eq!(hash.get("unknown"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Value<'a>> {
+        let index = KEYS.iter().position(|&x| x == key)?;
+        self.value(index)
+    }
+
+    /// Returns the value of the entry at an index of the keys, if it is set.
+    fn value(&self, index: usize) -> Option<Value<'a>> {
+        let fields = &self.fields;
+        let integer = |value: Option<i64>| value.map(Value::Integer);
+
+        match *KEYS.get(index)? {
+            "year" => integer(fields.year.map(i64::from)),
+            "mon" => integer(fields.month.map(i64::from)),
+            "mday" => integer(fields.day.map(i64::from)),
+            "yday" => integer(fields.day_of_year.map(i64::from)),
+            "wday" => integer(fields.day_of_week.map(i64::from)),
+            "cwyear" => integer(fields.iso_8601_year.map(i64::from)),
+            "cweek" => integer(fields.iso_8601_week_number.map(i64::from)),
+            "cwday" => integer(fields.day_of_week_from_monday.map(i64::from)),
+            "wnum0" => integer(fields.week_number_from_sunday.map(i64::from)),
+            "wnum1" => integer(fields.week_number_from_monday.map(i64::from)),
+            "hour" => integer(fields.hour.map(i64::from)),
+            "min" => integer(fields.minute.map(i64::from)),
+            "sec" => integer(fields.second.map(i64::from)),
+            "sec_fraction" => fields.nanoseconds.map(|nanoseconds| {
+                const NANOS_PER_SECOND: i64 = 1_000_000_000;
+                let numerator = i64::from(nanoseconds);
+                let divisor = gcd(numerator, NANOS_PER_SECOND);
+                Value::Rational {
+                    numerator: numerator / divisor,
+                    denominator: NANOS_PER_SECOND / divisor,
+                }
+            }),
+            "seconds" => integer(fields.seconds_since_epoch),
+            "zone" => fields.time_zone.map(|zone| Value::String(zone.as_bytes())),
+            "offset" => integer(fields.utc_offset.map(i64::from)),
+            "leftover" if !self.leftover.is_empty() => Some(Value::String(self.leftover)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Iterator for DateHash<'a> {
+    type Item = (&'static str, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&key) = KEYS.get(self.index) {
+            let value = self.value(self.index);
+            self.index += 1;
+            if let Some(value) = value {
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(KEYS.len().saturating_sub(self.index)))
+    }
+}
+
+impl core::iter::FusedIterator for DateHash<'_> {}
+
+/// Compute the greatest common divisor of two non-negative numbers.
+const fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::strptime::parse_partial;
+    use crate::options::ParseOptions;
+
+    #[test]
+    fn test_date_hash() {
+        let input = b"Mon Jul  8 10:00:00 2024 +0200 CEST!";
+        let (fields, leftover) = parse_partial(input, b"%c %z %Z", ParseOptions::new()).unwrap();
+
+        let hash = DateHash::new(fields, leftover);
+        assert!(hash.clone().eq([
+            ("year", Value::Integer(2024)),
+            ("mon", Value::Integer(7)),
+            ("mday", Value::Integer(8)),
+            ("wday", Value::Integer(1)),
+            ("hour", Value::Integer(10)),
+            ("min", Value::Integer(0)),
+            ("sec", Value::Integer(0)),
+            ("zone", Value::String(b"CEST")),
+            ("offset", Value::Integer(7200)),
+            ("leftover", Value::String(b"!")),
+        ]));
+        assert_eq!(hash.get("cwday"), None);
+
+        let fields = ParsedFields {
+            iso_8601_year: Some(2024),
+            iso_8601_week_number: Some(28),
+            day_of_week_from_monday: Some(1),
+            week_number_from_sunday: Some(27),
+            week_number_from_monday: Some(28),
+            day_of_year: Some(190),
+            seconds_since_epoch: Some(-1),
+            nanoseconds: Some(500_000_000),
+            ..ParsedFields::new()
+        };
+        assert!(DateHash::new(fields, b"").eq([
+            ("yday", Value::Integer(190)),
+            ("cwyear", Value::Integer(2024)),
+            ("cweek", Value::Integer(28)),
+            ("cwday", Value::Integer(1)),
+            ("wnum0", Value::Integer(27)),
+            ("wnum1", Value::Integer(28)),
+            (
+                "sec_fraction",
+                Value::Rational {
+                    numerator: 1,
+                    denominator: 2
+                }
+            ),
+            ("seconds", Value::Integer(-1)),
+        ]));
+
+        let fields = ParsedFields {
+            nanoseconds: Some(0),
+            ..ParsedFields::new()
+        };
+        assert_eq!(
+            DateHash::new(fields, b"").get("sec_fraction"),
+            Some(Value::Rational {
+                numerator: 0,
+                denominator: 1
+            })
+        );
+        assert_eq!(DateHash::new(ParsedFields::new(), b"").next(), None);
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(0, 1_000_000_000), 1_000_000_000);
+        assert_eq!(gcd(500_000_000, 1_000_000_000), 500_000_000);
+        assert_eq!(gcd(123_000_000, 1_000_000_000), 1_000_000);
+        assert_eq!(gcd(123_456_789, 1_000_000_000), 1);
+    }
+}
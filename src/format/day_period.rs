@@ -0,0 +1,122 @@
+//! Formatting of the locale-dependent day periods.
+
+use core::str;
+
+use super::{CheckedTime, Flag, Piece, SizeLimiter};
+use crate::{Error, Locale};
+
+/// Day periods of a locale, following the rules of the CLDR.
+struct DayPeriods {
+    /// Name of the day period at exactly midnight, if any.
+    midnight: Option<&'static str>,
+    /// Name of the day period at exactly noon, if any.
+    noon: Option<&'static str>,
+    /// Start hour and name of the day periods, sorted by start hour and
+    /// starting at midnight.
+    periods: &'static [(u8, &'static str)],
+}
+
+/// Day periods in English.
+const ENGLISH: DayPeriods = DayPeriods {
+    midnight: Some("midnight"),
+    noon: Some("noon"),
+    periods: &[
+        (0, "at night"),
+        (6, "in the morning"),
+        (12, "in the afternoon"),
+        (18, "in the evening"),
+        (21, "at night"),
+    ],
+};
+
+/// Day periods in German.
+const GERMAN: DayPeriods = DayPeriods {
+    midnight: Some("Mitternacht"),
+    noon: None,
+    periods: &[
+        (0, "nachts"),
+        (5, "morgens"),
+        (10, "vormittags"),
+        (12, "mittags"),
+        (13, "nachmittags"),
+        (18, "abends"),
+    ],
+};
+
+/// Day periods in Spanish.
+const SPANISH: DayPeriods = DayPeriods {
+    midnight: None,
+    noon: Some("del mediodía"),
+    periods: &[
+        (0, "de la madrugada"),
+        (6, "de la mañana"),
+        (12, "de la tarde"),
+        (20, "de la noche"),
+    ],
+};
+
+impl Locale {
+    /// Returns the day periods of the locale.
+    const fn day_periods(self) -> &'static DayPeriods {
+        match self {
+            Self::English => &ENGLISH,
+            Self::German => &GERMAN,
+            Self::Spanish => &SPANISH,
+        }
+    }
+}
+
+impl DayPeriods {
+    /// Returns the name of the day period of a time of the day.
+    fn name(&self, hour: u8, minute: u8, second: u8) -> &'static str {
+        let exact = minute == 0 && second == 0;
+        match (hour, exact, self.midnight, self.noon) {
+            (0, true, Some(midnight), _) => midnight,
+            (12, true, _, Some(noon)) => noon,
+            _ => {
+                let index = self.periods.partition_point(|&(start, _)| start <= hour);
+                self.periods[index - 1].1
+            }
+        }
+    }
+}
+
+impl Piece {
+    /// Format the day period of a time in the provided locale.
+    pub(super) fn format_day_period(
+        &self,
+        f: &mut SizeLimiter<'_>,
+        time: &(impl CheckedTime + ?Sized),
+        locale: Locale,
+    ) -> Result<(), Error> {
+        let name = locale
+            .day_periods()
+            .name(time.hour()?, time.minute()?, time.second()?);
+
+        if !self.flags.contains(Flag::UpperCase) {
+            return self.format_string(f, name);
+        }
+
+        // Converting ASCII letters to upper case keeps the string valid UTF-8
+        let mut buf = [0u8; 32];
+        let upper = &mut buf[..name.len()];
+        upper.copy_from_slice(name.as_bytes());
+        upper.make_ascii_uppercase();
+        self.format_string(f, str::from_utf8(upper).unwrap_or(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_periods_are_sorted() {
+        for locale in [Locale::English, Locale::German, Locale::Spanish] {
+            let periods = locale.day_periods().periods;
+            assert_eq!(periods[0].0, 0);
+            assert!(periods.windows(2).all(|w| w[0].0 < w[1].0));
+            assert!(periods.iter().all(|&(_, name)| name.len() <= 32));
+        }
+    }
+}
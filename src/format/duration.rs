@@ -0,0 +1,460 @@
+//! Formatting of elapsed times with clock-style directives.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use super::utils::{Cursor, SizeLimiter};
+use super::write::{FmtWrite, Write};
+use super::{parse_width, Padding, Piece};
+use crate::Error;
+
+/// A signed [`Duration`].
+///
+/// This type is constructed from a [`Duration`], or from a signed number of
+/// seconds and a number of nanoseconds with [`SignedDuration::new`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignedDuration {
+    /// Whether the duration is negative.
+    is_negative: bool,
+    /// Absolute value of the duration.
+    abs: Duration,
+}
+
+impl SignedDuration {
+    /// Construct a new `SignedDuration` from a signed number of seconds and a
+    /// number of nanoseconds, which are added to the seconds.
+    ///
+    /// Returns [`None`] if the number of nanoseconds is not in
+    /// `0..=999_999_999`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::duration::SignedDuration;
+    ///
+    /// let duration = SignedDuration::new(-2, 500_000_000).unwrap();
+    /// assert!(duration.is_negative());
+    /// assert_eq!(duration.abs().as_millis(), 1500);
+    /// ```
+    #[must_use]
+    pub const fn new(seconds: i64, nanoseconds: u32) -> Option<Self> {
+        if nanoseconds > 999_999_999 {
+            return None;
+        }
+
+        let abs = if seconds >= 0 {
+            Duration::new(seconds.unsigned_abs(), nanoseconds)
+        } else if nanoseconds == 0 {
+            Duration::new(seconds.unsigned_abs(), 0)
+        } else {
+            Duration::new(seconds.unsigned_abs() - 1, 1_000_000_000 - nanoseconds)
+        };
+
+        Some(Self {
+            is_negative: seconds < 0,
+            abs,
+        })
+    }
+
+    /// Returns `true` if the duration is strictly negative.
+    #[must_use]
+    pub const fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+
+    /// Returns the absolute value of the duration.
+    #[must_use]
+    pub const fn abs(&self) -> Duration {
+        self.abs
+    }
+}
+
+impl From<Duration> for SignedDuration {
+    fn from(duration: Duration) -> Self {
+        Self {
+            is_negative: false,
+            abs: duration,
+        }
+    }
+}
+
+/// Formatter for elapsed times, using clock-style directives.
+///
+/// The following directives are supported, with the same flags and width as
+/// the [`strftime`](crate) directives:
+///
+/// | Directive | Description                                                 |
+/// |-----------|-------------------------------------------------------------|
+/// | `%d`      | Number of whole days.                                       |
+/// | `%H`      | Hours in `00..=23`, or total hours in total hours mode.     |
+/// | `%M`      | Minutes in `00..=59`.                                       |
+/// | `%S`      | Seconds in `00..=59`.                                       |
+/// | `%L`      | Truncated milliseconds, the width sets the number of digits.|
+/// | `%N`      | Truncated nanoseconds, the width sets the number of digits. |
+/// | `%s`      | Total number of seconds.                                    |
+/// | `%n`      | Newline character `'\n'`.                                   |
+/// | `%t`      | Tab character `'\t'`.                                       |
+/// | `%%`      | Literal `'%'` character.                                    |
+///
+/// Other directives are copied verbatim to the output. A negative duration is
+/// formatted with a `-` sign before its first numeric directive.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use strftime::duration::{DurationFormatter, SignedDuration};
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let duration = Duration::new(93_784, 5_000_000);
+/// let mut buf = [0u8; 32];
+///
+/// let formatter = DurationFormatter::new("%dd %H:%M:%S.%L");
+/// assert_eq!(formatter.buffered(duration, &mut buf)?, b"1d 02:03:04.005");
+///
+/// let formatter = DurationFormatter::new("%H:%M:%S").with_total_hours();
+/// assert_eq!(formatter.buffered(duration, &mut buf)?, b"26:03:04");
+///
+/// let duration = SignedDuration::new(-90, 0).unwrap();
+/// let formatter = DurationFormatter::new("%M:%S");
+/// assert_eq!(formatter.buffered(duration, &mut buf)?, b"-01:30");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DurationFormatter<'f> {
+    /// Format string.
+    format: &'f [u8],
+    /// Whether `%H` formats the total number of hours.
+    total_hours: bool,
+}
+
+impl<'f> DurationFormatter<'f> {
+    /// Construct a new `DurationFormatter` with the specified format string.
+    #[must_use]
+    pub const fn new(format: &'f str) -> Self {
+        Self {
+            format: format.as_bytes(),
+            total_hours: false,
+        }
+    }
+
+    /// Format the total number of hours with `%H`, instead of the hours in
+    /// `00..=23`.
+    #[must_use]
+    pub const fn with_total_hours(mut self) -> Self {
+        self.total_hours = true;
+        self
+    }
+
+    /// Format a duration, writing to the provided byte slice.
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn buffered<'a>(
+        &self,
+        duration: impl Into<SignedDuration>,
+        buf: &'a mut [u8],
+    ) -> Result<&'a mut [u8], Error> {
+        let len = buf.len();
+
+        let mut cursor = &mut buf[..];
+        self.fmt(duration.into(), &mut cursor)?;
+        let remaining_len = cursor.len();
+
+        Ok(&mut buf[..len - remaining_len])
+    }
+
+    /// Format a duration, writing to the provided [`core::fmt::Write`] object.
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn write(
+        &self,
+        duration: impl Into<SignedDuration>,
+        buf: &mut dyn fmt::Write,
+    ) -> Result<(), Error> {
+        self.fmt(duration.into(), &mut FmtWrite::new(buf))
+    }
+
+    /// Format a duration, returning a newly allocated [`String`].
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn string(&self, duration: impl Into<SignedDuration>) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        self.fmt(duration.into(), &mut buf)?;
+        Ok(String::from_utf8(buf).expect("formatted string should be valid UTF-8"))
+    }
+
+    /// Format a duration using the format string.
+    fn fmt(&self, duration: SignedDuration, buf: &mut dyn Write) -> Result<(), Error> {
+        // Use the same size limit as for times
+        let size_limit = self.format.len().saturating_mul(512 * 1024);
+        let mut f = SizeLimiter::new(buf, size_limit);
+
+        let seconds = duration.abs.as_secs();
+        let nanoseconds = duration.abs.subsec_nanos();
+        let hours = seconds / 3600;
+
+        let mut pending_sign = duration.is_negative;
+        let mut cursor = Cursor::new(self.format);
+
+        loop {
+            f.write_all(cursor.read_until(|&x| x == b'%'))?;
+
+            let remaining_before = cursor.remaining();
+
+            // Read the '%' character
+            if cursor.next().is_none() {
+                return Ok(());
+            }
+
+            let directive = if let Some(directive) = parse_directive(&mut cursor)? {
+                directive
+            } else {
+                // Unsupported directives are copied verbatim
+                let remaining_after = cursor.remaining();
+                f.write_all(&remaining_before[..remaining_before.len() - remaining_after.len()])?;
+                continue;
+            };
+
+            let value = match directive.spec {
+                b'n' => {
+                    f.write_all(b"\n")?;
+                    continue;
+                }
+                b't' => {
+                    f.write_all(b"\t")?;
+                    continue;
+                }
+                b'%' => {
+                    f.write_all(b"%")?;
+                    continue;
+                }
+                b'L' | b'N' => None,
+                b'd' => Some((seconds / 86_400, 1)),
+                b'H' if self.total_hours => Some((hours, 2)),
+                b'H' => Some((hours % 24, 2)),
+                b'M' => Some((seconds / 60 % 60, 2)),
+                b'S' => Some((seconds % 60, 2)),
+                _ => Some((seconds, 1)),
+            };
+
+            if pending_sign {
+                f.write_all(b"-")?;
+                pending_sign = false;
+            }
+
+            if let Some((value, default_width)) = value {
+                directive.write_num(&mut f, value, default_width)?;
+            } else {
+                let default_digits = if directive.spec == b'L' { 3 } else { 9 };
+                let digits = directive.width.unwrap_or(default_digits);
+                write_fraction(&mut f, nanoseconds, digits)?;
+            }
+        }
+    }
+}
+
+/// Parsed duration directive.
+struct Directive {
+    /// Optional width.
+    width: Option<usize>,
+    /// Padding method.
+    padding: Padding,
+    /// Whether the `-` flag is set.
+    left_padding: bool,
+    /// Conversion specifier.
+    spec: u8,
+}
+
+impl Directive {
+    /// Write a number with the directive padding.
+    fn write_num(
+        &self,
+        f: &mut SizeLimiter<'_>,
+        value: u64,
+        default_width: usize,
+    ) -> Result<(), Error> {
+        if self.left_padding {
+            return write!(f, "{value}");
+        }
+
+        let width = self.width.unwrap_or(default_width);
+        Piece::write_padded(f, value, width, self.padding)
+    }
+}
+
+/// Parse a duration directive after its `%` character.
+///
+/// Returns [`None`] if the directive is not supported.
+fn parse_directive(cursor: &mut Cursor<'_>) -> Result<Option<Directive>, Error> {
+    const SPECS: &[u8] = b"%HLMNSdnst";
+
+    let mut padding = Padding::Zeros;
+    let mut left_padding = false;
+
+    loop {
+        match cursor.remaining().first() {
+            Some(&b'-') => left_padding = true,
+            Some(&b'_') => padding = Padding::Spaces,
+            Some(&b'0') => padding = Padding::Zeros,
+            _ => break,
+        }
+        cursor.next();
+    }
+
+    let width_digits = cursor.read_while(u8::is_ascii_digit);
+    let width = if width_digits.is_empty() {
+        None
+    } else {
+        match parse_width(width_digits) {
+            Some(width) => Some(width),
+            None => return Ok(None),
+        }
+    };
+
+    match cursor.next() {
+        Some(spec) if SPECS.contains(&spec) => Ok(Some(Directive {
+            width,
+            padding,
+            left_padding,
+            spec,
+        })),
+        Some(_) => Ok(None),
+        None => Err(Error::InvalidFormatString),
+    }
+}
+
+/// Write the truncated fractional seconds with the specified number of digits.
+fn write_fraction(f: &mut SizeLimiter<'_>, nanoseconds: u32, digits: usize) -> Result<(), Error> {
+    if digits <= 9 {
+        let value = nanoseconds / 10_u32.pow(9 - digits as u32);
+        write!(f, "{value:0digits$}")
+    } else {
+        write!(f, "{nanoseconds:09}")?;
+        Piece::write_repeated(f, Padding::Zeros, digits - 9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(
+        formatter: DurationFormatter<'_>,
+        duration: impl Into<SignedDuration>,
+        expected: &str,
+    ) {
+        let mut buf = [0u8; 64];
+        let result = formatter.buffered(duration, &mut buf).unwrap();
+        assert_eq!(core::str::from_utf8(result).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_signed_duration() {
+        let duration = SignedDuration::new(-1, 250_000_000).unwrap();
+        assert!(duration.is_negative());
+        assert_eq!(duration.abs(), Duration::new(0, 750_000_000));
+
+        let duration = SignedDuration::new(i64::MIN, 0).unwrap();
+        assert_eq!(duration.abs(), Duration::new(1 << 63, 0));
+
+        assert!(!SignedDuration::new(0, 0).unwrap().is_negative());
+        assert!(SignedDuration::new(0, 1_000_000_000).is_none());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        let duration = Duration::new(2 * 86_400 + 3 * 3600 + 4 * 60 + 5, 123_456_789);
+
+        check(
+            DurationFormatter::new("%d %H %M %S"),
+            duration,
+            "2 03 04 05",
+        );
+        check(
+            DurationFormatter::new("%H").with_total_hours(),
+            duration,
+            "51",
+        );
+        check(
+            DurationFormatter::new("%s %L %N"),
+            duration,
+            "183845 123 123456789",
+        );
+        check(
+            DurationFormatter::new("%1N %12N"),
+            duration,
+            "1 123456789000",
+        );
+        check(
+            DurationFormatter::new("%-H %_3M %05S"),
+            duration,
+            "3   4 00005",
+        );
+        check(
+            DurationFormatter::new("%% %n%t %Y %-q"),
+            duration,
+            "% \n\t %Y %-q",
+        );
+        check(DurationFormatter::new(""), duration, "");
+    }
+
+    #[test]
+    fn test_format_negative_duration() {
+        let duration = SignedDuration::new(-3661, 0).unwrap();
+
+        check(DurationFormatter::new("T%H:%M:%S"), duration, "T-01:01:01");
+        check(DurationFormatter::new("%%%N"), duration, "%-000000000");
+    }
+
+    #[test]
+    fn test_format_duration_errors() {
+        struct Sink;
+
+        impl fmt::Write for Sink {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                Ok(())
+            }
+        }
+
+        let mut buf = [0u8; 4];
+        let formatter = DurationFormatter::new("%H:%M:%S");
+        let result = formatter.buffered(Duration::new(0, 0), &mut buf);
+        assert!(matches!(result, Err(Error::WriteZero)));
+
+        let formatter = DurationFormatter::new("%H%");
+        let result = formatter.buffered(Duration::new(0, 0), &mut buf);
+        assert!(matches!(result, Err(Error::InvalidFormatString)));
+
+        let formatter = DurationFormatter::new("%100000000N");
+        let result = formatter.write(Duration::new(0, 0), &mut Sink);
+        assert!(matches!(result, Err(Error::FormattedStringTooLarge { .. })));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_format_duration_string() {
+        let mut s = String::new();
+        DurationFormatter::new("%M:%S")
+            .write(Duration::from_secs(61), &mut s)
+            .unwrap();
+        assert_eq!(s, "01:01");
+
+        let s = DurationFormatter::new("%M:%S").string(Duration::from_secs(61));
+        assert_eq!(s.unwrap(), "01:01");
+    }
+}
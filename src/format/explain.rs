@@ -0,0 +1,439 @@
+//! Human-readable descriptions of the directives of a format string.
+
+use core::fmt;
+
+use super::utils::Cursor;
+use super::{parse_spec, Flag, Flags, Padding as SpecPadding, Piece, Spec};
+use crate::{Dialect, Error, Snippet};
+
+/// Metadata of a formatting specifier.
+struct SpecInfo {
+    /// Human-readable description.
+    description: &'static str,
+    /// Range of the formatted values, for numeric specifiers.
+    range: Option<&'static str>,
+    /// Padding used when no padding flag is set.
+    padding: Padding,
+    /// Whether the specifier is numeric.
+    numeric: bool,
+    /// Whether the width sets the number of digits instead of the padding.
+    digits: bool,
+}
+
+impl SpecInfo {
+    /// Metadata of a numeric specifier.
+    const fn numeric(description: &'static str, range: &'static str, padding: Padding) -> Self {
+        Self {
+            description,
+            range: Some(range),
+            padding,
+            numeric: true,
+            digits: false,
+        }
+    }
+
+    /// Metadata of a numeric specifier without a bounded range.
+    const fn unbounded(description: &'static str) -> Self {
+        Self {
+            description,
+            range: None,
+            padding: Padding::Zeros,
+            numeric: true,
+            digits: false,
+        }
+    }
+
+    /// Metadata of a non-numeric specifier, which is blank-padded when a
+    /// width is specified.
+    const fn text(description: &'static str) -> Self {
+        Self {
+            description,
+            range: None,
+            padding: Padding::Spaces,
+            numeric: false,
+            digits: false,
+        }
+    }
+
+    /// Metadata of a fractional seconds specifier.
+    #[cfg(feature = "subsec")]
+    const fn digits(description: &'static str) -> Self {
+        Self {
+            description,
+            range: None,
+            padding: Padding::None,
+            numeric: false,
+            digits: true,
+        }
+    }
+}
+
+/// Returns the metadata of a formatting specifier.
+const fn spec_info(spec: Spec) -> SpecInfo {
+    use Padding::{Spaces, Zeros};
+
+    match spec {
+        Spec::Year4Digits => SpecInfo::unbounded("year with century"),
+        Spec::YearDiv100 => SpecInfo::unbounded("century (year / 100)"),
+        Spec::YearRem100 => SpecInfo::numeric("year without century", "00..99", Zeros),
+        Spec::Month => SpecInfo::numeric("month of the year", "1..12", Zeros),
+        Spec::MonthName => SpecInfo::text("full month name"),
+        Spec::MonthNameAbbr => SpecInfo::text("abbreviated month name"),
+        Spec::MonthDayZero | Spec::MonthDaySpace => {
+            let padding = match spec {
+                Spec::MonthDayZero => Zeros,
+                _ => Spaces,
+            };
+            SpecInfo::numeric("day of the month", "1..31", padding)
+        }
+        Spec::YearDay => SpecInfo::numeric("day of the year", "1..366", Zeros),
+        Spec::Hour24hZero => SpecInfo::numeric("hour of the day (24-hour clock)", "0..23", Zeros),
+        Spec::Hour24hSpace => SpecInfo::numeric("hour of the day (24-hour clock)", "0..23", Spaces),
+        Spec::Hour12hZero => SpecInfo::numeric("hour of the day (12-hour clock)", "1..12", Zeros),
+        Spec::Hour12hSpace => SpecInfo::numeric("hour of the day (12-hour clock)", "1..12", Spaces),
+        Spec::MeridianLower => SpecInfo::text("meridian indicator, lowercase (am or pm)"),
+        Spec::MeridianUpper => SpecInfo::text("meridian indicator, uppercase (AM or PM)"),
+        Spec::DayPeriod => SpecInfo::text("day period (in the morning, noon, ...)"),
+        Spec::Minute => SpecInfo::numeric("minute of the hour", "0..59", Zeros),
+        Spec::Second => SpecInfo::numeric("second of the minute", "0..60", Zeros),
+        #[cfg(feature = "subsec")]
+        Spec::MilliSecond => SpecInfo::digits("fractional seconds, 3 digits by default"),
+        #[cfg(feature = "subsec")]
+        Spec::FractionalSecond => SpecInfo::digits("fractional seconds, 9 digits by default"),
+        Spec::TimeZoneOffsetHourMinute => SpecInfo::text("UTC offset (+hhmm)"),
+        Spec::TimeZoneOffsetHourMinuteColon => SpecInfo::text("UTC offset (+hh:mm)"),
+        Spec::TimeZoneOffsetHourMinuteSecondColon => SpecInfo::text("UTC offset (+hh:mm:ss)"),
+        Spec::TimeZoneOffsetColonMinimal => SpecInfo::text("UTC offset (+hh[:mm[:ss]])"),
+        Spec::TimeZoneOffsetHourMinuteSecond => SpecInfo::text("UTC offset (+hhmmss)"),
+        Spec::TimeZoneName => SpecInfo::text("time zone abbreviation"),
+        Spec::WeekDayName => SpecInfo::text("full weekday name"),
+        Spec::WeekDayNameAbbr => SpecInfo::text("abbreviated weekday name"),
+        Spec::WeekDayFrom1 => SpecInfo::numeric("day of the week from Monday", "1..7", Zeros),
+        Spec::WeekDayFrom0 => SpecInfo::numeric("day of the week from Sunday", "0..6", Zeros),
+        Spec::YearIso8601 => SpecInfo::unbounded("ISO 8601 week-based year"),
+        Spec::YearIso8601Rem100 => {
+            SpecInfo::numeric("ISO 8601 week-based year without century", "00..99", Zeros)
+        }
+        Spec::WeekNumberIso8601 => SpecInfo::numeric("ISO 8601 week number", "1..53", Zeros),
+        Spec::WeekNumberFromSunday => {
+            SpecInfo::numeric("week number, weeks starting on Sunday", "0..53", Zeros)
+        }
+        Spec::WeekNumberFromMonday => {
+            SpecInfo::numeric("week number, weeks starting on Monday", "0..53", Zeros)
+        }
+        Spec::SecondsSinceEpoch => SpecInfo::unbounded("seconds since 1970-01-01 00:00:00 UTC"),
+        #[cfg(feature = "subsec")]
+        Spec::SecondsSinceEpochFractional => SpecInfo::digits(
+            "seconds since 1970-01-01 00:00:00 UTC with 9 fractional digits by default",
+        ),
+        Spec::Newline => SpecInfo::text("newline"),
+        Spec::Tabulation => SpecInfo::text("tab"),
+        Spec::Percent => SpecInfo::text("literal %"),
+        Spec::CombinationDateTime => SpecInfo::text("date and time (%a %b %e %H:%M:%S %Y)"),
+        Spec::CombinationDate => SpecInfo::text("date (%m/%d/%y)"),
+        Spec::CombinationIso8601 => SpecInfo::text("ISO 8601 date (%Y-%m-%d)"),
+        Spec::CombinationVmsDate => SpecInfo::text("VMS date (%e-%^b-%4Y)"),
+        Spec::CombinationTime12h => SpecInfo::text("12-hour time (%I:%M:%S %p)"),
+        Spec::CombinationHourMinute24h => SpecInfo::text("24-hour time without seconds (%H:%M)"),
+        Spec::CombinationTime24h => SpecInfo::text("24-hour time (%H:%M:%S)"),
+    }
+}
+
+/// Padding of a formatted directive.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// No padding.
+    None,
+    /// Padding with spaces.
+    Spaces,
+    /// Padding with zeros.
+    Zeros,
+}
+
+/// A piece of a format string, as described by [`explain`].
+///
+/// [`explain`]: crate::explain()
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item<'f> {
+    /// Text which is copied verbatim to the output, including invalid
+    /// directives.
+    Literal(&'f [u8]),
+    /// A formatting directive.
+    Directive(Directive<'f>),
+}
+
+/// Description of a formatting directive.
+///
+/// The [`Display`](fmt::Display) implementation writes a human-readable
+/// description, like `"day of the month, 1..31, no padding"` for `"%-d"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Directive<'f> {
+    /// Text of the directive in the format string.
+    text: &'f [u8],
+    /// Formatting specifier.
+    spec: Spec,
+    /// Padding method.
+    padding: Padding,
+    /// Optional width.
+    width: Option<usize>,
+    /// Combination of formatting flags.
+    flags: Flags,
+}
+
+impl<'f> Directive<'f> {
+    /// Construct a new `Directive` from its text and parsed piece.
+    fn new(text: &'f [u8], piece: &Piece) -> Self {
+        let padding = if piece.flags.contains(Flag::LeftPadding) {
+            Padding::None
+        } else {
+            match piece.padding {
+                SpecPadding::Left => spec_info(piece.spec).padding,
+                SpecPadding::Spaces => Padding::Spaces,
+                SpecPadding::Zeros => Padding::Zeros,
+            }
+        };
+
+        Self {
+            text,
+            spec: piece.spec,
+            padding,
+            width: piece.width,
+            flags: piece.flags,
+        }
+    }
+
+    /// Returns the text of the directive in the format string.
+    #[must_use]
+    pub const fn text(&self) -> &'f [u8] {
+        self.text
+    }
+
+    /// Returns the description of the formatting specifier.
+    #[must_use]
+    pub const fn description(&self) -> &'static str {
+        spec_info(self.spec).description
+    }
+
+    /// Returns the range of the formatted values for numeric specifiers, like
+    /// `"1..31"` for `"%d"`.
+    #[must_use]
+    pub const fn range(&self) -> Option<&'static str> {
+        spec_info(self.spec).range
+    }
+
+    /// Returns the padding method, taking the padding flags into account.
+    #[must_use]
+    pub const fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Returns the width of the directive, if any.
+    ///
+    /// For `"%L"` and `"%N"`, the width is the number of digits.
+    #[must_use]
+    pub const fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    /// Returns `true` if the `^` flag is set.
+    #[must_use]
+    pub fn is_upper_case(&self) -> bool {
+        self.flags.contains(Flag::UpperCase)
+    }
+
+    /// Returns `true` if the `#` flag is set.
+    #[must_use]
+    pub fn is_change_case(&self) -> bool {
+        self.flags.contains(Flag::ChangeCase)
+    }
+}
+
+impl fmt::Display for Directive<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let info = spec_info(self.spec);
+
+        f.write_str(info.description)?;
+
+        if let Some(range) = info.range {
+            write!(f, ", {range}")?;
+        }
+
+        match self.width {
+            Some(width) if info.digits => write!(f, ", {width} digits")?,
+            Some(width) => write!(f, ", width {width}")?,
+            None => {}
+        }
+
+        if !info.digits && (info.numeric || self.width.is_some()) {
+            f.write_str(match self.padding {
+                Padding::None => ", no padding",
+                Padding::Spaces => ", blank-padded",
+                Padding::Zeros => ", zero-padded",
+            })?;
+        }
+
+        if self.is_upper_case() {
+            f.write_str(", uppercase")?;
+        }
+        if self.is_change_case() {
+            f.write_str(", swapped case")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Iterator over the pieces of a format string, created by [`explain`].
+///
+/// [`explain`]: crate::explain()
+#[derive(Debug, Clone)]
+pub struct Explain<'f> {
+    /// Remaining format string.
+    cursor: Cursor<'f>,
+    /// Length of the format string.
+    len: usize,
+    /// Whether an error was returned.
+    failed: bool,
+}
+
+impl<'f> Explain<'f> {
+    /// Construct a new `Explain` iterator.
+    pub(crate) fn new(format: &'f [u8]) -> Self {
+        Self {
+            cursor: Cursor::new(format),
+            len: format.len(),
+            failed: false,
+        }
+    }
+}
+
+impl<'f> Iterator for Explain<'f> {
+    type Item = Result<Item<'f>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.cursor.remaining().is_empty() {
+            return None;
+        }
+
+        let literal = self.cursor.read_until(|&x| x == b'%');
+        if !literal.is_empty() {
+            return Some(Ok(Item::Literal(literal)));
+        }
+
+        let remaining_before = self.cursor.remaining();
+
+        // Read the '%' character
+        self.cursor.next();
+
+        let piece = if let Ok(piece) = parse_spec(&mut self.cursor, Dialect::Ruby) {
+            piece
+        } else {
+            self.failed = true;
+            return Some(Err(Error::UnterminatedDirective {
+                position: self.len - remaining_before.len(),
+                snippet: Snippet::new(remaining_before),
+            }));
+        };
+
+        let remaining_after = self.cursor.remaining();
+        let text = &remaining_before[..remaining_before.len() - remaining_after.len()];
+
+        Some(Ok(match piece {
+            Some(piece) => Item::Directive(Directive::new(text, &piece)),
+            None => Item::Literal(text),
+        }))
+    }
+}
+
+impl core::iter::FusedIterator for Explain<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    fn describe(format: &str) -> alloc::string::String {
+        use alloc::string::ToString;
+
+        match Explain::new(format.as_bytes()).next() {
+            Some(Ok(Item::Directive(directive))) => directive.to_string(),
+            item => panic!("unexpected item for {:?}: {:?}", format, item),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe("%d"), "day of the month, 1..31, zero-padded");
+        assert_eq!(describe("%-d"), "day of the month, 1..31, no padding");
+        assert_eq!(
+            describe("%_3d"),
+            "day of the month, 1..31, width 3, blank-padded"
+        );
+        assert_eq!(describe("%e"), "day of the month, 1..31, blank-padded");
+        assert_eq!(describe("%0e"), "day of the month, 1..31, zero-padded");
+        assert_eq!(describe("%B"), "full month name");
+        assert_eq!(
+            describe("%^10B"),
+            "full month name, width 10, blank-padded, uppercase"
+        );
+        assert_eq!(
+            describe("%#p"),
+            "meridian indicator, uppercase (AM or PM), swapped case"
+        );
+        #[cfg(feature = "subsec")]
+        assert_eq!(
+            describe("%6N"),
+            "fractional seconds, 9 digits by default, 6 digits"
+        );
+        assert_eq!(describe("%::z"), "UTC offset (+hh:mm:ss)");
+        assert_eq!(describe("%EY"), "year with century, zero-padded");
+        assert_eq!(
+            describe("%s"),
+            "seconds since 1970-01-01 00:00:00 UTC, zero-padded"
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut items = Explain::new(b"at %H:%M%q %%");
+
+        assert!(matches!(items.next(), Some(Ok(Item::Literal(b"at ")))));
+
+        let hour = match items.next() {
+            Some(Ok(Item::Directive(directive))) => directive,
+            item => panic!("unexpected item: {:?}", item),
+        };
+        assert_eq!(hour.text(), b"%H");
+        assert_eq!(hour.description(), "hour of the day (24-hour clock)");
+        assert_eq!(hour.range(), Some("0..23"));
+        assert_eq!(hour.padding(), Padding::Zeros);
+        assert_eq!(hour.width(), None);
+        assert!(!hour.is_upper_case());
+        assert!(!hour.is_change_case());
+
+        assert!(matches!(items.next(), Some(Ok(Item::Literal(b":")))));
+        assert!(matches!(items.next(), Some(Ok(Item::Directive(d))) if d.text() == b"%M"));
+        assert!(matches!(items.next(), Some(Ok(Item::Literal(b"%q")))));
+        assert!(matches!(items.next(), Some(Ok(Item::Literal(b" ")))));
+        assert!(matches!(items.next(), Some(Ok(Item::Directive(d))) if d.text() == b"%%"));
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn test_explain_invalid() {
+        let mut items = Explain::new(b"%Y%-");
+
+        assert!(matches!(items.next(), Some(Ok(Item::Directive(_)))));
+        assert!(matches!(
+            items.next(),
+            Some(Err(Error::UnterminatedDirective { position: 2, snippet, .. }))
+                if snippet.as_bytes() == b"%-"
+        ));
+        assert!(items.next().is_none());
+
+        assert!(Explain::new(b"").next().is_none());
+    }
+}
@@ -0,0 +1,101 @@
+//! Formatting of date directives in non-Gregorian calendars.
+
+use icu_calendar::{AnyCalendar, AnyCalendarKind, Date};
+
+use super::{CheckedTime, Piece, SizeLimiter, Spec};
+use crate::{Calendar, Error};
+
+impl Calendar {
+    /// Returns the corresponding ICU calendar kind.
+    const fn kind(self) -> AnyCalendarKind {
+        match self {
+            Self::Buddhist => AnyCalendarKind::Buddhist,
+            Self::Hebrew => AnyCalendarKind::Hebrew,
+            Self::Hijri => AnyCalendarKind::IslamicCivil,
+            Self::Japanese => AnyCalendarKind::Japanese,
+        }
+    }
+}
+
+impl Piece {
+    /// Format a date directive in the provided calendar.
+    ///
+    /// Returns `false` if the directive doesn't depend on the calendar.
+    pub(super) fn fmt_calendar(
+        &self,
+        f: &mut SizeLimiter<'_>,
+        time: &(impl CheckedTime + ?Sized),
+        calendar: Calendar,
+    ) -> Result<bool, Error> {
+        if !matches!(
+            self.spec,
+            Spec::Year4Digits
+                | Spec::Month
+                | Spec::MonthDayZero
+                | Spec::MonthDaySpace
+                | Spec::YearDay
+        ) {
+            return Ok(false);
+        }
+
+        let date = Date::try_new_iso_date(time.year(), time.month()?, time.day()?)
+            .map_err(|_| Error::InvalidTime)?
+            .to_calendar(AnyCalendar::new(calendar.kind()));
+
+        match self.spec {
+            Spec::Year4Digits => {
+                let year = date.year().number;
+                let default_width = if year < 0 { 5 } else { 4 };
+                self.format_num_zeros(f, year, default_width)?;
+            }
+            Spec::Month => self.format_num_zeros(f, date.month().ordinal, 2)?,
+            Spec::MonthDayZero => self.format_num_zeros(f, date.day_of_month().0, 2)?,
+            Spec::MonthDaySpace => self.format_num_spaces(f, date.day_of_month().0, 2)?,
+            // Japanese years follow the Gregorian year, except that they restart
+            // with each era, so use the Gregorian day of the year
+            _ if calendar == Calendar::Japanese => {
+                self.format_num_zeros(f, time.day_of_year()?, 3)?;
+            }
+            _ => self.format_num_zeros(f, date.day_of_year_info().day_of_year, 3)?,
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::strftime_with_options;
+    use crate::{Calendar, FormatOptions, Time};
+
+    include!("../mock.rs.in");
+
+    fn check(calendar: Calendar, (year, month, day): (i32, u8, u8), expected: &str) {
+        let time = MockTime {
+            year,
+            month,
+            day,
+            day_of_year: 71,
+            ..Default::default()
+        };
+        let options = FormatOptions::new().with_calendar(calendar);
+        let result = strftime_with_options(&time, "%Y/%m/%d %e %j %B", &options).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_calendars() {
+        check(Calendar::Buddhist, (2024, 3, 11), "2567/03/11 11 071 March");
+        check(Calendar::Hebrew, (2024, 3, 11), "5784/07/01  1 178 March");
+        check(Calendar::Hijri, (2024, 3, 11), "1445/09/01  1 237 March");
+        check(Calendar::Japanese, (2024, 3, 11), "0006/03/11 11 071 March");
+    }
+
+    #[test]
+    fn test_invalid_date() {
+        let time = MockTime::new(2023, 2, 29, 0, 0, 0, 0, 3, 60, 0, true, 0, "UTC");
+        let options = FormatOptions::new().with_calendar(Calendar::Hebrew);
+        assert!(strftime_with_options(&time, "%H", &options).is_ok());
+        assert!(strftime_with_options(&time, "%Y", &options).is_err());
+    }
+}
@@ -1,25 +1,49 @@
 //! Module containing the formatting logic.
 
 mod assert;
+pub(crate) mod capacity;
 mod check;
+#[cfg(feature = "alloc")]
+pub(crate) mod compiled;
+pub(crate) mod date_hash;
+mod day_period;
+pub(crate) mod duration;
+pub(crate) mod explain;
+#[cfg(feature = "icu")]
+mod icu;
+pub(crate) mod pieces;
+pub(crate) mod range;
+pub(crate) mod scan;
+pub(crate) mod strptime;
 mod utils;
-mod week;
+pub(crate) mod validate;
+pub(crate) mod week;
 mod write;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::fmt;
-use core::num::IntErrorKind;
+use core::slice;
 use core::str;
 
-use crate::Error;
+use crate::{
+    Dialect, Error, FormatOptions, FormatStats, Locale, NaivePolicy, OffsetPolicy, Snippet,
+};
 use assert::{assert_sorted, assert_sorted_elem_0, assert_to_ascii_uppercase};
-use check::CheckedTime;
-use utils::{Cursor, SizeLimiter};
+use check::{CheckedTime, OffsetCheckedTime};
+pub(crate) use utils::SizeLimiter;
+use utils::{Cursor, StackBuffer};
 use week::{iso_8601_year_and_week_number, week_number, WeekStart};
-use write::Write;
+pub(crate) use write::CountWrite;
+pub(crate) use write::Write;
 
 pub(crate) use write::FmtWrite;
 #[cfg(feature = "std")]
 pub(crate) use write::IoWrite;
+pub(crate) use write::JsonWrite;
+pub(crate) use write::Utf16SliceWrite;
+#[cfg(feature = "alloc")]
+pub(crate) use write::Utf16VecWrite;
 
 /// Alias to a `c_int`.
 #[cfg(feature = "std")]
@@ -90,28 +114,48 @@ const _: () = {
 
 /// Formatting flag.
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Flag {
-    /// Use left padding, removing all other padding options in most cases.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Flag {
+    /// `-`: Use left padding, removing all other padding options in most
+    /// cases.
     LeftPadding = 1 << 0,
-    /// Change case for a string value.
+    /// `#`: Change case for a string value.
     ChangeCase = 1 << 1,
-    /// Convert a string value to uppercase.
+    /// `^`: Convert a string value to uppercase.
     UpperCase = 1 << 2,
+    /// `.`: Truncate a string value to the width, with
+    /// [`Dialect::Extensions`].
+    Truncate = 1 << 3,
+    /// `<`: Left-justify a string value within the width, padding with spaces
+    /// on the right, with [`Dialect::Extensions`].
+    LeftJustify = 1 << 4,
+    /// `'`: Group the digits of a numerical value by thousands, with
+    /// [`Dialect::Extensions`].
+    Grouping = 1 << 5,
+    /// `*`: Take the width from the width arguments, with
+    /// [`Dialect::Extensions`].
+    DynamicWidth = 1 << 6,
 }
 
 /// Combination of formatting flags.
-#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
-struct Flags(u8);
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Flags(u8);
 
 impl Flags {
     /// Checks if a flag is set.
     #[must_use]
-    fn contains(self, flag: Flag) -> bool {
+    pub fn contains(self, flag: Flag) -> bool {
         let flag = flag as u8;
         (self.0 & flag) == flag
     }
 
+    /// Checks if no flag is set.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
     /// Sets a flag.
     fn set(&mut self, flag: Flag) {
         self.0 |= flag as u8;
@@ -126,19 +170,21 @@ impl Flags {
 }
 
 /// Padding method.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Padding {
-    /// Left padding.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Padding {
+    /// Default padding of the specifier, which is also set by the `-` flag.
     Left,
-    /// Padding with spaces.
+    /// `_`: Padding with spaces.
     Spaces,
-    /// Padding with zeros.
+    /// `0`: Padding with zeros.
     Zeros,
 }
 
 /// Formatting specifier.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Spec {
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Spec {
     /// `"%Y"`: Year with century if provided, zero-padded to at least 4 digits
     /// plus the possible negative sign.
     Year4Digits,
@@ -177,15 +223,20 @@ enum Spec {
     MeridianLower,
     /// `"%p"`: Uppercase meridian indicator (`"AM"` or `"PM"`).
     MeridianUpper,
+    /// `"%:p"`: Day period in the locale of the options, like `"in the
+    /// morning"` or `"noon"`. Only available with [`Dialect::Extensions`].
+    DayPeriod,
     /// `"%M"`: Minute of the hour in `00..=59`, zero-padded to 2 digits.
     Minute,
     /// `"%S"`: Second of the minute in `00..=60`, zero-padded to 2 digits.
     Second,
     /// `"%L"`: Truncated fractional seconds digits, with 3 digits by default.
     /// Number of digits is specified by the width field.
+    #[cfg(feature = "subsec")]
     MilliSecond,
     /// `"%N"`: Truncated fractional seconds digits, with 9 digits by default.
     /// Number of digits is specified by the width field.
+    #[cfg(feature = "subsec")]
     FractionalSecond,
     /// `"%z"`: Zero-padded signed time zone UTC hour and minute offsets
     /// (`+hhmm`).
@@ -199,6 +250,9 @@ enum Spec {
     /// `"%:::z"`: Zero-padded signed time zone UTC hour offset, with optional
     /// minute and second offsets with colons (`+hh[:mm[:ss]]`).
     TimeZoneOffsetColonMinimal,
+    /// `"%o"`: Zero-padded signed time zone UTC hour, minute and second
+    /// offsets (`+hhmmss`). Only available with [`Dialect::Extensions`].
+    TimeZoneOffsetHourMinuteSecond,
     /// `"%Z"`: Platform-dependent abbreviated time zone name.
     TimeZoneName,
     /// `"%A"`: Locale independent full weekday name.
@@ -225,6 +279,12 @@ enum Spec {
     /// `"%s"`: Number of seconds since `1970-01-01 00:00:00 UTC`, zero-padded
     /// to at least 1 digit.
     SecondsSinceEpoch,
+    /// `"%:s"`: Number of seconds since `1970-01-01 00:00:00 UTC` with
+    /// truncated fractional seconds digits, with 9 digits by default. Number of
+    /// digits is specified by the width field. Only available with
+    /// [`Dialect::Extensions`].
+    #[cfg(feature = "subsec")]
+    SecondsSinceEpochFractional,
     /// `"%n"`: Newline character `'\n'`.
     Newline,
     /// `"%t"`: Tab character `'\t'`.
@@ -269,8 +329,18 @@ impl UtcOffset {
     }
 }
 
+/// Wrapper for displaying the signed hour of a UTC offset, always including
+/// the sign.
+struct SignedHour(f64);
+
+impl fmt::Display for SignedHour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+.0}", self.0)
+    }
+}
+
 /// Formatting directive.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Piece {
     /// Optional width.
     width: Option<usize>,
@@ -293,6 +363,57 @@ impl Piece {
         }
     }
 
+    /// Write `count` padding bytes, using zeros for [`Padding::Zeros`] and
+    /// spaces otherwise.
+    ///
+    /// The padding is written by slices of a static block, to avoid going
+    /// through `core::fmt` for large widths.
+    fn write_repeated(
+        f: &mut SizeLimiter<'_>,
+        padding: Padding,
+        mut count: usize,
+    ) -> Result<(), Error> {
+        const ZEROS: &[u8; 64] = &[b'0'; 64];
+        const SPACES: &[u8; 64] = &[b' '; 64];
+
+        let block = match padding {
+            Padding::Zeros => ZEROS,
+            _ => SPACES,
+        };
+
+        while count > 0 {
+            let len = count.min(block.len());
+            f.write_all(&block[..len])?;
+            count -= len;
+        }
+        Ok(())
+    }
+
+    /// Write a value, padded to the specified width.
+    ///
+    /// When padding with zeros, the zeros are inserted after the sign of the
+    /// value, if any.
+    fn write_padded(
+        f: &mut SizeLimiter<'_>,
+        value: impl fmt::Display,
+        width: usize,
+        padding: Padding,
+    ) -> Result<(), Error> {
+        let mut buf = StackBuffer::new();
+        fmt::Write::write_fmt(&mut buf, format_args!("{value}"))?;
+        let value = buf.as_bytes();
+        let n = width.saturating_sub(value.len());
+
+        if let (Padding::Zeros, [sign @ (b'+' | b'-'), digits @ ..]) = (padding, value) {
+            f.write_all(&[*sign])?;
+            Self::write_repeated(f, Padding::Zeros, n)?;
+            f.write_all(digits)
+        } else {
+            Self::write_repeated(f, padding, n)?;
+            f.write_all(value)
+        }
+    }
+
     /// Format a numerical value, padding with zeros by default.
     fn format_num_zeros(
         &self,
@@ -304,10 +425,10 @@ impl Piece {
             write!(f, "{value}")
         } else if self.padding == Padding::Spaces {
             let width = self.width.unwrap_or(default_width);
-            write!(f, "{value: >width$}")
+            Self::write_padded(f, value, width, Padding::Spaces)
         } else {
             let width = self.width.unwrap_or(default_width);
-            write!(f, "{value:0width$}")
+            Self::write_padded(f, value, width, Padding::Zeros)
         }
     }
 
@@ -322,15 +443,52 @@ impl Piece {
             write!(f, "{value}")
         } else if self.padding == Padding::Zeros {
             let width = self.width.unwrap_or(default_width);
-            write!(f, "{value:0width$}")
+            Self::write_padded(f, value, width, Padding::Zeros)
         } else {
             let width = self.width.unwrap_or(default_width);
-            write!(f, "{value: >width$}")
+            Self::write_padded(f, value, width, Padding::Spaces)
         }
     }
 
+    /// Format an integer value with its digits grouped by thousands, padding
+    /// with zeros by default.
+    fn format_grouped(
+        &self,
+        f: &mut SizeLimiter<'_>,
+        value: i64,
+        separator: &str,
+    ) -> Result<(), Error> {
+        let mut buf = StackBuffer::new();
+        fmt::Write::write_fmt(&mut buf, format_args!("{}", value.unsigned_abs()))?;
+        let digits = buf.as_bytes();
+
+        let sign: &[u8] = if value < 0 { b"-" } else { b"" };
+        let len = sign.len() + digits.len() + (digits.len() - 1) / 3 * separator.len();
+
+        if self.flags.contains(Flag::LeftPadding) {
+            f.write_all(sign)?;
+        } else {
+            let n = self.width.unwrap_or(1).saturating_sub(len);
+            if self.padding == Padding::Spaces {
+                Self::write_repeated(f, Padding::Spaces, n)?;
+                f.write_all(sign)?;
+            } else {
+                f.write_all(sign)?;
+                Self::write_repeated(f, Padding::Zeros, n)?;
+            }
+        }
+
+        let (head, tail) = digits.split_at((digits.len() - 1) % 3 + 1);
+        f.write_all(head)?;
+        for group in tail.chunks(3) {
+            f.write_all(separator.as_bytes())?;
+            f.write_all(group)?;
+        }
+        Ok(())
+    }
+
     /// Format nanoseconds with the specified precision.
-    #[allow(clippy::uninlined_format_args)] // for readability and symmetry between if branches
+    #[cfg(feature = "subsec")]
     fn format_nanoseconds(
         &self,
         f: &mut SizeLimiter<'_>,
@@ -341,48 +499,75 @@ impl Piece {
 
         if width <= 9 {
             let value = nanoseconds / 10u32.pow(9 - width as u32);
-            write!(f, "{value:0n$}", n = width)
+            write!(f, "{value:0width$}")
         } else {
-            write!(f, "{nanoseconds:09}{:0n$}", 0, n = width - 9)
+            write!(f, "{nanoseconds:09}")?;
+            Self::write_repeated(f, Padding::Zeros, width - 9)
+        }
+    }
+
+    /// Truncate a string value to the width, if the truncation flag is set.
+    fn truncate<'s>(&self, s: &'s str) -> &'s str {
+        match self.width {
+            Some(width) if self.flags.contains(Flag::Truncate) => {
+                // Truncate at the nearest char boundary at or below the width
+                let mut end = width.min(s.len());
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                &s[..end]
+            }
+            _ => s,
         }
     }
 
-    /// Format a string value.
+    /// Format a string value, padded to the width in chars.
     fn format_string(&self, f: &mut SizeLimiter<'_>, s: &str) -> Result<(), Error> {
+        let s = self.truncate(s);
         match self.width {
-            None => write!(f, "{s}"),
+            Some(_) if self.flags.contains(Flag::LeftPadding) => f.write_all(s.as_bytes()),
+            Some(_) if self.flags.contains(Flag::LeftJustify) => {
+                f.write_all(s.as_bytes())?;
+                self.write_trailing_padding(f, s.chars().count())
+            }
             Some(width) => {
-                if self.flags.contains(Flag::LeftPadding) {
-                    write!(f, "{s}")
-                } else if self.padding == Padding::Zeros {
-                    write!(f, "{s:0>width$}")
-                } else {
-                    write!(f, "{s: >width$}")
-                }
+                let len = s.chars().count();
+                Self::write_repeated(f, self.padding, width.saturating_sub(len))?;
+                f.write_all(s.as_bytes())
             }
+            None => f.write_all(s.as_bytes()),
+        }
+    }
+
+    /// Write spaces after a left-justified string value.
+    fn write_trailing_padding(&self, f: &mut SizeLimiter<'_>, len: usize) -> Result<(), Error> {
+        if let Some(width) = self.width {
+            Self::write_repeated(f, Padding::Spaces, width.saturating_sub(len))?;
         }
+        Ok(())
     }
 
     /// Write padding separately.
     fn write_padding(&self, f: &mut SizeLimiter<'_>, min_width: usize) -> Result<(), Error> {
         if let Some(width) = self.width {
             let n = width.saturating_sub(min_width);
-
-            match self.padding {
-                Padding::Zeros => write!(f, "{:0>n$}", "")?,
-                _ => write!(f, "{: >n$}", "")?,
-            };
+            Self::write_repeated(f, self.padding, n)?;
         }
         Ok(())
     }
 
     /// Compute UTC offset parts for the `%z` specifier.
-    fn compute_offset_parts(&self, time: &impl CheckedTime) -> UtcOffset {
-        let utc_offset = time.utc_offset();
+    fn compute_offset_parts(&self, time: &(impl CheckedTime + ?Sized)) -> Result<UtcOffset, Error> {
+        Ok(self.offset_parts(time.utc_offset()?, time.is_utc()))
+    }
+
+    /// Compute UTC offset parts for the `%z` specifier from a UTC offset in
+    /// seconds.
+    fn offset_parts(&self, utc_offset: i32, is_utc: bool) -> UtcOffset {
         let utc_offset_abs = utc_offset.unsigned_abs();
 
         // UTC is represented as "-00:00" if the '-' flag is set
-        let sign = if utc_offset < 0 || time.is_utc() && self.flags.contains(Flag::LeftPadding) {
+        let sign = if utc_offset < 0 || is_utc && self.flags.contains(Flag::LeftPadding) {
             -1.0
         } else {
             1.0
@@ -396,29 +581,108 @@ impl Piece {
         UtcOffset::new(hour, minute, second)
     }
 
+    /// Format a `%z` specifier from UTC offset parts.
+    fn format_offset(&self, f: &mut SizeLimiter<'_>, utc_offset: &UtcOffset) -> Result<(), Error> {
+        match self.spec {
+            Spec::TimeZoneOffsetHourMinute => self.write_offset_hhmm(f, utc_offset),
+            Spec::TimeZoneOffsetHourMinuteColon => self.write_offset_hh_mm(f, utc_offset),
+            Spec::TimeZoneOffsetHourMinuteSecondColon => self.write_offset_hh_mm_ss(f, utc_offset),
+            Spec::TimeZoneOffsetHourMinuteSecond => self.write_offset_hhmmss(f, utc_offset),
+            // `%:::z` writes the shortest offset without losing precision
+            _ if utc_offset.second != 0 => self.write_offset_hh_mm_ss(f, utc_offset),
+            _ if utc_offset.minute != 0 => self.write_offset_hh_mm(f, utc_offset),
+            _ => self.write_offset_hh(f, utc_offset),
+        }
+    }
+
+    /// Format the `%Z` specifier from an ASCII time zone name.
+    fn format_time_zone(&self, f: &mut SizeLimiter<'_>, tz_name: &str) -> Result<(), Error> {
+        let tz_name = self.truncate(tz_name);
+        if tz_name.is_empty() {
+            return Ok(());
+        }
+
+        let left_padding = self.flags.contains(Flag::LeftPadding);
+        let left_justify = self.flags.contains(Flag::LeftJustify);
+
+        if !left_padding && !left_justify {
+            self.write_padding(f, tz_name.len())?;
+        }
+
+        let convert: fn(&u8) -> u8 = if self.flags.contains(Flag::ChangeCase) {
+            u8::to_ascii_lowercase
+        } else if self.flags.contains(Flag::UpperCase) {
+            u8::to_ascii_uppercase
+        } else {
+            |&x| x
+        };
+
+        for x in tz_name.as_bytes() {
+            f.write_all(&[convert(x)])?;
+        }
+
+        if !left_padding && left_justify {
+            self.write_trailing_padding(f, tz_name.len())?;
+        }
+        Ok(())
+    }
+
+    /// Format a time zone directive for a time without time zone
+    /// information, according to the policy.
+    ///
+    /// Returns `false` if the directive is not a time zone directive.
+    fn fmt_naive(&self, f: &mut SizeLimiter<'_>, policy: NaivePolicy) -> Result<bool, Error> {
+        let is_offset = match self.spec {
+            Spec::TimeZoneOffsetHourMinute
+            | Spec::TimeZoneOffsetHourMinuteColon
+            | Spec::TimeZoneOffsetHourMinuteSecondColon
+            | Spec::TimeZoneOffsetColonMinimal
+            | Spec::TimeZoneOffsetHourMinuteSecond => true,
+            Spec::TimeZoneName => false,
+            _ => return Ok(false),
+        };
+
+        match policy {
+            NaivePolicy::Reject => return Err(Error::MissingTimeZone),
+            NaivePolicy::Utc if is_offset => self.format_offset(f, &self.offset_parts(0, true))?,
+            NaivePolicy::Utc => self.format_time_zone(f, "UTC")?,
+            NaivePolicy::Unknown if is_offset => {
+                self.format_offset(f, &UtcOffset::new(-0.0, 0, 0))?;
+            }
+            NaivePolicy::Empty | NaivePolicy::Unknown => {}
+        }
+        Ok(true)
+    }
+
     /// Compute hour padding for the `%z` specifier.
     fn hour_padding(&self, min_width: usize) -> usize {
         const MIN_PADDING: usize = "+hh".len();
 
         match self.width {
-            Some(width) => width.saturating_sub(min_width) + MIN_PADDING,
+            Some(width) => width.saturating_sub(min_width).saturating_add(MIN_PADDING),
             None => MIN_PADDING,
         }
     }
 
+    /// Write the signed hour of the time zone UTC offset, padded to the
+    /// specified width.
+    fn write_offset_hour(&self, f: &mut SizeLimiter<'_>, hour: f64, n: usize) -> Result<(), Error> {
+        let hour = SignedHour(hour);
+
+        match self.padding {
+            Padding::Spaces => Self::write_padded(f, hour, n, Padding::Spaces),
+            _ => Self::write_padded(f, hour, n, Padding::Zeros),
+        }
+    }
+
     /// Write the time zone UTC offset as `"+hh"`.
     fn write_offset_hh(
         &self,
         f: &mut SizeLimiter<'_>,
         utc_offset: &UtcOffset,
     ) -> Result<(), Error> {
-        let hour = utc_offset.hour;
         let n = self.hour_padding("+hh".len());
-
-        match self.padding {
-            Padding::Spaces => write!(f, "{hour: >+n$.0}"),
-            _ => write!(f, "{hour:+0n$.0}"),
-        }
+        self.write_offset_hour(f, utc_offset.hour, n)
     }
 
     /// Write the time zone UTC offset as `"+hhmm"`.
@@ -430,10 +694,8 @@ impl Piece {
         let UtcOffset { hour, minute, .. } = utc_offset;
         let n = self.hour_padding("+hhmm".len());
 
-        match self.padding {
-            Padding::Spaces => write!(f, "{hour: >+n$.0}{minute:02}"),
-            _ => write!(f, "{hour:+0n$.0}{minute:02}"),
-        }
+        self.write_offset_hour(f, *hour, n)?;
+        write!(f, "{minute:02}")
     }
 
     /// Write the time zone UTC offset as `"+hh:mm"`.
@@ -445,10 +707,8 @@ impl Piece {
         let UtcOffset { hour, minute, .. } = utc_offset;
         let n = self.hour_padding("+hh:mm".len());
 
-        match self.padding {
-            Padding::Spaces => write!(f, "{hour: >+n$.0}:{minute:02}"),
-            _ => write!(f, "{hour:+0n$.0}:{minute:02}"),
-        }
+        self.write_offset_hour(f, *hour, n)?;
+        write!(f, ":{minute:02}")
     }
 
     /// Write the time zone UTC offset as `"+hh:mm:ss"`.
@@ -465,15 +725,35 @@ impl Piece {
 
         let n = self.hour_padding("+hh:mm:ss".len());
 
-        match self.padding {
-            Padding::Spaces => write!(f, "{hour: >+n$.0}:{minute:02}:{second:02}"),
-            _ => write!(f, "{hour:+0n$.0}:{minute:02}:{second:02}"),
-        }
+        self.write_offset_hour(f, *hour, n)?;
+        write!(f, ":{minute:02}:{second:02}")
+    }
+
+    /// Write the time zone UTC offset as `"+hhmmss"`.
+    fn write_offset_hhmmss(
+        &self,
+        f: &mut SizeLimiter<'_>,
+        utc_offset: &UtcOffset,
+    ) -> Result<(), Error> {
+        let UtcOffset {
+            hour,
+            minute,
+            second,
+        } = utc_offset;
+
+        let n = self.hour_padding("+hhmmss".len());
+
+        self.write_offset_hour(f, *hour, n)?;
+        write!(f, "{minute:02}{second:02}")
     }
 
     /// Format time using the formatting directive.
     #[allow(clippy::too_many_lines)]
-    fn fmt(&self, f: &mut SizeLimiter<'_>, time: &impl CheckedTime) -> Result<(), Error> {
+    fn fmt(
+        &self,
+        f: &mut SizeLimiter<'_>,
+        time: &(impl CheckedTime + ?Sized),
+    ) -> Result<(), Error> {
         match self.spec {
             Spec::Year4Digits => {
                 let year = time.year();
@@ -532,52 +812,22 @@ impl Piece {
                 let meridian = if time.hour()? < 12 { am } else { pm };
                 self.format_string(f, meridian)
             }
+            // The locale of the options is handled by `fmt_piece`
+            Spec::DayPeriod => self.format_day_period(f, time, Locale::default()),
             Spec::Minute => self.format_num_zeros(f, time.minute()?, 2),
             Spec::Second => self.format_num_zeros(f, time.second()?, 2),
+            #[cfg(feature = "subsec")]
             Spec::MilliSecond => self.format_nanoseconds(f, time.nanoseconds()?, 3),
+            #[cfg(feature = "subsec")]
             Spec::FractionalSecond => self.format_nanoseconds(f, time.nanoseconds()?, 9),
-            Spec::TimeZoneOffsetHourMinute => {
-                self.write_offset_hhmm(f, &self.compute_offset_parts(time))
-            }
-            Spec::TimeZoneOffsetHourMinuteColon => {
-                self.write_offset_hh_mm(f, &self.compute_offset_parts(time))
-            }
-            Spec::TimeZoneOffsetHourMinuteSecondColon => {
-                self.write_offset_hh_mm_ss(f, &self.compute_offset_parts(time))
-            }
-            Spec::TimeZoneOffsetColonMinimal => {
-                let utc_offset = self.compute_offset_parts(time);
-
-                if utc_offset.second != 0 {
-                    self.write_offset_hh_mm_ss(f, &utc_offset)
-                } else if utc_offset.minute != 0 {
-                    self.write_offset_hh_mm(f, &utc_offset)
-                } else {
-                    self.write_offset_hh(f, &utc_offset)
-                }
-            }
-            Spec::TimeZoneName => {
-                let tz_name = time.time_zone()?;
-                if !tz_name.is_empty() {
-                    if !self.flags.contains(Flag::LeftPadding) {
-                        self.write_padding(f, tz_name.len())?;
-                    }
-
-                    // The time zone name is guaranteed to be ASCII at this point.
-                    let convert: fn(&u8) -> u8 = if self.flags.contains(Flag::ChangeCase) {
-                        u8::to_ascii_lowercase
-                    } else if self.flags.contains(Flag::UpperCase) {
-                        u8::to_ascii_uppercase
-                    } else {
-                        |&x| x
-                    };
-
-                    for x in tz_name.as_bytes() {
-                        f.write_all(&[convert(x)])?;
-                    }
-                }
-                Ok(())
+            Spec::TimeZoneOffsetHourMinute
+            | Spec::TimeZoneOffsetHourMinuteColon
+            | Spec::TimeZoneOffsetHourMinuteSecondColon
+            | Spec::TimeZoneOffsetColonMinimal
+            | Spec::TimeZoneOffsetHourMinuteSecond => {
+                self.format_offset(f, &self.compute_offset_parts(time)?)
             }
+            Spec::TimeZoneName => self.format_time_zone(f, time.time_zone()?),
             Spec::WeekDayName => {
                 let index = time.day_of_week()? as usize;
                 if self.flags.has_change_or_upper_case() {
@@ -642,6 +892,16 @@ impl Piece {
                 self.format_num_zeros(f, week_number, 2)
             }
             Spec::SecondsSinceEpoch => self.format_num_zeros(f, time.to_int(), 1),
+            #[cfg(feature = "subsec")]
+            Spec::SecondsSinceEpochFractional => {
+                let nanoseconds =
+                    i128::from(time.to_int()) * 1_000_000_000 + i128::from(time.nanoseconds()?);
+                let sign = if nanoseconds < 0 { "-" } else { "" };
+                let nanoseconds = nanoseconds.unsigned_abs();
+
+                write!(f, "{sign}{}.", nanoseconds / 1_000_000_000)?;
+                self.format_nanoseconds(f, (nanoseconds % 1_000_000_000) as u32, 9)
+            }
             Spec::Newline => self.format_string(f, "\n"),
             Spec::Tabulation => self.format_string(f, "\t"),
             Spec::Percent => self.format_string(f, "%"),
@@ -725,24 +985,73 @@ impl Piece {
 }
 
 /// Wrapper struct for formatting time with the provided format string.
-pub(crate) struct TimeFormatter<'t, 'f, T> {
+pub(crate) struct TimeFormatter<'t, 'f, 'o, T: ?Sized> {
     /// Time implementation
     time: &'t T,
     /// Format string
     format: &'f [u8],
+    /// Format options
+    options: &'o FormatOptions<'o>,
 }
 
-impl<'t, 'f, T: CheckedTime> TimeFormatter<'t, 'f, T> {
-    /// Construct a new `TimeFormatter` wrapper.
+impl<'t, 'f, T: CheckedTime + ?Sized> TimeFormatter<'t, 'f, 'static, T> {
+    /// Construct a new `TimeFormatter` wrapper with the default options.
     pub(crate) fn new<F: AsRef<[u8]> + ?Sized>(time: &'t T, format: &'f F) -> Self {
+        const DEFAULT_OPTIONS: FormatOptions<'static> = FormatOptions::new();
+        Self::with_options(time, format, &DEFAULT_OPTIONS)
+    }
+}
+
+impl<'t, 'f, 'o, T: CheckedTime + ?Sized> TimeFormatter<'t, 'f, 'o, T> {
+    /// Construct a new `TimeFormatter` wrapper with the provided options.
+    pub(crate) fn with_options<F: AsRef<[u8]> + ?Sized>(
+        time: &'t T,
+        format: &'f F,
+        options: &'o FormatOptions<'o>,
+    ) -> Self {
         Self {
             time,
             format: format.as_ref(),
+            options,
         }
     }
 
     /// Format time using the format string.
     pub(crate) fn fmt(&self, buf: &mut dyn Write) -> Result<(), Error> {
+        #[cfg(feature = "alloc")]
+        if self.options.atomic() {
+            // Format into a scratch buffer first, so that nothing is written
+            // if the formatting fails or if the output is too small
+            let mut scratch = Vec::new();
+            self.fmt_direct(&mut scratch)?;
+            if buf
+                .capacity()
+                .map_or(false, |capacity| capacity < scratch.len())
+            {
+                return Err(Error::WriteZero);
+            }
+            return buf.write_all(&scratch);
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        if self.options.atomic() {
+            // Without a scratch buffer, check the formatting with a dry run
+            // before writing to the output
+            let mut count = CountWrite::default();
+            self.fmt_direct(&mut count)?;
+            if buf
+                .capacity()
+                .map_or(false, |capacity| capacity < count.count())
+            {
+                return Err(Error::WriteZero);
+            }
+        }
+
+        self.fmt_direct(buf)
+    }
+
+    /// Format time using the format string, writing to the output directly.
+    fn fmt_direct(&self, buf: &mut dyn Write) -> Result<(), Error> {
         // Do nothing if the format string is empty
         if self.format.is_empty() {
             return Ok(());
@@ -753,10 +1062,53 @@ impl<'t, 'f, T: CheckedTime> TimeFormatter<'t, 'f, T> {
         let size_limit = self.format.len().saturating_mul(512 * 1024);
         let mut f = SizeLimiter::new(buf, size_limit);
 
-        let mut cursor = Cursor::new(self.format);
+        let mut stats = FormatStats::new();
+        let mut width_args = self.options.width_args().iter();
+        let result = match self.options.offset_policy() {
+            OffsetPolicy::Allow => Self::fmt_format(
+                &mut f,
+                self.time,
+                self.format,
+                self.options,
+                &mut stats,
+                &mut width_args,
+            ),
+            policy => TimeFormatter::fmt_format(
+                &mut f,
+                &OffsetCheckedTime::new(self.time, policy),
+                self.format,
+                self.options,
+                &mut stats,
+                &mut width_args,
+            ),
+        };
+
+        if let Some(cell) = self.options.stats() {
+            stats.finish(&f, self.options.max_directives());
+            cell.set(stats);
+        }
+
+        result
+    }
+
+    /// Format time using a format string, collecting statistics and taking
+    /// the dynamic widths from the width arguments in order.
+    fn fmt_format(
+        f: &mut SizeLimiter<'_>,
+        time: &T,
+        format: &[u8],
+        options: &FormatOptions<'_>,
+        stats: &mut FormatStats,
+        width_args: &mut slice::Iter<'_, usize>,
+    ) -> Result<(), Error> {
+        let max_directives = options.max_directives();
+
+        let mut cursor = Cursor::new(format);
 
         loop {
-            f.write_all(cursor.read_until(|&x| x == b'%'))?;
+            let literal = cursor.read_until(|&x| x == b'%');
+            stats.add_literal_bytes(literal.len());
+            f.write_all(literal)?;
 
             let remaining_before = cursor.remaining();
 
@@ -765,141 +1117,338 @@ impl<'t, 'f, T: CheckedTime> TimeFormatter<'t, 'f, T> {
                 break;
             }
 
-            if let Some(piece) = Self::parse_spec(&mut cursor)? {
-                piece.fmt(&mut f, self.time)?;
+            let piece = match parse_spec_checked(&mut cursor, options.dialect()) {
+                // The unterminated directive is at the end of the format string
+                Err(Error::InvalidFormatString) if options.lenient_trailing_percent() => {
+                    stats.add_literal_bytes(remaining_before.len());
+                    f.write_all(remaining_before)?;
+                    break;
+                }
+                // A width which is too large invalidates the directive
+                Err(Error::WidthTooLarge { .. }) if !options.strict_widths() => Ok(None),
+                result => result,
+            };
+
+            let remaining_after = cursor.remaining();
+            let text = &remaining_before[..remaining_before.len() - remaining_after.len()];
+            let position = format.len() - remaining_before.len();
+
+            let piece = match piece {
+                // Errors in the format string are not wrapped, and the position
+                // of the unterminated directive is also recorded in the
+                // statistics
+                Err(Error::InvalidFormatString) => {
+                    stats.set_unterminated_directive(position);
+                    return Err(Error::UnterminatedDirective {
+                        position,
+                        snippet: Snippet::new(remaining_before),
+                    });
+                }
+                piece => piece.map_err(|err| options.error_context(err, text, position))?,
+            };
+
+            // Take the dynamic width from the next width argument. Like a
+            // literal width, a width which is too large invalidates the
+            // directive.
+            let piece = match piece {
+                Some(mut piece) if piece.flags.contains(Flag::DynamicWidth) => {
+                    match width_args.next() {
+                        Some(&width) if width <= MAX_WIDTH => {
+                            piece.width = Some(width);
+                            Some(piece)
+                        }
+                        Some(_) if options.strict_widths() => {
+                            let err = Error::WidthTooLarge { limit: MAX_WIDTH };
+                            return Err(options.error_context(err, text, position));
+                        }
+                        Some(_) => None,
+                        None => {
+                            let err = Error::MissingWidthArgument;
+                            return Err(options.error_context(err, text, position));
+                        }
+                    }
+                }
+                piece => piece,
+            };
+
+            if let Some(piece) = piece {
+                check_max_directives(stats.add_directive(), max_directives)?;
+
+                if let Err(err) = Self::fmt_piece(f, time, &piece, options) {
+                    return Err(options.error_context(err, text, position));
+                }
             } else {
-                // No valid format specifier was found
-                let remaining_after = cursor.remaining();
-                let text = &remaining_before[..remaining_before.len() - remaining_after.len()];
-                f.write_all(text)?;
+                // No valid format specifier was found, so expand the alias of a
+                // bare directive, if any
+                let alias = match *text {
+                    [b'%', spec] => options.alias(spec),
+                    _ => None,
+                };
+
+                // Expand a `%{name}` reference to a named format, if any
+                let named_format = match *text {
+                    [b'%', b'{'] => {
+                        let mut lookahead = cursor.clone();
+                        let name = lookahead.read_until(|&x| x == b'}');
+                        if lookahead.read_optional_tag(b"}") {
+                            options
+                                .named_format(name)
+                                .map(|named_format| (named_format, lookahead))
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+
+                if let Some(alias) = alias {
+                    let options = options.without_aliases();
+                    Self::fmt_format(f, time, alias.as_bytes(), &options, stats, width_args)?;
+                } else if let Some((named_format, lookahead)) = named_format {
+                    cursor = lookahead;
+                    let options = options.without_named_formats();
+                    let format = named_format.as_bytes();
+                    Self::fmt_format(f, time, format, &options, stats, width_args)?;
+                } else if options.strict_directives() {
+                    let err = Error::UnknownDirective;
+                    return Err(options.error_context(err, text, position));
+                } else {
+                    check_max_directives(stats.add_unknown_directive(), max_directives)?;
+                    stats.add_literal_bytes(text.len());
+                    f.write_all(text)?;
+                }
             }
         }
 
         Ok(())
     }
 
-    /// Parse a formatting directive.
-    fn parse_spec(cursor: &mut Cursor<'_>) -> Result<Option<Piece>, Error> {
-        // Parse flags
-        let mut padding = Padding::Left;
-        let mut flags = Flags::default();
+    /// Format time using a formatting directive.
+    fn fmt_piece(
+        f: &mut SizeLimiter<'_>,
+        time: &T,
+        piece: &Piece,
+        options: &FormatOptions<'_>,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "icu")]
+        if let Some(calendar) = options.calendar() {
+            if piece.fmt_calendar(f, time, calendar)? {
+                return Ok(());
+            }
+        }
 
-        loop {
-            // The left padding overrides the other padding options for most cases.
-            // It is also used for the hour sign in the `%z` specifier.
-            //
-            // Similarly, the change case flag overrides the upper case flag,
-            // except when using combination specifiers (`%c`, `%D`, `%x`, `%F`,
-            // `%v`, `%r`, `%R`, `%T`, `%X`).
-            match cursor.remaining().first() {
-                Some(&b'-') => {
-                    padding = Padding::Left;
-                    flags.set(Flag::LeftPadding);
-                }
-                Some(&b'_') => padding = Padding::Spaces,
-                Some(&b'0') => padding = Padding::Zeros,
-                Some(&b'^') => flags.set(Flag::UpperCase),
-                Some(&b'#') => flags.set(Flag::ChangeCase),
-                _ => break,
+        if let Some(policy) = options.naive_policy() {
+            if piece.fmt_naive(f, policy)? {
+                return Ok(());
             }
-            cursor.next();
         }
 
-        // Parse width
-        let width_digits = str::from_utf8(cursor.read_while(u8::is_ascii_digit))
-            .expect("reading ASCII digits should yield a valid UTF-8 slice");
+        if piece.flags.contains(Flag::Grouping) && matches!(piece.spec, Spec::SecondsSinceEpoch) {
+            return piece.format_grouped(f, time.to_int(), options.digit_separator());
+        }
 
-        let width = match width_digits.parse::<usize>() {
-            Ok(width) if Int::try_from(width).is_ok() => Some(width),
-            Err(err) if *err.kind() == IntErrorKind::Empty => None,
-            _ => return Ok(None),
-        };
+        if matches!(piece.spec, Spec::DayPeriod) {
+            return piece.format_day_period(f, time, options.locale());
+        }
+
+        piece.fmt(f, time)
+    }
+}
 
-        // Ignore POSIX locale extensions per MRI 3.1.2:
+/// Specifiers of the `E` POSIX locale extension.
+const EXT_E_SPECS: &[u8] = assert_sorted(b"CXYcxy");
+/// Specifiers of the `O` POSIX locale extension.
+const EXT_O_SPECS: &[u8] = assert_sorted(b"HIMSUVWdeklmuwy");
+
+/// Formatting specifiers without colons, sorted by character.
+const POSSIBLE_SPECS: &[(u8, Spec)] = assert_sorted_elem_0(&[
+    (b'%', Spec::Percent),
+    (b'A', Spec::WeekDayName),
+    (b'B', Spec::MonthName),
+    (b'C', Spec::YearDiv100),
+    (b'D', Spec::CombinationDate),
+    (b'F', Spec::CombinationIso8601),
+    (b'G', Spec::YearIso8601),
+    (b'H', Spec::Hour24hZero),
+    (b'I', Spec::Hour12hZero),
+    #[cfg(feature = "subsec")]
+    (b'L', Spec::MilliSecond),
+    (b'M', Spec::Minute),
+    #[cfg(feature = "subsec")]
+    (b'N', Spec::FractionalSecond),
+    (b'P', Spec::MeridianLower),
+    (b'R', Spec::CombinationHourMinute24h),
+    (b'S', Spec::Second),
+    (b'T', Spec::CombinationTime24h),
+    (b'U', Spec::WeekNumberFromSunday),
+    (b'V', Spec::WeekNumberIso8601),
+    (b'W', Spec::WeekNumberFromMonday),
+    (b'X', Spec::CombinationTime24h),
+    (b'Y', Spec::Year4Digits),
+    (b'Z', Spec::TimeZoneName),
+    (b'a', Spec::WeekDayNameAbbr),
+    (b'b', Spec::MonthNameAbbr),
+    (b'c', Spec::CombinationDateTime),
+    (b'd', Spec::MonthDayZero),
+    (b'e', Spec::MonthDaySpace),
+    (b'g', Spec::YearIso8601Rem100),
+    (b'h', Spec::MonthNameAbbr),
+    (b'j', Spec::YearDay),
+    (b'k', Spec::Hour24hSpace),
+    (b'l', Spec::Hour12hSpace),
+    (b'm', Spec::Month),
+    (b'n', Spec::Newline),
+    (b'p', Spec::MeridianUpper),
+    (b'r', Spec::CombinationTime12h),
+    (b's', Spec::SecondsSinceEpoch),
+    (b't', Spec::Tabulation),
+    (b'u', Spec::WeekDayFrom1),
+    (b'v', Spec::CombinationVmsDate),
+    (b'w', Spec::WeekDayFrom0),
+    (b'x', Spec::CombinationDate),
+    (b'y', Spec::YearRem100),
+    (b'z', Spec::TimeZoneOffsetHourMinute),
+]);
+
+/// Parse a formatting directive.
+///
+/// A directive with a width which doesn't fit in a `c_int` is invalid.
+fn parse_spec(cursor: &mut Cursor<'_>, dialect: Dialect) -> Result<Option<Piece>, Error> {
+    match parse_spec_checked(cursor, dialect) {
+        Err(Error::WidthTooLarge { .. }) => Ok(None),
+        result => result,
+    }
+}
+
+/// Parse a formatting directive, returning [`Error::WidthTooLarge`] if its
+/// width doesn't fit in a `c_int`.
+#[allow(clippy::too_many_lines)]
+fn parse_spec_checked(cursor: &mut Cursor<'_>, dialect: Dialect) -> Result<Option<Piece>, Error> {
+    // Parse flags
+    let mut padding = Padding::Left;
+    let mut flags = Flags::default();
+
+    loop {
+        // The left padding overrides the other padding options for most cases.
+        // It is also used for the hour sign in the `%z` specifier.
         //
-        // <https://github.com/ruby/ruby/blob/v3_1_2/strftime.c#L713-L722>
-        if let Some(&[ext, spec]) = cursor.remaining().get(..2) {
-            const EXT_E_SPECS: &[u8] = assert_sorted(b"CXYcxy");
-            const EXT_O_SPECS: &[u8] = assert_sorted(b"HIMSUVWdeklmuwy");
-
-            match ext {
-                b'E' if EXT_E_SPECS.binary_search(&spec).is_ok() => cursor.next(),
-                b'O' if EXT_O_SPECS.binary_search(&spec).is_ok() => cursor.next(),
-                _ => None,
-            };
+        // Similarly, the change case flag overrides the upper case flag,
+        // except when using combination specifiers (`%c`, `%D`, `%x`, `%F`,
+        // `%v`, `%r`, `%R`, `%T`, `%X`).
+        match cursor.remaining().first() {
+            Some(&b'-') => {
+                padding = Padding::Left;
+                flags.set(Flag::LeftPadding);
+            }
+            Some(&b'_') => padding = Padding::Spaces,
+            Some(&b'0') => padding = Padding::Zeros,
+            Some(&b'^') => flags.set(Flag::UpperCase),
+            Some(&b'#') => flags.set(Flag::ChangeCase),
+            Some(&b'.') if dialect == Dialect::Extensions => flags.set(Flag::Truncate),
+            Some(&b'<') if dialect == Dialect::Extensions => flags.set(Flag::LeftJustify),
+            Some(&b'\'') if dialect == Dialect::Extensions => flags.set(Flag::Grouping),
+            _ => break,
         }
+        cursor.next();
+    }
 
-        // Parse spec
-        let colons = cursor.read_while(|&x| x == b':');
-
-        let spec = if colons.is_empty() {
-            const POSSIBLE_SPECS: &[(u8, Spec)] = assert_sorted_elem_0(&[
-                (b'%', Spec::Percent),
-                (b'A', Spec::WeekDayName),
-                (b'B', Spec::MonthName),
-                (b'C', Spec::YearDiv100),
-                (b'D', Spec::CombinationDate),
-                (b'F', Spec::CombinationIso8601),
-                (b'G', Spec::YearIso8601),
-                (b'H', Spec::Hour24hZero),
-                (b'I', Spec::Hour12hZero),
-                (b'L', Spec::MilliSecond),
-                (b'M', Spec::Minute),
-                (b'N', Spec::FractionalSecond),
-                (b'P', Spec::MeridianLower),
-                (b'R', Spec::CombinationHourMinute24h),
-                (b'S', Spec::Second),
-                (b'T', Spec::CombinationTime24h),
-                (b'U', Spec::WeekNumberFromSunday),
-                (b'V', Spec::WeekNumberIso8601),
-                (b'W', Spec::WeekNumberFromMonday),
-                (b'X', Spec::CombinationTime24h),
-                (b'Y', Spec::Year4Digits),
-                (b'Z', Spec::TimeZoneName),
-                (b'a', Spec::WeekDayNameAbbr),
-                (b'b', Spec::MonthNameAbbr),
-                (b'c', Spec::CombinationDateTime),
-                (b'd', Spec::MonthDayZero),
-                (b'e', Spec::MonthDaySpace),
-                (b'g', Spec::YearIso8601Rem100),
-                (b'h', Spec::MonthNameAbbr),
-                (b'j', Spec::YearDay),
-                (b'k', Spec::Hour24hSpace),
-                (b'l', Spec::Hour12hSpace),
-                (b'm', Spec::Month),
-                (b'n', Spec::Newline),
-                (b'p', Spec::MeridianUpper),
-                (b'r', Spec::CombinationTime12h),
-                (b's', Spec::SecondsSinceEpoch),
-                (b't', Spec::Tabulation),
-                (b'u', Spec::WeekDayFrom1),
-                (b'v', Spec::CombinationVmsDate),
-                (b'w', Spec::WeekDayFrom0),
-                (b'x', Spec::CombinationDate),
-                (b'y', Spec::YearRem100),
-                (b'z', Spec::TimeZoneOffsetHourMinute),
-            ]);
-
-            match cursor.next() {
-                Some(x) => match POSSIBLE_SPECS.binary_search_by_key(&x, |&(c, _)| c) {
-                    Ok(index) => Some(POSSIBLE_SPECS[index].1),
-                    Err(_) => None,
-                },
-                None => return Err(Error::InvalidFormatString),
-            }
-        } else if cursor.read_optional_tag(b"z") {
-            match colons.len() {
-                1 => Some(Spec::TimeZoneOffsetHourMinuteColon),
-                2 => Some(Spec::TimeZoneOffsetHourMinuteSecondColon),
-                3 => Some(Spec::TimeZoneOffsetColonMinimal),
-                _ => None,
-            }
-        } else {
-            None
+    // Parse width
+    if dialect == Dialect::Extensions && cursor.read_optional_tag(b"*") {
+        flags.set(Flag::DynamicWidth);
+    }
+    let width_digits = if flags.contains(Flag::DynamicWidth) {
+        &[]
+    } else {
+        cursor.read_while(u8::is_ascii_digit)
+    };
+
+    let width = if width_digits.is_empty() {
+        None
+    } else {
+        match parse_width(width_digits) {
+            Some(width) => Some(width),
+            None => return Err(Error::WidthTooLarge { limit: MAX_WIDTH }),
+        }
+    };
+
+    // Ignore POSIX locale extensions per MRI 3.1.2:
+    //
+    // <https://github.com/ruby/ruby/blob/v3_1_2/strftime.c#L713-L722>
+    if let Some(&[ext, spec]) = cursor.remaining().get(..2) {
+        match ext {
+            b'E' if EXT_E_SPECS.binary_search(&spec).is_ok() => cursor.next(),
+            b'O' if EXT_O_SPECS.binary_search(&spec).is_ok() => cursor.next(),
+            _ => None,
         };
+    }
 
-        Ok(spec.map(|spec| Piece::new(width, padding, flags, spec)))
+    // Parse spec
+    let colons = cursor.read_while(|&x| x == b':');
+
+    let spec = if colons.is_empty() {
+        match cursor.next() {
+            Some(x) => match POSSIBLE_SPECS.binary_search_by_key(&x, |&(c, _)| c) {
+                Ok(index) => Some(POSSIBLE_SPECS[index].1),
+                Err(_) if x == b'o' && dialect == Dialect::Extensions => {
+                    Some(Spec::TimeZoneOffsetHourMinuteSecond)
+                }
+                Err(_) => None,
+            },
+            None => return Err(Error::InvalidFormatString),
+        }
+    } else if cursor.read_optional_tag(b"z") {
+        match colons.len() {
+            1 => Some(Spec::TimeZoneOffsetHourMinuteColon),
+            2 => Some(Spec::TimeZoneOffsetHourMinuteSecondColon),
+            3 => Some(Spec::TimeZoneOffsetColonMinimal),
+            _ => None,
+        }
+    } else if dialect == Dialect::Extensions && colons.len() == 1 && cursor.read_optional_tag(b"p")
+    {
+        Some(Spec::DayPeriod)
+    } else if dialect == Dialect::Extensions && colons.len() == 1 && cursor.read_optional_tag(b"s")
+    {
+        #[cfg(feature = "subsec")]
+        let spec = Some(Spec::SecondsSinceEpochFractional);
+        // Sub-second directives are unknown without the `subsec` feature
+        #[cfg(not(feature = "subsec"))]
+        let spec = None;
+        spec
+    } else {
+        None
+    };
+
+    Ok(spec.map(|spec| Piece::new(width, padding, flags, spec)))
+}
+
+/// Check that a number of directives, including the unknown directives, is in
+/// the limit.
+fn check_max_directives(directives: usize, max_directives: usize) -> Result<(), Error> {
+    if directives > max_directives {
+        return Err(Error::TooManyDirectives {
+            limit: max_directives,
+        });
     }
+    Ok(())
+}
+
+/// Maximum width of a directive, which is the maximum value of a `c_int`.
+const MAX_WIDTH: usize = Int::MAX as usize;
+
+/// Parse a width from a non-empty slice of ASCII digits.
+///
+/// Returns [`None`] if the width doesn't fit in a `c_int`.
+fn parse_width(digits: &[u8]) -> Option<usize> {
+    digits.iter().try_fold(0usize, |width, &digit| {
+        let width = width
+            .checked_mul(10)?
+            .checked_add(usize::from(digit - b'0'))?;
+        if width > MAX_WIDTH {
+            None
+        } else {
+            Some(width)
+        }
+    })
 }
 
 /// Compute the width of the string representation of a year.
@@ -918,6 +1467,17 @@ fn year_width(year: i32) -> usize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_width() {
+        assert_eq!(parse_width(b"0"), Some(0));
+        assert_eq!(parse_width(b"007"), Some(7));
+        assert_eq!(parse_width(b"100"), Some(100));
+        assert_eq!(parse_width(b"2147483647"), Some(2_147_483_647));
+        assert_eq!(parse_width(b"2147483648"), None);
+        assert_eq!(parse_width(b"00000000000000000000000000000001"), Some(1));
+        assert_eq!(parse_width(b"99999999999999999999999999999999"), None);
+    }
+
     #[test]
     fn test_year_width() {
         assert_eq!(year_width(-100), 4);
@@ -933,6 +1493,35 @@ mod tests {
         assert_eq!(year_width(100), 3);
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_write_repeated() {
+        use alloc::vec::Vec;
+
+        for count in [0, 1, 63, 64, 65, 200] {
+            for (padding, byte) in [(Padding::Zeros, b'0'), (Padding::Spaces, b' ')] {
+                let mut buf = Vec::new();
+                let mut f = SizeLimiter::new(&mut buf, usize::MAX);
+                Piece::write_repeated(&mut f, padding, count).unwrap();
+
+                assert_eq!(buf.len(), count);
+                assert!(buf.iter().all(|&x| x == byte));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hour_padding_saturates() {
+        let spec = Spec::TimeZoneOffsetHourMinute;
+
+        let piece = Piece::new(Some(usize::MAX), Padding::Zeros, Flags::default(), spec);
+        assert_eq!(piece.hour_padding("+hh".len()), usize::MAX);
+        assert_eq!(piece.hour_padding("+hh:mm:ss".len()), usize::MAX - 6);
+
+        let piece = Piece::new(Some(0), Padding::Zeros, Flags::default(), spec);
+        assert_eq!(piece.hour_padding("+hhmm".len()), "+hh".len());
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn test_flag_debug_is_non_empty() {
@@ -981,14 +1570,18 @@ mod tests {
         assert!(!format!("{:?}", Spec::Hour12hSpace).is_empty());
         assert!(!format!("{:?}", Spec::MeridianLower).is_empty());
         assert!(!format!("{:?}", Spec::MeridianUpper).is_empty());
+        assert!(!format!("{:?}", Spec::DayPeriod).is_empty());
         assert!(!format!("{:?}", Spec::Minute).is_empty());
         assert!(!format!("{:?}", Spec::Second).is_empty());
+        #[cfg(feature = "subsec")]
         assert!(!format!("{:?}", Spec::MilliSecond).is_empty());
+        #[cfg(feature = "subsec")]
         assert!(!format!("{:?}", Spec::FractionalSecond).is_empty());
         assert!(!format!("{:?}", Spec::TimeZoneOffsetHourMinute).is_empty());
         assert!(!format!("{:?}", Spec::TimeZoneOffsetHourMinuteColon).is_empty());
         assert!(!format!("{:?}", Spec::TimeZoneOffsetHourMinuteSecondColon).is_empty());
         assert!(!format!("{:?}", Spec::TimeZoneOffsetColonMinimal).is_empty());
+        assert!(!format!("{:?}", Spec::TimeZoneOffsetHourMinuteSecond).is_empty());
         assert!(!format!("{:?}", Spec::TimeZoneName).is_empty());
         assert!(!format!("{:?}", Spec::WeekDayName).is_empty());
         assert!(!format!("{:?}", Spec::WeekDayNameAbbr).is_empty());
@@ -1000,6 +1593,8 @@ mod tests {
         assert!(!format!("{:?}", Spec::WeekNumberFromSunday).is_empty());
         assert!(!format!("{:?}", Spec::WeekNumberFromMonday).is_empty());
         assert!(!format!("{:?}", Spec::SecondsSinceEpoch).is_empty());
+        #[cfg(feature = "subsec")]
+        assert!(!format!("{:?}", Spec::SecondsSinceEpochFractional).is_empty());
         assert!(!format!("{:?}", Spec::Newline).is_empty());
         assert!(!format!("{:?}", Spec::Tabulation).is_empty());
         assert!(!format!("{:?}", Spec::Percent).is_empty());
@@ -0,0 +1,176 @@
+//! Structured pieces of a format string.
+
+use super::utils::Cursor;
+use super::{parse_spec, Flags, Padding, Spec};
+use crate::{Dialect, Error, Snippet};
+
+/// A piece of a format string, yielded by [`Pieces`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Item<'f> {
+    /// Text which is copied verbatim to the output, including invalid
+    /// directives.
+    Literal(&'f [u8]),
+    /// A formatting directive.
+    Directive {
+        /// Formatting specifier.
+        spec: Spec,
+        /// Combination of formatting flags.
+        flags: Flags,
+        /// Optional width.
+        width: Option<usize>,
+        /// Padding method.
+        padding: Padding,
+    },
+}
+
+/// Iterator over the structured pieces of a format string, created by
+/// [`pieces`].
+///
+/// [`pieces`]: crate::pieces::pieces
+#[derive(Debug, Clone)]
+pub struct Pieces<'f> {
+    /// Remaining format string.
+    cursor: Cursor<'f>,
+    /// Length of the format string.
+    len: usize,
+    /// Dialect of the format string.
+    dialect: Dialect,
+    /// Whether an error was returned.
+    failed: bool,
+}
+
+impl<'f> Pieces<'f> {
+    /// Construct a new `Pieces` iterator.
+    pub(crate) fn new(format: &'f [u8], dialect: Dialect) -> Self {
+        Self {
+            cursor: Cursor::new(format),
+            len: format.len(),
+            dialect,
+            failed: false,
+        }
+    }
+}
+
+impl<'f> Iterator for Pieces<'f> {
+    type Item = Result<Item<'f>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.cursor.remaining().is_empty() {
+            return None;
+        }
+
+        let literal = self.cursor.read_until(|&x| x == b'%');
+        if !literal.is_empty() {
+            return Some(Ok(Item::Literal(literal)));
+        }
+
+        let remaining_before = self.cursor.remaining();
+
+        // Read the '%' character
+        self.cursor.next();
+
+        let piece = if let Ok(piece) = parse_spec(&mut self.cursor, self.dialect) {
+            piece
+        } else {
+            self.failed = true;
+            return Some(Err(Error::UnterminatedDirective {
+                position: self.len - remaining_before.len(),
+                snippet: Snippet::new(remaining_before),
+            }));
+        };
+
+        let item = if let Some(piece) = piece {
+            Item::Directive {
+                spec: piece.spec,
+                flags: piece.flags,
+                width: piece.width,
+                padding: piece.padding,
+            }
+        } else {
+            let len = remaining_before.len() - self.cursor.remaining().len();
+            Item::Literal(&remaining_before[..len])
+        };
+
+        Some(Ok(item))
+    }
+}
+
+impl core::iter::FusedIterator for Pieces<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Flag;
+
+    fn directive(
+        spec: Spec,
+        flags: Flags,
+        width: Option<usize>,
+        padding: Padding,
+    ) -> Item<'static> {
+        Item::Directive {
+            spec,
+            flags,
+            width,
+            padding,
+        }
+    }
+
+    #[test]
+    fn test_pieces() {
+        let mut upper_case = Flags::default();
+        upper_case.set(Flag::UpperCase);
+
+        let items = Pieces::new(b"at %q%^10B, %_d%%", Dialect::Ruby);
+        assert!(items.map(Result::ok).eq([
+            Some(Item::Literal(b"at ")),
+            Some(Item::Literal(b"%q")),
+            Some(directive(
+                Spec::MonthName,
+                upper_case,
+                Some(10),
+                Padding::Left
+            )),
+            Some(Item::Literal(b", ")),
+            Some(directive(
+                Spec::MonthDayZero,
+                Flags::default(),
+                None,
+                Padding::Spaces
+            )),
+            Some(directive(
+                Spec::Percent,
+                Flags::default(),
+                None,
+                Padding::Left
+            )),
+        ]));
+
+        let mut truncate = Flags::default();
+        truncate.set(Flag::Truncate);
+
+        let items = Pieces::new(b"%.3A", Dialect::Extensions);
+        assert!(items.map(Result::ok).eq([Some(directive(
+            Spec::WeekDayName,
+            truncate,
+            Some(3),
+            Padding::Left
+        ))]));
+        assert!(Pieces::new(b"%.3A", Dialect::Ruby)
+            .map(Result::ok)
+            .eq([Some(Item::Literal(b"%.")), Some(Item::Literal(b"3A"))]));
+    }
+
+    #[test]
+    fn test_pieces_error() {
+        let mut items = Pieces::new(b"%Y %", Dialect::Ruby);
+        assert!(matches!(items.next(), Some(Ok(Item::Directive { .. }))));
+        assert!(matches!(items.next(), Some(Ok(Item::Literal(b" ")))));
+        assert!(matches!(
+            items.next(),
+            Some(Err(Error::UnterminatedDirective { position: 3, .. }))
+        ));
+        assert!(items.next().is_none());
+    }
+}
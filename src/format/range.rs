@@ -0,0 +1,383 @@
+//! Formatting of time ranges with elision of the shared components.
+
+use super::utils::Cursor;
+use super::write::Write;
+use super::{parse_spec, Spec, TimeFormatter};
+use crate::{Error, FormatOptions, Time};
+
+/// Opening tag of the section formatting the start of the range.
+const START_TAG: &[u8] = b"%{start:";
+
+/// Opening tag of the section formatting the end of the range.
+const END_TAG: &[u8] = b"%{end:";
+
+/// Date components shared by the start and end of a range.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Shared {
+    /// No shared component.
+    Nothing,
+    /// Same year.
+    Year,
+    /// Same year and month.
+    Month,
+    /// Same date.
+    Date,
+}
+
+impl Shared {
+    /// Compare the dates of the start and end of a range.
+    fn new(start: &(impl Time + ?Sized), end: &(impl Time + ?Sized)) -> Self {
+        if start.year() != end.year() {
+            Self::Nothing
+        } else if start.month() != end.month() {
+            Self::Year
+        } else if start.day() != end.day() {
+            Self::Month
+        } else {
+            Self::Date
+        }
+    }
+}
+
+/// Date component of a directive.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Component {
+    /// Year directives.
+    Year,
+    /// Month directives.
+    Month,
+    /// Day directives, and combinations of the whole date.
+    Day,
+}
+
+impl Component {
+    /// Returns the date component of a formatting specifier, if any.
+    const fn of(spec: Spec) -> Option<Self> {
+        match spec {
+            Spec::Year4Digits
+            | Spec::YearDiv100
+            | Spec::YearRem100
+            | Spec::YearIso8601
+            | Spec::YearIso8601Rem100 => Some(Self::Year),
+            Spec::Month | Spec::MonthName | Spec::MonthNameAbbr => Some(Self::Month),
+            Spec::MonthDayZero
+            | Spec::MonthDaySpace
+            | Spec::YearDay
+            | Spec::WeekDayName
+            | Spec::WeekDayNameAbbr
+            | Spec::WeekDayFrom1
+            | Spec::WeekDayFrom0
+            | Spec::WeekNumberIso8601
+            | Spec::WeekNumberFromSunday
+            | Spec::WeekNumberFromMonday
+            | Spec::CombinationDate
+            | Spec::CombinationIso8601
+            | Spec::CombinationVmsDate => Some(Self::Day),
+            _ => None,
+        }
+    }
+}
+
+/// Section of a range template.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Section {
+    /// Start of the range.
+    Start,
+    /// End of the range.
+    End,
+}
+
+impl Section {
+    /// Check if a date component is elided in the section.
+    fn elides(self, component: Component, shared: Shared) -> bool {
+        match (self, shared) {
+            (Self::Start, Shared::Year | Shared::Month) => component == Component::Year,
+            (Self::End, Shared::Month) => component == Component::Month,
+            (Self::End, Shared::Date) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Formatter for a range template.
+struct RangeFormatter<'t, 'o, S: ?Sized, E: ?Sized> {
+    /// Start of the range.
+    start: &'t S,
+    /// End of the range.
+    end: &'t E,
+    /// Formatting options.
+    options: &'o FormatOptions<'o>,
+    /// Date components shared by the start and end of the range.
+    shared: Shared,
+}
+
+impl<S: Time + ?Sized, E: Time + ?Sized> RangeFormatter<'_, '_, S, E> {
+    /// Format a range template.
+    fn fmt(&self, buf: &mut dyn Write, template: &[u8]) -> Result<(), Error> {
+        let mut cursor = Cursor::new(template);
+        let mut after_section = false;
+
+        loop {
+            let literal = read_literal(&mut cursor);
+
+            let section = if cursor.read_optional_tag(START_TAG) {
+                Section::Start
+            } else if cursor.read_optional_tag(END_TAG) {
+                Section::End
+            } else {
+                buf.write_all(literal)?;
+                return Ok(());
+            };
+
+            let format = cursor.read_until(|&x| x == b'}');
+            if !cursor.read_optional_tag(b"}") {
+                return Err(Error::InvalidFormatString);
+            }
+
+            // Drop the end of a range within a single day if only its date
+            // is formatted, with the text separating it from the start
+            if section == Section::End && self.is_elided(format)? {
+                if !after_section {
+                    buf.write_all(literal)?;
+                }
+            } else {
+                buf.write_all(literal)?;
+                self.fmt_section(buf, format, section)?;
+            }
+
+            after_section = true;
+        }
+    }
+
+    /// Check if all the directives of the format string of an end section
+    /// are elided.
+    fn is_elided(&self, format: &[u8]) -> Result<bool, Error> {
+        let mut cursor = Cursor::new(format);
+        let mut has_directive = false;
+
+        while !cursor.remaining().is_empty() {
+            if let Some(spec) = next_spec(&mut cursor, self.options)? {
+                match Component::of(spec) {
+                    Some(component) if Section::End.elides(component, self.shared) => {
+                        has_directive = true;
+                    }
+                    _ => return Ok(false),
+                }
+            }
+        }
+
+        Ok(has_directive)
+    }
+
+    /// Format the format string of a section, eliding the directives of the
+    /// date components shared by the start and end of the range.
+    ///
+    /// The text before an elided directive is elided with it, or the text
+    /// after it if no directive was written before in the section.
+    fn fmt_section(
+        &self,
+        buf: &mut dyn Write,
+        format: &[u8],
+        section: Section,
+    ) -> Result<(), Error> {
+        let mut cursor = Cursor::new(format);
+        let mut pending: Option<&[u8]> = None;
+        let mut has_written_directive = false;
+        let mut skip_literal = false;
+
+        loop {
+            let remaining_before = cursor.remaining();
+            let spec = next_spec(&mut cursor, self.options)?;
+            let remaining_after = cursor.remaining();
+            let text = &remaining_before[..remaining_before.len() - remaining_after.len()];
+
+            let spec = match spec {
+                Some(spec) => spec,
+                None if text.is_empty() => break,
+                // Literal text
+                None => {
+                    if skip_literal {
+                        skip_literal = false;
+                    } else if let Some(literal) = pending.replace(text) {
+                        buf.write_all(literal)?;
+                    }
+                    continue;
+                }
+            };
+
+            match Component::of(spec) {
+                Some(component) if section.elides(component, self.shared) => {
+                    if pending.is_some() && has_written_directive {
+                        pending = None;
+                    } else {
+                        skip_literal = true;
+                    }
+                }
+                _ => {
+                    if let Some(literal) = pending.take() {
+                        buf.write_all(literal)?;
+                    }
+                    match section {
+                        Section::Start => self.fmt_directive(buf, self.start, text)?,
+                        Section::End => self.fmt_directive(buf, self.end, text)?,
+                    }
+                    has_written_directive = true;
+                    skip_literal = false;
+                }
+            }
+        }
+
+        if let Some(literal) = pending {
+            buf.write_all(literal)?;
+        }
+
+        Ok(())
+    }
+
+    /// Format a single directive.
+    fn fmt_directive(
+        &self,
+        buf: &mut dyn Write,
+        time: &(impl Time + ?Sized),
+        text: &[u8],
+    ) -> Result<(), Error> {
+        TimeFormatter::with_options(time, text, self.options).fmt(buf)
+    }
+}
+
+/// Read the literal text before the next section of a range template.
+fn read_literal<'a>(cursor: &mut Cursor<'a>) -> &'a [u8] {
+    let template = cursor.remaining();
+    let len = (0..template.len())
+        .find(|&i| template[i..].starts_with(START_TAG) || template[i..].starts_with(END_TAG))
+        .unwrap_or(template.len());
+
+    let (literal, rest) = template.split_at(len);
+    *cursor = Cursor::new(rest);
+    literal
+}
+
+/// Read the next item of the format string of a section, returning its
+/// formatting specifier if it is a valid directive.
+///
+/// Literal text and invalid directives return [`None`]. The end of the format
+/// string is reached when the cursor is empty.
+fn next_spec(cursor: &mut Cursor<'_>, options: &FormatOptions<'_>) -> Result<Option<Spec>, Error> {
+    if cursor.remaining().first() != Some(&b'%') {
+        cursor.read_until(|&x| x == b'%');
+        return Ok(None);
+    }
+
+    // Read the '%' character
+    cursor.next();
+
+    match parse_spec(cursor, options.dialect())? {
+        Some(piece) => Ok(Some(piece.spec)),
+        None => Ok(None),
+    }
+}
+
+/// Format a range of times with a range template.
+pub(crate) fn fmt_range(
+    buf: &mut dyn Write,
+    start: &(impl Time + ?Sized),
+    end: &(impl Time + ?Sized),
+    template: &[u8],
+    options: &FormatOptions<'_>,
+) -> Result<(), Error> {
+    let formatter = RangeFormatter {
+        start,
+        end,
+        options,
+        shared: Shared::new(start, end),
+    };
+    formatter.fmt(buf, template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DateTime;
+
+    fn check(start: i64, end: i64, template: &str, expected: &str) {
+        let start = DateTime::from_timestamp(start, 0).unwrap();
+        let end = DateTime::from_timestamp(end, 0).unwrap();
+
+        let mut buf = [0u8; 100];
+        let mut cursor = &mut buf[..];
+        fmt_range(
+            &mut cursor,
+            &start,
+            &end,
+            template.as_bytes(),
+            &FormatOptions::new(),
+        )
+        .unwrap();
+        let written = 100 - cursor.len();
+        assert_eq!(core::str::from_utf8(&buf[..written]).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_fmt_range() {
+        // 2024-01-02 00:00:00, 2024-01-02 12:00:00, 2024-01-05 00:00:00,
+        // 2024-03-05 00:00:00 and 2025-01-02 00:00:00
+        let (jan2, jan2_noon, jan5) = (1_704_153_600, 1_704_196_800, 1_704_412_800);
+        let (mar5, jan2_2025) = (1_709_596_800, 1_735_776_000);
+
+        let template = "[%{start:%a %b %-d, %Y} - %{end:%a %b %-d, %Y}]";
+        check(
+            jan2,
+            jan2_2025,
+            template,
+            "[Tue Jan 2, 2024 - Thu Jan 2, 2025]",
+        );
+        check(jan2, mar5, template, "[Tue Jan 2 - Tue Mar 5, 2024]");
+        check(jan2, jan5, template, "[Tue Jan 2 - Fri 5, 2024]");
+        check(jan2, jan2_noon, template, "[Tue Jan 2, 2024]");
+
+        let template = "%{start:%F %R}/%{end:%F %R}";
+        check(jan2, jan2_noon, template, "2024-01-02 00:00/12:00");
+        check(jan2, jan5, template, "2024-01-02 00:00/2024-01-05 00:00");
+
+        // Text around the elided directives
+        check(
+            jan2,
+            jan5,
+            "%{start:(%d %m %Y)}%{end:(%m.%d)}",
+            "(02 01)(05)",
+        );
+        check(jan2, jan5, "%{start:%Y%m}", "01");
+
+        // Invalid directives and other text
+        check(
+            jan2,
+            jan2_2025,
+            "%{start:%Y %q %%} %n %{other:%Y}",
+            "2024 %q % %n %{other:%Y}",
+        );
+        check(jan2, jan2, "%{end:%d}", "");
+        check(jan2, jan2, "On %{end:%d}", "On ");
+        check(jan2, jan2, "%{end:%H}%{end:%d}", "00");
+    }
+
+    #[test]
+    fn test_fmt_range_error() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+        let options = FormatOptions::new();
+
+        for template in ["%{start:%Y", "%{end:%", "%{start:%}"] {
+            let mut buf = [0u8; 100];
+            let result = fmt_range(
+                &mut &mut buf[..],
+                &time,
+                &time,
+                template.as_bytes(),
+                &options,
+            );
+            assert!(
+                matches!(result, Err(Error::InvalidFormatString)),
+                "{template}"
+            );
+        }
+    }
+}
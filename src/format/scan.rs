@@ -0,0 +1,225 @@
+//! Search of the times matching a format string in a text.
+
+use core::ops::Range;
+
+use super::strptime::{parse_partial, ParsedFields};
+use crate::options::ParseOptions;
+
+/// Time found in a text by [`scan`](crate::strptime::scan) or
+/// [`scan_bytes`](crate::strptime::scan_bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match<'a> {
+    /// Byte offset of the start of the match.
+    start: usize,
+    /// Byte offset of the end of the match.
+    end: usize,
+    /// Fields extracted from the match.
+    fields: ParsedFields<'a>,
+}
+
+impl<'a> Match<'a> {
+    /// Byte offset of the start of the match in the text.
+    #[must_use]
+    pub const fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Byte offset of the end of the match in the text.
+    #[must_use]
+    pub const fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Byte range of the match in the text.
+    #[must_use]
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Fields extracted from the match.
+    #[must_use]
+    pub const fn fields(&self) -> ParsedFields<'a> {
+        self.fields
+    }
+}
+
+/// Iterator over the non-overlapping times matching a format string in a
+/// text, from left to right.
+///
+/// This struct is created by [`scan`](crate::strptime::scan) and
+/// [`scan_bytes`](crate::strptime::scan_bytes).
+#[derive(Debug, Clone)]
+pub struct Scan<'a, 'f> {
+    /// Text to search.
+    haystack: &'a [u8],
+    /// Format string.
+    format: &'f [u8],
+    /// Parsing options.
+    options: ParseOptions,
+    /// Byte offset of the next candidate start.
+    position: usize,
+    /// Whether matches must start and end on UTF-8 char boundaries.
+    utf8: bool,
+}
+
+impl<'a, 'f> Scan<'a, 'f> {
+    /// Construct a new `Scan` iterator.
+    pub(crate) const fn new(
+        haystack: &'a [u8],
+        format: &'f [u8],
+        options: ParseOptions,
+        utf8: bool,
+    ) -> Self {
+        Self {
+            haystack,
+            format,
+            options,
+            position: 0,
+            utf8,
+        }
+    }
+
+    /// Check if a match can start at the specified byte offset.
+    fn can_start_at(&self, start: usize) -> bool {
+        let byte = self.haystack[start];
+        if self.utf8 && !is_char_boundary(byte) {
+            return false;
+        }
+        // Padding before a number is not part of the match
+        if byte.is_ascii_whitespace() && !self.format.first().map_or(false, u8::is_ascii_whitespace)
+        {
+            return false;
+        }
+        // Never start a match in the middle of a number
+        !(start > 0 && byte.is_ascii_digit() && self.haystack[start - 1].is_ascii_digit())
+    }
+
+    /// Check if a match can end at the specified byte offset.
+    fn can_end_at(&self, end: usize) -> bool {
+        match self.haystack.get(end) {
+            Some(&byte) => !self.utf8 || is_char_boundary(byte),
+            None => true,
+        }
+    }
+}
+
+impl<'a> Iterator for Scan<'a, '_> {
+    type Item = Match<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.haystack.len() {
+            let start = self.position;
+            self.position += 1;
+
+            if !self.can_start_at(start) {
+                continue;
+            }
+
+            if let Ok((fields, remaining)) =
+                parse_partial(&self.haystack[start..], self.format, self.options)
+            {
+                let end = self.haystack.len() - remaining.len();
+                if end > start && self.can_end_at(end) {
+                    self.position = end;
+                    return Some(Match { start, end, fields });
+                }
+            }
+        }
+        None
+    }
+}
+
+impl core::iter::FusedIterator for Scan<'_, '_> {}
+
+/// Check if a byte starts a UTF-8 char, i.e. it is not a continuation byte.
+const fn is_char_boundary(byte: u8) -> bool {
+    byte & 0xC0 != 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan<'a>(
+        haystack: &'a str,
+        format: &'static str,
+    ) -> impl Iterator<Item = (&'a str, Match<'a>)> {
+        Scan::new(
+            haystack.as_bytes(),
+            format.as_bytes(),
+            ParseOptions::new(),
+            true,
+        )
+        .map(move |m| (&haystack[m.range()], m))
+    }
+
+    #[test]
+    fn test_scan() {
+        let text = "start 2024-07-08 10:00 then 2024-07-09 11:30, end";
+        let mut matches = scan(text, "%F %R");
+
+        let (s, m) = matches.next().unwrap();
+        assert_eq!(s, "2024-07-08 10:00");
+        assert_eq!(m.range(), 6..22);
+        assert_eq!(m.fields().day, Some(8));
+        assert_eq!(m.fields().hour, Some(10));
+
+        let (s, m) = matches.next().unwrap();
+        assert_eq!(s, "2024-07-09 11:30");
+        assert_eq!((m.start(), m.end()), (28, 44));
+        assert_eq!(m.fields().minute, Some(30));
+
+        assert!(matches.next().is_none());
+    }
+
+    #[test]
+    fn test_scan_no_match() {
+        assert!(scan("", "%F").next().is_none());
+        assert!(scan("no time here", "%F").next().is_none());
+        assert!(scan("2024-07-08", "%Q").next().is_none());
+        assert!(scan("2024-07-08", "%").next().is_none());
+    }
+
+    #[test]
+    fn test_scan_numbers() {
+        // Matches don't start in the middle of a number
+        let mut matches = scan("123/04 and 23/05", "%d/%m").map(|(s, _)| s);
+        assert_eq!(matches.next(), Some("23/05"));
+        assert_eq!(matches.next(), None);
+
+        let mut matches = scan("1 2 3", "%d").map(|(s, _)| s);
+        assert_eq!(matches.next(), Some("1"));
+        assert_eq!(matches.next(), Some("2"));
+        assert_eq!(matches.next(), Some("3"));
+        assert_eq!(matches.next(), None);
+
+        let mut matches = scan("on  8 Jul", "%e %b").map(|(s, _)| s);
+        assert_eq!(matches.next(), Some("8 Jul"));
+        assert_eq!(matches.next(), None);
+
+        let mut matches = scan("on  8 Jul", " %e %b").map(|(s, _)| s);
+        assert_eq!(matches.next(), Some("  8 Jul"));
+        assert_eq!(matches.next(), None);
+    }
+
+    #[test]
+    fn test_scan_non_ascii() {
+        let text = "día 8 – Jul 2024 – año";
+        let mut matches = scan(text, "%b %Y");
+        let (s, m) = matches.next().unwrap();
+        assert_eq!(s, "Jul 2024");
+        assert_eq!(m.range(), 11..19);
+        assert_eq!(m.fields().year, Some(2024));
+        assert!(matches.next().is_none());
+    }
+
+    #[test]
+    fn test_scan_bytes() {
+        let haystack = b"\xFF10:30\xFF\xFF11:45";
+        let mut matches = Scan::new(haystack, b"%R", ParseOptions::new(), false);
+        assert_eq!(matches.next().map(|m| m.range()), Some(1..6));
+        assert_eq!(matches.next().map(|m| m.range()), Some(8..13));
+        assert_eq!(matches.next(), None);
+        assert_eq!(matches.next(), None);
+    }
+}
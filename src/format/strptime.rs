@@ -0,0 +1,1496 @@
+//! Parsing of times with a format string, inverse of the formatting logic.
+
+use core::fmt;
+use core::ops::Range;
+use core::str;
+
+use super::utils::Cursor;
+use super::{parse_spec, Flag, Padding, Piece, Spec, DAYS, DAYS_UPPER, MONTHS, MONTHS_UPPER};
+use crate::calendar::{
+    civil_from_days, day_of_week, day_of_year, days_from_civil, SECONDS_PER_DAY,
+};
+use crate::options::ParseOptions;
+use crate::{Dialect, Time};
+
+/// Fields of a time extracted from an input string by a format string.
+///
+/// Each field is [`None`] if the format string has no directive for it. The
+/// fields are not checked for consistency with each other, like a day of the
+/// month which is invalid for the month, so a partial date can be completed
+/// by the caller.
+///
+/// When a field is set by several directives, the last one wins.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParsedFields<'a> {
+    /// Year, from `%Y`, or from `%y` and `%C`.
+    ///
+    /// A year from `%y` without `%C` is in `1969..=2068`, unless another
+    /// pivot is set with [`ParseOptions::with_year_pivot`].
+    pub year: Option<i32>,
+    /// Month of the year in `1..=12`, from `%m`, `%B` or `%b`.
+    pub month: Option<u8>,
+    /// Day of the month in `1..=31`, from `%d` or `%e`.
+    pub day: Option<u8>,
+    /// Day of the year in `1..=366`, from `%j`.
+    pub day_of_year: Option<u16>,
+    /// Hour of the day in `0..=24`, from `%H` or `%k`, or from `%I` or `%l`
+    /// and `%p` or `%P`.
+    ///
+    /// An hour from `%I` or `%l` without a meridian indicator is in `1..=12`.
+    pub hour: Option<u8>,
+    /// Minute of the hour in `0..=59`, from `%M`.
+    pub minute: Option<u8>,
+    /// Second of the minute in `0..=60`, from `%S`.
+    pub second: Option<u8>,
+    /// Truncated nanoseconds of the second, from `%L` or `%N`.
+    pub nanoseconds: Option<u32>,
+    /// Day of the week from Sunday in `0..=6`, from `%w`, `%A` or `%a`.
+    pub day_of_week: Option<u8>,
+    /// Day of the week from Monday in `1..=7`, from `%u`.
+    pub day_of_week_from_monday: Option<u8>,
+    /// ISO 8601 week-based year, from `%G`, or from `%g` and `%C`.
+    pub iso_8601_year: Option<i32>,
+    /// ISO 8601 week number in `1..=53`, from `%V`.
+    pub iso_8601_week_number: Option<u8>,
+    /// Week number from Sunday in `0..=53`, from `%U`.
+    pub week_number_from_sunday: Option<u8>,
+    /// Week number from Monday in `0..=53`, from `%W`.
+    pub week_number_from_monday: Option<u8>,
+    /// Number of seconds since `1970-01-01 00:00:00 UTC`, from `%s`.
+    pub seconds_since_epoch: Option<i64>,
+    /// Offset from UTC in seconds, from `%z`, `%:z`, `%::z` or `%:::z`.
+    pub utc_offset: Option<i32>,
+    /// Time zone abbreviation, from `%Z`.
+    ///
+    /// The abbreviation is returned verbatim, since it can be ambiguous, like
+    /// `CST` which is used for several time zones. It is not resolved to a
+    /// UTC offset.
+    pub time_zone: Option<&'a str>,
+}
+
+impl ParsedFields<'_> {
+    /// Construct a new `ParsedFields` with no field set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::ParsedFields;
+    ///
+    /// const EMPTY: ParsedFields = ParsedFields::new();
+    /// assert_eq!(EMPTY.year, None);
+    /// assert_eq!(EMPTY, ParsedFields::default());
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            year: None,
+            month: None,
+            day: None,
+            day_of_year: None,
+            hour: None,
+            minute: None,
+            second: None,
+            nanoseconds: None,
+            day_of_week: None,
+            day_of_week_from_monday: None,
+            iso_8601_year: None,
+            iso_8601_week_number: None,
+            week_number_from_sunday: None,
+            week_number_from_monday: None,
+            seconds_since_epoch: None,
+            utc_offset: None,
+            time_zone: None,
+        }
+    }
+
+    /// Returns the local date as a number of days since `1970-01-01`, and the
+    /// local time of the day as `(hour, minute, second)`.
+    ///
+    /// The number of seconds since the Epoch takes precedence over the other
+    /// fields. Otherwise, the date is taken from the first available of the
+    /// month and the day, the day of the year, the ISO 8601 week, the week
+    /// from Monday, the week from Sunday, and the month or the day alone. A
+    /// missing year is 1970, and a missing month, day or time field is the
+    /// first one.
+    fn local_date_time(&self) -> (i64, u8, u8, u8) {
+        if let Some(seconds) = self.seconds_since_epoch {
+            let local = seconds.saturating_add(i64::from(self.utc_offset.unwrap_or(0)));
+            let days = local.div_euclid(SECONDS_PER_DAY);
+            let seconds = local.rem_euclid(SECONDS_PER_DAY);
+            // The values are in range of a day
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let time = (
+                (seconds / 3600) as u8,
+                (seconds / 60 % 60) as u8,
+                (seconds % 60) as u8,
+            );
+            return (days, time.0, time.1, time.2);
+        }
+
+        let days = self.local_days();
+        let (minute, second) = (self.minute.unwrap_or(0), self.second.unwrap_or(0));
+        match self.hour.unwrap_or(0) {
+            // `24:00:00` is the midnight at the end of the day
+            hour @ 24.. => (days + 1, hour - 24, minute, second),
+            hour => (days, hour, minute, second),
+        }
+    }
+
+    /// Returns the local date as a number of days since `1970-01-01`.
+    fn local_days(&self) -> i64 {
+        let year = i64::from(self.year.unwrap_or(1970));
+        if let (Some(month), Some(day)) = (self.month, self.day) {
+            return days_from_civil(year, month, day);
+        }
+
+        let jan_1 = days_from_civil(year, 1, 1);
+        if let Some(day_of_year) = self.day_of_year {
+            return jan_1 + i64::from(day_of_year) - 1;
+        }
+
+        // Days since the start of the week
+        let from_monday = match (self.day_of_week_from_monday, self.day_of_week) {
+            (Some(day), _) => i64::from(day) - 1,
+            (None, Some(day)) => i64::from((day + 6) % 7),
+            (None, None) => 0,
+        };
+        let from_sunday = (from_monday + 1) % 7;
+
+        if let Some(week) = self.iso_8601_week_number {
+            // The first ISO 8601 week has the 4th of January
+            let year = self.iso_8601_year.map_or(year, i64::from);
+            let jan_4 = days_from_civil(year, 1, 4);
+            let first_monday = jan_4 - i64::from((day_of_week(jan_4) + 6) % 7);
+            first_monday + 7 * (i64::from(week) - 1) + from_monday
+        } else if let Some(week) = self.week_number_from_monday {
+            let first_monday = jan_1 + i64::from((8 - day_of_week(jan_1)) % 7);
+            first_monday + 7 * (i64::from(week) - 1) + from_monday
+        } else if let Some(week) = self.week_number_from_sunday {
+            let first_sunday = jan_1 + i64::from((7 - day_of_week(jan_1)) % 7);
+            first_sunday + 7 * (i64::from(week) - 1) + from_sunday
+        } else {
+            days_from_civil(year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+        }
+    }
+
+    /// Returns the local civil date as `(year, month, day)`.
+    fn local_date(&self) -> (i64, u8, u8) {
+        civil_from_days(self.local_date_time().0)
+    }
+}
+
+/// The parsed fields are a [`Time`], so a parsed time can be formatted again
+/// without an intermediate type.
+///
+/// The fields which are not set are derived from the other fields with the
+/// proleptic Gregorian calendar:
+///
+/// - The number of seconds since the Epoch from `%s` takes precedence over the
+///   other fields, and is converted to the local time with the UTC offset.
+/// - Otherwise, the date is taken from the month and the day, from the day of
+///   the year, or from a week number and a day of the week, in this order of
+///   precedence. A missing year is 1970, and a missing month, day, hour,
+///   minute or second is the first one.
+///   Out of range values like the 31st of February or the hour 24 overflow to
+///   the next days.
+/// - The day of the week, the day of the year and the number of seconds since
+///   the Epoch are computed from the date, the time and the UTC offset.
+/// - A time without UTC offset is in UTC. The time zone abbreviation is
+///   returned verbatim, and is `"UTC"` for a time in UTC without abbreviation.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::buffered::strftime;
+/// use strftime::strptime::strptime;
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = strptime("2024-190 13:05 +0200", "%Y-%j %H:%M %z").unwrap();
+///
+/// let mut buf = [0u8; 64];
+/// assert_eq!(strftime(&time, b"%a %b %-d %T %z (%s)", &mut buf)?, b"Mon Jul 8 13:05:00 +0200 (1720436700)");
+/// # Ok(())
+/// # }
+/// ```
+impl Time for ParsedFields<'_> {
+    fn year(&self) -> i32 {
+        let (year, _, _) = self.local_date();
+        // Saturate a year out of range of an `i32`
+        i32::try_from(year).unwrap_or(if year < 0 { i32::MIN } else { i32::MAX })
+    }
+
+    fn month(&self) -> u8 {
+        self.local_date().1
+    }
+
+    fn day(&self) -> u8 {
+        self.local_date().2
+    }
+
+    fn hour(&self) -> u8 {
+        self.local_date_time().1
+    }
+
+    fn minute(&self) -> u8 {
+        self.local_date_time().2
+    }
+
+    fn second(&self) -> u8 {
+        self.local_date_time().3
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.nanoseconds.unwrap_or(0)
+    }
+
+    fn day_of_week(&self) -> u8 {
+        day_of_week(self.local_date_time().0)
+    }
+
+    fn day_of_year(&self) -> u16 {
+        let (year, month, day) = self.local_date();
+        day_of_year(year, month, day)
+    }
+
+    fn to_int(&self) -> i64 {
+        if let Some(seconds) = self.seconds_since_epoch {
+            return seconds;
+        }
+
+        let (days, hour, minute, second) = self.local_date_time();
+        let time = i64::from(hour) * 3600 + i64::from(minute) * 60 + i64::from(second);
+        days * SECONDS_PER_DAY + time - i64::from(self.utc_offset())
+    }
+
+    fn is_utc(&self) -> bool {
+        self.utc_offset.is_none()
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset.unwrap_or(0)
+    }
+
+    fn time_zone(&self) -> &str {
+        match self.time_zone {
+            Some(time_zone) => time_zone,
+            None if self.is_utc() => "UTC",
+            None => "",
+        }
+    }
+}
+
+/// Kind of a [`ParseError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// Provided format string is ended by an unterminated format specifier.
+    InvalidFormatString,
+    /// Format string has a directive which cannot be parsed, like the day
+    /// period `%:p`.
+    UnsupportedDirective,
+    /// Input doesn't match the format string, or has a value out of range.
+    InputMismatch,
+    /// Input has remaining text after the end of the format string.
+    TrailingInput,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormatString => f.write_str("invalid format string"),
+            Self::UnsupportedDirective => f.write_str("unsupported directive for parsing"),
+            Self::InputMismatch => f.write_str("input does not match the format string"),
+            Self::TrailingInput => f.write_str("trailing input after the format string"),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for ParseErrorKind {
+    // The interned strings of `defmt::write!` expand to identical code
+    #[allow(clippy::match_same_arms)]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::InvalidFormatString => defmt::write!(f, "invalid format string"),
+            Self::UnsupportedDirective => defmt::write!(f, "unsupported directive for parsing"),
+            Self::InputMismatch => defmt::write!(f, "input does not match the format string"),
+            Self::TrailingInput => defmt::write!(f, "trailing input after the format string"),
+        }
+    }
+}
+
+/// Input expected by the parser when a [`ParseError`] occurred.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expected {
+    /// Literal text of the format string.
+    Literal,
+    /// ASCII digits.
+    Digits,
+    /// A number in `min..=max`.
+    Range {
+        /// Minimum value.
+        min: i64,
+        /// Maximum value.
+        max: i64,
+    },
+    /// A full or abbreviated month name.
+    MonthName,
+    /// A full or abbreviated weekday name.
+    WeekDayName,
+    /// A meridian indicator, like `AM`.
+    Meridian,
+    /// The sign of a UTC offset.
+    Sign,
+    /// A time zone abbreviation.
+    TimeZone,
+    /// The end of the input.
+    End,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal => f.write_str("the literal text of the format string"),
+            Self::Digits => f.write_str("digits"),
+            Self::Range { min, max } => write!(f, "a number in {min}..={max}"),
+            Self::MonthName => f.write_str("a month name"),
+            Self::WeekDayName => f.write_str("a weekday name"),
+            Self::Meridian => f.write_str("a meridian indicator"),
+            Self::Sign => f.write_str("a sign"),
+            Self::TimeZone => f.write_str("a time zone abbreviation"),
+            Self::End => f.write_str("the end of the input"),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for Expected {
+    // The interned strings of `defmt::write!` expand to identical code
+    #[allow(clippy::match_same_arms)]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::Literal => defmt::write!(f, "the literal text of the format string"),
+            Self::Digits => defmt::write!(f, "digits"),
+            Self::Range { min, max } => defmt::write!(f, "a number in {=i64}..={=i64}", min, max),
+            Self::MonthName => defmt::write!(f, "a month name"),
+            Self::WeekDayName => defmt::write!(f, "a weekday name"),
+            Self::Meridian => defmt::write!(f, "a meridian indicator"),
+            Self::Sign => defmt::write!(f, "a sign"),
+            Self::TimeZone => defmt::write!(f, "a time zone abbreviation"),
+            Self::End => defmt::write!(f, "the end of the input"),
+        }
+    }
+}
+
+/// Error type returned by the `strptime` functions.
+///
+/// The error reports the byte offset in the input where it occurred, the
+/// directive of the format string being matched and the expected input, so
+/// it can be rendered for users.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::strptime::{strptime, Expected, ParseErrorKind};
+///
+/// let err = strptime("2024-13-01", "%Y-%m-%d").unwrap_err();
+///
+/// assert_eq!(err.kind(), ParseErrorKind::InputMismatch);
+/// assert_eq!(err.input_offset(), 5);
+/// assert_eq!(err.directive(), Some(3..5));
+/// assert_eq!(err.expected(), Some(Expected::Range { min: 1, max: 12 }));
+/// assert_eq!(
+///     err.to_string(),
+///     "input does not match the format string at byte 5 of the input, \
+///      expected a number in 1..=12 for the directive at byte 3 of the format string"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// Kind of the error.
+    kind: ParseErrorKind,
+    /// Byte offset in the input where the error occurred.
+    input_offset: usize,
+    /// Byte offset and length of the directive in the format string.
+    directive: Option<(usize, usize)>,
+    /// Expected input.
+    expected: Option<Expected>,
+}
+
+impl ParseError {
+    /// Construct a new `ParseError`.
+    pub(crate) const fn new(
+        kind: ParseErrorKind,
+        input_offset: usize,
+        expected: Option<Expected>,
+    ) -> Self {
+        Self {
+            kind,
+            input_offset,
+            directive: None,
+            expected,
+        }
+    }
+
+    /// Construct an error for an input which doesn't match the format string
+    /// at the provided offset.
+    const fn mismatch(input_offset: usize, expected: Expected) -> Self {
+        Self::new(ParseErrorKind::InputMismatch, input_offset, Some(expected))
+    }
+
+    /// Set the position of the directive being matched, if it is not set.
+    fn with_directive(mut self, start: usize, len: usize) -> Self {
+        self.directive = self.directive.or(Some((start, len)));
+        self
+    }
+
+    /// Returns the kind of the error.
+    #[must_use]
+    pub const fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset in the input where the error occurred.
+    ///
+    /// For a value out of range, this is the offset of the start of the value.
+    #[must_use]
+    pub const fn input_offset(&self) -> usize {
+        self.input_offset
+    }
+
+    /// Returns the byte range of the directive being matched in the format
+    /// string, if any.
+    ///
+    /// For an error in a combination like `%F`, this is the range of the
+    /// combination.
+    #[must_use]
+    pub fn directive(&self) -> Option<Range<usize>> {
+        self.directive.map(|(start, len)| start..start + len)
+    }
+
+    /// Returns the expected input, if it is known.
+    #[must_use]
+    pub const fn expected(&self) -> Option<Expected> {
+        self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at byte {} of the input",
+            self.kind, self.input_offset
+        )?;
+        if let Some(expected) = self.expected {
+            write!(f, ", expected {expected}")?;
+        }
+        if let Some((start, _)) = self.directive {
+            write!(f, " for the directive at byte {start} of the format string")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for ParseError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "{} at byte {=usize} of the input",
+            self.kind,
+            self.input_offset
+        );
+        if let Some(expected) = self.expected {
+            defmt::write!(f, ", expected {}", expected);
+        }
+        if let Some((start, _)) = self.directive {
+            defmt::write!(
+                f,
+                " for the directive at byte {=usize} of the format string",
+                start
+            );
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "core-error"))))]
+impl crate::error::Error for ParseError {}
+
+/// Specifier characters of the numeric directives, which limit the number of
+/// digits of a preceding `%Y`, `%G` or `%C` directive.
+pub(crate) const NUMERIC_SPECS: &[u8] = b"CDdeFGgHIjkLlMmNRrSsTUuVvWwXxYy";
+
+/// Parsing state.
+struct Parser<'i> {
+    /// Remaining input.
+    input: Cursor<'i>,
+    /// Length of the whole input.
+    input_len: usize,
+    /// Parsed fields.
+    fields: ParsedFields<'i>,
+    /// Century, from `%C`.
+    century: Option<i32>,
+    /// Year without century, from `%y`.
+    year_rem_100: Option<i32>,
+    /// ISO 8601 week-based year without century, from `%g`.
+    iso_8601_year_rem_100: Option<i32>,
+    /// Whether the hour is after noon, from `%p` or `%P`.
+    is_pm: Option<bool>,
+    /// Parsing options.
+    options: ParseOptions,
+}
+
+impl<'i> Parser<'i> {
+    /// Construct a new `Parser`.
+    fn new(input: &'i [u8], options: ParseOptions) -> Self {
+        Self {
+            input: Cursor::new(input),
+            input_len: input.len(),
+            fields: ParsedFields::new(),
+            century: None,
+            year_rem_100: None,
+            iso_8601_year_rem_100: None,
+            is_pm: None,
+            options,
+        }
+    }
+
+    /// Returns the byte offset of the remaining input.
+    fn offset(&self) -> usize {
+        self.input_len - self.input.remaining().len()
+    }
+
+    /// Parse the input with a format string.
+    ///
+    /// The position of the directives is only reported for the format string
+    /// provided by the caller, not for the expansion of a combination.
+    fn parse_format(&mut self, format: &[u8], is_expansion: bool) -> Result<(), ParseError> {
+        let mut cursor = Cursor::new(format);
+
+        loop {
+            self.expect(cursor.read_until(|&x| x == b'%'))?;
+
+            let remaining_before = cursor.remaining();
+            let start = format.len() - remaining_before.len();
+
+            // Read the '%' character
+            if cursor.next().is_none() {
+                return Ok(());
+            }
+
+            let piece = parse_spec(&mut cursor, Dialect::Ruby);
+
+            let remaining_after = cursor.remaining();
+            let len = remaining_before.len() - remaining_after.len();
+
+            let result = match piece {
+                Ok(Some(piece)) => {
+                    if self.options.lenient() {
+                        self.input.read_while(u8::is_ascii_whitespace);
+                    }
+                    self.parse_piece(&piece, is_followed_by_number(remaining_after))
+                }
+                // Invalid directives are matched verbatim
+                Ok(None) => self.expect(&remaining_before[..len]),
+                Err(_) => Err(ParseError::new(
+                    ParseErrorKind::InvalidFormatString,
+                    self.offset(),
+                    None,
+                )),
+            };
+
+            match result {
+                Err(err) if !is_expansion => return Err(err.with_directive(start, len)),
+                result => result?,
+            }
+        }
+    }
+
+    /// Parse a directive.
+    #[allow(clippy::too_many_lines)]
+    fn parse_piece(&mut self, piece: &Piece, followed_by_number: bool) -> Result<(), ParseError> {
+        // A year directive followed by a number is limited to 4 digits
+        let year_digits = if followed_by_number { 4 } else { usize::MAX };
+
+        match piece.spec {
+            Spec::Year4Digits => {
+                self.fields.year = Some(self.read_signed(piece, year_digits)?);
+                self.year_rem_100 = None;
+            }
+            Spec::YearDiv100 => {
+                let digits = if followed_by_number { 2 } else { usize::MAX };
+                self.century = Some(self.read_signed(piece, digits)?);
+            }
+            Spec::YearRem100 => self.year_rem_100 = Some(self.read_number(piece, 2, 0, 99)?),
+            Spec::Month => self.fields.month = Some(self.read_number(piece, 2, 1, 12)?),
+            Spec::MonthName | Spec::MonthNameAbbr => {
+                let index = self.read_name(piece, &MONTHS, &MONTHS_UPPER, Expected::MonthName)?;
+                self.fields.month = Some(index + 1);
+            }
+            Spec::MonthDayZero | Spec::MonthDaySpace => {
+                self.fields.day = Some(self.read_number(piece, 2, 1, 31)?);
+            }
+            Spec::YearDay => self.fields.day_of_year = Some(self.read_number(piece, 3, 1, 366)?),
+            Spec::Hour24hZero | Spec::Hour24hSpace => {
+                self.fields.hour = Some(self.read_number(piece, 2, 0, 24)?);
+            }
+            Spec::Hour12hZero | Spec::Hour12hSpace => {
+                self.fields.hour = Some(self.read_number(piece, 2, 1, 12)?);
+            }
+            Spec::MeridianLower | Spec::MeridianUpper => {
+                self.is_pm = Some(self.read_meridian(piece)?);
+            }
+            Spec::Minute => self.fields.minute = Some(self.read_number(piece, 2, 0, 59)?),
+            Spec::Second => self.fields.second = Some(self.read_number(piece, 2, 0, 60)?),
+            #[cfg(feature = "subsec")]
+            Spec::MilliSecond | Spec::FractionalSecond => {
+                let default_digits = if piece.spec == Spec::MilliSecond {
+                    3
+                } else {
+                    9
+                };
+                let digits = if followed_by_number {
+                    default_digits
+                } else {
+                    usize::MAX
+                };
+                self.fields.nanoseconds = Some(self.read_fraction(piece.width.unwrap_or(digits))?);
+            }
+            Spec::WeekDayName | Spec::WeekDayNameAbbr => {
+                self.fields.day_of_week =
+                    Some(self.read_name(piece, &DAYS, &DAYS_UPPER, Expected::WeekDayName)?);
+            }
+            Spec::WeekDayFrom1 => {
+                self.fields.day_of_week_from_monday = Some(self.read_number(piece, 1, 1, 7)?);
+            }
+            Spec::WeekDayFrom0 => self.fields.day_of_week = Some(self.read_number(piece, 1, 0, 6)?),
+            Spec::YearIso8601 => {
+                self.fields.iso_8601_year = Some(self.read_signed(piece, year_digits)?);
+                self.iso_8601_year_rem_100 = None;
+            }
+            Spec::YearIso8601Rem100 => {
+                self.iso_8601_year_rem_100 = Some(self.read_number(piece, 2, 0, 99)?);
+            }
+            Spec::WeekNumberIso8601 => {
+                self.fields.iso_8601_week_number = Some(self.read_number(piece, 2, 1, 53)?);
+            }
+            Spec::WeekNumberFromSunday => {
+                self.fields.week_number_from_sunday = Some(self.read_number(piece, 2, 0, 53)?);
+            }
+            Spec::WeekNumberFromMonday => {
+                self.fields.week_number_from_monday = Some(self.read_number(piece, 2, 0, 53)?);
+            }
+            Spec::SecondsSinceEpoch => {
+                self.fields.seconds_since_epoch = Some(self.read_signed(piece, usize::MAX)?);
+            }
+            Spec::Newline => self.expect(b"\n")?,
+            Spec::Tabulation => self.expect(b"\t")?,
+            Spec::Percent => self.expect(b"%")?,
+            Spec::CombinationDateTime => self.parse_format(b"%a %b %e %H:%M:%S %Y", true)?,
+            Spec::CombinationDate => self.parse_format(b"%m/%d/%y", true)?,
+            Spec::CombinationIso8601 => self.parse_format(b"%Y-%m-%d", true)?,
+            Spec::CombinationVmsDate => self.parse_format(b"%e-%^b-%Y", true)?,
+            Spec::CombinationTime12h => self.parse_format(b"%I:%M:%S %p", true)?,
+            Spec::CombinationHourMinute24h => self.parse_format(b"%H:%M", true)?,
+            Spec::CombinationTime24h => self.parse_format(b"%H:%M:%S", true)?,
+            Spec::TimeZoneOffsetHourMinute => {
+                self.fields.utc_offset = Some(self.read_utc_offset(b"", 2, 2)?);
+            }
+            Spec::TimeZoneOffsetHourMinuteColon => {
+                self.fields.utc_offset = Some(self.read_utc_offset(b":", 2, 2)?);
+            }
+            Spec::TimeZoneOffsetHourMinuteSecondColon => {
+                self.fields.utc_offset = Some(self.read_utc_offset(b":", 3, 3)?);
+            }
+            Spec::TimeZoneOffsetColonMinimal => {
+                self.fields.utc_offset = Some(self.read_utc_offset(b":", 1, 3)?);
+            }
+            Spec::TimeZoneOffsetHourMinuteSecond => {
+                self.fields.utc_offset = Some(self.read_utc_offset(b"", 3, 3)?);
+            }
+            Spec::TimeZoneName => self.fields.time_zone = Some(self.read_time_zone()?),
+            #[cfg(feature = "subsec")]
+            Spec::SecondsSinceEpochFractional => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnsupportedDirective,
+                    self.offset(),
+                    None,
+                ));
+            }
+            Spec::DayPeriod => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnsupportedDirective,
+                    self.offset(),
+                    None,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read bytes which must match the provided text.
+    ///
+    /// In lenient mode, whitespace in the text matches any run of whitespace,
+    /// including none.
+    fn expect(&mut self, text: &[u8]) -> Result<(), ParseError> {
+        if !self.options.lenient() {
+            let offset = self.offset();
+            return if self.input.read_optional_tag(text) {
+                Ok(())
+            } else {
+                Err(ParseError::mismatch(offset, Expected::Literal))
+            };
+        }
+
+        let mut text = Cursor::new(text);
+        loop {
+            if !text.read_while(u8::is_ascii_whitespace).is_empty() {
+                self.input.read_while(u8::is_ascii_whitespace);
+            }
+
+            let word = text.read_until(u8::is_ascii_whitespace);
+            if word.is_empty() {
+                return Ok(());
+            }
+            let offset = self.offset();
+            if !self.input.read_optional_tag(word) {
+                return Err(ParseError::mismatch(offset, Expected::Literal));
+            }
+        }
+    }
+
+    /// Read a name if the input starts with it, ignoring case in lenient
+    /// mode.
+    fn read_name_tag(&mut self, name: &[u8]) -> bool {
+        let remaining = self.input.remaining();
+        match remaining.get(..name.len()) {
+            Some(prefix)
+                if prefix == name
+                    || self.options.lenient() && prefix.eq_ignore_ascii_case(name) =>
+            {
+                self.input = Cursor::new(&remaining[name.len()..]);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Read the blank padding of a directive, returning the number of read
+    /// spaces.
+    ///
+    /// Like Ruby, a single space is accepted before a day of the month or an
+    /// hour, whatever the padding flag.
+    fn read_padding(&mut self, piece: &Piece) -> usize {
+        match piece.spec {
+            _ if piece.padding == Padding::Spaces => self.input.read_while(|&x| x == b' ').len(),
+            Spec::MonthDayZero
+            | Spec::MonthDaySpace
+            | Spec::Hour24hZero
+            | Spec::Hour24hSpace
+            | Spec::Hour12hZero
+            | Spec::Hour12hSpace => usize::from(self.input.read_optional_tag(b" ")),
+            _ => 0,
+        }
+    }
+
+    /// Read at most `max_digits` ASCII digits, or the number of digits set by
+    /// the width.
+    fn read_digits(&mut self, piece: &Piece, max_digits: usize) -> Result<&'i [u8], ParseError> {
+        let max_digits = piece.width.unwrap_or(max_digits).max(1);
+
+        let remaining = self.input.remaining();
+        let len = remaining
+            .iter()
+            .take(max_digits)
+            .take_while(|x| x.is_ascii_digit())
+            .count();
+
+        if len == 0 {
+            return Err(ParseError::mismatch(self.offset(), Expected::Digits));
+        }
+
+        let digits = &remaining[..len];
+        self.input = Cursor::new(&remaining[len..]);
+        Ok(digits)
+    }
+
+    /// Read an unsigned number in `min..=max`.
+    fn read_number<T: TryFrom<i64>>(
+        &mut self,
+        piece: &Piece,
+        max_digits: usize,
+        min: i64,
+        max: i64,
+    ) -> Result<T, ParseError> {
+        let padding = self.read_padding(piece);
+        let offset = self.offset();
+        let digits = self.read_digits(piece, max_digits.saturating_sub(padding).max(1))?;
+
+        match parse_int(digits).filter(|value| (min..=max).contains(value)) {
+            Some(value) => T::try_from(value).ok(),
+            None => None,
+        }
+        .ok_or_else(|| ParseError::mismatch(offset, Expected::Range { min, max }))
+    }
+
+    /// Read a signed number with an optional sign, in the range of `T`.
+    fn read_signed<T: TryFrom<i64> + Into<i64> + Bounded>(
+        &mut self,
+        piece: &Piece,
+        max_digits: usize,
+    ) -> Result<T, ParseError> {
+        self.read_padding(piece);
+        let offset = self.offset();
+
+        let is_negative = if self.input.read_optional_tag(b"-") {
+            true
+        } else {
+            self.input.read_optional_tag(b"+");
+            false
+        };
+
+        let digits = self.read_digits(piece, max_digits)?;
+
+        parse_int(digits)
+            .map(|value| if is_negative { -value } else { value })
+            .and_then(|value| T::try_from(value).ok())
+            .ok_or_else(|| {
+                let (min, max) = (T::MIN.into(), T::MAX.into());
+                ParseError::mismatch(offset, Expected::Range { min, max })
+            })
+    }
+
+    /// Read fractional seconds digits, returning the truncated number of
+    /// nanoseconds.
+    #[cfg(feature = "subsec")]
+    fn read_fraction(&mut self, max_digits: usize) -> Result<u32, ParseError> {
+        let remaining = self.input.remaining();
+        let len = remaining
+            .iter()
+            .take(max_digits.max(1))
+            .take_while(|x| x.is_ascii_digit())
+            .count();
+
+        if len == 0 {
+            return Err(ParseError::mismatch(self.offset(), Expected::Digits));
+        }
+
+        self.input = Cursor::new(&remaining[len..]);
+
+        let nanoseconds = remaining[..len]
+            .iter()
+            .chain(core::iter::repeat(&b'0'))
+            .take(9)
+            .fold(0, |acc, &digit| acc * 10 + u32::from(digit - b'0'));
+
+        Ok(nanoseconds)
+    }
+
+    /// Read a full or abbreviated name, returning its index.
+    ///
+    /// The name must be capitalized like the formatted names, or in upper case
+    /// if the directive has the `^` or `#` flags. The case is ignored in
+    /// lenient mode.
+    fn read_name(
+        &mut self,
+        piece: &Piece,
+        names: &[&str],
+        names_upper: &[&str],
+        expected: Expected,
+    ) -> Result<u8, ParseError> {
+        if piece.width.is_some() {
+            self.input.read_while(|&x| x == b' ');
+        }
+
+        let names = if piece.flags.has_change_or_upper_case() {
+            names_upper
+        } else {
+            names
+        };
+
+        for (index, name) in names.iter().enumerate() {
+            let name = name.as_bytes();
+            if self.read_name_tag(name) || self.read_name_tag(&name[..3]) {
+                if let Ok(index) = u8::try_from(index) {
+                    return Ok(index);
+                }
+            }
+        }
+
+        Err(ParseError::mismatch(self.offset(), expected))
+    }
+
+    /// Read a meridian indicator, returning `true` if the hour is after
+    /// noon.
+    fn read_meridian(&mut self, piece: &Piece) -> Result<bool, ParseError> {
+        let is_upper = match piece.spec {
+            Spec::MeridianUpper => !piece.flags.contains(Flag::ChangeCase),
+            _ => piece.flags.has_change_or_upper_case(),
+        };
+
+        let (am, pm): (&[u8], &[u8]) = if is_upper {
+            (b"AM", b"PM")
+        } else {
+            (b"am", b"pm")
+        };
+
+        if self.read_name_tag(am) {
+            Ok(false)
+        } else if self.read_name_tag(pm) {
+            Ok(true)
+        } else {
+            Err(ParseError::mismatch(self.offset(), Expected::Meridian))
+        }
+    }
+
+    /// Read a signed UTC offset with an hour and optional minute and second,
+    /// returning the offset in seconds.
+    ///
+    /// Each part has 2 digits. The parts are separated by the provided
+    /// separator, and the number of parts is in `min_parts..=max_parts`.
+    fn read_utc_offset(
+        &mut self,
+        separator: &[u8],
+        min_parts: usize,
+        max_parts: usize,
+    ) -> Result<i32, ParseError> {
+        let sign = match self.input.remaining().first() {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Err(ParseError::mismatch(self.offset(), Expected::Sign)),
+        };
+        self.input.next();
+
+        let hour = self.read_two_digits(99)?;
+        let mut minute_second = [0; 2];
+
+        for (part, value) in (1..max_parts).zip(&mut minute_second) {
+            if part >= min_parts {
+                let remaining = self.input.remaining();
+                let has_part = remaining.starts_with(separator)
+                    && remaining
+                        .get(separator.len())
+                        .map_or(false, u8::is_ascii_digit);
+                if !has_part {
+                    break;
+                }
+            }
+
+            self.expect(separator)?;
+            *value = self.read_two_digits(59)?;
+        }
+
+        let [minute, second] = minute_second;
+        Ok(sign * (hour * 3600 + minute * 60 + second))
+    }
+
+    /// Read a number of exactly 2 digits in `0..=max`.
+    fn read_two_digits(&mut self, max: i32) -> Result<i32, ParseError> {
+        let offset = self.offset();
+        match self.input.remaining() {
+            [tens @ b'0'..=b'9', units @ b'0'..=b'9', ..] => {
+                let value = i32::from(tens - b'0') * 10 + i32::from(units - b'0');
+                if value > max {
+                    let (min, max) = (0, i64::from(max));
+                    return Err(ParseError::mismatch(offset, Expected::Range { min, max }));
+                }
+                self.input.next();
+                self.input.next();
+                Ok(value)
+            }
+            _ => Err(ParseError::mismatch(offset, Expected::Digits)),
+        }
+    }
+
+    /// Read a time zone abbreviation, made of ASCII letters like `CEST`, or of
+    /// a sign and digits like `+03`.
+    fn read_time_zone(&mut self) -> Result<&'i str, ParseError> {
+        let remaining = self.input.remaining();
+
+        let len = match remaining {
+            [b'+' | b'-', rest @ ..] => {
+                match rest.iter().take_while(|x| x.is_ascii_digit()).count() {
+                    0 => 0,
+                    digits => digits + 1,
+                }
+            }
+            _ => remaining
+                .iter()
+                .take_while(|x| x.is_ascii_alphabetic())
+                .count(),
+        };
+
+        let time_zone = match str::from_utf8(&remaining[..len]) {
+            Ok(time_zone) if !time_zone.is_empty() => time_zone,
+            _ => return Err(ParseError::mismatch(self.offset(), Expected::TimeZone)),
+        };
+
+        self.input = Cursor::new(&remaining[len..]);
+        Ok(time_zone)
+    }
+
+    /// Combine the parsed values into the fields.
+    fn finish(self) -> ParsedFields<'i> {
+        let mut fields = self.fields;
+
+        // Like Ruby, a year without century is in `1969..=2068` by default
+        let year_pivot = i32::from(self.options.year_pivot());
+        let with_century = |year: i32| {
+            let century = self
+                .century
+                .unwrap_or(if year >= year_pivot { 19 } else { 20 });
+            century.checked_mul(100)?.checked_add(year)
+        };
+
+        if let Some(year) = self.year_rem_100 {
+            fields.year = with_century(year);
+        }
+        if let Some(year) = self.iso_8601_year_rem_100 {
+            fields.iso_8601_year = with_century(year);
+        }
+
+        if let (Some(hour), Some(is_pm)) = (fields.hour, self.is_pm) {
+            fields.hour = Some(hour % 12 + if is_pm { 12 } else { 0 });
+        }
+
+        fields
+    }
+}
+
+/// Parse a non-empty slice of ASCII digits, returning [`None`] on overflow.
+fn parse_int(digits: &[u8]) -> Option<i64> {
+    digits.iter().try_fold(0i64, |acc, &digit| {
+        acc.checked_mul(10)?.checked_add(i64::from(digit - b'0'))
+    })
+}
+
+/// Integer types with bounds, for the range of signed numbers.
+trait Bounded {
+    /// Minimum value.
+    const MIN: Self;
+    /// Maximum value.
+    const MAX: Self;
+}
+
+impl Bounded for i32 {
+    const MIN: Self = i32::MIN;
+    const MAX: Self = i32::MAX;
+}
+
+impl Bounded for i64 {
+    const MIN: Self = i64::MIN;
+    const MAX: Self = i64::MAX;
+}
+
+/// Check if the remaining format string starts with a number, which is a
+/// digit or a numeric directive.
+fn is_followed_by_number(format: &[u8]) -> bool {
+    match format {
+        [x, ..] if x.is_ascii_digit() => true,
+        [b'%', rest @ ..] => {
+            let mut cursor = Cursor::new(rest);
+            cursor.read_while(|&x| b"-_0^#".contains(&x) || x.is_ascii_digit());
+            if !cursor.read_optional_tag(b"E") {
+                cursor.read_optional_tag(b"O");
+            }
+            matches!(cursor.next(), Some(x) if NUMERIC_SPECS.contains(&x))
+        }
+        _ => false,
+    }
+}
+
+/// Parse an input string with a format string.
+///
+/// The whole input must match the format string.
+pub(crate) fn parse<'i>(
+    input: &'i [u8],
+    format: &[u8],
+    options: ParseOptions,
+) -> Result<ParsedFields<'i>, ParseError> {
+    match parse_partial(input, format, options)? {
+        (fields, []) => Ok(fields),
+        (_, remaining) => Err(ParseError::new(
+            ParseErrorKind::TrailingInput,
+            input.len() - remaining.len(),
+            Some(Expected::End),
+        )),
+    }
+}
+
+/// Parse the start of an input string with a format string, returning the
+/// parsed fields and the remaining input.
+pub(crate) fn parse_partial<'i>(
+    input: &'i [u8],
+    format: &[u8],
+    options: ParseOptions,
+) -> Result<(ParsedFields<'i>, &'i [u8]), ParseError> {
+    let mut parser = Parser::new(input, options);
+    parser.parse_format(format, false)?;
+
+    let remaining = parser.input.remaining();
+    Ok((parser.finish(), remaining))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<'i>(input: &'i [u8], format: &[u8]) -> Result<ParsedFields<'i>, ParseErrorKind> {
+        super::parse(input, format, ParseOptions::new()).map_err(|err| err.kind())
+    }
+
+    fn parse_lenient<'i>(
+        input: &'i [u8],
+        format: &[u8],
+    ) -> Result<ParsedFields<'i>, ParseErrorKind> {
+        super::parse(input, format, ParseOptions::new().with_lenient()).map_err(|err| err.kind())
+    }
+
+    #[test]
+    fn test_parse() {
+        let fields = parse(b"2024-07-08 13:05:09", b"%F %T").unwrap();
+        assert_eq!(fields.year, Some(2024));
+        assert_eq!(fields.month, Some(7));
+        assert_eq!(fields.day, Some(8));
+        assert_eq!(fields.hour, Some(13));
+        assert_eq!(fields.minute, Some(5));
+        assert_eq!(fields.second, Some(9));
+        assert_eq!(fields.day_of_year, None);
+
+        let fields = parse(b"Mon Jul  8 01:02:03 2024", b"%c").unwrap();
+        assert_eq!(fields.day_of_week, Some(1));
+        assert_eq!(fields.month, Some(7));
+        assert_eq!(fields.day, Some(8));
+        assert_eq!(fields.year, Some(2024));
+
+        let fields = parse(b"Monday July 8", b"%A %B %-d").unwrap();
+        assert_eq!((fields.day_of_week, fields.month), (Some(1), Some(7)));
+
+        let fields = parse(b" 8-JUL-2024", b"%v").unwrap();
+        assert_eq!(
+            (fields.year, fields.month, fields.day),
+            (Some(2024), Some(7), Some(8))
+        );
+
+        let fields = parse(b"-0042 190 1700000000", b"%Y %j %s").unwrap();
+        assert_eq!(fields.year, Some(-42));
+        assert_eq!(fields.day_of_year, Some(190));
+        assert_eq!(fields.seconds_since_epoch, Some(1_700_000_000));
+
+        let fields = parse(b"2024 1 0 28 27 28", b"%G %u %w %V %U %W").unwrap();
+        assert_eq!(fields.iso_8601_year, Some(2024));
+        assert_eq!(fields.day_of_week_from_monday, Some(1));
+        assert_eq!(fields.day_of_week, Some(0));
+        assert_eq!(fields.iso_8601_week_number, Some(28));
+        assert_eq!(fields.week_number_from_sunday, Some(27));
+        assert_eq!(fields.week_number_from_monday, Some(28));
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        // Years followed by a number are limited to 4 digits
+        let fields = parse(b"20240708", b"%Y%m%d").unwrap();
+        assert_eq!(
+            (fields.year, fields.month, fields.day),
+            (Some(2024), Some(7), Some(8))
+        );
+        assert_eq!(parse(b"12345", b"%Y").unwrap().year, Some(12345));
+
+        // The width sets the maximum number of digits
+        assert_eq!(parse(b"123", b"%1m%2d").unwrap().day, Some(23));
+
+        // Two-digit years
+        assert_eq!(parse(b"69", b"%y").unwrap().year, Some(1969));
+        assert_eq!(parse(b"68", b"%y").unwrap().year, Some(2068));
+        assert_eq!(parse(b"19 24", b"%C %y").unwrap().year, Some(1924));
+        assert_eq!(parse(b"24 19", b"%g %C").unwrap().iso_8601_year, Some(1924));
+        assert_eq!(parse(b"07/08/24", b"%D").unwrap().year, Some(2024));
+
+        let parse_with_pivot = |input: &'static [u8], year_pivot| {
+            let options = ParseOptions::new().with_year_pivot(year_pivot);
+            super::parse(input, b"%y %g", options).unwrap()
+        };
+        let fields = parse_with_pivot(b"50 49", 50);
+        assert_eq!(
+            (fields.year, fields.iso_8601_year),
+            (Some(1950), Some(2049))
+        );
+        assert_eq!(parse_with_pivot(b"00 99", 0).year, Some(1900));
+        assert_eq!(parse_with_pivot(b"99 00", 100).year, Some(2099));
+        assert_eq!(parse_with_pivot(b"99 00", u8::MAX).year, Some(2099));
+
+        // Meridian indicator
+        assert_eq!(parse(b"12:30 AM", b"%I:%M %p").unwrap().hour, Some(0));
+        assert_eq!(parse(b"01:30 pm", b"%I:%M %P").unwrap().hour, Some(13));
+        assert_eq!(parse(b"12:00:00 PM", b"%r").unwrap().hour, Some(12));
+        assert_eq!(parse(b" 1", b"%l").unwrap().hour, Some(1));
+    }
+
+    #[cfg(feature = "subsec")]
+    #[test]
+    fn test_parse_fractional_seconds() {
+        assert_eq!(parse(b"5", b"%N").unwrap().nanoseconds, Some(500_000_000));
+        assert_eq!(parse(b"123", b"%L").unwrap().nanoseconds, Some(123_000_000));
+        assert_eq!(
+            parse(b"1234567891", b"%N").unwrap().nanoseconds,
+            Some(123_456_789)
+        );
+        let fields = parse(b"12309", b"%L%S").unwrap();
+        assert_eq!(
+            (fields.nanoseconds, fields.second),
+            (Some(123_000_000), Some(9))
+        );
+    }
+
+    #[test]
+    fn test_parse_time_zone() {
+        assert_eq!(parse(b"+0530", b"%z").unwrap().utc_offset, Some(19800));
+        assert_eq!(parse(b"-0000", b"%z").unwrap().utc_offset, Some(0));
+        assert_eq!(parse(b"-05:30", b"%:z").unwrap().utc_offset, Some(-19800));
+        assert_eq!(
+            parse(b"+05:30:15", b"%::z").unwrap().utc_offset,
+            Some(19815)
+        );
+        assert_eq!(parse(b"+05", b"%:::z").unwrap().utc_offset, Some(18000));
+        assert_eq!(parse(b"+05:30", b"%:::z").unwrap().utc_offset, Some(19800));
+        assert_eq!(
+            parse(b"+05:30:15", b"%:::z").unwrap().utc_offset,
+            Some(19815)
+        );
+        assert_eq!(parse(b"+05:", b"%:::z:").unwrap().utc_offset, Some(18000));
+
+        let fields = parse(b"2024-07-08 10:00 CEST +0200", b"%F %R %Z %z").unwrap();
+        assert_eq!(fields.time_zone, Some("CEST"));
+        assert_eq!(fields.utc_offset, Some(7200));
+
+        // Abbreviations are not resolved
+        let fields = parse(b"CST", b"%Z").unwrap();
+        assert_eq!((fields.time_zone, fields.utc_offset), (Some("CST"), None));
+        assert_eq!(parse(b"-03", b"%Z").unwrap().time_zone, Some("-03"));
+        assert_eq!(parse(b"UTC+", b"%Z+").unwrap().time_zone, Some("UTC"));
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let fields = parse_lenient(b"  Jul 8", b"%b %e").unwrap();
+        assert_eq!((fields.month, fields.day), (Some(7), Some(8)));
+        assert_eq!(
+            parse(b"  Jul 8", b"%b %e"),
+            Err(ParseErrorKind::InputMismatch)
+        );
+
+        assert_eq!(parse_lenient(b"JUL", b"%b").unwrap().month, Some(7));
+        assert_eq!(parse_lenient(b"jUlY", b"%B").unwrap().month, Some(7));
+        assert_eq!(
+            parse_lenient(b"sunday", b"%^A").unwrap().day_of_week,
+            Some(0)
+        );
+        assert_eq!(parse_lenient(b"1 PM", b"%l %P").unwrap().hour, Some(13));
+        assert_eq!(parse(b"JUL", b"%b"), Err(ParseErrorKind::InputMismatch));
+
+        // Whitespace in the format string matches any run of whitespace
+        let fields = parse_lenient(b"2024-07-08\t \n10:00", b"%F %R").unwrap();
+        assert_eq!((fields.day, fields.hour), (Some(8), Some(10)));
+        assert!(parse_lenient(b"2024-07-0810:00", b"%F %R").is_ok());
+        assert!(parse_lenient(b"at  10", b"at%n%H").is_ok());
+
+        // Other literal text is still matched exactly
+        assert_eq!(
+            parse_lenient(b"AT 10", b"at %H"),
+            Err(ParseErrorKind::InputMismatch)
+        );
+        assert_eq!(
+            parse_lenient(b"10 ", b"%H"),
+            Err(ParseErrorKind::TrailingInput)
+        );
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let line = b"2024-07-08T10:00:00 GET /index.html";
+        let (fields, rest) = parse_partial(line, b"%FT%T ", ParseOptions::new()).unwrap();
+        assert_eq!((fields.day, fields.second), (Some(8), Some(0)));
+        assert_eq!(rest, b"GET /index.html");
+
+        let (_, rest) = parse_partial(b"2024", b"%Y", ParseOptions::new()).unwrap();
+        assert_eq!(rest, b"");
+
+        // Numbers stop at the first non-digit
+        let (fields, rest) = parse_partial(b"12345abc", b"%m", ParseOptions::new()).unwrap();
+        assert_eq!((fields.month, rest), (Some(12), &b"345abc"[..]));
+
+        assert_eq!(
+            parse_partial(b"2024", b"%F", ParseOptions::new()).map_err(|err| err.kind()),
+            Err(ParseErrorKind::InputMismatch)
+        );
+    }
+
+    #[test]
+    fn test_parse_literals() {
+        assert!(parse(b"%q %\n\t", b"%q %%%n%t").is_ok());
+        assert!(parse(b"at 10", b"at %H").is_ok());
+        assert_eq!(parse(b"", b""), Ok(ParsedFields::new()));
+
+        // Invalid UTF-8 is matched byte by byte
+        assert_eq!(parse(b"\xE9\xFF07", b"\xE9\xFF%m").unwrap().month, Some(7));
+        assert_eq!(parse(b"\xFF", b"\xFE"), Err(ParseErrorKind::InputMismatch));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse(b"", b"%"), Err(ParseErrorKind::InvalidFormatString));
+        assert_eq!(parse(b"+05:30", b"%z"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"0530", b"%z"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"+0560", b"%z"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"+05", b"%:z"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"123", b"%Z"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"+", b"%Z"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"13", b"%m"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"00", b"%d"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"jul", b"%b"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(parse(b"x", b"%Y"), Err(ParseErrorKind::InputMismatch));
+        assert_eq!(
+            parse(b"at 10", b"on %H"),
+            Err(ParseErrorKind::InputMismatch)
+        );
+        assert_eq!(
+            parse(b"99999999999999999999", b"%s"),
+            Err(ParseErrorKind::InputMismatch)
+        );
+        assert_eq!(
+            parse(b"3000000000", b"%Y"),
+            Err(ParseErrorKind::InputMismatch)
+        );
+        assert_eq!(parse(b"2024-07", b"%Y"), Err(ParseErrorKind::TrailingInput));
+    }
+
+    #[test]
+    fn test_parse_error_context() {
+        let error = |input: &[u8], format: &[u8]| {
+            let err = super::parse(input, format, ParseOptions::new()).unwrap_err();
+            (err.input_offset(), err.directive(), err.expected())
+        };
+
+        let range = |min, max| Some(Expected::Range { min, max });
+
+        assert_eq!(
+            error(b"2024-13-01", b"%Y-%m-%d"),
+            (5, Some(3..5), range(1, 12))
+        );
+        assert_eq!(
+            error(b"2024/07", b"%Y-%m"),
+            (4, None, Some(Expected::Literal))
+        );
+        assert_eq!(
+            error(b"2024-07-x", b"%F"),
+            (8, Some(0..2), Some(Expected::Digits))
+        );
+        assert_eq!(
+            error(b"Jux", b"%-10b"),
+            (0, Some(0..5), Some(Expected::MonthName))
+        );
+        assert_eq!(
+            error(b"10 xm", b"%I %p"),
+            (3, Some(3..5), Some(Expected::Meridian))
+        );
+        assert_eq!(error(b"0530", b"%z"), (0, Some(0..2), Some(Expected::Sign)));
+        assert_eq!(error(b"+0560", b"%z"), (3, Some(0..2), range(0, 59)));
+        assert_eq!(
+            error(b"!", b"%Z"),
+            (0, Some(0..2), Some(Expected::TimeZone))
+        );
+        assert_eq!(
+            error(b"-3000000000", b"%Y"),
+            (0, Some(0..2), range(i32::MIN.into(), i32::MAX.into()))
+        );
+        assert_eq!(error(b"2024-07", b"%Y"), (4, None, Some(Expected::End)));
+        assert_eq!(error(b"10 ", b"%H %"), (3, Some(3..4), None));
+    }
+
+    #[test]
+    fn test_time() {
+        let fields = ParsedFields::new();
+        assert_eq!((fields.year(), fields.month(), fields.day()), (1970, 1, 1));
+        assert_eq!((fields.hour(), fields.minute(), fields.second()), (0, 0, 0));
+        assert_eq!((fields.day_of_week(), fields.day_of_year()), (4, 1));
+        assert_eq!(fields.to_int(), 0);
+        assert!(fields.is_utc());
+        assert_eq!(fields.time_zone(), "UTC");
+
+        let fields = parse(b"2024-07-08 13:05:09 +0200 CEST", b"%F %T %z %Z").unwrap();
+        assert_eq!((fields.year(), fields.month(), fields.day()), (2024, 7, 8));
+        assert_eq!((fields.day_of_week(), fields.day_of_year()), (1, 190));
+        assert_eq!(fields.nanoseconds(), 0);
+        assert_eq!(fields.to_int(), 1_720_436_709);
+        assert!(!fields.is_utc());
+        assert_eq!((fields.utc_offset(), fields.time_zone()), (7200, "CEST"));
+
+        #[cfg(feature = "subsec")]
+        {
+            let fields = parse(b"13:05:09.5", b"%T.%N").unwrap();
+            assert_eq!(fields.nanoseconds(), 500_000_000);
+        }
+
+        // The seconds since the Epoch take precedence
+        let fields = parse(b"2024-07-08 0 -0100", b"%F %s %z").unwrap();
+        assert_eq!(
+            (fields.year(), fields.month(), fields.day()),
+            (1969, 12, 31)
+        );
+        assert_eq!((fields.hour(), fields.day_of_year()), (23, 365));
+        assert_eq!(fields.to_int(), 0);
+
+        // Missing fields are the first ones
+        let fields = parse(b"2024 13", b"%Y %H").unwrap();
+        assert_eq!((fields.month(), fields.day(), fields.minute()), (1, 1, 0));
+
+        // Out of range values overflow to the next days
+        let fields = parse(b"2023-02-29 24:00:00", b"%F %T").unwrap();
+        assert_eq!((fields.month(), fields.day(), fields.hour()), (3, 2, 0));
+
+        // The month and the day take precedence over the day of the year
+        let fields = parse(b"2024 07 190", b"%Y %m %j").unwrap();
+        assert_eq!((fields.month(), fields.day()), (7, 8));
+        let fields = parse(b"2024-07-08 001", b"%F %j").unwrap();
+        assert_eq!((fields.month(), fields.day()), (7, 8));
+
+        // Week dates
+        let fields = parse(b"2020-W53-5", b"%G-W%V-%u").unwrap();
+        assert_eq!((fields.year(), fields.month(), fields.day()), (2021, 1, 1));
+        let fields = parse(b"2024 00 Sun", b"%Y %W %a").unwrap();
+        assert_eq!(
+            (fields.year(), fields.month(), fields.day()),
+            (2023, 12, 31)
+        );
+        let fields = parse(b"2024 01 6", b"%Y %U %w").unwrap();
+        assert_eq!((fields.month(), fields.day()), (1, 13));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_parse_error_display() {
+        use alloc::string::ToString;
+
+        let err = super::parse(b"10:7x", b"%H:%M:%S", ParseOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input does not match the format string at byte 4 of the input, \
+             expected the literal text of the format string"
+        );
+
+        let err = super::parse(b"", b"%Y", ParseOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input does not match the format string at byte 0 of the input, \
+             expected digits for the directive at byte 0 of the format string"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! Some useful types.
 
+use core::fmt;
+
 use super::write::Write;
 use crate::Error;
 
@@ -70,6 +72,8 @@ pub(crate) struct SizeLimiter<'a> {
     size_limit: usize,
     /// Current write count.
     count: usize,
+    /// Size the output would have reached with the write exceeding the limit.
+    attempted_size: Option<usize>,
 }
 
 impl<'a> SizeLimiter<'a> {
@@ -79,14 +83,36 @@ impl<'a> SizeLimiter<'a> {
             inner,
             size_limit,
             count: 0,
+            attempted_size: None,
         }
     }
+
+    /// Returns the number of written bytes.
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the size limit.
+    pub(crate) fn size_limit(&self) -> usize {
+        self.size_limit
+    }
+
+    /// Returns the size the output would have reached with the write which
+    /// exceeded the size limit, if any.
+    pub(crate) fn attempted_size(&self) -> Option<usize> {
+        self.attempted_size
+    }
 }
 
-impl<'a> Write for SizeLimiter<'a> {
+impl Write for SizeLimiter<'_> {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
-        if self.count + buf.len() > self.size_limit {
-            return Err(Error::FormattedStringTooLarge);
+        let attempted = self.count.saturating_add(buf.len());
+        if attempted > self.size_limit {
+            self.attempted_size = Some(attempted);
+            return Err(Error::FormattedStringTooLarge {
+                limit: self.size_limit,
+                attempted_size: attempted,
+            });
         }
 
         let written = self.inner.write(buf)?;
@@ -95,6 +121,42 @@ impl<'a> Write for SizeLimiter<'a> {
     }
 }
 
+/// A `StackBuffer` is a small fixed-size buffer implementing
+/// [`core::fmt::Write`], used to render a value before padding it.
+pub(crate) struct StackBuffer {
+    /// Buffer data.
+    data: [u8; 32],
+    /// Number of bytes written.
+    len: usize,
+}
+
+impl StackBuffer {
+    /// Construct a new empty `StackBuffer`.
+    pub(crate) fn new() -> Self {
+        Self {
+            data: [0; 32],
+            len: 0,
+        }
+    }
+
+    /// Returns the written bytes.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl fmt::Write for StackBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = &mut self.data[self.len..];
+        if s.len() > remaining.len() {
+            return Err(fmt::Error);
+        }
+        remaining[..s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "alloc")]
@@ -106,4 +168,18 @@ mod tests {
 
         assert!(!format!("{:?}", Cursor::new(&[])).is_empty());
     }
+
+    #[test]
+    fn test_stack_buffer() {
+        use core::fmt::Write;
+
+        use super::StackBuffer;
+
+        let mut buf = StackBuffer::new();
+        write!(buf, "{}", -1234).unwrap();
+        assert_eq!(buf.as_bytes(), b"-1234");
+
+        assert!(buf.write_str("0000000000000000000000000000").is_err());
+        assert_eq!(buf.as_bytes(), b"-1234");
+    }
 }
@@ -0,0 +1,201 @@
+//! Compile-time validation of format strings.
+
+use super::{EXT_E_SPECS, EXT_O_SPECS, MAX_WIDTH};
+
+/// Reason of a format string being rejected by the `strftime` functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Invalid {
+    /// The format string is ended by an unterminated directive.
+    UnterminatedDirective,
+}
+
+/// Checks if a byte is in a table.
+const fn contains(table: &[u8], x: u8) -> bool {
+    let mut index = 0;
+    while index < table.len() {
+        if table[index] == x {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Check a format string with the default options.
+///
+/// This mirrors `parse_spec` for [`Dialect::Ruby`](crate::Dialect::Ruby),
+/// without constructing the directives.
+const fn check(format: &[u8]) -> Result<(), Invalid> {
+    let mut i = 0;
+
+    while i < format.len() {
+        if format[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        i += 1;
+
+        // Flags
+        while i < format.len() && matches!(format[i], b'-' | b'_' | b'0' | b'^' | b'#') {
+            i += 1;
+        }
+
+        // Width, which makes the directive invalid when it is too large
+        let mut width = 0;
+        let mut width_overflows = false;
+        while i < format.len() && format[i].is_ascii_digit() {
+            if !width_overflows {
+                width = width * 10 + (format[i] - b'0') as usize;
+                width_overflows = width > MAX_WIDTH;
+            }
+            i += 1;
+        }
+        if width_overflows {
+            continue;
+        }
+
+        // POSIX locale extensions
+        if i + 1 < format.len() {
+            let is_extension = match format[i] {
+                b'E' => contains(EXT_E_SPECS, format[i + 1]),
+                b'O' => contains(EXT_O_SPECS, format[i + 1]),
+                _ => false,
+            };
+            if is_extension {
+                i += 1;
+            }
+        }
+
+        // Spec
+        let mut colons = 0;
+        while i < format.len() && format[i] == b':' {
+            colons += 1;
+            i += 1;
+        }
+
+        if colons == 0 {
+            if i == format.len() {
+                return Err(Invalid::UnterminatedDirective);
+            }
+            i += 1;
+        } else if i < format.len() && format[i] == b'z' {
+            i += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks if a format string is accepted by the `strftime` functions with
+/// the default options.
+///
+/// A format string is rejected if it is ended by an unterminated directive.
+/// Like the `strftime` functions, invalid directives are accepted and copied
+/// verbatim to the output.
+///
+/// This function is a `const fn`, so it can check format constants at
+/// compile time without the **derive** feature. See also [`assert_valid`].
+///
+/// # Examples
+///
+/// ```
+/// use strftime::validate;
+///
+/// const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+/// const IS_VALID: bool = validate(FORMAT.as_bytes());
+///
+/// assert!(IS_VALID);
+/// assert!(validate(b"%Q is copied"));
+/// assert!(!validate(b"%Y-%"));
+/// ```
+#[must_use]
+pub const fn validate(format: &[u8]) -> bool {
+    check(format).is_ok()
+}
+
+/// Asserts that a format string is accepted by the `strftime` functions with
+/// the default options.
+///
+/// When evaluated in a `const` item, an invalid format string is a compile
+/// error. See [`validate`] for the rules.
+///
+/// # Panics
+///
+/// Panics if the format string is ended by an unterminated directive.
+///
+/// # Examples
+///
+/// ```
+/// const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+/// const _: () = strftime::assert_valid(FORMAT.as_bytes());
+/// ```
+///
+/// An invalid format string does not compile:
+///
+/// ```compile_fail
+/// const FORMAT: &str = "%Y-%";
+/// const _: () = strftime::assert_valid(FORMAT.as_bytes());
+/// ```
+pub const fn assert_valid(format: &[u8]) {
+    match check(format) {
+        Ok(()) => {}
+        Err(Invalid::UnterminatedDirective) => {
+            panic!("invalid format string: unterminated directive");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(check(b""), Ok(()));
+        assert_eq!(check(b"%Y-%m-%dT%H:%M:%S%:z"), Ok(()));
+        assert_eq!(check(b"%%"), Ok(()));
+        assert_eq!(check(b"%Q"), Ok(()));
+        assert_eq!(check(b"%::"), Ok(()));
+        assert_eq!(check(b"%-_0^#10Ey"), Ok(()));
+        assert_eq!(check(b"%E"), Ok(()));
+        assert_eq!(check(b"%2147483648"), Ok(()));
+
+        assert_eq!(check(b"%"), Err(Invalid::UnterminatedDirective));
+        assert_eq!(check(b"%Y%"), Err(Invalid::UnterminatedDirective));
+        assert_eq!(check(b"%%%"), Err(Invalid::UnterminatedDirective));
+        assert_eq!(check(b"%-_0^#"), Err(Invalid::UnterminatedDirective));
+        assert_eq!(check(b"%2147483647"), Err(Invalid::UnterminatedDirective));
+
+        assert!(validate(b"%c"));
+        assert!(!validate(b"%c%"));
+    }
+
+    #[test]
+    fn test_validate_same_as_strftime() {
+        use crate::buffered::strftime;
+        use crate::DateTime;
+
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+
+        let formats: &[&[u8]] = &[
+            b"%Y-%",
+            b"%::z",
+            b"%:::z%",
+            b"%10Ey",
+            b"%4294967296",
+            b"%^%",
+        ];
+        for &format in formats {
+            let mut buf = [0; 64];
+            let result = strftime(&time, format, &mut buf);
+            assert_eq!(validate(format), result.is_ok(), "{format:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unterminated directive")]
+    fn test_assert_valid() {
+        assert_valid(b"%Y");
+        assert_valid(b"%Y %");
+    }
+}
@@ -1,5 +1,7 @@
 //! Module containing week-related items.
 
+use crate::calendar::is_leap_year;
+
 /// Start day of the week.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub(crate) enum WeekStart {
@@ -81,11 +83,6 @@ pub(crate) fn iso_8601_year_and_week_number(
     (year, week_number)
 }
 
-/// Check if a year is a leap year.
-fn is_leap_year(year: i64) -> bool {
-    year % 400 == 0 || (year % 4 == 0 && year % 100 != 0)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,17 +145,6 @@ mod tests {
         assert_eq!(iso_8601_year_and_week_number(2021, 1, 4), (2021, 1));
     }
 
-    #[test]
-    fn test_is_leap_year() {
-        assert!(is_leap_year(2000));
-        assert!(!is_leap_year(2001));
-        assert!(is_leap_year(2004));
-        assert!(!is_leap_year(2100));
-        assert!(!is_leap_year(2200));
-        assert!(!is_leap_year(2300));
-        assert!(is_leap_year(2400));
-    }
-
     #[cfg(feature = "alloc")]
     #[test]
     fn test_week_start_debug_is_non_empty() {
@@ -49,6 +49,12 @@ pub(crate) trait Write {
         Ok(())
     }
 
+    /// Returns the number of bytes which can still be written, if it is
+    /// bounded.
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
     /// Writes a formatted string into this writer, returning any error
     /// encountered.
     fn write_fmt(&mut self, fmt_args: fmt::Arguments<'_>) -> Result<(), Error> {
@@ -75,6 +81,31 @@ impl Write for &mut [u8] {
         *self = b;
         Ok(size)
     }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+/// A writer discarding the data, counting the written bytes.
+#[derive(Debug, Default)]
+pub(crate) struct CountWrite {
+    /// Number of written bytes.
+    count: usize,
+}
+
+impl CountWrite {
+    /// Returns the number of written bytes.
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Write for CountWrite {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        self.count += data.len();
+        Ok(data.len())
+    }
 }
 
 /// Wrapper for a [`core::fmt::Write`] writer.
@@ -115,35 +146,266 @@ impl Write for Vec<u8> {
     }
 }
 
+/// Write is implemented for `bytes::BytesMut` by appending to the buffer,
+/// growing as needed.
+#[cfg(feature = "bytes")]
+impl Write for ::bytes::BytesMut {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        self.extend_from_slice(data);
+        Ok(data.len())
+    }
+}
+
+/// Write is implemented for `heapless::Vec<u8, N>` by appending to the vector
+/// up to its fixed capacity.
+#[cfg(feature = "heapless")]
+impl<const N: usize> Write for ::heapless::Vec<u8, N> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        let size = data.len().min(N - self.len());
+        // The size fits in the remaining capacity
+        let _ = self.extend_from_slice(&data[..size]);
+        Ok(size)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(N - self.len())
+    }
+}
+
+/// Wrapper for a `&mut [u16]` buffer, transcoding the UTF-8 data written into
+/// it to UTF-16.
+pub(crate) struct Utf16SliceWrite<'a> {
+    /// Output buffer.
+    buf: &'a mut [u16],
+    /// Number of code units written.
+    len: usize,
+}
+
+impl<'a> Utf16SliceWrite<'a> {
+    /// Construct a new `Utf16SliceWrite`.
+    pub(crate) fn new(buf: &'a mut [u16]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Returns the number of code units written.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Write is implemented for `Utf16SliceWrite` by copying complete characters
+/// into the buffer, until there is no more space for the next character.
+impl Write for Utf16SliceWrite<'_> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        let data = str::from_utf8(data).expect("Utf16SliceWrite should only receive UTF-8 data");
+
+        let mut written = 0;
+        for ch in data.chars() {
+            let mut units = [0; 2];
+            let units = ch.encode_utf16(&mut units);
+
+            let remaining = &mut self.buf[self.len..];
+            if units.len() > remaining.len() {
+                break;
+            }
+            remaining[..units.len()].copy_from_slice(units);
+
+            self.len += units.len();
+            written += ch.len_utf8();
+        }
+        Ok(written)
+    }
+}
+
+/// Wrapper for a `Vec<u16>` buffer, transcoding the UTF-8 data written into it
+/// to UTF-16.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub(crate) struct Utf16VecWrite<'a> {
+    /// Output buffer.
+    inner: &'a mut Vec<u16>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Utf16VecWrite<'a> {
+    /// Construct a new `Utf16VecWrite`.
+    pub(crate) fn new(inner: &'a mut Vec<u16>) -> Self {
+        Self { inner }
+    }
+}
+
+/// Write is implemented for `Utf16VecWrite` by appending to the vector,
+/// growing as needed.
+#[cfg(feature = "alloc")]
+impl Write for Utf16VecWrite<'_> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        let data = str::from_utf8(data).expect("Utf16VecWrite should only receive UTF-8 data");
+
+        // A UTF-8 string never has more UTF-16 code units than bytes
+        self.inner.try_reserve(data.len())?;
+        self.inner.extend(data.encode_utf16());
+        Ok(data.len())
+    }
+}
+
+/// Wrapper for a [`Write`] writer, escaping the data written into it for a
+/// JSON string.
+pub(crate) struct JsonWrite<'a, W: ?Sized> {
+    /// Inner writer.
+    inner: &'a mut W,
+}
+
+impl<'a, W: Write + ?Sized> JsonWrite<'a, W> {
+    /// Construct a new `JsonWrite`.
+    pub(crate) fn new(inner: &'a mut W) -> Self {
+        Self { inner }
+    }
+}
+
+/// Write is implemented for `JsonWrite` by writing the unescaped runs of bytes
+/// directly to the inner writer, and escaping quotes, backslashes and control
+/// characters in between.
+impl<W: Write + ?Sized> Write for JsonWrite<'_, W> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let mut unicode_escape = *b"\\u0000";
+        let mut start = 0;
+
+        for (index, &byte) in data.iter().enumerate() {
+            let escaped: &[u8] = match byte {
+                b'"' => b"\\\"",
+                b'\\' => b"\\\\",
+                b'\n' => b"\\n",
+                b'\r' => b"\\r",
+                b'\t' => b"\\t",
+                0x08 => b"\\b",
+                0x0c => b"\\f",
+                0x00..=0x1f => {
+                    unicode_escape[4] = HEX_DIGITS[usize::from(byte >> 4)];
+                    unicode_escape[5] = HEX_DIGITS[usize::from(byte & 0xf)];
+                    &unicode_escape
+                }
+                _ => continue,
+            };
+
+            self.inner.write_all(&data[start..index])?;
+            self.inner.write_all(escaped)?;
+            start = index + 1;
+        }
+
+        self.inner.write_all(&data[start..])?;
+        Ok(data.len())
+    }
+}
+
+/// Size of the buffer of an [`IoWrite`].
+#[cfg(feature = "std")]
+const IO_BUFFER_SIZE: usize = 512;
+
+/// Maximum number of segments buffered by an [`IoWrite`].
+#[cfg(feature = "std")]
+const IO_MAX_SEGMENTS: usize = 32;
+
 /// Wrapper for a [`std::io::Write`] writer.
+///
+/// Each write is buffered as a separate segment, and the segments are written
+/// to the inner writer with [`std::io::Write::write_vectored`] when the buffer
+/// is full or when [`IoWrite::flush`] is called, which reduces the number of
+/// calls to the inner writer.
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub(crate) struct IoWrite<'a> {
     /// Inner writer.
     inner: &'a mut dyn std::io::Write,
+    /// Buffered data.
+    buf: [u8; IO_BUFFER_SIZE],
+    /// End offsets in the buffer of the buffered segments.
+    ends: [usize; IO_MAX_SEGMENTS],
+    /// Number of buffered segments.
+    segments: usize,
 }
 
 #[cfg(feature = "std")]
 impl<'a> IoWrite<'a> {
     /// Construct a new `IoWrite`.
     pub(crate) fn new(inner: &'a mut dyn std::io::Write) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            buf: [0; IO_BUFFER_SIZE],
+            ends: [0; IO_MAX_SEGMENTS],
+            segments: 0,
+        }
+    }
+
+    /// Returns the number of buffered bytes.
+    fn len(&self) -> usize {
+        match self.segments {
+            0 => 0,
+            n => self.ends[n - 1],
+        }
+    }
+
+    /// Write all the buffered segments to the inner writer.
+    pub(crate) fn flush(&mut self) -> Result<(), Error> {
+        use std::io::{ErrorKind, IoSlice};
+
+        let len = self.len();
+        let mut written = 0;
+
+        while written < len {
+            let mut slices = [IoSlice::new(&[]); IO_MAX_SEGMENTS];
+            let mut count = 0;
+            let mut start = 0;
+
+            for &end in &self.ends[..self.segments] {
+                if end > written {
+                    slices[count] = IoSlice::new(&self.buf[start.max(written)..end]);
+                    count += 1;
+                }
+                start = end;
+            }
+
+            match self.inner.write_vectored(&slices[..count]) {
+                Ok(0) => return Err(std::io::Error::from(ErrorKind::WriteZero).into()),
+                Ok(n) => written += n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.segments = 0;
+                    return Err(err.into());
+                }
+            }
+        }
+
+        self.segments = 0;
+        Ok(())
     }
 }
 
-/// Write is implemented for `IoWrite` by writing to its inner writer.
+/// Write is implemented for `IoWrite` by buffering the data as a new segment,
+/// or by writing it directly to its inner writer if it doesn't fit in the
+/// buffer.
 #[cfg(feature = "std")]
 impl Write for IoWrite<'_> {
     fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
-        Ok(self.inner.write(data)?)
-    }
+        if data.is_empty() {
+            return Ok(0);
+        }
 
-    fn write_all(&mut self, data: &[u8]) -> Result<(), Error> {
-        Ok(self.inner.write_all(data)?)
-    }
+        if self.segments == IO_MAX_SEGMENTS || self.len() + data.len() > IO_BUFFER_SIZE {
+            self.flush()?;
+        }
 
-    fn write_fmt(&mut self, fmt_args: fmt::Arguments<'_>) -> Result<(), Error> {
-        Ok(self.inner.write_fmt(fmt_args)?)
+        if data.len() > IO_BUFFER_SIZE {
+            return Ok(self.inner.write(data)?);
+        }
+
+        let start = self.len();
+        let end = start + data.len();
+        self.buf[start..end].copy_from_slice(data);
+        self.ends[self.segments] = end;
+        self.segments += 1;
+        Ok(data.len())
     }
 }
 
@@ -175,6 +437,7 @@ mod tests {
         let mut writer = IoWrite::new(&mut buf);
         writer.write_all(b"ok").unwrap();
         write!(writer, "{}", 1).unwrap();
+        writer.flush().unwrap();
 
         assert_eq!(buf, *b"ok1");
     }
@@ -188,4 +451,123 @@ mod tests {
         write!(FmtWrite::new(&mut buf), "{}", 1).unwrap();
         assert_eq!(buf, "1");
     }
+
+    #[test]
+    fn test_utf16_slice_write() {
+        let mut buf = [0u16; 4];
+
+        let mut writer = Utf16SliceWrite::new(&mut buf);
+        writer.write_all("a\u{e9}".as_bytes()).unwrap();
+        assert_eq!(writer.write("\u{1f600}\u{1f600}".as_bytes()).unwrap(), 4);
+        assert!(matches!(writer.write_all(b"b"), Err(Error::WriteZero)));
+        assert_eq!(writer.len(), 4);
+
+        assert_eq!(buf, [0x61, 0xe9, 0xd83d, 0xde00]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_utf16_vec_write() {
+        let mut buf = Vec::new();
+
+        let mut writer = Utf16VecWrite::new(&mut buf);
+        writer.write_all("a\u{e9}\u{1f600}".as_bytes()).unwrap();
+        write!(writer, "{}", 1).unwrap();
+
+        assert_eq!(buf, [0x61, 0xe9, 0xd83d, 0xde00, 0x31]);
+    }
+
+    #[test]
+    fn test_json_write() {
+        let mut buf = [0u8; 32];
+        let mut cursor = &mut buf[..];
+
+        let mut writer = JsonWrite::new(&mut cursor);
+        writer.write_all(b"a\"b\\c\n\t\x01\x1f\x7f").unwrap();
+        writer.write_all("\u{e9}".as_bytes()).unwrap();
+        let written = 32 - cursor.len();
+
+        assert_eq!(
+            &buf[..written],
+            "a\\\"b\\\\c\\n\\t\\u0001\\u001f\x7f\u{e9}".as_bytes()
+        );
+
+        let mut buf = [0u8; 2];
+        let mut cursor = &mut buf[..];
+        let mut writer = JsonWrite::new(&mut cursor);
+        assert!(matches!(writer.write_all(b"a\""), Err(Error::WriteZero)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_write_vectored() {
+        use std::io::{self, IoSlice};
+
+        /// Writer accepting at most 5 bytes per call.
+        #[derive(Default)]
+        struct Partial {
+            data: Vec<u8>,
+            calls: usize,
+        }
+
+        impl io::Write for Partial {
+            fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+                self.write_vectored(&[IoSlice::new(data)])
+            }
+
+            fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+                self.calls += 1;
+                let mut written = 0;
+                for buf in bufs {
+                    let len = buf.len().min(5 - written);
+                    self.data.extend_from_slice(&buf[..len]);
+                    written += len;
+                }
+                Ok(written)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut inner = Partial::default();
+        let mut writer = IoWrite::new(&mut inner);
+        for data in [&b"ab"[..], b"", b"cde", b"fghij", b"k"] {
+            writer.write_all(data).unwrap();
+        }
+        write!(writer, "{}", 12).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(inner.data, b"abcdefghijk12");
+        assert_eq!(inner.calls, 3);
+
+        let mut inner = Partial::default();
+        let mut writer = IoWrite::new(&mut inner);
+        let data = [b'a'; IO_BUFFER_SIZE + 1];
+        writer.write_all(b"b").unwrap();
+        writer.write_all(&data).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(inner.data[0], b'b');
+        assert_eq!(&inner.data[1..], data);
+
+        let mut inner = Partial::default();
+        let mut writer = IoWrite::new(&mut inner);
+        for _ in 0..=IO_MAX_SEGMENTS {
+            writer.write_all(b"a").unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(inner.data.len(), IO_MAX_SEGMENTS + 1);
+        // The full buffer is written in 5-byte calls, then the last segment
+        assert_eq!(inner.calls, (IO_MAX_SEGMENTS + 4) / 5 + 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_write_zero() {
+        let mut buf = [0u8; 2];
+        let mut inner = &mut buf[..];
+        let mut writer = IoWrite::new(&mut inner);
+        writer.write_all(b"abc").unwrap();
+        assert!(matches!(writer.flush(), Err(Error::IoError(_))));
+    }
 }
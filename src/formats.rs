@@ -0,0 +1,72 @@
+//! Format strings for widely used date and time formats.
+//!
+//! # Examples
+//!
+//! ```
+//! use strftime::buffered::strftime;
+//! use strftime::{formats, DateTime};
+//!
+//! # fn main() -> Result<(), strftime::Error> {
+//! let time = DateTime::from_timestamp_with_offset(1_000_000_000, 123_456_789, 7200).unwrap();
+//!
+//! let mut buf = [0u8; 64];
+//! assert_eq!(strftime(&time, formats::RFC3339.as_bytes(), &mut buf)?, b"2001-09-09T03:46:40+02:00");
+//! # #[cfg(feature = "subsec")]
+//! assert_eq!(strftime(&time, formats::RFC3339_MILLIS.as_bytes(), &mut buf)?, b"2001-09-09T03:46:40.123+02:00");
+//! assert_eq!(strftime(&time, formats::RFC2822.as_bytes(), &mut buf)?, b"Sun, 09 Sep 2001 03:46:40 +0200");
+//! assert_eq!(strftime(&time, formats::DB.as_bytes(), &mut buf)?, b"2001-09-09 03:46:40");
+//! assert_eq!(strftime(&time, formats::SYSLOG.as_bytes(), &mut buf)?, b"Sep  9 03:46:40");
+//!
+//! let time = DateTime::from_timestamp(1_000_000_000, 0).unwrap();
+//! assert_eq!(strftime(&time, formats::HTTP_DATE.as_bytes(), &mut buf)?, b"Sun, 09 Sep 2001 01:46:40 GMT");
+//! # Ok(())
+//! # }
+//! ```
+
+/// [ISO 8601] and [RFC 3339] date and time with a UTC offset, like
+/// `2001-09-09T01:46:40+00:00`.
+///
+/// [ISO 8601]: <https://www.iso.org/iso-8601-date-and-time-format.html>
+/// [RFC 3339]: <https://datatracker.ietf.org/doc/html/rfc3339>
+pub const RFC3339: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// [ISO 8601] and [RFC 3339] date and time with milliseconds and a UTC
+/// offset, like `2001-09-09T01:46:40.123+00:00`.
+///
+/// The sub-second digits are only formatted with the `subsec` feature.
+///
+/// [ISO 8601]: <https://www.iso.org/iso-8601-date-and-time-format.html>
+/// [RFC 3339]: <https://datatracker.ietf.org/doc/html/rfc3339>
+pub const RFC3339_MILLIS: &str = "%Y-%m-%dT%H:%M:%S.%L%:z";
+
+/// [ISO 8601] and [RFC 3339] date and time with nanoseconds and a UTC
+/// offset, like `2001-09-09T01:46:40.123456789+00:00`.
+///
+/// The sub-second digits are only formatted with the `subsec` feature.
+///
+/// [ISO 8601]: <https://www.iso.org/iso-8601-date-and-time-format.html>
+/// [RFC 3339]: <https://datatracker.ietf.org/doc/html/rfc3339>
+pub const RFC3339_NANOS: &str = "%Y-%m-%dT%H:%M:%S.%N%:z";
+
+/// [RFC 2822] date and time, like `Sun, 09 Sep 2001 01:46:40 +0000`, as
+/// returned by Ruby's `Time#rfc2822` for a local time.
+///
+/// [RFC 2822]: <https://datatracker.ietf.org/doc/html/rfc2822#section-3.3>
+pub const RFC2822: &str = "%a, %d %b %Y %H:%M:%S %z";
+
+/// [HTTP-date] in the preferred IMF-fixdate format, like
+/// `Sun, 09 Sep 2001 01:46:40 GMT`.
+///
+/// The time zone is always written as `GMT`, so the time must be in UTC.
+///
+/// [HTTP-date]: <https://datatracker.ietf.org/doc/html/rfc9110#section-5.6.7>
+pub const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Database date and time without a UTC offset, like `2001-09-09 01:46:40`,
+/// as returned by Rails' `Time#to_s(:db)`.
+pub const DB: &str = "%Y-%m-%d %H:%M:%S";
+
+/// [BSD syslog] timestamp, like `Sep  9 01:46:40`.
+///
+/// [BSD syslog]: <https://datatracker.ietf.org/doc/html/rfc3164#section-4.1.2>
+pub const SYSLOG: &str = "%b %e %H:%M:%S";
@@ -0,0 +1,186 @@
+//! A [`Time`] adapter converting GPS time to UTC.
+
+use crate::{DateTime, Time};
+
+/// Number of seconds between the Unix epoch and the GPS epoch,
+/// `1980-01-06 00:00:00 UTC`.
+const GPS_EPOCH: i64 = 315_964_800;
+
+/// Number of seconds in a GPS week.
+const SECONDS_PER_WEEK: i64 = 7 * 86_400;
+
+/// A [`Time`] implementation for a GPS timestamp, as reported by GNSS
+/// receivers, converted to UTC.
+///
+/// GPS time is a continuous time scale starting at the GPS epoch,
+/// `1980-01-06 00:00:00 UTC`, which does not include leap seconds. The
+/// current difference between GPS time and UTC, `leap_seconds`, is broadcast
+/// by the satellites and reported by most receivers.
+///
+/// The `week` is the full GPS week number since the GPS epoch, not truncated
+/// to 10 or 13 bits. A `time_of_week_ms` greater than a week is carried into
+/// the following weeks.
+///
+/// A leap second itself cannot be represented, so the second before it is
+/// formatted twice.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::buffered::strftime;
+/// use strftime::GpsTime;
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = GpsTime {
+///     week: 2200,
+///     time_of_week_ms: 388_818_250,
+///     leap_seconds: 18,
+/// };
+///
+/// let mut buf = [0u8; 32];
+/// assert_eq!(strftime(&time, b"%F %T %Z", &mut buf)?, b"2022-03-10 12:00:00 UTC");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GpsTime {
+    /// Number of weeks since the GPS epoch.
+    pub week: u16,
+    /// Number of milliseconds since the start of the GPS week, at midnight
+    /// between Saturday and Sunday.
+    pub time_of_week_ms: u32,
+    /// Number of seconds GPS time is ahead of UTC.
+    pub leap_seconds: i8,
+}
+
+impl GpsTime {
+    /// Returns the number of seconds since `1970-01-01 00:00:00 UTC` and the
+    /// number of nanoseconds.
+    const fn to_unix(self) -> (i64, u32) {
+        let seconds =
+            GPS_EPOCH + self.week as i64 * SECONDS_PER_WEEK + (self.time_of_week_ms / 1000) as i64
+                - self.leap_seconds as i64;
+        let nanoseconds = self.time_of_week_ms % 1000 * 1_000_000;
+        (seconds, nanoseconds)
+    }
+
+    /// Convert this GPS time to a UTC [`DateTime`].
+    #[must_use]
+    pub const fn to_utc(self) -> DateTime {
+        let (seconds, nanoseconds) = self.to_unix();
+        match DateTime::from_timestamp(seconds, nanoseconds) {
+            Some(datetime) => datetime,
+            // The year is in `1979..=3237` and there are less than one billion
+            // nanoseconds, so the conversion cannot fail.
+            None => unreachable!(),
+        }
+    }
+}
+
+impl Time for GpsTime {
+    fn year(&self) -> i32 {
+        self.to_utc().year()
+    }
+
+    fn month(&self) -> u8 {
+        self.to_utc().month()
+    }
+
+    fn day(&self) -> u8 {
+        self.to_utc().day()
+    }
+
+    fn hour(&self) -> u8 {
+        self.to_utc().hour()
+    }
+
+    fn minute(&self) -> u8 {
+        self.to_utc().minute()
+    }
+
+    fn second(&self) -> u8 {
+        self.to_utc().second()
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.to_unix().1
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.to_utc().day_of_week()
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.to_utc().day_of_year()
+    }
+
+    fn to_int(&self) -> i64 {
+        self.to_unix().0
+    }
+
+    fn is_utc(&self) -> bool {
+        true
+    }
+
+    fn utc_offset(&self) -> i32 {
+        0
+    }
+
+    // The signature must match the trait.
+    #[allow(clippy::unnecessary_literal_bound)]
+    fn time_zone(&self) -> &str {
+        "UTC"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gps_time() {
+        let time = GpsTime::default();
+        assert_eq!(time.year(), 1980);
+        assert_eq!(time.month(), 1);
+        assert_eq!(time.day(), 6);
+        assert_eq!(time.hour(), 0);
+        assert_eq!(time.day_of_week(), 0);
+        assert_eq!(time.day_of_year(), 6);
+        assert_eq!(time.to_int(), 315_964_800);
+        assert!(time.is_utc());
+
+        let time = GpsTime {
+            week: 2200,
+            time_of_week_ms: 388_818_250,
+            leap_seconds: 18,
+        };
+        assert_eq!(time.year(), 2022);
+        assert_eq!(time.month(), 3);
+        assert_eq!(time.day(), 10);
+        assert_eq!(time.hour(), 12);
+        assert_eq!(time.minute(), 0);
+        assert_eq!(time.second(), 0);
+        assert_eq!(time.nanoseconds(), 250_000_000);
+        assert_eq!(time.day_of_week(), 4);
+        assert_eq!(time.to_int(), 1_646_913_600);
+        assert_eq!(
+            time.to_utc(),
+            DateTime::from_timestamp(1_646_913_600, 250_000_000).unwrap()
+        );
+
+        // The time of week is carried into the next week
+        let time = GpsTime {
+            week: 2199,
+            time_of_week_ms: 993_618_250,
+            leap_seconds: 18,
+        };
+        assert_eq!(time.to_int(), 1_646_913_600);
+
+        let time = GpsTime {
+            week: u16::MAX,
+            time_of_week_ms: u32::MAX,
+            leap_seconds: i8::MIN,
+        };
+        assert_eq!(time.year(), 3236);
+    }
+}
@@ -0,0 +1,124 @@
+//! Provides `strftime` implementations writing to the fixed-capacity
+//! containers of the [`heapless`] crate, which are owned and usable in a
+//! `no_std` context without an allocator.
+//!
+//! [`heapless`]: https://docs.rs/heapless
+
+use ::heapless::{String, Vec};
+
+use crate::format::TimeFormatter;
+use crate::{Error, Time};
+
+/// Format a _time_ implementation with the specified format byte string,
+/// writing to a new [`heapless::Vec`] with a capacity of `N` bytes.
+///
+/// See the [crate-level documentation](crate) for a complete description of
+/// possible format specifiers.
+///
+/// # Allocations
+///
+/// This `strftime` implementation makes no heap allocations and is usable in
+/// a `no_std` context.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::Time;
+///
+/// // Not shown: create a time implementation with the year 1970
+/// // let time = ...;
+/// # include!("mock.rs.in");
+/// # fn main() -> Result<(), strftime::Error> {
+/// # let time = MockTime { year: 1970, ..Default::default() };
+/// assert_eq!(time.year(), 1970);
+///
+/// let formatted = strftime::heapless::strftime::<8>(&time, b"%Y")?;
+/// assert_eq!(formatted, b"1970");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Can produce an [`Error`] when the formatting fails. In particular, returns
+/// [`Error::WriteZero`] if the formatted string is longer than `N` bytes.
+///
+/// [`heapless::Vec`]: Vec
+pub fn strftime<const N: usize>(
+    time: &(impl Time + ?Sized),
+    format: &[u8],
+) -> Result<Vec<u8, N>, Error> {
+    let mut buf = Vec::new();
+    TimeFormatter::new(time, format).fmt(&mut buf)?;
+    Ok(buf)
+}
+
+/// Format a _time_ implementation with the specified UTF-8 format string,
+/// writing to a new [`heapless::String`] with a capacity of `N` bytes.
+///
+/// See the [crate-level documentation](crate) for a complete description of
+/// possible format specifiers.
+///
+/// # Allocations
+///
+/// This `strftime` implementation makes no heap allocations and is usable in
+/// a `no_std` context.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::Time;
+///
+/// // Not shown: create a time implementation with the year 1970
+/// // let time = ...;
+/// # include!("mock.rs.in");
+/// # fn main() -> Result<(), strftime::Error> {
+/// # let time = MockTime { year: 1970, ..Default::default() };
+/// assert_eq!(time.year(), 1970);
+///
+/// let formatted = strftime::heapless::strftime_string::<8>(&time, "%Y")?;
+/// assert_eq!(formatted, "1970");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Can produce an [`Error`] when the formatting fails. In particular, returns
+/// [`Error::WriteZero`] if the formatted string is longer than `N` bytes.
+///
+/// [`heapless::String`]: String
+pub fn strftime_string<const N: usize>(
+    time: &(impl Time + ?Sized),
+    format: &str,
+) -> Result<String<N>, Error> {
+    let buf = strftime(time, format.as_bytes())?;
+    // The output of a UTF-8 format string is valid UTF-8
+    String::from_utf8(buf).map_err(|_| Error::InvalidFormatString)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DateTime;
+
+    #[test]
+    fn test_heapless() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+
+        assert_eq!(strftime::<10>(&time, b"%F").unwrap(), b"1970-01-01");
+        assert_eq!(strftime::<5>(&time, b"\xff%Y").unwrap(), b"\xff1970");
+        assert_eq!(strftime::<0>(&time, b"").unwrap(), b"");
+        assert_eq!(strftime_string::<8>(&time, "%T").unwrap(), "00:00:00");
+
+        assert!(matches!(strftime::<9>(&time, b"%F"), Err(Error::WriteZero)));
+        assert!(matches!(
+            strftime_string::<7>(&time, "%T"),
+            Err(Error::WriteZero)
+        ));
+        assert!(matches!(
+            strftime::<8>(&time, b"%Y%"),
+            Err(Error::UnterminatedDirective { .. })
+        ));
+    }
+}
@@ -121,14 +121,69 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+// The `Error` trait is in `core` since Rust 1.81, which is more recent than
+// the minimum supported Rust version of this crate.
+#[cfg(feature = "core-error")]
+use core::error;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 #[cfg(feature = "alloc")]
 use alloc::collections::TryReserveError;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 
+mod bcd;
+mod cached;
+mod calendar;
+#[cfg(feature = "chrono")]
+mod chrono;
+mod counting;
+mod datetime;
+mod ext;
 mod format;
+mod gps;
+mod offset;
+mod options;
+mod snippet;
+mod stats;
+mod tee;
 
 #[cfg(test)]
 mod tests;
 
+pub use cached::CachedTime;
+pub use counting::CountingWriter;
+pub use datetime::DateTime;
+pub use ext::TimeExt;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use format::compiled::Item as __FormatItem;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use format::compiled::{Format, FormatBuilder, RequiredFields};
+pub use format::validate::{assert_valid, validate};
+pub use gps::GpsTime;
+pub use offset::WithOffset;
+#[cfg(feature = "icu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "icu")))]
+pub use options::Calendar;
+pub use options::{
+    Dialect, FormatOptions, Locale, NaivePolicy, OffsetPolicy, DEFAULT_MAX_DIRECTIVES,
+    MAX_UTC_OFFSET,
+};
+pub use snippet::Snippet;
+pub use stats::FormatStats;
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use strftime_ruby_macros::strftime_display;
+#[cfg(all(feature = "derive", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "derive", feature = "alloc"))))]
+pub use strftime_ruby_macros::strftime_format;
+pub use tee::Tee;
+
 /// Error type returned by the `strftime` functions.
 #[derive(Debug)]
 // To ensure the API is the same for all feature combinations, do not derive
@@ -140,10 +195,81 @@ mod tests;
 pub enum Error {
     /// Provided time implementation returns invalid values.
     InvalidTime,
-    /// Provided format string is ended by an unterminated format specifier.
+    /// Provided time implementation returns a UTC offset which is rejected by
+    /// the policy configured with [`FormatOptions::with_offset_policy`].
+    ///
+    /// The UTC offset is rejected if it is not in `-93600..=93600` (±26
+    /// hours), or if it is not zero for a time in UTC.
+    InvalidUtcOffset {
+        /// Offset in seconds from UTC returned by the time implementation.
+        utc_offset: i32,
+        /// Whether the time implementation is in UTC.
+        is_utc: bool,
+    },
+    /// Format string has a time zone directive, but the time has no time zone
+    /// information.
+    ///
+    /// This variant is only returned with [`NaivePolicy::Reject`].
+    MissingTimeZone,
+    /// Provided format string is invalid.
+    ///
+    /// A format string ended by an unterminated directive is reported by
+    /// [`Error::UnterminatedDirective`] instead.
     InvalidFormatString,
+    /// Provided format string is ended by an unterminated directive, like
+    /// `"%Y %-"`.
+    ///
+    /// The position is relative to the format string of the alias or the
+    /// named format which has the unterminated directive, if any. It is also
+    /// reported by [`FormatStats::unterminated_directive`].
+    #[non_exhaustive]
+    UnterminatedDirective {
+        /// Byte offset of the unterminated directive in the format string.
+        position: usize,
+        /// Start of the text of the unterminated directive.
+        snippet: Snippet,
+    },
+    /// Provided [`OsStr`] format string is not valid Unicode.
+    ///
+    /// This variant is only returned by the [`path`] functions on platforms
+    /// other than Unix, where a format string must be valid Unicode.
+    ///
+    /// [`OsStr`]: std::ffi::OsStr
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    NonUnicodeFormatString,
+    /// Format string has an unknown or malformed directive, which is rejected
+    /// by [`FormatOptions::with_strict_directives`].
+    UnknownDirective,
+    /// Format string has a directive with a width which is too large, which is
+    /// rejected by [`FormatOptions::with_strict_widths`].
+    WidthTooLarge {
+        /// Maximum width of a directive.
+        limit: usize,
+    },
+    /// Format string has more `*` width placeholders than the width arguments
+    /// configured with [`FormatOptions::with_width_args`].
+    MissingWidthArgument,
     /// Formatted string is too large and could cause an out-of-memory error.
-    FormattedStringTooLarge,
+    ///
+    /// The size limit is derived from the length of the format string. With
+    /// [`FormatOptions::with_stats`], the size limit and the attempted size
+    /// are also reported by [`FormatStats::size_limit`] and
+    /// [`FormatStats::attempted_size`].
+    #[non_exhaustive]
+    FormattedStringTooLarge {
+        /// Maximum size in bytes of the formatted string.
+        limit: usize,
+        /// Size in bytes the formatted string would have reached with the
+        /// write which exceeded the limit.
+        attempted_size: usize,
+    },
+    /// Format string has more directives than the limit configured with
+    /// [`FormatOptions::with_max_directives`].
+    TooManyDirectives {
+        /// Maximum number of directives in a format string.
+        limit: usize,
+    },
     /// Provided buffer for the [`buffered::strftime`] function is too small for
     /// the formatted string.
     ///
@@ -162,32 +288,153 @@ pub enum Error {
     #[cfg(feature = "std")]
     #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     IoError(std::io::Error),
+    /// An error has occurred when formatting a directive.
+    ///
+    /// This variant is only returned when enabled with
+    /// [`FormatOptions::with_error_context`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    Directive {
+        /// Text of the directive in the format string, like `"%-d"`.
+        directive: String,
+        /// Byte offset of the directive in the format string.
+        position: usize,
+        /// Error which occurred when formatting the directive.
+        source: Box<Error>,
+    },
 }
 
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::InvalidTime => f.write_str("invalid time"),
+            Error::InvalidUtcOffset { utc_offset, is_utc } => {
+                write!(f, "invalid UTC offset: {utc_offset} seconds")?;
+                if *is_utc {
+                    f.write_str(" for a time in UTC")?;
+                }
+                Ok(())
+            }
+            Error::MissingTimeZone => f.write_str("time zone directive used with a naive time"),
             Error::InvalidFormatString => f.write_str("invalid format string"),
-            Error::FormattedStringTooLarge => f.write_str("formatted string too large"),
+            Error::UnterminatedDirective { position, snippet } => {
+                write!(f, "unterminated directive `{snippet}` at byte {position}")
+            }
+            #[cfg(feature = "std")]
+            Error::NonUnicodeFormatString => f.write_str("format string is not valid Unicode"),
+            Error::UnknownDirective => f.write_str("unknown directive in the format string"),
+            Error::WidthTooLarge { limit } => {
+                write!(f, "directive width exceeds the limit of {limit}")
+            }
+            Error::MissingWidthArgument => f.write_str("missing width argument"),
+            Error::FormattedStringTooLarge {
+                limit,
+                attempted_size,
+            } => write!(
+                f,
+                "formatted string too large: {attempted_size} bytes exceed the limit of {limit} bytes"
+            ),
+            Error::TooManyDirectives { limit } => {
+                write!(f, "format string has more than {limit} directives")
+            }
             Error::WriteZero => f.write_str("failed to write the whole buffer"),
             Error::FmtError(_) => f.write_str("formatter error"),
             #[cfg(feature = "alloc")]
             Error::OutOfMemory(_) => f.write_str("allocation failure"),
             #[cfg(feature = "std")]
             Error::IoError(_) => f.write_str("I/O error"),
+            #[cfg(feature = "alloc")]
+            Error::Directive {
+                directive,
+                position,
+                source,
+            } => write!(
+                f,
+                "failed to format directive `{directive}` at byte {position}: {source}"
+            ),
         }
     }
 }
 
-#[cfg(feature = "std")]
-#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Error::InvalidTime => defmt::write!(f, "invalid time"),
+            Error::InvalidUtcOffset { utc_offset, is_utc } => {
+                defmt::write!(f, "invalid UTC offset: {=i32} seconds", utc_offset);
+                if *is_utc {
+                    defmt::write!(f, " for a time in UTC");
+                }
+            }
+            Error::MissingTimeZone => {
+                defmt::write!(f, "time zone directive used with a naive time");
+            }
+            Error::InvalidFormatString => defmt::write!(f, "invalid format string"),
+            Error::UnterminatedDirective { position, snippet } => defmt::write!(
+                f,
+                "unterminated directive `{}` at byte {=usize}",
+                snippet,
+                position
+            ),
+            #[cfg(feature = "std")]
+            Error::NonUnicodeFormatString => {
+                defmt::write!(f, "format string is not valid Unicode");
+            }
+            Error::UnknownDirective => {
+                defmt::write!(f, "unknown directive in the format string");
+            }
+            Error::WidthTooLarge { limit } => {
+                defmt::write!(f, "directive width exceeds the limit of {=usize}", limit);
+            }
+            Error::MissingWidthArgument => defmt::write!(f, "missing width argument"),
+            Error::FormattedStringTooLarge {
+                limit,
+                attempted_size,
+            } => defmt::write!(
+                f,
+                "formatted string too large: {=usize} bytes exceed the limit of {=usize} bytes",
+                attempted_size,
+                limit
+            ),
+            Error::TooManyDirectives { limit } => {
+                defmt::write!(f, "format string has more than {=usize} directives", limit);
+            }
+            Error::WriteZero => defmt::write!(f, "failed to write the whole buffer"),
+            Error::FmtError(_) => defmt::write!(f, "formatter error"),
+            #[cfg(feature = "alloc")]
+            Error::OutOfMemory(_) => defmt::write!(f, "allocation failure"),
+            #[cfg(feature = "std")]
+            Error::IoError(_) => defmt::write!(f, "I/O error"),
+            #[cfg(feature = "alloc")]
+            Error::Directive {
+                directive,
+                position,
+                source,
+            } => defmt::write!(
+                f,
+                "failed to format directive `{=str}` at byte {=usize}: {}",
+                directive.as_str(),
+                position,
+                &**source
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "core-error"))))]
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             Self::FmtError(inner) => Some(inner),
+            #[cfg(feature = "alloc")]
             Self::OutOfMemory(inner) => Some(inner),
+            #[cfg(feature = "std")]
             Self::IoError(inner) => Some(inner),
+            #[cfg(feature = "alloc")]
+            Self::Directive { source, .. } => Some(&**source),
             _ => None,
         }
     }
@@ -219,7 +466,8 @@ impl From<std::io::Error> for Error {
 ///
 /// This should be implemented for structs representing a _time_.
 ///
-/// All the `strftime` functions take as input an implementation of this trait.
+/// All the `strftime` functions take as input an implementation of this trait,
+/// which can be a `&dyn Time` trait object.
 pub trait Time {
     /// Returns the year for _time_ (including the century).
     fn year(&self) -> i32;
@@ -234,7 +482,13 @@ pub trait Time {
     /// Returns the second of the minute in `0..=60` for _time_.
     fn second(&self) -> u8;
     /// Returns the number of nanoseconds in `0..=999_999_999` for _time_.
-    fn nanoseconds(&self) -> u32;
+    ///
+    /// The default implementation returns zero, for clocks with a resolution
+    /// of one second. Without the `subsec` feature, sub-second directives are
+    /// not supported and this method is never called.
+    fn nanoseconds(&self) -> u32 {
+        0
+    }
     /// Returns an integer representing the day of the week in `0..=6`, with
     /// `Sunday == 0`.
     fn day_of_week(&self) -> u8;
@@ -261,7 +515,7 @@ pub const ASCTIME_FORMAT_STRING: &str = "%c";
 /// Provides a `strftime` implementation using a format string with arbitrary
 /// bytes, writing to a provided byte slice.
 pub mod buffered {
-    use super::{Error, Time};
+    use super::{Error, FormatOptions, Time};
     use crate::format::TimeFormatter;
 
     /// Format a _time_ implementation with the specified format byte string,
@@ -299,55 +553,153 @@ pub mod buffered {
     ///
     /// Can produce an [`Error`] when the formatting fails.
     pub fn strftime<'a>(
-        time: &impl Time,
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        buf: &'a mut [u8],
+    ) -> Result<&'a mut [u8], Error> {
+        strftime_with_options(time, format, buf, &FormatOptions::new())
+    }
+
+    /// Format a _time_ implementation with the specified format byte string,
+    /// writing in the provided buffer and returning the number of written
+    /// bytes.
+    ///
+    /// Unlike [`strftime`], the buffer is not borrowed by the result, so the
+    /// caller can keep composing into the rest of the same buffer.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_len;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 16];
+    /// let mut len = strftime_len(&time, b"%Y", &mut buf)?;
+    /// buf[len] = b'/';
+    /// len += 1;
+    /// len += strftime_len(&time, b"%m", &mut buf[len..])?;
+    /// assert_eq!(&buf[..len], b"1970/01");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_len(
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        strftime(time, format, buf).map(|written| written.len())
+    }
+
+    /// Format a _time_ implementation with the specified format byte string
+    /// and [`FormatOptions`], writing in the provided buffer and returning the
+    /// written subslice.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_max_directives(16);
+    ///
+    /// let mut buf = [0u8; 8];
+    /// assert_eq!(strftime_with_options(&time, b"%Y", &mut buf, &options)?, b"1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_with_options<'a>(
+        time: &(impl Time + ?Sized),
         format: &[u8],
         buf: &'a mut [u8],
+        options: &FormatOptions<'_>,
     ) -> Result<&'a mut [u8], Error> {
         let len = buf.len();
 
         let mut cursor = &mut buf[..];
-        TimeFormatter::new(time, format).fmt(&mut cursor)?;
+        TimeFormatter::with_options(time, format, options).fmt(&mut cursor)?;
         let remaining_len = cursor.len();
 
         Ok(&mut buf[..len - remaining_len])
     }
 }
 
-/// Provides a `strftime` implementation using a UTF-8 format string, writing to
-/// a [`core::fmt::Write`] object.
-pub mod fmt {
-    use core::fmt::Write;
-
+/// Provides a `strftime` implementation discarding the output, returning the
+/// length of the formatted string.
+///
+/// This is a dry run of the formatting, so callers like FFI layers can
+/// allocate a buffer of the exact size before formatting into it.
+pub mod count {
     use super::{Error, Time};
-    use crate::format::{FmtWrite, TimeFormatter};
+    use crate::format::{CountWrite, TimeFormatter};
 
-    /// Format a _time_ implementation with the specified UTF-8 format string,
-    /// writing to the provided [`core::fmt::Write`] object.
+    /// Format a _time_ implementation with the specified format byte string,
+    /// discarding the output and returning its length in bytes.
     ///
     /// See the [crate-level documentation](crate) for a complete description of
     /// possible format specifiers.
     ///
     /// # Allocations
     ///
-    /// This `strftime` implementation makes no heap allocations on its own, but
-    /// the provided writer may allocate.
+    /// This `strftime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
     ///
     /// # Examples
     ///
     /// ```
-    /// use strftime::fmt::strftime;
+    /// use strftime::count::strftime_len;
     /// use strftime::Time;
     ///
     /// // Not shown: create a time implementation with the year 1970
     /// // let time = ...;
     /// # include!("mock.rs.in");
     /// # fn main() -> Result<(), strftime::Error> {
-    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, ..Default::default() };
     /// assert_eq!(time.year(), 1970);
     ///
-    /// let mut buf = String::new();
-    /// strftime(&time, "%Y", &mut buf)?;
-    /// assert_eq!(buf, "1970");
+    /// let len = strftime_len(&time, b"%F %B")?;
+    /// assert_eq!(len, 18);
+    ///
+    /// let mut buf = vec![0u8; len];
+    /// strftime::buffered::strftime(&time, b"%F %B", &mut buf)?;
+    /// assert_eq!(buf, b"1970-01-01 January");
     /// # Ok(())
     /// # }
     /// ```
@@ -355,49 +707,43 @@ pub mod fmt {
     /// # Errors
     ///
     /// Can produce an [`Error`] when the formatting fails.
-    pub fn strftime(time: &impl Time, format: &str, buf: &mut dyn Write) -> Result<(), Error> {
-        TimeFormatter::new(time, format).fmt(&mut FmtWrite::new(buf))
+    pub fn strftime_len(time: &(impl Time + ?Sized), format: &[u8]) -> Result<usize, Error> {
+        let mut counter = CountWrite::default();
+        TimeFormatter::new(time, format).fmt(&mut counter)?;
+        Ok(counter.count())
     }
-}
-
-/// Provides a `strftime` implementation using a format string with arbitrary
-/// bytes, writing to a newly allocated [`Vec`].
-///
-/// [`Vec`]: alloc::vec::Vec
-#[cfg(feature = "alloc")]
-#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-pub mod bytes {
-    use alloc::vec::Vec;
-
-    use super::{Error, Time};
-    use crate::format::TimeFormatter;
 
-    /// Format a _time_ implementation with the specified format byte string.
+    /// Returns the exact length in bytes of the formatted string of a _time_
+    /// implementation with the specified format byte string.
+    ///
+    /// Unlike [`strftime_len`], the length of the common directives without
+    /// flags and width, like `%F`, `%T` or `%B`, is computed arithmetically
+    /// from the fields of the _time_. The format string is only formatted
+    /// without output, like with [`strftime_len`], if it has other directives
+    /// or if the formatting fails.
     ///
     /// See the [crate-level documentation](crate) for a complete description of
     /// possible format specifiers.
     ///
     /// # Allocations
     ///
-    /// This `strftime` implementation writes its output to a heap-allocated
-    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
-    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
-    /// if there is an allocation failure.
+    /// This function makes no heap allocations and is usable in a `no_std`
+    /// context.
     ///
     /// # Examples
     ///
     /// ```
-    /// use strftime::bytes::strftime;
+    /// use strftime::count::required_capacity;
     /// use strftime::Time;
     ///
     /// // Not shown: create a time implementation with the year 1970
     /// // let time = ...;
     /// # include!("mock.rs.in");
     /// # fn main() -> Result<(), strftime::Error> {
-    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, day_of_week: 4, ..Default::default() };
     /// assert_eq!(time.year(), 1970);
     ///
-    /// assert_eq!(strftime(&time, b"%Y")?, b"1970");
+    /// assert_eq!(required_capacity(&time, b"%A %F %T")?, 28);
     /// # Ok(())
     /// # }
     /// ```
@@ -405,42 +751,65 @@ pub mod bytes {
     /// # Errors
     ///
     /// Can produce an [`Error`] when the formatting fails.
-    pub fn strftime(time: &impl Time, format: &[u8]) -> Result<Vec<u8>, Error> {
-        let mut buf = Vec::new();
-        TimeFormatter::new(time, format).fmt(&mut buf)?;
-        Ok(buf)
+    pub fn required_capacity(time: &(impl Time + ?Sized), format: &[u8]) -> Result<usize, Error> {
+        crate::format::capacity::required_capacity(time, format)
     }
 }
 
 /// Provides a `strftime` implementation using a UTF-8 format string, writing to
-/// a newly allocated [`String`].
+/// a [`core::fmt::Write`] object.
 ///
-/// [`String`]: alloc::string::String
-#[cfg(feature = "alloc")]
-#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-pub mod string {
-    use alloc::string::String;
-    use alloc::vec::Vec;
+/// The writer can be a `String`, a fixed capacity string of a `no_std` crate,
+/// or the [`Formatter`] of a [`Display`] implementation, so the output is
+/// written without an intermediate buffer.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt;
+///
+/// use strftime::Time;
+///
+/// // Not shown: a time implementation
+/// # include!("mock.rs.in");
+///
+/// struct LogTimestamp<'a>(MockTime<'a>);
+///
+/// impl fmt::Display for LogTimestamp<'_> {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         strftime::fmt::strftime(&self.0, "%Y-%m-%dT%H:%M:%S", f).map_err(|_| fmt::Error)
+///     }
+/// }
+///
+/// # fn main() {
+/// let time = LogTimestamp(MockTime { year: 1970, month: 1, day: 1, ..Default::default() });
+/// assert_eq!(time.to_string(), "1970-01-01T00:00:00");
+/// # }
+/// ```
+///
+/// [`Formatter`]: core::fmt::Formatter
+/// [`Display`]: core::fmt::Display
+pub mod fmt {
+    use core::fmt::{self, Write};
 
-    use super::{Error, Time};
-    use crate::format::TimeFormatter;
+    use super::{Error, FormatOptions, Time};
+    use crate::format::{FmtWrite, TimeFormatter};
 
-    /// Format a _time_ implementation with the specified UTF-8 format string.
+    /// Format a _time_ implementation with the specified UTF-8 format string,
+    /// writing to the provided [`core::fmt::Write`] object.
     ///
     /// See the [crate-level documentation](crate) for a complete description of
     /// possible format specifiers.
     ///
     /// # Allocations
     ///
-    /// This `strftime` implementation writes its output to a heap-allocated
-    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
-    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
-    /// if there is an allocation failure.
+    /// This `strftime` implementation makes no heap allocations on its own, but
+    /// the provided writer may allocate.
     ///
     /// # Examples
     ///
     /// ```
-    /// use strftime::string::strftime;
+    /// use strftime::fmt::strftime;
     /// use strftime::Time;
     ///
     /// // Not shown: create a time implementation with the year 1970
@@ -450,7 +819,9 @@ pub mod string {
     /// # let time = MockTime { year: 1970, ..Default::default() };
     /// assert_eq!(time.year(), 1970);
     ///
-    /// assert_eq!(strftime(&time, "%Y")?, "1970");
+    /// let mut buf = String::new();
+    /// strftime(&time, "%Y", &mut buf)?;
+    /// assert_eq!(buf, "1970");
     /// # Ok(())
     /// # }
     /// ```
@@ -458,26 +829,17 @@ pub mod string {
     /// # Errors
     ///
     /// Can produce an [`Error`] when the formatting fails.
-    #[allow(clippy::missing_panics_doc)]
-    pub fn strftime(time: &impl Time, format: &str) -> Result<String, Error> {
-        let mut buf = Vec::new();
-        TimeFormatter::new(time, format).fmt(&mut buf)?;
-        Ok(String::from_utf8(buf).expect("formatted string should be valid UTF-8"))
+    pub fn strftime(
+        time: &(impl Time + ?Sized),
+        format: &str,
+        buf: &mut dyn Write,
+    ) -> Result<(), Error> {
+        TimeFormatter::new(time, format).fmt(&mut FmtWrite::new(buf))
     }
-}
-
-/// Provides a `strftime` implementation using a format string with arbitrary
-/// bytes, writing to a [`std::io::Write`] object.
-#[cfg(feature = "std")]
-#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
-pub mod io {
-    use std::io::Write;
-
-    use super::{Error, Time};
-    use crate::format::{IoWrite, TimeFormatter};
 
-    /// Format a _time_ implementation with the specified format byte string,
-    /// writing to the provided [`std::io::Write`] object.
+    /// Format a _time_ implementation with the specified UTF-8 format string
+    /// and [`FormatOptions`], writing to the provided [`core::fmt::Write`]
+    /// object.
     ///
     /// See the [crate-level documentation](crate) for a complete description of
     /// possible format specifiers.
@@ -490,8 +852,8 @@ pub mod io {
     /// # Examples
     ///
     /// ```
-    /// use strftime::io::strftime;
-    /// use strftime::Time;
+    /// use strftime::fmt::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
     ///
     /// // Not shown: create a time implementation with the year 1970
     /// // let time = ...;
@@ -500,9 +862,11 @@ pub mod io {
     /// # let time = MockTime { year: 1970, ..Default::default() };
     /// assert_eq!(time.year(), 1970);
     ///
-    /// let mut buf = Vec::new();
-    /// strftime(&time, b"%Y", &mut buf)?;
-    /// assert_eq!(buf, *b"1970");
+    /// let options = FormatOptions::new().with_max_directives(16);
+    ///
+    /// let mut buf = String::new();
+    /// strftime_with_options(&time, "%Y", &mut buf, &options)?;
+    /// assert_eq!(buf, "1970");
     /// # Ok(())
     /// # }
     /// ```
@@ -510,11 +874,1971 @@ pub mod io {
     /// # Errors
     ///
     /// Can produce an [`Error`] when the formatting fails.
-    pub fn strftime(time: &impl Time, format: &[u8], buf: &mut dyn Write) -> Result<(), Error> {
-        TimeFormatter::new(time, format).fmt(&mut IoWrite::new(buf))
+    pub fn strftime_with_options(
+        time: &(impl Time + ?Sized),
+        format: &str,
+        buf: &mut dyn Write,
+        options: &FormatOptions<'_>,
+    ) -> Result<(), Error> {
+        TimeFormatter::with_options(time, format, options).fmt(&mut FmtWrite::new(buf))
+    }
+
+    /// Create a [`TimeDisplay`] adapter formatting a _time_ implementation with
+    /// the specified UTF-8 format string.
+    ///
+    /// The time is formatted lazily, each time the adapter is displayed, so it
+    /// can be embedded in `format!`, `write!` or `println!` arguments without
+    /// an intermediate buffer.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::fmt::display;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let message = format!("[{}] started", display(&time, "%Y"));
+    /// assert_eq!(message, "[1970] started");
+    /// # }
+    /// ```
+    pub fn display<'a, T: Time + ?Sized>(time: &'a T, format: &'a str) -> TimeDisplay<'a, T> {
+        TimeDisplay { time, format }
+    }
+
+    /// Adapter implementing [`Display`] for a _time_ implementation and a
+    /// UTF-8 format string, created by [`display`].
+    ///
+    /// Displaying the adapter fails with [`fmt::Error`] if the formatting
+    /// fails, which makes the `to_string` method and the `format!` macro
+    /// panic. Use [`strftime`] to handle the formatting errors.
+    ///
+    /// [`Display`]: fmt::Display
+    #[must_use]
+    #[derive(Debug)]
+    pub struct TimeDisplay<'a, T: ?Sized> {
+        /// Time implementation.
+        time: &'a T,
+        /// Format string.
+        format: &'a str,
+    }
+
+    impl<T: ?Sized> Clone for TimeDisplay<'_, T> {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+
+    impl<T: ?Sized> Copy for TimeDisplay<'_, T> {}
+
+    impl<T: Time + ?Sized> fmt::Display for TimeDisplay<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            strftime(self.time, self.format, f).map_err(|_| fmt::Error)
+        }
     }
 }
 
+/// Provides a `strftime` implementation using a format string with arbitrary
+/// bytes, writing to a newly allocated [`Vec`].
+///
+/// [`Vec`]: alloc::vec::Vec
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod bytes {
+    use alloc::borrow::Cow;
+    use alloc::vec::Vec;
+
+    use super::{Error, FormatOptions, Time};
+    use crate::format::TimeFormatter;
+
+    /// Format a _time_ implementation with the specified format byte string.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation writes its output to a heap-allocated
+    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
+    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::bytes::strftime;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// assert_eq!(strftime(&time, b"%Y")?, b"1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime(time: &(impl Time + ?Sized), format: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        TimeFormatter::new(time, format).fmt(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Format a _time_ implementation with the specified format byte string,
+    /// borrowing the format string if it has no directives.
+    ///
+    /// A format string without `%` characters is copied verbatim to the
+    /// output, so it is returned as a [`Cow::Borrowed`] without allocating.
+    /// Otherwise, this function is equivalent to [`strftime`].
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation only allocates if the format string has
+    /// a `%` character, and exclusively uses fallible allocation APIs like
+    /// [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use strftime::bytes::strftime_cow;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// assert!(matches!(strftime_cow(&time, b"static")?, Cow::Borrowed(b"static")));
+    /// assert_eq!(strftime_cow(&time, b"%Y")?, &b"1970"[..]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_cow<'f>(
+        time: &(impl Time + ?Sized),
+        format: &'f [u8],
+    ) -> Result<Cow<'f, [u8]>, Error> {
+        if format.contains(&b'%') {
+            strftime(time, format).map(Cow::Owned)
+        } else {
+            Ok(Cow::Borrowed(format))
+        }
+    }
+
+    /// Format a _time_ implementation with the specified format byte string,
+    /// appending to the provided [`Vec`].
+    ///
+    /// The existing contents of the vector are kept, so a single buffer can
+    /// accumulate many formatted records. If the formatting fails, the vector
+    /// is truncated to its original length.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation grows the provided [`Vec`] with fallible
+    /// allocation APIs like [`Vec::try_reserve`]. This function will return
+    /// [`Error::OutOfMemory`] if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::bytes::strftime_into;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create time implementations with the years 1970 and 1971
+    /// // let times = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let times = [1970, 1971].map(|year| MockTime { year, ..Default::default() });
+    /// let mut buf = b"years:".to_vec();
+    /// for time in &times {
+    ///     strftime_into(time, b" %Y", &mut buf)?;
+    /// }
+    /// assert_eq!(buf, b"years: 1970 1971");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_into(
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let len = buf.len();
+        let result = TimeFormatter::new(time, format).fmt(buf);
+        if result.is_err() {
+            buf.truncate(len);
+        }
+        result
+    }
+
+    /// Format a _time_ implementation with the specified format byte string,
+    /// appending to the provided [`BytesMut`] buffer.
+    ///
+    /// This avoids copying the output of [`strftime`] into a frame buffer
+    /// when assembling network messages. If the formatting fails, the buffer
+    /// is truncated to its original length.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation grows the provided [`BytesMut`] as
+    /// needed. Unlike [`strftime_into`], the allocations are infallible since
+    /// [`BytesMut`] has no fallible allocation APIs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use strftime::bytes::strftime_into_bytes_mut;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut frame = BytesMut::from(&b"date: "[..]);
+    /// strftime_into_bytes_mut(&time, b"%Y", &mut frame)?;
+    /// assert_eq!(frame, b"date: 1970"[..]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    ///
+    /// [`BytesMut`]: ::bytes::BytesMut
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+    pub fn strftime_into_bytes_mut(
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        buf: &mut ::bytes::BytesMut,
+    ) -> Result<(), Error> {
+        let len = buf.len();
+        let result = TimeFormatter::new(time, format).fmt(buf);
+        if result.is_err() {
+            buf.truncate(len);
+        }
+        result
+    }
+
+    /// Format a _time_ implementation with the specified format byte string
+    /// and [`FormatOptions`].
+    ///
+    /// The bytes transformation registered with
+    /// [`FormatOptions::with_post_process`], if any, runs on the formatted
+    /// bytes before they are returned.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation writes its output to a heap-allocated
+    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
+    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::bytes::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_post_process(&|buf| buf.push(b'!'));
+    ///
+    /// assert_eq!(strftime_with_options(&time, b"%Y", &options)?, b"1970!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_with_options(
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        options: &FormatOptions<'_>,
+    ) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        TimeFormatter::with_options(time, format, options).fmt(&mut buf)?;
+        options.post_process(&mut buf);
+        Ok(buf)
+    }
+}
+
+/// Provides a `strftime` implementation using a UTF-8 format string, writing to
+/// a newly allocated [`String`].
+///
+/// [`String`]: alloc::string::String
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod string {
+    use alloc::borrow::Cow;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::{Error, FormatOptions, Time};
+    use crate::format::TimeFormatter;
+
+    /// Format a _time_ implementation with the specified UTF-8 format string.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation writes its output to a heap-allocated
+    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
+    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::string::strftime;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// assert_eq!(strftime(&time, "%Y")?, "1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn strftime(time: &(impl Time + ?Sized), format: &str) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        TimeFormatter::new(time, format).fmt(&mut buf)?;
+        Ok(String::from_utf8(buf).expect("formatted string should be valid UTF-8"))
+    }
+
+    /// Format a _time_ implementation with the specified UTF-8 format string,
+    /// borrowing the format string if it has no directives.
+    ///
+    /// A format string without `%` characters is copied verbatim to the
+    /// output, so it is returned as a [`Cow::Borrowed`] without allocating.
+    /// Otherwise, this function is equivalent to [`strftime`].
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation only allocates if the format string has
+    /// a `%` character, and exclusively uses fallible allocation APIs like
+    /// [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use strftime::string::strftime_cow;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// assert!(matches!(strftime_cow(&time, "static")?, Cow::Borrowed("static")));
+    /// assert_eq!(strftime_cow(&time, "%Y")?, "1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_cow<'f>(
+        time: &(impl Time + ?Sized),
+        format: &'f str,
+    ) -> Result<Cow<'f, str>, Error> {
+        if format.contains('%') {
+            strftime(time, format).map(Cow::Owned)
+        } else {
+            Ok(Cow::Borrowed(format))
+        }
+    }
+
+    /// Format a _time_ implementation with the specified UTF-8 format string
+    /// and [`FormatOptions`].
+    ///
+    /// The string transformation registered with
+    /// [`FormatOptions::with_post_process_str`], if any, runs on the formatted
+    /// string before it is returned.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation writes its output to a heap-allocated
+    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
+    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::string::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_post_process_str(&|s| s.push_str(" AD"));
+    ///
+    /// assert_eq!(strftime_with_options(&time, "%Y", &options)?, "1970 AD");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn strftime_with_options(
+        time: &(impl Time + ?Sized),
+        format: &str,
+        options: &FormatOptions<'_>,
+    ) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        TimeFormatter::with_options(time, format, options).fmt(&mut buf)?;
+        let mut buf = String::from_utf8(buf).expect("formatted string should be valid UTF-8");
+        options.post_process_str(&mut buf);
+        Ok(buf)
+    }
+}
+
+/// Provides a `strftime` implementation using a format string with arbitrary
+/// bytes, writing to a [`std::io::Write`] object.
+///
+/// The output is streamed to the writer, like a file, a socket or a
+/// [`BufWriter`], without allocating an intermediate buffer for each call.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufWriter, Write};
+///
+/// use strftime::Time;
+///
+/// // Not shown: create time implementations
+/// # include!("mock.rs.in");
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let times = [1970, 1971].map(|year| MockTime { year, ..Default::default() });
+/// let mut log = BufWriter::new(Vec::new());
+/// for time in &times {
+///     strftime::io::strftime(time, b"%Y", &mut log)?;
+///     log.write_all(b" started\n")?;
+/// }
+///
+/// assert_eq!(log.into_inner()?, b"1970 started\n1971 started\n");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`BufWriter`]: std::io::BufWriter
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod io {
+    use std::io::Write;
+
+    use super::{Error, FormatOptions, Time};
+    use crate::format::{IoWrite, TimeFormatter};
+
+    /// Format a _time_ implementation with the specified format byte string,
+    /// writing to the provided [`std::io::Write`] object.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// The literal segments of the format string and the formatted directives
+    /// are buffered and written in batches with
+    /// [`std::io::Write::write_vectored`], which reduces the number of calls to
+    /// the provided writer. The buffered output is written before returning,
+    /// even if the formatting fails. The provided writer is only flushed when
+    /// enabled with [`FormatOptions::with_flush`].
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations on its own, but
+    /// the provided writer may allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::io::strftime;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = Vec::new();
+    /// strftime(&time, b"%Y", &mut buf)?;
+    /// assert_eq!(buf, *b"1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime(
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        buf: &mut dyn Write,
+    ) -> Result<(), Error> {
+        strftime_with_options(time, format, buf, &FormatOptions::new())
+    }
+
+    /// Format a _time_ implementation with the specified format byte string
+    /// and [`FormatOptions`], writing to the provided [`std::io::Write`]
+    /// object.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// The literal segments of the format string and the formatted directives
+    /// are buffered and written in batches with
+    /// [`std::io::Write::write_vectored`], which reduces the number of calls to
+    /// the provided writer. The buffered output is written before returning,
+    /// even if the formatting fails.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations on its own, but
+    /// the provided writer may allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::io::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_max_directives(16);
+    ///
+    /// let mut buf = Vec::new();
+    /// strftime_with_options(&time, b"%Y", &mut buf, &options)?;
+    /// assert_eq!(buf, *b"1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime_with_options(
+        time: &(impl Time + ?Sized),
+        format: &[u8],
+        buf: &mut dyn Write,
+        options: &FormatOptions<'_>,
+    ) -> Result<(), Error> {
+        let mut writer = IoWrite::new(buf);
+        let result = TimeFormatter::with_options(time, format, options).fmt(&mut writer);
+        // Write the buffered output even on error, like an unbuffered writer
+        let mut flushed = writer.flush();
+        if options.flush() && flushed.is_ok() {
+            flushed = buf.flush().map_err(Error::from);
+        }
+        result.and(flushed)
+    }
+}
+
+/// Provides a `strftime` implementation formatting into a reusable buffer of
+/// the current thread, for hot code paths which format many times.
+///
+/// The buffer is lent to the returned [`Formatted`](fast::Formatted) guard,
+/// and given back to the thread when the guard is dropped, so that repeated
+/// calls don't allocate once the buffer is large enough.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod fast {
+    use core::cell::Cell;
+    use core::fmt;
+    use core::mem;
+    use core::ops::Deref;
+    use std::string::String;
+    use std::vec::Vec;
+
+    use super::{Error, Time};
+    use crate::format::TimeFormatter;
+
+    std::thread_local! {
+        /// Reusable buffer of the current thread.
+        static BUFFER: Cell<Vec<u8>> = Cell::new(Vec::new());
+    }
+
+    /// Largest capacity of a buffer kept by the current thread.
+    ///
+    /// Larger buffers are shrunk when given back, so that formatting a single
+    /// large string doesn't hold its memory until the thread exits.
+    const MAX_RECYCLED_CAPACITY: usize = 4096;
+
+    /// Give a buffer back to the current thread, keeping the largest buffer
+    /// if another one was given back in the meantime.
+    fn recycle(mut buf: Vec<u8>) {
+        buf.clear();
+        buf.shrink_to(MAX_RECYCLED_CAPACITY);
+        // The buffer is dropped if the thread-local storage is destroyed
+        let _ = BUFFER.try_with(|cell| {
+            let previous = cell.take();
+            cell.set(if previous.capacity() > buf.capacity() {
+                previous
+            } else {
+                buf
+            });
+        });
+    }
+
+    /// A formatted string borrowing the reusable buffer of the current
+    /// thread, returned by [`strftime`].
+    ///
+    /// The buffer is given back to the thread when the `Formatted` is dropped.
+    #[derive(Debug)]
+    pub struct Formatted {
+        /// Formatted string.
+        buf: String,
+    }
+
+    impl Deref for Formatted {
+        type Target = str;
+
+        fn deref(&self) -> &str {
+            &self.buf
+        }
+    }
+
+    impl AsRef<str> for Formatted {
+        fn as_ref(&self) -> &str {
+            &self.buf
+        }
+    }
+
+    impl fmt::Display for Formatted {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.buf)
+        }
+    }
+
+    impl Drop for Formatted {
+        fn drop(&mut self) {
+            recycle(mem::take(&mut self.buf).into_bytes());
+        }
+    }
+
+    /// Format a _time_ implementation with the specified UTF-8 format string
+    /// into the reusable buffer of the current thread.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation only allocates when the buffer of the
+    /// current thread is too small, or when it is already lent to another
+    /// [`Formatted`] which is still alive. The thread keeps a buffer of at most
+    /// 4 KiB: larger buffers are shrunk when the [`Formatted`] is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::fast::strftime;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// for _ in 0..3 {
+    ///     let formatted = strftime(&time, "year %Y")?;
+    ///     assert_eq!(&*formatted, "year 1970");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn strftime(time: &(impl Time + ?Sized), format: &str) -> Result<Formatted, Error> {
+        let mut buf = BUFFER.try_with(Cell::take).unwrap_or_default();
+
+        if let Err(err) = TimeFormatter::new(time, format).fmt(&mut buf) {
+            recycle(buf);
+            return Err(err);
+        }
+
+        let buf = String::from_utf8(buf).expect("formatted string should be valid UTF-8");
+        Ok(Formatted { buf })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn buffer_capacity() -> usize {
+            BUFFER.with(|cell| {
+                let buf = cell.take();
+                let capacity = buf.capacity();
+                cell.set(buf);
+                capacity
+            })
+        }
+
+        #[test]
+        fn test_recycle() {
+            recycle(Vec::with_capacity(100));
+            assert_eq!(buffer_capacity(), 100);
+
+            // A smaller buffer doesn't replace the kept one
+            recycle(Vec::with_capacity(10));
+            assert_eq!(buffer_capacity(), 100);
+
+            // A large buffer is shrunk to the ceiling
+            recycle(Vec::with_capacity(1 << 20));
+            let capacity = buffer_capacity();
+            assert!((100..=MAX_RECYCLED_CAPACITY).contains(&capacity));
+
+            recycle(Vec::with_capacity(MAX_RECYCLED_CAPACITY));
+            assert_eq!(buffer_capacity(), MAX_RECYCLED_CAPACITY);
+        }
+    }
+}
+
+/// Provides `strftime` implementations writing to an [`OsString`] or a
+/// [`PathBuf`], like the file names of rotated logs, without going through a
+/// [`String`].
+///
+/// On Unix platforms, the format string can contain arbitrary bytes, which are
+/// copied to the output. On other platforms, it must be valid Unicode, else
+/// [`Error::NonUnicodeFormatString`] is returned.
+///
+/// [`OsString`]: std::ffi::OsString
+/// [`PathBuf`]: std::path::PathBuf
+/// [`String`]: alloc::string::String
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod path {
+    use std::ffi::{OsStr, OsString};
+    use std::path::PathBuf;
+
+    use super::{Error, Time};
+
+    /// Format a _time_ implementation with the specified format string,
+    /// returning a newly allocated [`OsString`].
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Platform behavior
+    ///
+    /// On Unix, the format string is formatted as bytes like with
+    /// [`bytes::strftime`](crate::bytes::strftime), so bytes which are not
+    /// valid UTF-8 are copied to the output. On other platforms, like Windows,
+    /// the format string is converted to a [`str`] and formatted with
+    /// [`string::strftime`](crate::string::strftime), which rejects a format
+    /// string that is not valid Unicode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::path::strftime;
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let time = DateTime::from_timestamp(1_000_000_000, 0).unwrap();
+    ///
+    /// assert_eq!(strftime(&time, "app-%Y%m%d.log".as_ref())?, "app-20010909.log");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails. On platforms other
+    /// than Unix, returns [`Error::NonUnicodeFormatString`] if the format
+    /// string is not valid Unicode.
+    pub fn strftime(time: &(impl Time + ?Sized), format: &OsStr) -> Result<OsString, Error> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+            crate::bytes::strftime(time, format.as_bytes()).map(OsString::from_vec)
+        }
+        #[cfg(not(unix))]
+        {
+            let format = format.to_str().ok_or(Error::NonUnicodeFormatString)?;
+            crate::string::strftime(time, format).map(OsString::from)
+        }
+    }
+
+    /// Format a _time_ implementation with the specified format string and
+    /// push the result onto a [`PathBuf`], with the semantics of
+    /// [`PathBuf::push`].
+    ///
+    /// Path separators in the output, like the ones written by `%D`, split the
+    /// result into several components. The path is not modified on error.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::{Path, PathBuf};
+    /// use strftime::path::push;
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let time = DateTime::from_timestamp(1_000_000_000, 0).unwrap();
+    ///
+    /// let mut path = PathBuf::from("logs");
+    /// push(&mut path, &time, "%Y-%m-%d.log".as_ref())?;
+    /// assert_eq!(path, Path::new("logs").join("2001-09-09.log"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails. On platforms other
+    /// than Unix, returns [`Error::NonUnicodeFormatString`] if the format
+    /// string is not valid Unicode.
+    pub fn push(
+        path: &mut PathBuf,
+        time: &(impl Time + ?Sized),
+        format: &OsStr,
+    ) -> Result<(), Error> {
+        path.push(strftime(time, format)?);
+        Ok(())
+    }
+}
+
+/// Provides `strftime` implementations producing UTF-16 output, suitable for
+/// wide-char APIs like the ones found on Windows.
+pub mod utf16 {
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+
+    use super::{Error, Time};
+    #[cfg(feature = "alloc")]
+    use crate::format::Utf16VecWrite;
+    use crate::format::{TimeFormatter, Utf16SliceWrite};
+
+    /// Format a _time_ implementation with the specified UTF-8 format string,
+    /// writing UTF-16 code units in the provided buffer and returning the
+    /// written subslice.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::utf16::strftime;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u16; 8];
+    /// let expected: Vec<u16> = "1970".encode_utf16().collect();
+    /// assert_eq!(strftime(&time, "%Y", &mut buf)?, expected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime<'a>(
+        time: &(impl Time + ?Sized),
+        format: &str,
+        buf: &'a mut [u16],
+    ) -> Result<&'a mut [u16], Error> {
+        let mut writer = Utf16SliceWrite::new(buf);
+        TimeFormatter::new(time, format).fmt(&mut writer)?;
+        let len = writer.len();
+
+        Ok(&mut buf[..len])
+    }
+
+    /// Format a _time_ implementation with the specified UTF-8 format string,
+    /// returning UTF-16 code units.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation writes its output to a heap-allocated
+    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
+    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::utf16::strftime_to_vec;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let expected: Vec<u16> = "1970".encode_utf16().collect();
+    /// assert_eq!(strftime_to_vec(&time, "%Y")?, expected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn strftime_to_vec(time: &(impl Time + ?Sized), format: &str) -> Result<Vec<u16>, Error> {
+        let mut buf = Vec::new();
+        TimeFormatter::new(time, format).fmt(&mut Utf16VecWrite::new(&mut buf))?;
+        Ok(buf)
+    }
+}
+
+/// Provides a `strftime` implementation using a UTF-8 format string, escaping
+/// the formatted string for the content of a JSON string.
+///
+/// Quotes, backslashes and control characters are escaped as they are
+/// written, so the formatted string can be written between quotes in a JSON
+/// document without a second escaping pass.
+pub mod json {
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+
+    use super::{Error, Time};
+    use crate::format::{JsonWrite, TimeFormatter};
+
+    /// Format a _time_ implementation with the specified UTF-8 format string,
+    /// writing the JSON-escaped output in the provided buffer and returning the
+    /// written subslice.
+    ///
+    /// The surrounding quotes of the JSON string are not written.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::json::strftime;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 16];
+    /// assert_eq!(strftime(&time, "\"%Y\"\t", &mut buf)?, br#"\"1970\"\t"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    pub fn strftime<'a>(
+        time: &(impl Time + ?Sized),
+        format: &str,
+        buf: &'a mut [u8],
+    ) -> Result<&'a mut [u8], Error> {
+        let len = buf.len();
+
+        let mut cursor = &mut buf[..];
+        TimeFormatter::new(time, format).fmt(&mut JsonWrite::new(&mut cursor))?;
+        let remaining_len = cursor.len();
+
+        Ok(&mut buf[..len - remaining_len])
+    }
+
+    /// Format a _time_ implementation with the specified UTF-8 format string,
+    /// appending the JSON-escaped output to the provided vector.
+    ///
+    /// The surrounding quotes of the JSON string are not written. If the
+    /// formatting fails, the vector is truncated to its original length.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation writes its output to a heap-allocated
+    /// [`Vec`]. The implementation exclusively uses fallible allocation APIs
+    /// like [`Vec::try_reserve`]. This function will return [`Error::OutOfMemory`]
+    /// if there is an allocation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::json::strftime_append;
+    /// use strftime::Time;
+    ///
+    /// // Not shown: create a time implementation with the time zone "A\B"
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, time_zone: "A\\B", ..Default::default() };
+    /// assert_eq!(time.time_zone(), "A\\B");
+    ///
+    /// let mut buf = br#"{"time":""#.to_vec();
+    /// strftime_append(&time, "%Y %Z", &mut buf)?;
+    /// buf.extend_from_slice(br#""}"#);
+    ///
+    /// assert_eq!(buf, br#"{"time":"1970 A\\B"}"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn strftime_append(
+        time: &(impl Time + ?Sized),
+        format: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let len = buf.len();
+
+        let result = TimeFormatter::new(time, format).fmt(&mut JsonWrite::new(buf));
+        if result.is_err() {
+            buf.truncate(len);
+        }
+        result
+    }
+}
+
+/// Provides functions formatting many _times_ with the same format string into
+/// a single output, joined with a separator.
+///
+/// This is useful for exporters, like CSV or NDJSON writers, which would
+/// otherwise allocate a string for each formatted _time_.
+pub mod bulk {
+    use core::fmt;
+
+    use super::{Error, Time};
+    use crate::format::{FmtWrite, TimeFormatter, Write as _};
+
+    /// Format _time_ implementations with the specified UTF-8 format string,
+    /// writing the formatted strings separated by `separator` to a
+    /// [`core::fmt::Write`] object.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Allocations
+    ///
+    /// This function makes no heap allocations and is usable in a `no_std`
+    /// context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::bulk::write_joined;
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let times = [0, 86_400, 172_800].map(|seconds| DateTime::from_timestamp(seconds, 0).unwrap());
+    ///
+    /// let mut buf = String::new();
+    /// write_joined(times, "%F", ",", &mut buf)?;
+    /// assert_eq!(buf, "1970-01-01,1970-01-02,1970-01-03");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails. The _times_ before
+    /// the failing one are already written.
+    pub fn write_joined<I>(
+        times: I,
+        format: &str,
+        separator: &str,
+        buf: &mut dyn fmt::Write,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: Time,
+    {
+        let mut writer = FmtWrite::new(buf);
+
+        for (index, time) in times.into_iter().enumerate() {
+            if index > 0 {
+                writer.write_all(separator.as_bytes())?;
+            }
+            TimeFormatter::new(&time, format).fmt(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Format _time_ implementations with the specified format byte string,
+    /// writing the formatted strings separated by `separator` to a
+    /// [`std::io::Write`] object.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// The output is written through the same buffer for all the _times_, which
+    /// is flushed with vectored writes to the inner writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::bulk::write_joined_io;
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let times = [0, 1_000_000_000].map(|seconds| DateTime::from_timestamp(seconds, 0).unwrap());
+    ///
+    /// let mut buf = Vec::new();
+    /// write_joined_io(times, b"\"%FT%TZ\"", b"\n", &mut buf)?;
+    /// assert_eq!(buf, b"\"1970-01-01T00:00:00Z\"\n\"2001-09-09T01:46:40Z\"");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails. The _times_ before
+    /// the failing one are already written.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn write_joined_io<I>(
+        times: I,
+        format: &[u8],
+        separator: &[u8],
+        buf: &mut dyn std::io::Write,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: Time,
+    {
+        let mut writer = crate::format::IoWrite::new(buf);
+
+        let write_all = || {
+            for (index, time) in times.into_iter().enumerate() {
+                if index > 0 {
+                    writer.write_all(separator)?;
+                }
+                TimeFormatter::new(&time, format).fmt(&mut writer)?;
+            }
+            Ok(())
+        };
+        let result = write_all();
+
+        // Write the buffered output even on error, like an unbuffered writer
+        let flushed = writer.flush();
+        result.and(flushed)
+    }
+}
+
+/// Provides formatting of time ranges, like `"Jan 2 – 5, 2024"`, eliding the
+/// date components shared by the start and end of the range.
+///
+/// A range template contains a `%{start:...}` section, formatted with the
+/// start of the range, and a `%{end:...}` section, formatted with the end of
+/// the range. The sections contain regular format strings, which cannot
+/// contain a `}` character. The text outside of the sections is copied
+/// verbatim.
+///
+/// The directives of the date components shared by the start and end of the
+/// range are elided:
+///
+/// - If the start and end are in the same year, the year directives of the
+///   start section are elided, like in `"Jan 2 – Mar 5, 2024"`.
+/// - If they are also in the same month, the month directives of the end
+///   section are elided, like in `"Jan 2 – 5, 2024"`.
+/// - If they are on the same date, all the date directives of the end section
+///   are elided, like in `"Jan 2, 2024 10:00 – 11:00"`. If the end section
+///   only contains date directives, it is dropped with the text before it,
+///   like in `"Jan 2, 2024"`.
+///
+/// The text before an elided directive is elided with it, or the text after
+/// it if it is the first directive of the section. This works best with
+/// textual formats, like `"%b %e, %Y"`.
+pub mod range {
+    use core::fmt::Write;
+
+    use super::{Error, FormatOptions, Time};
+    use crate::format::range::fmt_range;
+    use crate::format::FmtWrite;
+
+    /// Format a range of times with the specified UTF-8 range template,
+    /// writing to the provided [`core::fmt::Write`] object.
+    ///
+    /// See the [module-level documentation](self) for a description of range
+    /// templates.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations on its own, but
+    /// the provided writer may allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::range::strftime;
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let template = "%{start:%b %e, %Y} – %{end:%b %e, %Y}";
+    /// let time = |seconds| DateTime::from_timestamp(seconds, 0).unwrap();
+    ///
+    /// let mut buf = String::new();
+    /// strftime(&time(1_703_894_400), &time(1_704_153_600), template, &mut buf)?;
+    /// assert_eq!(buf, "Dec 30, 2023 – Jan  2, 2024");
+    ///
+    /// let mut buf = String::new();
+    /// strftime(&time(1_704_153_600), &time(1_709_596_800), template, &mut buf)?;
+    /// assert_eq!(buf, "Jan  2 – Mar  5, 2024");
+    ///
+    /// let mut buf = String::new();
+    /// strftime(&time(1_704_153_600), &time(1_704_412_800), template, &mut buf)?;
+    /// assert_eq!(buf, "Jan  2 –  5, 2024");
+    ///
+    /// let mut buf = String::new();
+    /// strftime(&time(1_704_153_600), &time(1_704_157_200), template, &mut buf)?;
+    /// assert_eq!(buf, "Jan  2, 2024");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails. An unterminated
+    /// section returns [`Error::InvalidFormatString`].
+    pub fn strftime(
+        start: &(impl Time + ?Sized),
+        end: &(impl Time + ?Sized),
+        template: &str,
+        buf: &mut dyn Write,
+    ) -> Result<(), Error> {
+        strftime_with_options(start, end, template, buf, &FormatOptions::new())
+    }
+
+    /// Format a range of times with the specified UTF-8 range template and
+    /// [`FormatOptions`], writing to the provided [`core::fmt::Write`]
+    /// object.
+    ///
+    /// See the [module-level documentation](self) for a description of range
+    /// templates.
+    ///
+    /// # Allocations
+    ///
+    /// This `strftime` implementation makes no heap allocations on its own, but
+    /// the provided writer may allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::range::strftime_with_options;
+    /// use strftime::{DateTime, FormatOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let template = "%{start:%-d %B %Y, %H:%M} to %{end:%-d %B %Y, %H:%M}";
+    /// let start = DateTime::from_timestamp(1_704_189_600, 0).unwrap();
+    /// let end = DateTime::from_timestamp(1_704_193_200, 0).unwrap();
+    ///
+    /// let options = FormatOptions::new().with_max_directives(16);
+    ///
+    /// let mut buf = String::new();
+    /// strftime_with_options(&start, &end, template, &mut buf, &options)?;
+    /// assert_eq!(buf, "2 January 2024, 10:00 to 11:00");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Can produce an [`Error`] when the formatting fails. An unterminated
+    /// section returns [`Error::InvalidFormatString`].
+    pub fn strftime_with_options(
+        start: &(impl Time + ?Sized),
+        end: &(impl Time + ?Sized),
+        template: &str,
+        buf: &mut dyn Write,
+        options: &FormatOptions<'_>,
+    ) -> Result<(), Error> {
+        fmt_range(
+            &mut FmtWrite::new(buf),
+            start,
+            end,
+            template.as_bytes(),
+            options,
+        )
+    }
+}
+
+/// Provides a formatter for elapsed times, like `"%H:%M:%S"` for a
+/// [`Duration`](core::time::Duration), without going through a [`Time`]
+/// implementation.
+pub mod duration {
+    pub use crate::format::duration::{DurationFormatter, SignedDuration};
+}
+
+/// Provides human-readable descriptions of the directives of a format string,
+/// for use in command line tools, user interfaces and error messages.
+pub mod explain {
+    pub use crate::format::explain::{Directive, Explain, Item, Padding};
+
+    /// Describe the pieces of a format string.
+    ///
+    /// Returns an iterator over the literal text and the directives of the
+    /// format string, in order. Invalid directives are returned as literal
+    /// text, since they are copied verbatim to the output when formatting.
+    ///
+    /// The iterator yields an
+    /// [`Error::UnterminatedDirective`](crate::Error::UnterminatedDirective)
+    /// and stops if the format string is ended by an unterminated directive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::explain::Item;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let mut items = strftime::explain("%-d/%m");
+    ///
+    /// match items.next().transpose()? {
+    ///     Some(Item::Directive(directive)) => {
+    ///         assert_eq!(directive.text(), b"%-d");
+    ///         assert_eq!(directive.to_string(), "day of the month, 1..31, no padding");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// assert!(matches!(items.next().transpose()?, Some(Item::Literal(b"/"))));
+    /// assert!(matches!(items.next().transpose()?, Some(Item::Directive(_))));
+    /// assert!(items.next().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn explain<F: AsRef<[u8]> + ?Sized>(format: &F) -> Explain<'_> {
+        Explain::new(format.as_ref())
+    }
+}
+
+pub use explain::explain;
+
+/// Provides the structured pieces of a format string, for tools which validate
+/// or rewrite format strings without reimplementing the parser.
+pub mod pieces {
+    pub use crate::format::pieces::{Item, Pieces};
+    pub use crate::format::{Flag, Flags, Padding, Spec};
+    use crate::Dialect;
+
+    /// Iterate over the pieces of a format string.
+    ///
+    /// Returns an iterator over the literal text and the directives of the
+    /// format string in [`Dialect::Ruby`], in order. Invalid directives are
+    /// returned as literal text, since they are copied verbatim to the output
+    /// when formatting.
+    ///
+    /// The iterator yields an
+    /// [`Error::UnterminatedDirective`](crate::Error::UnterminatedDirective)
+    /// and stops if the format string is ended by an unterminated directive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::pieces::{pieces, Flag, Item, Padding, Spec};
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let mut items = pieces("%^10B/%q");
+    ///
+    /// match items.next().transpose()? {
+    ///     Some(Item::Directive { spec, flags, width, padding }) => {
+    ///         assert_eq!(spec, Spec::MonthName);
+    ///         assert!(flags.contains(Flag::UpperCase));
+    ///         assert_eq!(width, Some(10));
+    ///         assert_eq!(padding, Padding::Left);
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// assert_eq!(items.next().transpose()?, Some(Item::Literal(b"/")));
+    /// assert_eq!(items.next().transpose()?, Some(Item::Literal(b"%q")));
+    /// assert!(items.next().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pieces<F: AsRef<[u8]> + ?Sized>(format: &F) -> Pieces<'_> {
+        Pieces::new(format.as_ref(), Dialect::Ruby)
+    }
+
+    /// Iterate over the pieces of a format string in the provided
+    /// [`Dialect`].
+    ///
+    /// See [`pieces`] for a description of the returned iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::pieces::{pieces_with_dialect, Flag, Item};
+    /// use strftime::Dialect;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let mut items = pieces_with_dialect("%'s", Dialect::Extensions);
+    ///
+    /// match items.next().transpose()? {
+    ///     Some(Item::Directive { flags, .. }) => assert!(flags.contains(Flag::Grouping)),
+    ///     _ => unreachable!(),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pieces_with_dialect<F: AsRef<[u8]> + ?Sized>(
+        format: &F,
+        dialect: Dialect,
+    ) -> Pieces<'_> {
+        Pieces::new(format.as_ref(), dialect)
+    }
+}
+
+/// Provides parsing of times, the inverse of formatting, with the semantics
+/// of Ruby's [`Date._strptime`].
+///
+/// The format string is parsed with the same directives as the formatting
+/// functions. Each directive reads the value it would format:
+///
+/// - Numeric directives read at most as many digits as they would format, or
+///   the number of digits set by the width. `%Y`, `%G`, `%C` and `%s` read an
+///   optional sign and any number of digits, except that `%Y`, `%G` and `%C`
+///   are limited to 4 digits when followed by a number, like in `"%Y%m%d"`.
+/// - Names read the full or abbreviated name, capitalized like the formatted
+///   names, or in upper case with the `^` and `#` flags.
+/// - Combinations like `%F` and `%c` read their expansion.
+/// - Literal text and invalid directives must match the input exactly.
+///
+/// A two-digit year from `%y` without `%C` is in `1969..=2068` by default,
+/// which is configured with [`ParseOptions::with_year_pivot`], and an hour
+/// from `%I` or `%l` is combined with the meridian indicator from `%p` or
+/// `%P`.
+///
+/// The UTC offset directives `%z`, `%:z`, `%::z` and `%:::z` read the offsets
+/// they would format, like `+0530` for `%z`. `%Z` reads a time zone
+/// abbreviation made of ASCII letters, like `CEST`, or of a sign and digits,
+/// like `-03`. Abbreviations are returned verbatim without being resolved to a
+/// UTC offset, since they can be ambiguous.
+///
+/// A [`ParseError`] reports the byte offset in the input where parsing
+/// failed, the directive of the format string being matched and the expected
+/// input.
+///
+/// The parsing functions are available without the `std` and `alloc`
+/// features and make no heap allocations. The extracted fields are returned
+/// in a plain [`ParsedFields`] struct, with [`None`] for the fields which are
+/// not in the format string.
+///
+/// [`Date._strptime`]: <https://ruby-doc.org/stdlib-3.1.2/libdoc/date/rdoc/Date.html#method-c-_strptime>
+/// [`ParseOptions::with_year_pivot`]: strptime::ParseOptions::with_year_pivot
+/// [`ParsedFields`]: strptime::ParsedFields
+/// [`ParseError`]: strptime::ParseError
+pub mod strptime {
+    pub use crate::format::date_hash::{DateHash, Value};
+    pub use crate::format::scan::{Match, Scan};
+    pub use crate::format::strptime::{Expected, ParseError, ParseErrorKind, ParsedFields};
+    pub use crate::options::ParseOptions;
+
+    /// Parse a UTF-8 string with the specified UTF-8 format string, returning
+    /// the extracted fields.
+    ///
+    /// The whole input must match the format string.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::strptime;
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let fields = strptime("Jul 08, 2024 01:30 PM", "%b %d, %Y %I:%M %p")?;
+    ///
+    /// assert_eq!(fields.year, Some(2024));
+    /// assert_eq!(fields.month, Some(7));
+    /// assert_eq!(fields.day, Some(8));
+    /// assert_eq!(fields.hour, Some(13));
+    /// assert_eq!(fields.minute, Some(30));
+    /// assert_eq!(fields.second, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the input doesn't match the format string,
+    /// if the format string is invalid or if it has an unsupported directive.
+    pub fn strptime<'a>(input: &'a str, format: &str) -> Result<ParsedFields<'a>, ParseError> {
+        strptime_with_options(input, format, &ParseOptions::new())
+    }
+
+    /// Parse a UTF-8 string with the specified UTF-8 format string and
+    /// options, returning the extracted fields.
+    ///
+    /// The whole input must match the format string.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{strptime_with_options, ParseOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let options = ParseOptions::new().with_lenient();
+    /// let fields = strptime_with_options("MONDAY,  jul 8", "%A, %b %e", &options)?;
+    ///
+    /// assert_eq!(fields.day_of_week, Some(1));
+    /// assert_eq!(fields.month, Some(7));
+    /// assert_eq!(fields.day, Some(8));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the input doesn't match the format string,
+    /// if the format string is invalid or if it has an unsupported directive.
+    pub fn strptime_with_options<'a>(
+        input: &'a str,
+        format: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedFields<'a>, ParseError> {
+        strptime_bytes_with_options(input.as_bytes(), format.as_bytes(), options)
+    }
+
+    /// Parse a byte string with the specified format string, returning the
+    /// extracted fields.
+    ///
+    /// Neither the input nor the format string need to be valid UTF-8. Names
+    /// and literal text are matched byte by byte.
+    ///
+    /// The whole input must match the format string.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::strptime_bytes;
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let fields = strptime_bytes(b"\xFF 2024-07-08", b"\xFF %F")?;
+    ///
+    /// assert_eq!(fields.year, Some(2024));
+    /// assert_eq!(fields.month, Some(7));
+    /// assert_eq!(fields.day, Some(8));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the input doesn't match the format string,
+    /// if the format string is invalid or if it has an unsupported directive.
+    pub fn strptime_bytes<'a>(
+        input: &'a [u8],
+        format: &[u8],
+    ) -> Result<ParsedFields<'a>, ParseError> {
+        strptime_bytes_with_options(input, format, &ParseOptions::new())
+    }
+
+    /// Parse a byte string with the specified format string and options,
+    /// returning the extracted fields.
+    ///
+    /// Neither the input nor the format string need to be valid UTF-8. Names
+    /// and literal text are matched byte by byte.
+    ///
+    /// The whole input must match the format string.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{strptime_bytes_with_options, ParseOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let options = ParseOptions::new().with_lenient();
+    /// let fields = strptime_bytes_with_options(b"\xFF  12:30 pm", b"\xFF %I:%M %p", &options)?;
+    /// assert_eq!(fields.hour, Some(12));
+    /// assert_eq!(fields.minute, Some(30));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the input doesn't match the format string,
+    /// if the format string is invalid or if it has an unsupported directive.
+    pub fn strptime_bytes_with_options<'a>(
+        input: &'a [u8],
+        format: &[u8],
+        options: &ParseOptions,
+    ) -> Result<ParsedFields<'a>, ParseError> {
+        crate::format::strptime::parse(input, format, *options)
+    }
+
+    /// Parse the start of a UTF-8 string with the specified UTF-8 format string
+    /// and options, returning the extracted fields and the remaining input.
+    ///
+    /// Parsing stops at the end of the format string, so the input can have
+    /// any text after the parsed time, like a timestamp at the start of a log
+    /// line.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{strptime_partial, ParseOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let line = "2024-07-08 10:00:05 INFO server started";
+    /// let (fields, message) = strptime_partial(line, "%F %T ", &ParseOptions::new())?;
+    ///
+    /// assert_eq!(fields.day, Some(8));
+    /// assert_eq!(fields.second, Some(5));
+    /// assert_eq!(message, "INFO server started");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the start of the input doesn't match the
+    /// format string, if the format string is invalid or if it has an
+    /// unsupported directive.
+    pub fn strptime_partial<'a>(
+        input: &'a str,
+        format: &str,
+        options: &ParseOptions,
+    ) -> Result<(ParsedFields<'a>, &'a str), ParseError> {
+        let (fields, remaining) =
+            strptime_bytes_partial(input.as_bytes(), format.as_bytes(), options)?;
+
+        // The matched text ends on a character boundary, since the input is
+        // only split after ASCII bytes or after complete literal text from
+        // the UTF-8 format string.
+        let offset = input.len() - remaining.len();
+        let remaining = input
+            .get(offset..)
+            .ok_or_else(|| ParseError::new(ParseErrorKind::InputMismatch, offset, None))?;
+        Ok((fields, remaining))
+    }
+
+    /// Parse the start of a byte string with the specified format string and
+    /// options, returning the extracted fields and the remaining input.
+    ///
+    /// Parsing stops at the end of the format string, so the input can have
+    /// any bytes after the parsed time.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{strptime_bytes_partial, ParseOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let (fields, rest) = strptime_bytes_partial(b"[10:00]\xFF", b"[%R]", &ParseOptions::new())?;
+    ///
+    /// assert_eq!(fields.hour, Some(10));
+    /// assert_eq!(rest, b"\xFF");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the start of the input doesn't match the
+    /// format string, if the format string is invalid or if it has an
+    /// unsupported directive.
+    pub fn strptime_bytes_partial<'a>(
+        input: &'a [u8],
+        format: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(ParsedFields<'a>, &'a [u8]), ParseError> {
+        crate::format::strptime::parse_partial(input, format, *options)
+    }
+
+    /// Parse the start of a byte string with the specified format string and
+    /// options, returning the entries of the hash returned by Ruby's
+    /// [`Date._strptime`].
+    ///
+    /// The remaining input after the end of the format string is returned in
+    /// the `leftover` entry. Ruby's `Date._strptime` is lenient, which is
+    /// enabled with [`ParseOptions::with_lenient`].
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{date_strptime, ParseOptions, Value};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// let options = ParseOptions::new().with_lenient();
+    /// let mut hash = date_strptime(b"jul  8 2024 10:30 UTC", b"%b %e %Y %R", &options)?;
+    ///
+    /// assert_eq!(hash.next(), Some(("year", Value::Integer(2024))));
+    /// assert_eq!(hash.next(), Some(("mon", Value::Integer(7))));
+    /// assert_eq!(hash.next(), Some(("mday", Value::Integer(8))));
+    /// assert_eq!(hash.next(), Some(("hour", Value::Integer(10))));
+    /// assert_eq!(hash.next(), Some(("min", Value::Integer(30))));
+    /// assert_eq!(hash.next(), Some(("leftover", Value::String(b" UTC"))));
+    /// assert_eq!(hash.next(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the start of the input doesn't match the
+    /// format string, if the format string is invalid or if it has an
+    /// unsupported directive. Ruby returns `nil` instead.
+    ///
+    /// [`Date._strptime`]: <https://ruby-doc.org/stdlib-3.1.2/libdoc/date/rdoc/Date.html#method-c-_strptime>
+    pub fn date_strptime<'a>(
+        input: &'a [u8],
+        format: &[u8],
+        options: &ParseOptions,
+    ) -> Result<DateHash<'a>, ParseError> {
+        let (fields, leftover) = strptime_bytes_partial(input, format, options)?;
+        Ok(DateHash::new(fields, leftover))
+    }
+
+    /// Search a UTF-8 text for the times matching the specified UTF-8 format
+    /// string, returning an iterator over the matches.
+    ///
+    /// The matches are found from left to right and don't overlap. A match
+    /// never starts in the middle of a number, so `%d/%m` doesn't match
+    /// `23/05` in `123/05`, nor on whitespace unless the format string starts
+    /// with whitespace. Each match yields its byte range in the text and the
+    /// extracted fields.
+    ///
+    /// An invalid format string, or one with an unsupported directive, matches
+    /// nothing.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{scan, ParseOptions};
+    ///
+    /// let log = "server started at 2024-07-08 10:00:05, stopped at 2024-07-08 18:30:00";
+    /// let mut matches = scan(log, "%F %T", &ParseOptions::new());
+    ///
+    /// let first = matches.next().unwrap();
+    /// assert_eq!(&log[first.range()], "2024-07-08 10:00:05");
+    /// assert_eq!(first.fields().hour, Some(10));
+    ///
+    /// let second = matches.next().unwrap();
+    /// assert_eq!(second.start(), 50);
+    /// assert_eq!(second.fields().hour, Some(18));
+    ///
+    /// assert!(matches.next().is_none());
+    /// ```
+    #[must_use]
+    pub fn scan<'a, 'f>(
+        haystack: &'a str,
+        format: &'f str,
+        options: &ParseOptions,
+    ) -> Scan<'a, 'f> {
+        Scan::new(haystack.as_bytes(), format.as_bytes(), *options, true)
+    }
+
+    /// Search a byte string for the times matching the specified format
+    /// string, returning an iterator over the matches.
+    ///
+    /// Neither the text nor the format string need to be valid UTF-8. See
+    /// [`scan`] for how matches are found.
+    ///
+    /// # Allocations
+    ///
+    /// This `strptime` implementation makes no heap allocations and is usable
+    /// in a `no_std` context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{scan_bytes, ParseOptions};
+    ///
+    /// let data = b"\xFF\x0012:30\xFF13:45";
+    /// let ranges = scan_bytes(data, b"%R", &ParseOptions::new()).map(|m| m.range());
+    ///
+    /// assert!(ranges.eq([2..7, 8..13]));
+    /// ```
+    #[must_use]
+    pub fn scan_bytes<'a, 'f>(
+        haystack: &'a [u8],
+        format: &'f [u8],
+        options: &ParseOptions,
+    ) -> Scan<'a, 'f> {
+        Scan::new(haystack, format, *options, false)
+    }
+}
+
+pub mod clock;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod diagnostic;
+pub mod formats;
+pub mod series;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod sql;
+
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow;
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(docsrs, doc(cfg(feature = "heapless")))]
+pub mod heapless;
+
+#[cfg(all(feature = "tokio", feature = "std"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "tokio", feature = "std"))))]
+pub mod tokio;
+
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
+
 // Ensure code blocks in `README.md` compile.
 //
 // This module declaration should be kept at the end of the file, in order to
@@ -0,0 +1,145 @@
+//! A [`Time`] adapter shifting another implementation into a fixed offset.
+
+use crate::{DateTime, Time};
+
+/// A [`Time`] implementation representing the same instant as another
+/// implementation, shifted into a fixed UTC offset with a time zone name.
+///
+/// The civil fields are computed from the number of seconds since the Unix
+/// epoch ([`Time::to_int`]) and the number of nanoseconds of the wrapped
+/// implementation, like [`DateTime`]. This is useful to store times in UTC and
+/// format them in another offset.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::buffered::strftime;
+/// use strftime::{DateTime, WithOffset};
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = DateTime::from_timestamp(1_000_000_000, 0).unwrap();
+/// let shifted = WithOffset::new(&time, -5 * 3600, "EST").unwrap();
+///
+/// let mut buf = [0u8; 32];
+/// assert_eq!(strftime(&shifted, b"%F %T %z %Z", &mut buf)?, b"2001-09-08 20:46:40 -0500 EST");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WithOffset<'a> {
+    /// Shifted date and time.
+    datetime: DateTime,
+    /// Name of the time zone.
+    time_zone: &'a str,
+}
+
+impl<'a> WithOffset<'a> {
+    /// Construct a new `WithOffset` from the instant of a _time_
+    /// implementation, a fixed offset in seconds from UTC and a time zone
+    /// name.
+    ///
+    /// The resulting time is never considered to be in UTC, even if the offset
+    /// is zero.
+    ///
+    /// Returns [`None`] if the number of nanoseconds of the _time_
+    /// implementation is not in `0..=999_999_999`, if the offset is not
+    /// strictly between `-86400` and `86400` or if the shifted year is out of
+    /// range of an [`i32`].
+    #[must_use]
+    pub fn new<T: Time + ?Sized>(time: &T, utc_offset: i32, time_zone: &'a str) -> Option<Self> {
+        let datetime =
+            DateTime::from_timestamp_with_offset(time.to_int(), time.nanoseconds(), utc_offset)?;
+        Some(Self {
+            datetime,
+            time_zone,
+        })
+    }
+}
+
+impl Time for WithOffset<'_> {
+    fn year(&self) -> i32 {
+        self.datetime.year()
+    }
+
+    fn month(&self) -> u8 {
+        self.datetime.month()
+    }
+
+    fn day(&self) -> u8 {
+        self.datetime.day()
+    }
+
+    fn hour(&self) -> u8 {
+        self.datetime.hour()
+    }
+
+    fn minute(&self) -> u8 {
+        self.datetime.minute()
+    }
+
+    fn second(&self) -> u8 {
+        self.datetime.second()
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.datetime.nanoseconds()
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.datetime.day_of_week()
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.datetime.day_of_year()
+    }
+
+    fn to_int(&self) -> i64 {
+        self.datetime.to_int()
+    }
+
+    fn is_utc(&self) -> bool {
+        false
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.datetime.utc_offset()
+    }
+
+    fn time_zone(&self) -> &str {
+        self.time_zone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_offset() {
+        let time = DateTime::from_timestamp(0, 123).unwrap();
+
+        let shifted = WithOffset::new(&time, 9 * 3600, "JST").unwrap();
+        assert_eq!(shifted.year(), 1970);
+        assert_eq!(shifted.month(), 1);
+        assert_eq!(shifted.day(), 1);
+        assert_eq!(shifted.hour(), 9);
+        assert_eq!(shifted.nanoseconds(), 123);
+        assert_eq!(shifted.day_of_week(), 4);
+        assert_eq!(shifted.day_of_year(), 1);
+        assert_eq!(shifted.to_int(), 0);
+        assert!(!shifted.is_utc());
+        assert_eq!(shifted.utc_offset(), 32_400);
+        assert_eq!(shifted.time_zone(), "JST");
+
+        let shifted = WithOffset::new(&time, -1, "").unwrap();
+        assert_eq!(shifted.year(), 1969);
+        assert_eq!(shifted.month(), 12);
+        assert_eq!(shifted.day(), 31);
+        assert_eq!(shifted.second(), 59);
+        assert_eq!(shifted.day_of_week(), 3);
+        assert_eq!(shifted.day_of_year(), 365);
+        assert_eq!(shifted.to_int(), 0);
+
+        assert!(WithOffset::new(&time, 86_400, "").is_none());
+    }
+}
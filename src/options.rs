@@ -0,0 +1,1260 @@
+//! Options for customizing the behavior of the `strftime` functions.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::cell::Cell;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use crate::{Error, FormatStats};
+
+/// Default maximum number of directives in a format string, which is
+/// unlimited like Ruby.
+///
+/// A limit is only enforced when it is set with
+/// [`FormatOptions::with_max_directives`].
+pub const DEFAULT_MAX_DIRECTIVES: usize = usize::MAX;
+
+/// Non-Gregorian calendar used to format the date directives.
+///
+/// See [`FormatOptions::with_calendar`].
+#[cfg(feature = "icu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "icu")))]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Calendar {
+    /// Thai Buddhist calendar.
+    Buddhist,
+    /// Hebrew calendar.
+    Hebrew,
+    /// Hijri calendar, using the tabular civil Islamic calendar.
+    Hijri,
+    /// Japanese imperial calendar, with years counted from the start of the
+    /// current era.
+    Japanese,
+}
+
+/// Locale used to format the locale-dependent directives.
+///
+/// Only the day period directive `%:p` of [`Dialect::Extensions`] depends on
+/// the locale. The names of days and months are always in English, like in
+/// Ruby.
+///
+/// See [`FormatOptions::with_locale`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// English.
+    English,
+    /// German.
+    German,
+    /// Spanish.
+    Spanish,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::English
+    }
+}
+
+/// Set of directives accepted in a format string.
+///
+/// See [`FormatOptions::with_dialect`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// Directives supported by Ruby's [`Time#strftime`].
+    ///
+    /// [`Time#strftime`]: <https://ruby-doc.org/core-3.1.2/Time.html#method-i-strftime>
+    Ruby,
+    /// Directives supported by Ruby, with the following extensions:
+    ///
+    /// | Directive | Description                                              |
+    /// |-----------|----------------------------------------------------------|
+    /// | `%:s`     | Number of seconds since `1970-01-01 00:00:00 UTC` with truncated fractional seconds digits, like `1700000000.123456789`. The width sets the number of fractional digits, which is 9 by default. |
+    /// | `%:p`     | Day period in the locale set with [`FormatOptions::with_locale`], like `in the morning`, `noon` or `at night`. The `^` flag converts ASCII letters to upper case. |
+    /// | `%o`      | UTC offset with hour, minute and second and without colons, like `+053000` for ISO 8601 basic format. Flags and width are handled like `%z`. |
+    /// | `.` flag  | Truncate string values, like `%A`, `%B` and `%Z`, to the width instead of only padding them, like `%.2A` for `Mo`. |
+    /// | `<` flag  | Left-justify string values, like `%A`, `%B` and `%Z`, within the width by padding them with spaces on the right, like `%<6B` for `July  `. |
+    /// | `*` width | Take the width from the arguments set with [`FormatOptions::with_width_args`], like `%*N` to choose the number of fractional digits at runtime. |
+    /// | `'` flag  | Group the digits of `%s` by thousands, like `%'s` for `1,700,000,000`. The separator is set with [`FormatOptions::with_digit_separator`]. |
+    Extensions,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self::Ruby
+    }
+}
+
+/// Maximum absolute value in seconds of a valid UTC offset, which is 26 hours.
+///
+/// See [`OffsetPolicy`].
+pub const MAX_UTC_OFFSET: i32 = 26 * 3600;
+
+/// Policy for UTC offsets which are out of range or inconsistent.
+///
+/// A UTC offset is out of range if its absolute value is greater than
+/// [`MAX_UTC_OFFSET`], and it is inconsistent if it is not zero for a time in
+/// UTC. The policy only applies to the `%z` directives.
+///
+/// See [`FormatOptions::with_offset_policy`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetPolicy {
+    /// Format the UTC offset returned by the time implementation as is.
+    Allow,
+    /// Return [`Error::InvalidUtcOffset`].
+    ///
+    /// [`Error::InvalidUtcOffset`]: crate::Error::InvalidUtcOffset
+    Reject,
+    /// Clamp an out of range UTC offset to [`MAX_UTC_OFFSET`], and use a zero
+    /// UTC offset for a time in UTC.
+    Clamp,
+}
+
+impl Default for OffsetPolicy {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+/// Policy for the time zone directives (`%z`, `%:z`, `%::z`, `%:::z` and
+/// `%Z`) of a time without time zone information.
+///
+/// See [`FormatOptions::with_naive_policy`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NaivePolicy {
+    /// Return [`Error::MissingTimeZone`].
+    ///
+    /// [`Error::MissingTimeZone`]: crate::Error::MissingTimeZone
+    Reject,
+    /// Write nothing, ignoring the width and flags of the directive.
+    Empty,
+    /// Format the time as if it was in UTC, with a zero UTC offset and the
+    /// `UTC` time zone name.
+    Utc,
+    /// Format the UTC offset as `-00:00`, which means that the offset to local
+    /// time is unknown in [RFC 3339], and write nothing for `%Z`.
+    ///
+    /// The offset is written with the format of the directive, like `-0000`
+    /// for `%z` or `-00` for `%:::z`.
+    ///
+    /// [RFC 3339]: <https://datatracker.ietf.org/doc/html/rfc3339#section-4.3>
+    Unknown,
+}
+
+/// Transformation applied to the formatted output before it is returned.
+#[cfg(feature = "alloc")]
+struct PostProcess<'a, T: ?Sized>(&'a dyn Fn(&mut T));
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> Clone for PostProcess<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> Copy for PostProcess<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> fmt::Debug for PostProcess<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PostProcess(..)")
+    }
+}
+
+/// Options for customizing the behavior of the `strftime` functions.
+///
+/// The default options produce the same output as Ruby's [`Time#strftime`].
+///
+/// [`Time#strftime`]: <https://ruby-doc.org/core-3.1.2/Time.html#method-i-strftime>
+#[derive(Debug, Clone, Copy)]
+// The options are independent switches, set with a builder method each.
+#[allow(clippy::struct_excessive_bools)]
+pub struct FormatOptions<'a> {
+    /// Maximum number of directives in a format string.
+    max_directives: usize,
+    /// Set of accepted directives.
+    dialect: Dialect,
+    /// Policy for invalid UTC offsets.
+    offset_policy: OffsetPolicy,
+    /// Policy for the time zone directives of a time without time zone
+    /// information.
+    naive_policy: Option<NaivePolicy>,
+    /// Whether an unterminated directive at the end of the format string is
+    /// copied verbatim to the output.
+    lenient_trailing_percent: bool,
+    /// Whether unknown or malformed directives are rejected.
+    strict_directives: bool,
+    /// Whether directives with a width which is too large are rejected.
+    strict_widths: bool,
+    /// Whether the output is written only if the formatting succeeds.
+    atomic: bool,
+    /// Separator between groups of digits.
+    digit_separator: &'a str,
+    /// Aliases for unsupported conversion specifiers.
+    aliases: &'a [(u8, &'a str)],
+    /// Named formats referenced with `%{name}`.
+    named_formats: &'a [(&'a str, &'a str)],
+    /// Locale of the day period directive.
+    locale: Locale,
+    /// Statistics filled after formatting.
+    stats: Option<&'a Cell<FormatStats>>,
+    /// Widths of the `*` width placeholders.
+    width_args: &'a [usize],
+    /// Calendar used to format the date directives.
+    #[cfg(feature = "icu")]
+    calendar: Option<Calendar>,
+    /// Whether errors are wrapped with the directive which failed.
+    #[cfg(feature = "alloc")]
+    error_context: bool,
+    /// Whether the writer of the `io` functions is flushed.
+    #[cfg(feature = "std")]
+    flush: bool,
+    /// Transformation applied to the formatted bytes before they are returned.
+    #[cfg(feature = "alloc")]
+    post_process: Option<PostProcess<'a, Vec<u8>>>,
+    /// Transformation applied to the formatted string before it is returned.
+    #[cfg(feature = "alloc")]
+    post_process_str: Option<PostProcess<'a, String>>,
+}
+
+impl<'a> FormatOptions<'a> {
+    /// Construct a new `FormatOptions` with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_directives: DEFAULT_MAX_DIRECTIVES,
+            dialect: Dialect::Ruby,
+            offset_policy: OffsetPolicy::Allow,
+            naive_policy: None,
+            lenient_trailing_percent: false,
+            strict_directives: false,
+            strict_widths: false,
+            atomic: false,
+            digit_separator: ",",
+            aliases: &[],
+            named_formats: &[],
+            locale: Locale::English,
+            stats: None,
+            width_args: &[],
+            #[cfg(feature = "icu")]
+            calendar: None,
+            #[cfg(feature = "alloc")]
+            error_context: false,
+            #[cfg(feature = "std")]
+            flush: false,
+            #[cfg(feature = "alloc")]
+            post_process: None,
+            #[cfg(feature = "alloc")]
+            post_process_str: None,
+        }
+    }
+
+    /// Set the maximum number of directives in a format string.
+    ///
+    /// Formatting a format string with more directives returns
+    /// [`Error::TooManyDirectives`]. This protects against hostile format
+    /// strings like `"%N%N%N..."`, which would otherwise consume CPU until the
+    /// formatted string reaches its size limit. The unknown directives copied
+    /// verbatim to the output, like `%Q`, are counted too.
+    ///
+    /// By default, the number of directives is unlimited like Ruby, see
+    /// [`DEFAULT_MAX_DIRECTIVES`].
+    ///
+    /// [`Error::TooManyDirectives`]: crate::Error::TooManyDirectives
+    /// [`DEFAULT_MAX_DIRECTIVES`]: crate::DEFAULT_MAX_DIRECTIVES
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_max_directives(2);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// assert_eq!(strftime_with_options(&time, b"%Y-%m", &mut buf, &options)?, b"1970-01");
+    ///
+    /// let result = strftime_with_options(&time, b"%Y-%m-%d", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::TooManyDirectives { limit: 2 })));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_max_directives(mut self, max_directives: usize) -> Self {
+        self.max_directives = max_directives;
+        self
+    }
+
+    /// Returns the maximum number of directives in a format string.
+    #[must_use]
+    pub const fn max_directives(&self) -> usize {
+        self.max_directives
+    }
+
+    /// Set the dialect of the format string, which enables directives not
+    /// supported by Ruby.
+    ///
+    /// With the default [`Dialect::Ruby`], these directives are copied verbatim
+    /// to the output, like other invalid directives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Dialect, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, to_int: 1_700_000_000, nanoseconds: 12_345_678, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    /// # #[cfg(feature = "subsec")]
+    /// assert_eq!(strftime_with_options(&time, b"%:s", &mut buf, &options)?, b"1700000000.012345678");
+    /// # #[cfg(feature = "subsec")]
+    /// assert_eq!(strftime_with_options(&time, b"%3:s", &mut buf, &options)?, b"1700000000.012");
+    ///
+    /// let options = FormatOptions::new().with_dialect(Dialect::Ruby);
+    /// assert_eq!(strftime_with_options(&time, b"%:s", &mut buf, &options)?, b"%:s");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Returns the dialect of the format string.
+    #[must_use]
+    pub const fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Set the policy for UTC offsets which are out of range or inconsistent.
+    ///
+    /// The default [`OffsetPolicy::Allow`] formats the UTC offset returned by
+    /// the time implementation as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, OffsetPolicy, Time};
+    ///
+    /// // Not shown: create a time implementation with a UTC offset of 30 hours
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { utc_offset: 30 * 3600, ..Default::default() };
+    /// assert_eq!(time.utc_offset(), 108_000);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// assert_eq!(strftime_with_options(&time, b"%z", &mut buf, &FormatOptions::new())?, b"+3000");
+    ///
+    /// let options = FormatOptions::new().with_offset_policy(OffsetPolicy::Clamp);
+    /// assert_eq!(strftime_with_options(&time, b"%z", &mut buf, &options)?, b"+2600");
+    ///
+    /// let options = FormatOptions::new().with_offset_policy(OffsetPolicy::Reject);
+    /// let result = strftime_with_options(&time, b"%z", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::InvalidUtcOffset { utc_offset: 108_000, is_utc: false })));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_offset_policy(mut self, offset_policy: OffsetPolicy) -> Self {
+        self.offset_policy = offset_policy;
+        self
+    }
+
+    /// Returns the policy for UTC offsets which are out of range or
+    /// inconsistent.
+    #[must_use]
+    pub const fn offset_policy(&self) -> OffsetPolicy {
+        self.offset_policy
+    }
+
+    /// Format the time as a naive time, which has no time zone information,
+    /// using the policy for the time zone directives.
+    ///
+    /// The UTC offset and the time zone name returned by the time
+    /// implementation are ignored. By default, they are always formatted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, NaivePolicy, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let options = FormatOptions::new().with_naive_policy(NaivePolicy::Utc);
+    /// assert_eq!(strftime_with_options(&time, b"%F %:z %Z", &mut buf, &options)?, b"1970-01-01 +00:00 UTC");
+    ///
+    /// let options = FormatOptions::new().with_naive_policy(NaivePolicy::Empty);
+    /// assert_eq!(strftime_with_options(&time, b"%F%z%Z", &mut buf, &options)?, b"1970-01-01");
+    ///
+    /// let options = FormatOptions::new().with_naive_policy(NaivePolicy::Unknown);
+    /// assert_eq!(strftime_with_options(&time, b"%FT00:00:00%:z", &mut buf, &options)?, b"1970-01-01T00:00:00-00:00");
+    ///
+    /// let options = FormatOptions::new().with_naive_policy(NaivePolicy::Reject);
+    /// let result = strftime_with_options(&time, b"%F %z", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::MissingTimeZone)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_naive_policy(mut self, naive_policy: NaivePolicy) -> Self {
+        self.naive_policy = Some(naive_policy);
+        self
+    }
+
+    /// Returns the policy for the time zone directives of a naive time, if the
+    /// time is formatted as a naive time.
+    #[must_use]
+    pub const fn naive_policy(&self) -> Option<NaivePolicy> {
+        self.naive_policy
+    }
+
+    /// Copy an unterminated directive at the end of the format string, like a
+    /// bare `%`, verbatim to the output instead of returning
+    /// [`Error::UnterminatedDirective`].
+    ///
+    /// This matches the behavior of some C libraries.
+    ///
+    /// [`Error::UnterminatedDirective`]: crate::Error::UnterminatedDirective
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let result = strftime_with_options(&time, b"%Y %", &mut buf, &FormatOptions::new());
+    /// assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+    ///
+    /// let options = FormatOptions::new().with_lenient_trailing_percent();
+    /// assert_eq!(strftime_with_options(&time, b"%Y %", &mut buf, &options)?, b"1970 %");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_lenient_trailing_percent(mut self) -> Self {
+        self.lenient_trailing_percent = true;
+        self
+    }
+
+    /// Returns whether an unterminated directive at the end of the format
+    /// string is copied verbatim to the output.
+    #[must_use]
+    pub const fn lenient_trailing_percent(&self) -> bool {
+        self.lenient_trailing_percent
+    }
+
+    /// Return [`Error::UnknownDirective`] for an unknown or malformed
+    /// directive, like `%Q` or `%-E`, instead of copying it verbatim to the
+    /// output like Ruby.
+    ///
+    /// The directives expanded by [`with_aliases`] and
+    /// [`with_named_formats`] are accepted. With [`with_error_context`], the
+    /// error reports the rejected directive and its position.
+    ///
+    /// [`Error::UnknownDirective`]: crate::Error::UnknownDirective
+    /// [`with_aliases`]: Self::with_aliases
+    /// [`with_named_formats`]: Self::with_named_formats
+    /// [`with_error_context`]: Self::with_error_context
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let options = FormatOptions::new();
+    /// assert_eq!(strftime_with_options(&time, b"%Y %Q", &mut buf, &options)?, b"1970 %Q");
+    ///
+    /// let options = FormatOptions::new().with_strict_directives();
+    /// let result = strftime_with_options(&time, b"%Y %Q", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::UnknownDirective)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_strict_directives(mut self) -> Self {
+        self.strict_directives = true;
+        self
+    }
+
+    /// Returns whether unknown or malformed directives are rejected.
+    #[must_use]
+    pub const fn strict_directives(&self) -> bool {
+        self.strict_directives
+    }
+
+    /// Return [`Error::WidthTooLarge`] for a directive with a width which
+    /// doesn't fit in a `c_int`, like `%2147483648m`, instead of copying it
+    /// verbatim to the output like Ruby.
+    ///
+    /// This also applies to the widths taken from [`with_width_args`]. With
+    /// [`with_error_context`], the error reports the rejected directive and its
+    /// position.
+    ///
+    /// [`Error::WidthTooLarge`]: crate::Error::WidthTooLarge
+    /// [`with_width_args`]: Self::with_width_args
+    /// [`with_error_context`]: Self::with_error_context
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the month 1
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { month: 1, ..Default::default() };
+    /// assert_eq!(time.month(), 1);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let options = FormatOptions::new();
+    /// let result = strftime_with_options(&time, b"%2147483648m", &mut buf, &options)?;
+    /// assert_eq!(result, b"%2147483648m");
+    ///
+    /// let options = FormatOptions::new().with_strict_widths();
+    /// let result = strftime_with_options(&time, b"%2147483648m", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::WidthTooLarge { limit: 2147483647 })));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_strict_widths(mut self) -> Self {
+        self.strict_widths = true;
+        self
+    }
+
+    /// Returns whether directives with a width which is too large are rejected.
+    #[must_use]
+    pub const fn strict_widths(&self) -> bool {
+        self.strict_widths
+    }
+
+    /// Write the output only if the formatting succeeds.
+    ///
+    /// With the **alloc** feature, the time is formatted into a scratch
+    /// buffer, which is written to the output once the formatting succeeded.
+    /// The scratch buffer is allocated with fallible allocation APIs, and an
+    /// allocation failure returns [`Error::OutOfMemory`].
+    ///
+    /// Without the **alloc** feature, the time is formatted twice: a dry run
+    /// checks the format string and the time and counts the formatted bytes,
+    /// then the time is formatted into the output. The time implementation
+    /// must return the same values for both passes.
+    ///
+    /// An invalid format string or time leaves the output untouched with every
+    /// sink. With the [`buffered`] functions, a buffer which is too small
+    /// returns [`Error::WriteZero`] without overwriting any byte of the buffer.
+    /// With the other sinks, only the errors of the sink itself, like an I/O
+    /// error, can leave a partial output.
+    ///
+    /// [`buffered`]: crate::buffered
+    /// [`Error::WriteZero`]: crate::Error::WriteZero
+    /// [`Error::OutOfMemory`]: crate::Error::OutOfMemory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = *b"--------";
+    /// let result = strftime_with_options(&time, b"%Y-%m-%d", &mut buf, &FormatOptions::new());
+    /// assert!(matches!(result, Err(Error::InvalidTime)));
+    /// assert_eq!(buf, *b"1970----");
+    ///
+    /// let mut buf = *b"--------";
+    /// let options = FormatOptions::new().with_atomic();
+    /// let result = strftime_with_options(&time, b"%Y-%m-%d", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::InvalidTime)));
+    /// assert_eq!(buf, *b"--------");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_atomic(mut self) -> Self {
+        self.atomic = true;
+        self
+    }
+
+    /// Returns whether the output is written only if the formatting succeeds.
+    #[must_use]
+    pub const fn atomic(&self) -> bool {
+        self.atomic
+    }
+
+    /// Set the separator between groups of thousands digits, which is used
+    /// with the `'` flag of [`Dialect::Extensions`].
+    ///
+    /// The default separator is `","`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Dialect, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, to_int: 1_700_000_000, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    /// assert_eq!(strftime_with_options(&time, b"%'s", &mut buf, &options)?, b"1,700,000,000");
+    ///
+    /// let options = options.with_digit_separator(" ");
+    /// assert_eq!(strftime_with_options(&time, b"%'s", &mut buf, &options)?, b"1 700 000 000");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_digit_separator(mut self, digit_separator: &'a str) -> Self {
+        self.digit_separator = digit_separator;
+        self
+    }
+
+    /// Returns the separator between groups of thousands digits.
+    #[must_use]
+    pub const fn digit_separator(&self) -> &'a str {
+        self.digit_separator
+    }
+
+    /// Set the locale used to format the day period directive `%:p` of
+    /// [`Dialect::Extensions`].
+    ///
+    /// The day periods follow the rules of the [CLDR], with the names used in
+    /// the middle of a sentence. The default locale is [`Locale::English`].
+    ///
+    /// [CLDR]: <https://www.unicode.org/cldr/charts/latest/supplemental/day_periods.html>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Dialect, FormatOptions, Locale, Time};
+    ///
+    /// // Not shown: create a time implementation with the hour 7
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { hour: 7, ..Default::default() };
+    /// assert_eq!(time.hour(), 7);
+    ///
+    /// let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// assert_eq!(strftime_with_options(&time, b"%-l %:p", &mut buf, &options)?, b"7 in the morning");
+    ///
+    /// let options = options.with_locale(Locale::German);
+    /// assert_eq!(strftime_with_options(&time, b"%-H Uhr %:p", &mut buf, &options)?, b"7 Uhr morgens");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Returns the locale used to format the day period directive.
+    #[must_use]
+    pub const fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Fill the provided cell with the [`FormatStats`] of each formatting
+    /// call, to profile format strings without timing them.
+    ///
+    /// The statistics are overwritten by each call using these options.
+    ///
+    /// See [`FormatStats`] for an example.
+    #[must_use]
+    pub const fn with_stats(mut self, stats: &'a Cell<FormatStats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Returns the cell filled with the statistics of each formatting call, if
+    /// any.
+    #[must_use]
+    pub const fn stats(&self) -> Option<&'a Cell<FormatStats>> {
+        self.stats
+    }
+
+    /// Set the widths used by the `*` width placeholders of
+    /// [`Dialect::Extensions`], in order of appearance in the format string.
+    ///
+    /// This allows a single format string to be formatted with widths chosen
+    /// at runtime, like the number of fractional digits. A width which is too
+    /// large makes the directive invalid, like a literal width, and a missing
+    /// width returns [`Error::MissingWidthArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Dialect, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, day: 8, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    ///
+    /// let options = options.with_width_args(&[4]);
+    /// assert_eq!(strftime_with_options(&time, b"[%*d]", &mut buf, &options)?, b"[0008]");
+    ///
+    /// let options = options.with_width_args(&[3, 6]);
+    /// assert_eq!(strftime_with_options(&time, b"[%-*d|%_*d]", &mut buf, &options)?, b"[8|     8]");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_width_args(mut self, width_args: &'a [usize]) -> Self {
+        self.width_args = width_args;
+        self
+    }
+
+    /// Returns the widths used by the `*` width placeholders.
+    #[must_use]
+    pub const fn width_args(&self) -> &'a [usize] {
+        self.width_args
+    }
+
+    /// Register aliases for conversion specifiers, which are expanded to their
+    /// format string when formatting.
+    ///
+    /// Each alias maps an ASCII conversion specifier to a format string, like
+    /// `(b'f', "%6N")`. Aliases only apply to bare directives without flags or
+    /// width, and only to conversion specifiers which are not supported by
+    /// Ruby, so the supported directives keep their meaning. The format string
+    /// of an alias is formatted with the same options, without expanding
+    /// aliases, and its directives count towards the [maximum number of
+    /// directives](Self::with_max_directives).
+    ///
+    /// If several aliases share the same conversion specifier, the first one
+    /// is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, nanoseconds: 123_456_789, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_aliases(&[(b'f', "%6N"), (b'i', "%Y-%m-%d")]);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// # #[cfg(feature = "subsec")]
+    /// assert_eq!(strftime_with_options(&time, b"%i %f", &mut buf, &options)?, b"1970-01-01 123456");
+    /// // Supported directives are not affected by aliases
+    /// let options = FormatOptions::new().with_aliases(&[(b'Y', "%y")]);
+    /// assert_eq!(strftime_with_options(&time, b"%Y", &mut buf, &options)?, b"1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_aliases(mut self, aliases: &'a [(u8, &'a str)]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Returns the format string of the alias for a conversion specifier, if
+    /// any.
+    pub(crate) fn alias(&self, spec: u8) -> Option<&'a str> {
+        self.aliases
+            .iter()
+            .find(|&&(alias, _)| alias == spec)
+            .map(|&(_, format)| format)
+    }
+
+    /// Returns the options used to format the format string of an alias,
+    /// which don't expand aliases.
+    pub(crate) const fn without_aliases(&self) -> Self {
+        Self {
+            aliases: &[],
+            ..*self
+        }
+    }
+
+    /// Register named formats, which are expanded when formatting `%{name}`
+    /// references.
+    ///
+    /// Each named format maps a name to a format string, like
+    /// `("date", "%Y-%m-%d")`, so that a template like `%{date} %{time}` can
+    /// be composed from separately maintained fragments. The format string of
+    /// a named format is formatted with the same options, without expanding
+    /// named formats, and its directives count towards the [maximum number of
+    /// directives](Self::with_max_directives).
+    ///
+    /// A reference to an unknown name, or without the closing `}`, is copied
+    /// verbatim to the output. If several named formats share the same name,
+    /// the first one is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the date 1970-01-01
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, time_zone: "UTC", ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let named_formats = [("date", "%Y-%m-%d"), ("time", "%H:%M"), ("zone", "%Z")];
+    /// let options = FormatOptions::new().with_named_formats(&named_formats);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// assert_eq!(
+    ///     strftime_with_options(&time, b"%{date} %{time} %{zone}", &mut buf, &options)?,
+    ///     b"1970-01-01 00:00 UTC"
+    /// );
+    /// assert_eq!(strftime_with_options(&time, b"%{week}", &mut buf, &options)?, b"%{week}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_named_formats(mut self, named_formats: &'a [(&'a str, &'a str)]) -> Self {
+        self.named_formats = named_formats;
+        self
+    }
+
+    /// Returns the format string of a named format, if any.
+    pub(crate) fn named_format(&self, name: &[u8]) -> Option<&'a str> {
+        self.named_formats
+            .iter()
+            .find(|&&(named, _)| named.as_bytes() == name)
+            .map(|&(_, format)| format)
+    }
+
+    /// Returns the options used to format the format string of a named
+    /// format, which don't expand named formats.
+    pub(crate) const fn without_named_formats(&self) -> Self {
+        Self {
+            named_formats: &[],
+            ..*self
+        }
+    }
+
+    /// Format the `%Y`, `%m`, `%d`, `%e` and `%j` directives in a
+    /// non-Gregorian calendar.
+    ///
+    /// The date in the calendar is computed from the year, month and day of the
+    /// _time_ implementation. Other directives, including month names and
+    /// combinations like `%F`, are still formatted in the Gregorian calendar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::string::strftime_with_options;
+    /// use strftime::{Calendar, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the date 2024-03-11
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 2024, month: 3, day: 11, day_of_year: 71, ..Default::default() };
+    /// assert_eq!(time.year(), 2024);
+    ///
+    /// let options = FormatOptions::new().with_calendar(Calendar::Buddhist);
+    /// assert_eq!(strftime_with_options(&time, "%Y/%m/%d", &options)?, "2567/03/11");
+    ///
+    /// let options = FormatOptions::new().with_calendar(Calendar::Hijri);
+    /// assert_eq!(strftime_with_options(&time, "%Y/%m/%d", &options)?, "1445/09/01");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "icu")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "icu")))]
+    #[must_use]
+    pub const fn with_calendar(mut self, calendar: Calendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// Returns the calendar used to format the date directives, if any.
+    #[cfg(feature = "icu")]
+    pub(crate) const fn calendar(&self) -> Option<Calendar> {
+        self.calendar
+    }
+
+    /// Wrap the errors which occur when formatting a directive in an
+    /// [`Error::Directive`], which names the directive and its position in the
+    /// format string.
+    ///
+    /// This is disabled by default, since the wrapped error is allocated on the
+    /// heap with an infallible allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{Error, FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_error_context();
+    ///
+    /// let mut buf = [0u8; 8];
+    /// let err = strftime_with_options(&time, b"%Y-%m-%d", &mut buf, &options).unwrap_err();
+    ///
+    /// assert_eq!(err.to_string(), "failed to format directive `%d` at byte 6: failed to write the whole buffer");
+    /// match err {
+    ///     Error::Directive { directive, position, source } => {
+    ///         assert_eq!(directive, "%d");
+    ///         assert_eq!(position, 6);
+    ///         assert!(matches!(*source, Error::WriteZero));
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub const fn with_error_context(mut self) -> Self {
+        self.error_context = true;
+        self
+    }
+
+    /// Wrap an error which occurred when formatting a directive, if enabled.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_variables, clippy::unused_self))]
+    pub(crate) fn error_context(&self, err: Error, directive: &[u8], position: usize) -> Error {
+        #[cfg(feature = "alloc")]
+        if self.error_context && !matches!(err, Error::Directive { .. }) {
+            return Error::Directive {
+                directive: String::from_utf8_lossy(directive).into_owned(),
+                position,
+                source: Box::new(err),
+            };
+        }
+
+        err
+    }
+
+    /// Flush the writer of the [`io`] functions after writing the formatted
+    /// string, so that it reaches the sink of a buffered or line-buffered
+    /// writer.
+    ///
+    /// The writer is flushed even if the formatting fails, after writing the
+    /// partial output. A flush error is returned as an [`Error::IoError`].
+    ///
+    /// [`io`]: crate::io
+    /// [`Error::IoError`]: crate::Error::IoError
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufWriter, Write};
+    ///
+    /// use strftime::io::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_flush();
+    ///
+    /// let mut writer = BufWriter::new(Vec::new());
+    /// strftime_with_options(&time, b"%Y", &mut writer, &options)?;
+    /// assert_eq!(writer.buffer(), b"");
+    /// assert_eq!(writer.get_ref(), b"1970");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[must_use]
+    pub const fn with_flush(mut self) -> Self {
+        self.flush = true;
+        self
+    }
+
+    /// Returns whether the writer of the `io` functions is flushed.
+    #[cfg(feature = "std")]
+    pub(crate) const fn flush(&self) -> bool {
+        self.flush
+    }
+
+    /// Register a transformation which runs on the formatted bytes before they
+    /// are returned by [`bytes::strftime_with_options`].
+    ///
+    /// The transformation is a closure, which can capture its state.
+    ///
+    /// [`bytes::strftime_with_options`]: crate::bytes::strftime_with_options
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::bytes::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, month: 1, day: 1, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let separator = b'/';
+    /// let replace = |buf: &mut Vec<u8>| {
+    ///     for byte in buf.iter_mut().filter(|&&mut byte| byte == b'-') {
+    ///         *byte = separator;
+    ///     }
+    /// };
+    /// let options = FormatOptions::new().with_post_process(&replace);
+    ///
+    /// assert_eq!(strftime_with_options(&time, b"%F", &options)?, b"1970/01/01");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn with_post_process(mut self, post_process: &'a dyn Fn(&mut Vec<u8>)) -> Self {
+        self.post_process = Some(PostProcess(post_process));
+        self
+    }
+
+    /// Register a transformation which runs on the formatted string before it
+    /// is returned by [`string::strftime_with_options`].
+    ///
+    /// The transformation is a closure, which can capture its state.
+    ///
+    /// [`string::strftime_with_options`]: crate::string::strftime_with_options
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::string::strftime_with_options;
+    /// use strftime::{FormatOptions, Time};
+    ///
+    /// // Not shown: create a time implementation with the year 1970
+    /// // let time = ...;
+    /// # include!("mock.rs.in");
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// # let time = MockTime { year: 1970, ..Default::default() };
+    /// assert_eq!(time.year(), 1970);
+    ///
+    /// let options = FormatOptions::new().with_post_process_str(&|s| s.insert(0, '\''));
+    ///
+    /// assert_eq!(strftime_with_options(&time, "%y", &options)?, "'70");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn with_post_process_str(mut self, post_process: &'a dyn Fn(&mut String)) -> Self {
+        self.post_process_str = Some(PostProcess(post_process));
+        self
+    }
+
+    /// Run the registered bytes transformation, if any.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn post_process(&self, buf: &mut Vec<u8>) {
+        if let Some(PostProcess(post_process)) = self.post_process {
+            post_process(buf);
+        }
+    }
+
+    /// Run the registered string transformation, if any.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn post_process_str(&self, buf: &mut String) {
+        if let Some(PostProcess(post_process)) = self.post_process_str {
+            post_process(buf);
+        }
+    }
+}
+
+impl Default for FormatOptions<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Options for customizing the behavior of the `strptime` functions.
+///
+/// The default options are strict: literal text must match the input exactly,
+/// and names must be capitalized like the formatted names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// Whether whitespace and case are ignored.
+    lenient: bool,
+    /// Smallest two-digit year in the 20th century.
+    year_pivot: u8,
+}
+
+impl ParseOptions {
+    /// Construct a new `ParseOptions` with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            lenient: false,
+            year_pivot: 69,
+        }
+    }
+
+    /// Enable the lenient mode, which is forgiving like Ruby's `strptime`:
+    ///
+    /// - Whitespace in the format string, including `%n` and `%t`, matches
+    ///   any run of whitespace in the input, including none.
+    /// - Whitespace in the input is skipped before each directive.
+    /// - Month and weekday names and meridian indicators are matched
+    ///   case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{strptime, strptime_with_options, ParseOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// assert!(strptime("  JUL   8", "%b %e").is_err());
+    ///
+    /// let options = ParseOptions::new().with_lenient();
+    /// let fields = strptime_with_options("  JUL   8", "%b %e", &options)?;
+    /// assert_eq!(fields.month, Some(7));
+    /// assert_eq!(fields.day, Some(8));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Returns whether the lenient mode is enabled.
+    #[must_use]
+    pub const fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Set the pivot assigning a century to the two-digit years of `%y` and
+    /// `%g`, when there is no `%C` directive.
+    ///
+    /// Years greater than or equal to the pivot are in the 20th century
+    /// (`19xx`), and years less than the pivot are in the 21st century
+    /// (`20xx`). A pivot of `0` puts all the years in the 20th century, and a
+    /// pivot of `100` or more puts them all in the 21st century.
+    ///
+    /// The default pivot is `69`, like Ruby and POSIX, so two-digit years are
+    /// in `1969..=2068`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::strptime::{strptime, strptime_with_options, ParseOptions};
+    ///
+    /// # fn main() -> Result<(), strftime::strptime::ParseError> {
+    /// assert_eq!(strptime("50", "%y")?.year, Some(2050));
+    ///
+    /// let options = ParseOptions::new().with_year_pivot(50);
+    /// assert_eq!(strptime_with_options("50", "%y", &options)?.year, Some(1950));
+    /// assert_eq!(strptime_with_options("49", "%y", &options)?.year, Some(2049));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_year_pivot(mut self, year_pivot: u8) -> Self {
+        self.year_pivot = year_pivot;
+        self
+    }
+
+    /// Returns the pivot assigning a century to two-digit years.
+    #[must_use]
+    pub const fn year_pivot(&self) -> u8 {
+        self.year_pivot
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
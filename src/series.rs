@@ -0,0 +1,275 @@
+//! Generation of evenly spaced times, like the labels of a chart axis.
+//!
+//! # Examples
+//!
+//! ```
+//! use strftime::buffered::strftime;
+//! use strftime::series::{Step, TimeSeries};
+//! use strftime::DateTime;
+//!
+//! # fn main() -> Result<(), strftime::Error> {
+//! // 2024-01-31 00:00:00 UTC
+//! let start = DateTime::from_timestamp(1_706_659_200, 0).unwrap();
+//!
+//! let mut buf = [0u8; 32];
+//! let mut series = TimeSeries::new(start, Step::Months(1), 3);
+//! assert_eq!(strftime(&series.next().unwrap(), b"%F", &mut buf)?, b"2024-01-31");
+//! assert_eq!(strftime(&series.next().unwrap(), b"%F", &mut buf)?, b"2024-02-29");
+//! assert_eq!(strftime(&series.next().unwrap(), b"%F", &mut buf)?, b"2024-03-31");
+//! assert!(series.next().is_none());
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::calendar::{days_from_civil, is_leap_year, SECONDS_PER_DAY};
+#[cfg(feature = "alloc")]
+use crate::Error;
+use crate::{DateTime, Time};
+
+/// Interval between two consecutive times of a [`TimeSeries`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    /// Number of seconds.
+    Seconds(u32),
+    /// Number of minutes.
+    Minutes(u32),
+    /// Number of hours.
+    Hours(u32),
+    /// Number of days.
+    Days(u32),
+    /// Number of weeks.
+    Weeks(u32),
+    /// Number of calendar months.
+    ///
+    /// The day of the month is clamped to the last day of shorter months,
+    /// without shifting the following times.
+    Months(u32),
+    /// Number of calendar years.
+    ///
+    /// February 29 is clamped to February 28 in common years.
+    Years(u32),
+}
+
+impl Step {
+    /// Returns the length of the step in seconds, or in months for calendar
+    /// steps.
+    const fn len(self) -> (i64, bool) {
+        match self {
+            Self::Seconds(n) => (n as i64, false),
+            Self::Minutes(n) => (n as i64 * 60, false),
+            Self::Hours(n) => (n as i64 * 3600, false),
+            Self::Days(n) => (n as i64 * SECONDS_PER_DAY, false),
+            Self::Weeks(n) => (n as i64 * 7 * SECONDS_PER_DAY, false),
+            Self::Months(n) => (n as i64, true),
+            Self::Years(n) => (n as i64 * 12, true),
+        }
+    }
+}
+
+/// Returns the number of days in a month.
+const fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// An iterator over evenly spaced times, starting at a [`DateTime`].
+///
+/// Each time is computed from the start time, so that calendar steps don't
+/// drift after a clamped day of the month. Calendar steps are computed in the
+/// local time of the start, with its UTC offset.
+///
+/// The iterator stops after `count` times, or when a time is out of range of
+/// a [`DateTime`].
+#[derive(Debug, Clone)]
+pub struct TimeSeries {
+    /// Start time.
+    start: DateTime,
+    /// Interval between two consecutive times.
+    step: Step,
+    /// Index of the next time.
+    index: usize,
+    /// Number of times.
+    count: usize,
+}
+
+impl TimeSeries {
+    /// Construct a new `TimeSeries` of `count` times, starting at `start` and
+    /// spaced by `step`.
+    #[must_use]
+    pub const fn new(start: DateTime, step: Step, count: usize) -> Self {
+        Self {
+            start,
+            step,
+            index: 0,
+            count,
+        }
+    }
+
+    /// Format the times of the series with the specified UTF-8 format string.
+    ///
+    /// See the [crate-level documentation](crate) for a complete description of
+    /// possible format specifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strftime::series::{Step, TimeSeries};
+    /// use strftime::DateTime;
+    ///
+    /// # fn main() -> Result<(), strftime::Error> {
+    /// let start = DateTime::from_timestamp(1_706_659_200, 0).unwrap();
+    ///
+    /// let labels = TimeSeries::new(start, Step::Hours(6), 4)
+    ///     .labels("%a %H:%M")
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(labels, ["Wed 00:00", "Wed 06:00", "Wed 12:00", "Wed 18:00"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn labels(self, format: &str) -> Labels<'_> {
+        Labels {
+            times: self,
+            format,
+        }
+    }
+
+    /// Returns the time at an index of the series.
+    fn nth_time(&self, index: usize) -> Option<DateTime> {
+        let (len, is_calendar) = self.step.len();
+        let offset = i64::try_from(index).ok()?.checked_mul(len)?;
+
+        if !is_calendar {
+            return self
+                .start
+                .with_timestamp(self.start.to_int().checked_add(offset)?);
+        }
+
+        let start = &self.start;
+        let months = i64::from(start.year()) * 12 + i64::from(start.month()) - 1 + offset;
+        let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u8 + 1);
+        let day = start.day().min(days_in_month(year, month));
+
+        let start_days = days_from_civil(i64::from(start.year()), start.month(), start.day());
+        let days = days_from_civil(year, month, day) - start_days;
+        start.with_timestamp(
+            start
+                .to_int()
+                .checked_add(days.checked_mul(SECONDS_PER_DAY)?)?,
+        )
+    }
+}
+
+impl Iterator for TimeSeries {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let time = self.nth_time(self.index);
+        self.index = if time.is_some() {
+            self.index + 1
+        } else {
+            self.count
+        };
+        time
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count - self.index))
+    }
+}
+
+impl core::iter::FusedIterator for TimeSeries {}
+
+/// An iterator over the formatted times of a [`TimeSeries`], created by
+/// [`TimeSeries::labels`].
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Debug, Clone)]
+pub struct Labels<'f> {
+    /// Times to format.
+    times: TimeSeries,
+    /// Format string.
+    format: &'f str,
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for Labels<'_> {
+    type Item = Result<String, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let time = self.times.next()?;
+        Some(crate::string::strftime(&time, self.format))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.times.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::iter::FusedIterator for Labels<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamps(start: DateTime, step: Step, count: usize) -> impl Iterator<Item = i64> {
+        TimeSeries::new(start, step, count).map(|time| time.to_int())
+    }
+
+    #[test]
+    fn test_time_series() {
+        let start = DateTime::from_timestamp(0, 0).unwrap();
+        assert!(timestamps(start, Step::Seconds(1), 3).eq([0, 1, 2]));
+        assert!(timestamps(start, Step::Minutes(2), 3).eq([0, 120, 240]));
+        assert!(timestamps(start, Step::Hours(1), 2).eq([0, 3600]));
+        assert!(timestamps(start, Step::Days(1), 2).eq([0, 86_400]));
+        assert!(timestamps(start, Step::Weeks(1), 2).eq([0, 604_800]));
+        assert!(timestamps(start, Step::Months(1), 3).eq([0, 2_678_400, 5_097_600]));
+        assert!(timestamps(start, Step::Years(1), 2).eq([0, 31_536_000]));
+        assert_eq!(timestamps(start, Step::Days(1), 0).count(), 0);
+
+        // The day of the month is clamped without drifting
+        let start = DateTime::from_timestamp(951_782_400, 0).unwrap();
+        let dates: [_; 5] = [
+            (2000, 2, 29),
+            (2001, 2, 28),
+            (2002, 2, 28),
+            (2003, 2, 28),
+            (2004, 2, 29),
+        ];
+        for (time, date) in TimeSeries::new(start, Step::Years(1), 5).zip(dates) {
+            assert_eq!((time.year(), time.month(), time.day()), date);
+        }
+
+        // Calendar steps use the local time of the start
+        let start = DateTime::from_timestamp_with_offset(-3600, 0, 3600).unwrap();
+        let time = TimeSeries::new(start, Step::Months(1), 2).nth(1).unwrap();
+        assert_eq!((time.month(), time.day(), time.hour()), (2, 1, 0));
+        assert_eq!(time.utc_offset(), 3600);
+    }
+
+    #[test]
+    fn test_time_series_out_of_range() {
+        let start = DateTime::from_timestamp(0, 0).unwrap();
+        let mut series = TimeSeries::new(start, Step::Years(u32::MAX), 3);
+        assert!(series.next().is_some());
+        assert!(series.next().is_none());
+        assert!(series.next().is_none());
+        assert_eq!(series.size_hint(), (0, Some(0)));
+    }
+}
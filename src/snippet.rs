@@ -0,0 +1,133 @@
+//! A bounded copy of a part of a format string, reported in errors.
+
+use core::fmt;
+
+/// Maximum number of bytes copied in a [`Snippet`].
+const MAX_LEN: usize = 16;
+
+/// Start of the text of a directive in a format string, copied in an
+/// [`Error::UnterminatedDirective`].
+///
+/// At most 16 bytes of the directive are copied, so the snippet does not
+/// allocate and does not borrow the format string.
+///
+/// [`Error::UnterminatedDirective`]: crate::Error::UnterminatedDirective
+///
+/// # Examples
+///
+/// ```
+/// use strftime::buffered::strftime;
+/// use strftime::{DateTime, Error};
+///
+/// let time = DateTime::from_timestamp(0, 0).unwrap();
+///
+/// let mut buf = [0u8; 32];
+/// match strftime(&time, b"%Y %-10", &mut buf) {
+///     Err(Error::UnterminatedDirective { position, snippet, .. }) => {
+///         assert_eq!(position, 3);
+///         assert_eq!(snippet.as_bytes(), b"%-10");
+///         assert!(!snippet.is_truncated());
+///     }
+///     result => panic!("unexpected result: {:?}", result),
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Snippet {
+    /// Copied bytes, of which only the first `len` are used.
+    data: [u8; MAX_LEN],
+    /// Number of copied bytes.
+    len: usize,
+    /// Whether the text was longer than the copied bytes.
+    truncated: bool,
+}
+
+impl Snippet {
+    /// Construct a new `Snippet` from the start of the provided text.
+    pub(crate) fn new(text: &[u8]) -> Self {
+        let len = text.len().min(MAX_LEN);
+
+        let mut data = [0; MAX_LEN];
+        data[..len].copy_from_slice(&text[..len]);
+
+        Self {
+            data,
+            len,
+            truncated: text.len() > MAX_LEN,
+        }
+    }
+
+    /// Returns the copied bytes, which are at most 16 bytes long.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    /// Returns `true` if the text was longer than the copied bytes.
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl fmt::Display for Snippet {
+    /// Writes the copied bytes with the non-printable bytes escaped, followed
+    /// by `...` if the text was truncated.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &byte in self.as_bytes() {
+            for escaped in core::ascii::escape_default(byte) {
+                fmt::Write::write_char(f, char::from(escaped))?;
+            }
+        }
+        if self.truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Snippet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Snippet(\"{self}\")")
+    }
+}
+
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for Snippet {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=[u8]}", self.as_bytes());
+        if self.truncated {
+            defmt::write!(f, "...");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let snippet = Snippet::new(b"%-10");
+        assert_eq!(snippet.as_bytes(), b"%-10");
+        assert!(!snippet.is_truncated());
+
+        let snippet = Snippet::new(b"%0000000000000000000001");
+        assert_eq!(snippet.as_bytes(), b"%000000000000000");
+        assert!(snippet.is_truncated());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_snippet_display() {
+        use alloc::format;
+        use alloc::string::ToString;
+
+        assert_eq!(Snippet::new(b"%-\t").to_string(), "%-\\t");
+        assert_eq!(
+            Snippet::new(b"%00000000000000000").to_string(),
+            "%000000000000000..."
+        );
+        assert_eq!(format!("{:?}", Snippet::new(b"%:")), "Snippet(\"%:\")");
+    }
+}
@@ -0,0 +1,453 @@
+//! Translation between `strftime` format strings and SQL formatting patterns.
+//!
+//! Two SQL dialects are supported:
+//!
+//! - The `%`-codes of MySQL's [`DATE_FORMAT`] function.
+//! - The templates of PostgreSQL's [`to_char`] function.
+//!
+//! Only directives with an exact equivalent are translated. The other
+//! directives, like `%e` which is blank-padded in Ruby but not in MySQL, are
+//! reported as an [`UntranslatableError`].
+//!
+//! [`DATE_FORMAT`]: <https://dev.mysql.com/doc/refman/8.0/en/date-and-time-functions.html#function_date-format>
+//! [`to_char`]: <https://www.postgresql.org/docs/current/functions-formatting.html>
+//!
+//! # Examples
+//!
+//! ```
+//! use strftime::sql::{self, SqlDialect};
+//!
+//! # fn main() -> Result<(), strftime::sql::UntranslatableError> {
+//! assert_eq!(sql::from_strftime("%F %H:%M", SqlDialect::MySql)?, "%Y-%m-%d %H:%i");
+//! assert_eq!(sql::from_strftime("%-d %B at %I %p", SqlDialect::PostgreSql)?, r#"FMDD FMMonth" at "HH12 AM"#);
+//!
+//! assert_eq!(sql::to_strftime("%e/%c/%Y", SqlDialect::MySql)?, "%-d/%-m/%Y");
+//! assert_eq!(sql::to_strftime(r#"YYYY-MM-DD"T"HH24:MI:SS"#, SqlDialect::PostgreSql)?, "%FT%T");
+//!
+//! let err = sql::from_strftime("%Y %s", SqlDialect::MySql).unwrap_err();
+//! assert_eq!(err.directive(), "%s");
+//! assert_eq!(err.position(), 3);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::explain::Item;
+
+/// SQL dialect of a formatting pattern.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SqlDialect {
+    /// `%`-codes of MySQL's `DATE_FORMAT` function.
+    MySql,
+    /// Templates of PostgreSQL's `to_char` function.
+    PostgreSql,
+}
+
+impl SqlDialect {
+    /// Returns the pairs of equivalent SQL patterns and `strftime` directives.
+    ///
+    /// When translating to a `strftime` format string, the first pair with the
+    /// SQL pattern is used.
+    const fn table(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::MySql => MYSQL,
+            Self::PostgreSql => POSTGRESQL,
+        }
+    }
+}
+
+/// Equivalent MySQL `%`-codes and `strftime` directives.
+const MYSQL: &[(&str, &str)] = &[
+    ("%%", "%%"),
+    ("%Y", "%Y"),
+    ("%y", "%y"),
+    ("%m", "%m"),
+    ("%c", "%-m"),
+    ("%d", "%d"),
+    ("%e", "%-d"),
+    ("%e", "%-e"),
+    ("%H", "%H"),
+    ("%k", "%-H"),
+    ("%k", "%-k"),
+    ("%I", "%I"),
+    ("%h", "%I"),
+    ("%l", "%-I"),
+    ("%l", "%-l"),
+    ("%i", "%M"),
+    ("%S", "%S"),
+    ("%s", "%S"),
+    ("%f", "%6N"),
+    ("%p", "%p"),
+    ("%W", "%A"),
+    ("%a", "%a"),
+    ("%M", "%B"),
+    ("%b", "%b"),
+    ("%b", "%h"),
+    ("%j", "%j"),
+    ("%U", "%U"),
+    ("%u", "%W"),
+    ("%v", "%V"),
+    ("%x", "%G"),
+    ("%w", "%w"),
+    ("%T", "%T"),
+    ("%T", "%X"),
+    ("%r", "%r"),
+    ("%Y-%m-%d", "%F"),
+    ("%m/%d/%y", "%D"),
+    ("%m/%d/%y", "%x"),
+    ("%H:%i", "%R"),
+    ("\n", "%n"),
+    ("\t", "%t"),
+];
+
+/// Equivalent PostgreSQL templates and `strftime` directives.
+const POSTGRESQL: &[(&str, &str)] = &[
+    ("%", "%%"),
+    ("YYYY-MM-DD", "%F"),
+    ("HH24:MI:SS", "%T"),
+    ("HH24:MI:SS", "%X"),
+    ("HH24:MI", "%R"),
+    ("MM/DD/YY", "%D"),
+    ("MM/DD/YY", "%x"),
+    ("YYYY", "%Y"),
+    ("YY", "%y"),
+    ("MM", "%m"),
+    ("FMMM", "%-m"),
+    ("DD", "%d"),
+    ("FMDD", "%-d"),
+    ("FMDD", "%-e"),
+    ("HH24", "%H"),
+    ("FMHH24", "%-H"),
+    ("FMHH24", "%-k"),
+    ("HH12", "%I"),
+    ("HH", "%I"),
+    ("FMHH12", "%-I"),
+    ("FMHH", "%-I"),
+    ("FMHH12", "%-l"),
+    ("MI", "%M"),
+    ("SS", "%S"),
+    ("MS", "%L"),
+    ("MS", "%3N"),
+    ("US", "%6N"),
+    ("AM", "%p"),
+    ("PM", "%p"),
+    ("am", "%P"),
+    ("pm", "%P"),
+    ("FMDay", "%A"),
+    ("FMDAY", "%^A"),
+    ("Dy", "%a"),
+    ("DY", "%^a"),
+    ("FMMonth", "%B"),
+    ("FMMONTH", "%^B"),
+    ("Mon", "%b"),
+    ("Mon", "%h"),
+    ("MON", "%^b"),
+    ("DDD", "%j"),
+    ("FMDDD", "%-j"),
+    ("IW", "%V"),
+    ("IYYY", "%G"),
+    ("ID", "%u"),
+    ("TZ", "%Z"),
+    ("\n", "%n"),
+    ("\t", "%t"),
+];
+
+/// Error returned when a directive or pattern has no equivalent in the
+/// target format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntranslatableError {
+    /// Text of the directive or pattern.
+    directive: String,
+    /// Byte offset of the directive or pattern in the input.
+    position: usize,
+}
+
+impl UntranslatableError {
+    /// Construct a new `UntranslatableError`.
+    fn new(directive: &str, position: usize) -> Self {
+        Self {
+            directive: directive.into(),
+            position,
+        }
+    }
+
+    /// Returns the text of the untranslatable directive or pattern.
+    #[must_use]
+    pub fn directive(&self) -> &str {
+        &self.directive
+    }
+
+    /// Returns the byte offset of the untranslatable directive or pattern in
+    /// the input.
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for UntranslatableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "untranslatable directive `{}` at byte {}",
+            self.directive, self.position
+        )
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "core-error"))))]
+impl crate::error::Error for UntranslatableError {}
+
+/// Translate a `strftime` format string to a SQL formatting pattern.
+///
+/// # Errors
+///
+/// Returns an [`UntranslatableError`] for the first directive which has no
+/// equivalent in the SQL dialect, or for an unterminated directive at the end
+/// of the format string.
+pub fn from_strftime(format: &str, dialect: SqlDialect) -> Result<String, UntranslatableError> {
+    let mut pattern = String::new();
+    let mut position = 0;
+
+    for item in crate::explain(format) {
+        let text = match item {
+            Ok(Item::Literal(text)) => {
+                // Literals are made of complete characters of the format string
+                let text = &format[position..position + text.len()];
+                write_sql_literal(&mut pattern, text, dialect);
+                text
+            }
+            Ok(Item::Directive(directive)) => {
+                let text = &format[position..position + directive.text().len()];
+                let translated = dialect
+                    .table()
+                    .iter()
+                    .find(|&&(_, ruby)| ruby == text)
+                    .map(|&(sql, _)| sql)
+                    .ok_or_else(|| UntranslatableError::new(text, position))?;
+                pattern.push_str(translated);
+                text
+            }
+            Err(_) => return Err(UntranslatableError::new(&format[position..], position)),
+        };
+        position += text.len();
+    }
+
+    Ok(pattern)
+}
+
+/// Write literal text in a SQL formatting pattern.
+fn write_sql_literal(pattern: &mut String, text: &str, dialect: SqlDialect) {
+    match dialect {
+        SqlDialect::MySql => {
+            for ch in text.chars() {
+                if ch == '%' {
+                    pattern.push('%');
+                }
+                pattern.push(ch);
+            }
+        }
+        SqlDialect::PostgreSql => {
+            // Letters and digits could be parsed as template patterns
+            if text.contains(|ch: char| ch.is_ascii_alphanumeric() || ch == '"' || ch == '\\') {
+                pattern.push('"');
+                for ch in text.chars() {
+                    if ch == '"' || ch == '\\' {
+                        pattern.push('\\');
+                    }
+                    pattern.push(ch);
+                }
+                pattern.push('"');
+            } else {
+                pattern.push_str(text);
+            }
+        }
+    }
+}
+
+/// Translate a SQL formatting pattern to a `strftime` format string.
+///
+/// With [`SqlDialect::MySql`], a `%` followed by a character which is not a
+/// letter is the literal character, like in MySQL.
+///
+/// With [`SqlDialect::PostgreSql`], the text between double quotes and the
+/// characters which are not ASCII letters are literal text.
+///
+/// # Errors
+///
+/// Returns an [`UntranslatableError`] for the first pattern which has no
+/// equivalent `strftime` directive, or which is not supported.
+pub fn to_strftime(pattern: &str, dialect: SqlDialect) -> Result<String, UntranslatableError> {
+    let mut format = String::new();
+    let mut position = 0;
+
+    while let Some(ch) = pattern[position..].chars().next() {
+        let remaining = &pattern[position..];
+
+        // Find the longest SQL pattern at the current position
+        let found = dialect
+            .table()
+            .iter()
+            .filter(|&&(sql, _)| remaining.starts_with(sql))
+            .fold(
+                None,
+                |longest: Option<(&str, &str)>, &(sql, ruby)| match longest {
+                    Some((longest_sql, _)) if longest_sql.len() >= sql.len() => longest,
+                    _ => Some((sql, ruby)),
+                },
+            );
+
+        if let Some((sql, ruby)) = found {
+            format.push_str(ruby);
+            position += sql.len();
+            continue;
+        }
+
+        let len = match (dialect, ch) {
+            (SqlDialect::MySql, '%') => match remaining[1..].chars().next() {
+                Some(next) if next.is_ascii_alphabetic() => {
+                    return Err(UntranslatableError::new(&remaining[..2], position));
+                }
+                Some(next) => {
+                    write_ruby_literal(&mut format, next);
+                    1 + next.len_utf8()
+                }
+                None => {
+                    write_ruby_literal(&mut format, '%');
+                    1
+                }
+            },
+            (SqlDialect::PostgreSql, '"') => {
+                let mut chars = remaining[1..].char_indices();
+                let mut end = None;
+                while let Some((index, ch)) = chars.next() {
+                    match ch {
+                        '"' => {
+                            end = Some(index + 2);
+                            break;
+                        }
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                write_ruby_literal(&mut format, escaped);
+                            }
+                        }
+                        _ => write_ruby_literal(&mut format, ch),
+                    }
+                }
+                end.ok_or_else(|| UntranslatableError::new(remaining, position))?
+            }
+            (SqlDialect::PostgreSql, _) if ch.is_ascii_alphabetic() => {
+                let len = remaining
+                    .find(|ch: char| !ch.is_ascii_alphabetic())
+                    .unwrap_or(remaining.len());
+                return Err(UntranslatableError::new(&remaining[..len], position));
+            }
+            _ => {
+                write_ruby_literal(&mut format, ch);
+                ch.len_utf8()
+            }
+        };
+        position += len;
+    }
+
+    Ok(format)
+}
+
+/// Write a literal character in a `strftime` format string.
+fn write_ruby_literal(format: &mut String, ch: char) {
+    if ch == '%' {
+        format.push('%');
+    }
+    format.push(ch);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_from_strftime_mysql() {
+        let translate = |format| from_strftime(format, SqlDialect::MySql);
+
+        assert_eq!(translate("%Y-%m-%d").unwrap(), "%Y-%m-%d");
+        assert_eq!(
+            translate("%-d %B %Y, %-l:%M %p").unwrap(),
+            "%e %M %Y, %l:%i %p"
+        );
+        #[cfg(feature = "subsec")]
+        assert_eq!(translate("%F %T.%6N").unwrap(), "%Y-%m-%d %T.%f");
+        assert_eq!(translate("100%% %q").unwrap(), "100%% %%q");
+        assert_eq!(translate("").unwrap(), "");
+
+        let err = translate("%d %e").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("%e", 3));
+        assert_eq!(err.to_string(), "untranslatable directive `%e` at byte 3");
+
+        let err = translate("é%05Y").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("%05Y", 2));
+
+        let err = translate("%Y %").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("%", 3));
+    }
+
+    #[test]
+    fn test_from_strftime_postgresql() {
+        let translate = |format| from_strftime(format, SqlDialect::PostgreSql);
+
+        assert_eq!(translate("%F %T").unwrap(), "YYYY-MM-DD HH24:MI:SS");
+        assert_eq!(translate("%a, %d %b %Y").unwrap(), "Dy, DD Mon YYYY");
+        #[cfg(feature = "subsec")]
+        assert_eq!(
+            translate("%FT%T.%L%%").unwrap(),
+            r#"YYYY-MM-DD"T"HH24:MI:SS.MS%"#
+        );
+        assert_eq!(translate(r#"%^A "q\"#).unwrap(), r#"FMDAY" \"q\\""#);
+
+        let err = translate("%Y %s").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("%s", 3));
+    }
+
+    #[test]
+    fn test_to_strftime_mysql() {
+        let translate = |pattern| to_strftime(pattern, SqlDialect::MySql);
+
+        assert_eq!(translate("%Y-%m-%d %H:%i:%s").unwrap(), "%F %R:%S");
+        assert_eq!(translate("%W %M %e, %l %p").unwrap(), "%A %B %-d, %-I %p");
+        assert_eq!(translate("%% %- 100%").unwrap(), "%% - 100%%");
+        assert_eq!(translate("%x-W%v").unwrap(), "%G-W%V");
+
+        let err = translate("%Y %D").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("%D", 3));
+    }
+
+    #[test]
+    fn test_to_strftime_postgresql() {
+        let translate = |pattern| to_strftime(pattern, SqlDialect::PostgreSql);
+
+        assert_eq!(translate("YYYY-MM-DD HH24:MI:SS").unwrap(), "%F %T");
+        assert_eq!(
+            translate("FMDay, FMDD Mon YYYY HH12:MI am").unwrap(),
+            "%A, %-d %b %Y %I:%M %P"
+        );
+        assert_eq!(
+            translate(r#"IYYY-"W"IW-ID "a\"b" 100%"#).unwrap(),
+            r#"%G-W%V-%u a"b 100%%"#
+        );
+        assert_eq!(translate("SS.US").unwrap(), "%S.%6N");
+
+        let err = translate("YYYY Day").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("Day", 5));
+
+        let err = translate("YYYY FMYYYY").unwrap_err();
+        assert_eq!(err, UntranslatableError::new("FMYYYY", 5));
+
+        let err = translate(r#"YYYY "abc"#).unwrap_err();
+        assert_eq!(err, UntranslatableError::new(r#""abc"#, 5));
+    }
+}
@@ -0,0 +1,239 @@
+//! Statistics collected when formatting a time.
+
+use crate::format::SizeLimiter;
+
+/// Statistics of a call to a `strftime` function, filled when they are
+/// requested with [`FormatOptions::with_stats`].
+///
+/// The statistics are collected even if the formatting fails, in which case
+/// they describe the work done before the error.
+///
+/// [`FormatOptions::with_stats`]: crate::FormatOptions::with_stats
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::Cell;
+/// use strftime::buffered::strftime_with_options;
+/// use strftime::{DateTime, FormatOptions, FormatStats};
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = DateTime::from_timestamp(0, 0).unwrap();
+///
+/// let stats = Cell::new(FormatStats::new());
+/// let options = FormatOptions::new().with_stats(&stats);
+///
+/// let mut buf = [0u8; 32];
+/// assert_eq!(strftime_with_options(&time, b"Date: %F", &mut buf, &options)?, b"Date: 1970-01-01");
+///
+/// let stats = stats.get();
+/// assert_eq!(stats.directives(), 1);
+/// assert_eq!(stats.literal_bytes(), 6);
+/// assert_eq!(stats.bytes_written(), 16);
+/// assert!(!stats.limit_approached());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatStats {
+    /// Number of formatted directives.
+    directives: usize,
+    /// Number of unknown directives copied verbatim.
+    unknown_directives: usize,
+    /// Number of bytes copied from the format string.
+    literal_bytes: usize,
+    /// Number of bytes written.
+    bytes_written: usize,
+    /// Maximum size of the formatted string.
+    size_limit: usize,
+    /// Size the formatted string would have reached with the write which
+    /// exceeded the size limit.
+    attempted_size: Option<usize>,
+    /// Byte offset of the unterminated directive at the end of the format
+    /// string.
+    unterminated_directive: Option<usize>,
+    /// Whether a limit was approached.
+    limit_approached: bool,
+}
+
+impl FormatStats {
+    /// Construct a new empty `FormatStats`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            directives: 0,
+            unknown_directives: 0,
+            literal_bytes: 0,
+            bytes_written: 0,
+            size_limit: 0,
+            attempted_size: None,
+            unterminated_directive: None,
+            limit_approached: false,
+        }
+    }
+
+    /// Returns the number of formatted directives, including the directives
+    /// of expanded aliases.
+    #[must_use]
+    pub const fn directives(&self) -> usize {
+        self.directives
+    }
+
+    /// Returns the number of unknown directives, like `%Q`, copied verbatim to
+    /// the output.
+    #[must_use]
+    pub const fn unknown_directives(&self) -> usize {
+        self.unknown_directives
+    }
+
+    /// Returns the number of bytes copied verbatim from the format string,
+    /// including unknown directives.
+    #[must_use]
+    pub const fn literal_bytes(&self) -> usize {
+        self.literal_bytes
+    }
+
+    /// Returns the number of bytes written to the output.
+    #[must_use]
+    pub const fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
+    /// Returns the maximum size in bytes of the formatted string, which is
+    /// derived from the length of the format string.
+    #[must_use]
+    pub const fn size_limit(&self) -> usize {
+        self.size_limit
+    }
+
+    /// Returns the size in bytes the formatted string would have reached with
+    /// the write which exceeded the size limit, if the formatting failed with
+    /// [`Error::FormattedStringTooLarge`].
+    ///
+    /// [`Error::FormattedStringTooLarge`]: crate::Error::FormattedStringTooLarge
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::Cell;
+    /// use strftime::{DateTime, Error, FormatOptions, FormatStats};
+    ///
+    /// let time = DateTime::from_timestamp(0, 0).unwrap();
+    ///
+    /// let stats = Cell::new(FormatStats::new());
+    /// let options = FormatOptions::new().with_stats(&stats);
+    ///
+    /// let mut sink = strftime::CountingWriter::new();
+    /// let result = strftime::fmt::strftime_with_options(&time, "%5000000Y", &mut sink, &options);
+    /// assert!(matches!(result, Err(Error::FormattedStringTooLarge { .. })));
+    ///
+    /// let stats = stats.get();
+    /// assert_eq!(stats.size_limit(), 9 * 512 * 1024);
+    /// assert!(stats.attempted_size() > Some(stats.size_limit()));
+    /// ```
+    #[must_use]
+    pub const fn attempted_size(&self) -> Option<usize> {
+        self.attempted_size
+    }
+
+    /// Returns the byte offset of the unterminated directive at the end of the
+    /// format string, if the formatting failed with
+    /// [`Error::UnterminatedDirective`].
+    ///
+    /// This is the position reported by the error. It is relative to the
+    /// format string of the alias or the named format which has the
+    /// unterminated directive, if any.
+    ///
+    /// [`Error::UnterminatedDirective`]: crate::Error::UnterminatedDirective
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::Cell;
+    /// use strftime::buffered::strftime_with_options;
+    /// use strftime::{DateTime, Error, FormatOptions, FormatStats};
+    ///
+    /// let time = DateTime::from_timestamp(0, 0).unwrap();
+    ///
+    /// let stats = Cell::new(FormatStats::new());
+    /// let options = FormatOptions::new().with_stats(&stats);
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let result = strftime_with_options(&time, b"%Y %-1", &mut buf, &options);
+    /// assert!(matches!(result, Err(Error::UnterminatedDirective { position: 3, .. })));
+    /// assert_eq!(stats.get().unterminated_directive(), Some(3));
+    /// ```
+    #[must_use]
+    pub const fn unterminated_directive(&self) -> Option<usize> {
+        self.unterminated_directive
+    }
+
+    /// Returns `true` if the number of directives, including the unknown
+    /// directives, or the size of the formatted string reached at least 75% of
+    /// its limit.
+    ///
+    /// The limits are the maximum number of directives set with
+    /// [`FormatOptions::with_max_directives`] and the maximum size of the
+    /// formatted string, which is derived from the length of the format
+    /// string.
+    ///
+    /// [`FormatOptions::with_max_directives`]: crate::FormatOptions::with_max_directives
+    #[must_use]
+    pub const fn limit_approached(&self) -> bool {
+        self.limit_approached
+    }
+
+    /// Record a formatted directive, returning the number of directives
+    /// including the unknown directives.
+    pub(crate) fn add_directive(&mut self) -> usize {
+        self.directives += 1;
+        self.directives + self.unknown_directives
+    }
+
+    /// Record an unknown directive, returning the number of directives
+    /// including the unknown directives.
+    pub(crate) fn add_unknown_directive(&mut self) -> usize {
+        self.unknown_directives += 1;
+        self.directives + self.unknown_directives
+    }
+
+    /// Record the byte offset of an unterminated directive.
+    pub(crate) fn set_unterminated_directive(&mut self, position: usize) {
+        self.unterminated_directive = Some(position);
+    }
+
+    /// Record bytes copied from the format string.
+    pub(crate) fn add_literal_bytes(&mut self, count: usize) {
+        self.literal_bytes += count;
+    }
+
+    /// Record the number of written bytes and check the limits.
+    pub(crate) fn finish(&mut self, f: &SizeLimiter<'_>, max_directives: usize) {
+        self.bytes_written = f.count();
+        self.size_limit = f.size_limit();
+        self.attempted_size = f.attempted_size();
+        self.limit_approached = approaches(self.bytes_written, self.size_limit)
+            || approaches(self.directives + self.unknown_directives, max_directives);
+    }
+}
+
+/// Check if a non-zero value reached at least 75% of a limit.
+const fn approaches(value: usize, limit: usize) -> bool {
+    value > 0 && value >= limit - limit / 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approaches() {
+        assert!(!approaches(0, 100));
+        assert!(!approaches(74, 100));
+        assert!(approaches(75, 100));
+        assert!(approaches(101, 100));
+        assert!(!approaches(0, 0));
+        assert!(approaches(1, 0));
+        assert!(approaches(usize::MAX, usize::MAX));
+    }
+}
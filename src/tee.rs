@@ -0,0 +1,142 @@
+//! A writer duplicating its output into two sinks.
+
+use core::fmt;
+
+/// A writer duplicating the formatted output into two sinks, so a time is
+/// formatted once for both of them.
+///
+/// `Tee` implements [`core::fmt::Write`] for sinks implementing
+/// [`core::fmt::Write`], and [`std::io::Write`] for sinks implementing
+/// [`std::io::Write`] when the `std` feature is enabled. More than two sinks
+/// can be combined by nesting `Tee`s.
+///
+/// Each write is completely written to the first sink, then to the second
+/// sink. If the first sink fails, nothing is written to the second sink.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::fmt::strftime;
+/// use strftime::{DateTime, Tee};
+///
+/// # fn main() -> Result<(), strftime::Error> {
+/// let time = DateTime::from_timestamp(0, 0).unwrap();
+///
+/// let (mut log, mut ring, mut metrics) = (String::new(), String::new(), String::new());
+/// let mut tee = Tee::new(&mut log, Tee::new(&mut ring, &mut metrics));
+/// strftime(&time, "%F %T", &mut tee)?;
+///
+/// assert_eq!(log, "1970-01-01 00:00:00");
+/// assert_eq!(ring, "1970-01-01 00:00:00");
+/// assert_eq!(metrics, "1970-01-01 00:00:00");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tee<A, B> {
+    /// First sink.
+    first: A,
+    /// Second sink.
+    second: B,
+}
+
+impl<A, B> Tee<A, B> {
+    /// Construct a new `Tee` writing to both sinks.
+    #[must_use]
+    pub const fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns references to the sinks.
+    #[must_use]
+    pub const fn get_ref(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Unwraps this `Tee`, returning the sinks.
+    #[must_use]
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: fmt::Write, B: fmt::Write> fmt::Write for Tee<A, B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.first.write_str(s)?;
+        self.second.write_str(s)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<A: std::io::Write, B: std::io::Write> std::io::Write for Tee<A, B> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Write all the data to both sinks, so they stay in sync
+        self.first.write_all(buf)?;
+        self.second.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.first.flush()?;
+        self.second.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `core::fmt::Write` sink counting the written bytes, failing after a
+    /// limit.
+    struct Counter {
+        count: usize,
+        limit: usize,
+    }
+
+    impl fmt::Write for Counter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if self.count + s.len() > self.limit {
+                return Err(fmt::Error);
+            }
+            self.count += s.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tee_fmt() {
+        use fmt::Write;
+
+        let first = Counter { count: 0, limit: 8 };
+        let second = Counter { count: 0, limit: 4 };
+        let mut tee = Tee::new(first, second);
+
+        tee.write_str("abc").unwrap();
+        assert!(tee.write_str("de").is_err());
+        assert!(tee.write_str("fghi").is_err());
+
+        let (first, second) = tee.into_inner();
+        assert_eq!(first.count, 5);
+        assert_eq!(second.count, 3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_tee_io() {
+        use std::io::Write;
+        use std::vec::Vec;
+
+        let time = crate::DateTime::from_timestamp(0, 0).unwrap();
+
+        let mut third = Vec::new();
+        let mut tee = Tee::new(Vec::new(), Tee::new(Vec::new(), &mut third));
+        crate::io::strftime(&time, b"%F", &mut tee).unwrap();
+        tee.flush().unwrap();
+
+        let (first, second) = tee.into_inner();
+        assert_eq!(first, b"1970-01-01");
+        assert_eq!(second.get_ref().0, b"1970-01-01");
+        assert_eq!(third, b"1970-01-01");
+    }
+}
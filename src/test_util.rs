@@ -0,0 +1,410 @@
+//! Utilities for testing [`Time`] implementations and format strings.
+//!
+//! This module is enabled by the **test-util** feature.
+
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{buffered, bytes, fmt, io, string, utf16, Time};
+
+/// A [`Time`] implementation with fixed values, for use in tests and examples.
+///
+/// The associated constants provide canonical values covering common edge
+/// cases. Individual fields can be overridden with the struct update syntax.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::assert_format_eq;
+/// use strftime::test_util::FixedTime;
+///
+/// assert_format_eq!(FixedTime::UNIX_EPOCH, "%c %Z", "Thu Jan  1 00:00:00 1970 UTC");
+///
+/// let time = FixedTime { nanoseconds: 5_000_000, ..FixedTime::UNIX_EPOCH };
+/// assert_format_eq!(time, "%T.%L", "00:00:00.005");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedTime {
+    /// Year.
+    pub year: i32,
+    /// Month of the year in `1..=12`.
+    pub month: u8,
+    /// Day of the month in `1..=31`.
+    pub day: u8,
+    /// Hour of the day in `0..=23`.
+    pub hour: u8,
+    /// Minute of the hour in `0..=59`.
+    pub minute: u8,
+    /// Second of the minute in `0..=60`.
+    pub second: u8,
+    /// Number of nanoseconds in `0..=999_999_999`.
+    pub nanoseconds: u32,
+    /// Day of the week from Sunday in `0..=6`.
+    pub day_of_week: u8,
+    /// Day of the year in `1..=366`.
+    pub day_of_year: u16,
+    /// Number of seconds since `1970-01-01 00:00:00 UTC`.
+    pub to_int: i64,
+    /// Whether the time zone is UTC.
+    pub is_utc: bool,
+    /// Offset in seconds from UTC.
+    pub utc_offset: i32,
+    /// Name of the time zone.
+    pub time_zone: &'static str,
+}
+
+impl FixedTime {
+    /// `1970-01-01 00:00:00 UTC`, a Thursday.
+    pub const UNIX_EPOCH: Self = Self {
+        year: 1970,
+        month: 1,
+        day: 1,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanoseconds: 0,
+        day_of_week: 4,
+        day_of_year: 1,
+        to_int: 0,
+        is_utc: true,
+        utc_offset: 0,
+        time_zone: "UTC",
+    };
+
+    /// `2024-02-29 13:14:15.123456789 +0100 CET`, a leap day with all the time
+    /// fields set and a non-UTC offset.
+    pub const LEAP_DAY: Self = Self {
+        year: 2024,
+        month: 2,
+        day: 29,
+        hour: 13,
+        minute: 14,
+        second: 15,
+        nanoseconds: 123_456_789,
+        day_of_week: 4,
+        day_of_year: 60,
+        to_int: 1_709_208_855,
+        is_utc: false,
+        utc_offset: 3600,
+        time_zone: "CET",
+    };
+
+    /// `2008-12-29 00:00:00 UTC`, a Monday whose ISO 8601 week-based year is
+    /// `2009` (week `2009-W01`).
+    pub const ISO_WEEK_YEAR_BOUNDARY: Self = Self {
+        year: 2008,
+        month: 12,
+        day: 29,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanoseconds: 0,
+        day_of_week: 1,
+        day_of_year: 364,
+        to_int: 1_230_508_800,
+        is_utc: true,
+        utc_offset: 0,
+        time_zone: "UTC",
+    };
+
+    /// `-0044-03-15 12:00:00 UTC` in the proleptic Gregorian calendar, a time
+    /// with a negative year.
+    pub const NEGATIVE_YEAR: Self = Self {
+        year: -44,
+        month: 3,
+        day: 15,
+        hour: 12,
+        minute: 0,
+        second: 0,
+        nanoseconds: 0,
+        day_of_week: 4,
+        day_of_year: 75,
+        to_int: -63_549_316_800,
+        is_utc: true,
+        utc_offset: 0,
+        time_zone: "UTC",
+    };
+}
+
+impl Time for FixedTime {
+    fn year(&self) -> i32 {
+        self.year
+    }
+
+    fn month(&self) -> u8 {
+        self.month
+    }
+
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    fn second(&self) -> u8 {
+        self.second
+    }
+
+    fn nanoseconds(&self) -> u32 {
+        self.nanoseconds
+    }
+
+    fn day_of_week(&self) -> u8 {
+        self.day_of_week
+    }
+
+    fn day_of_year(&self) -> u16 {
+        self.day_of_year
+    }
+
+    fn to_int(&self) -> i64 {
+        self.to_int
+    }
+
+    fn is_utc(&self) -> bool {
+        self.is_utc
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    fn time_zone(&self) -> &str {
+        self.time_zone
+    }
+}
+
+/// Assert that formatting a _time_ implementation with the specified UTF-8
+/// format string produces the expected string, with every sink in this crate.
+///
+/// The formatted string is produced by the [`buffered`], [`fmt`], [`bytes`],
+/// [`string`], [`io`] and [`utf16`] implementations of `strftime`. On
+/// mismatch, the panic message names the failing sink and shows the expected
+/// and actual strings along with the offset of the first difference.
+///
+/// [`buffered`]: crate::buffered
+/// [`fmt`]: crate::fmt
+/// [`bytes`]: crate::bytes
+/// [`string`]: crate::string
+/// [`io`]: crate::io
+/// [`utf16`]: crate::utf16
+///
+/// # Examples
+///
+/// ```
+/// use strftime::assert_format_eq;
+/// use strftime::test_util::FixedTime;
+///
+/// assert_format_eq!(FixedTime::UNIX_EPOCH, "%F %T", "1970-01-01 00:00:00");
+/// ```
+///
+/// # Panics
+///
+/// Panics if the formatting fails or if any sink produces a string different
+/// from the expected one.
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+macro_rules! assert_format_eq {
+    ($time:expr, $format:expr, $expected:expr $(,)?) => {
+        $crate::test_util::assert_format_eq(&$time, $format, $expected)
+    };
+}
+
+/// Implementation of the [`assert_format_eq!`] macro.
+///
+/// [`assert_format_eq!`]: crate::assert_format_eq
+///
+/// # Panics
+///
+/// Panics if the formatting fails or if any sink produces a string different
+/// from the expected one.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_format_eq(time: &(impl Time + ?Sized), format: &str, expected: &str) {
+    let bytes = check(format, "bytes", bytes::strftime(time, format.as_bytes()));
+    check_eq(format, "bytes", expected, &String::from_utf8_lossy(&bytes));
+
+    let actual = check(format, "string", string::strftime(time, format));
+    check_eq(format, "string", expected, &actual);
+
+    let mut actual = String::new();
+    check(format, "fmt", fmt::strftime(time, format, &mut actual));
+    check_eq(format, "fmt", expected, &actual);
+
+    let mut actual = Vec::new();
+    check(
+        format,
+        "io",
+        io::strftime(time, format.as_bytes(), &mut actual),
+    );
+    check_eq(format, "io", expected, &String::from_utf8_lossy(&actual));
+
+    let mut buf = std::vec![0; bytes.len()];
+    let actual = check(
+        format,
+        "buffered",
+        buffered::strftime(time, format.as_bytes(), &mut buf),
+    );
+    check_eq(
+        format,
+        "buffered",
+        expected,
+        &String::from_utf8_lossy(actual),
+    );
+
+    let actual = check(format, "utf16", utf16::strftime_to_vec(time, format));
+    check_eq(
+        format,
+        "utf16",
+        expected,
+        &String::from_utf16_lossy(&actual),
+    );
+}
+
+/// Compute the week number of `%U` or `%W`, exposed for the fuzz targets.
+///
+/// The day of the week is in `0..=6` from Sunday, and the day of the year is
+/// in `1..=366`. The week starts on Monday if `monday_start` is `true`, and on
+/// Sunday otherwise.
+#[doc(hidden)]
+#[must_use]
+pub fn week_number(week_day: i64, year_day_1: i64, monday_start: bool) -> i64 {
+    use crate::format::week::{self, WeekStart};
+
+    let week_start = if monday_start {
+        WeekStart::Monday
+    } else {
+        WeekStart::Sunday
+    };
+    week::week_number(week_day, year_day_1, week_start)
+}
+
+/// Compute the ISO 8601 week-based year and week number of `%G` and `%V`,
+/// exposed for the fuzz targets.
+///
+/// The day of the week is in `0..=6` from Sunday, and the day of the year is
+/// in `1..=366`.
+#[doc(hidden)]
+#[must_use]
+pub fn iso_8601_year_and_week_number(year: i64, week_day: i64, year_day_1: i64) -> (i64, i64) {
+    crate::format::week::iso_8601_year_and_week_number(year, week_day, year_day_1)
+}
+
+/// Unwrap the formatting result of a sink, panicking with a readable message
+/// on error.
+#[track_caller]
+fn check<T>(format: &str, sink: &str, result: Result<T, crate::Error>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => panic!("formatting {format:?} with the `{sink}` sink failed: {err}"),
+    }
+}
+
+/// Compare the formatted string of a sink with the expected string, panicking
+/// with a readable diff on mismatch.
+#[track_caller]
+fn check_eq(format: &str, sink: &str, expected: &str, actual: &str) {
+    if expected == actual {
+        return;
+    }
+
+    let offset = expected
+        .bytes()
+        .zip(actual.bytes())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+
+    panic!(
+        "formatting {format:?} with the `{sink}` sink produced an unexpected string\n \
+         expected: {expected:?}\n   \
+         actual: {actual:?}\n\
+         first difference at byte offset {offset}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::FixedTime;
+    use crate::{DateTime, Time};
+
+    include!("mock.rs.in");
+
+    #[test]
+    fn test_fixed_time_constants() {
+        let constants = [
+            FixedTime::UNIX_EPOCH,
+            FixedTime::LEAP_DAY,
+            FixedTime::ISO_WEEK_YEAR_BOUNDARY,
+            FixedTime::NEGATIVE_YEAR,
+        ];
+
+        for time in constants {
+            let expected = if time.is_utc {
+                DateTime::from_timestamp(time.to_int, time.nanoseconds)
+            } else {
+                DateTime::from_timestamp_with_offset(time.to_int, time.nanoseconds, time.utc_offset)
+            }
+            .unwrap();
+
+            assert_eq!(time.year(), expected.year());
+            assert_eq!(time.month(), expected.month());
+            assert_eq!(time.day(), expected.day());
+            assert_eq!(time.hour(), expected.hour());
+            assert_eq!(time.minute(), expected.minute());
+            assert_eq!(time.second(), expected.second());
+            assert_eq!(time.day_of_week(), expected.day_of_week());
+            assert_eq!(time.day_of_year(), expected.day_of_year());
+        }
+
+        crate::assert_format_eq!(
+            FixedTime::LEAP_DAY,
+            "%F %T.%N %z %Z",
+            "2024-02-29 13:14:15.123456789 +0100 CET"
+        );
+        crate::assert_format_eq!(FixedTime::ISO_WEEK_YEAR_BOUNDARY, "%G-W%V-%u", "2009-W01-1");
+        crate::assert_format_eq!(FixedTime::NEGATIVE_YEAR, "%Y-%m-%d %a", "-0044-03-15 Thu");
+    }
+
+    #[test]
+    fn test_assert_format_eq() {
+        let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 0, "UTC");
+
+        crate::assert_format_eq!(time, "%F %T %Z", "1970-01-01 00:00:00 UTC");
+        crate::assert_format_eq!(time, "\u{1f600} %-d", "\u{1f600} 1",);
+    }
+
+    #[test]
+    fn test_assert_format_eq_mismatch() {
+        let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 0, "UTC");
+
+        let err = std::panic::catch_unwind(|| {
+            crate::assert_format_eq!(time, "%F", "1970-01-02");
+        })
+        .unwrap_err();
+
+        let message = err.downcast_ref::<std::string::String>().unwrap();
+        assert_eq!(
+            *message,
+            "formatting \"%F\" with the `bytes` sink produced an unexpected string\n \
+             expected: \"1970-01-02\"\n   \
+             actual: \"1970-01-01\"\n\
+             first difference at byte offset 9"
+                .to_string()
+        );
+    }
+
+    #[test]
+    #[should_panic = "formatting \"%\" with the `bytes` sink failed: unterminated directive `%` at byte 0"]
+    fn test_assert_format_eq_error() {
+        let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 0, "UTC");
+        crate::assert_format_eq!(time, "%", "");
+    }
+}
@@ -8,8 +8,30 @@ fn test_error_display_is_non_empty() {
     use crate::Error;
 
     assert!(!Error::InvalidTime.to_string().is_empty());
+    assert!(!Error::MissingTimeZone.to_string().is_empty());
     assert!(!Error::InvalidFormatString.to_string().is_empty());
-    assert!(!Error::FormattedStringTooLarge.to_string().is_empty());
+    assert!(!Error::UnterminatedDirective {
+        position: 0,
+        snippet: crate::Snippet::new(b"%"),
+    }
+    .to_string()
+    .is_empty());
+    assert!(!Error::UnknownDirective.to_string().is_empty());
+    assert!(!Error::WidthTooLarge { limit: 1 }.to_string().is_empty());
+    assert!(!Error::MissingWidthArgument.to_string().is_empty());
+    assert!(!Error::FormattedStringTooLarge {
+        limit: 1,
+        attempted_size: 2,
+    }
+    .to_string()
+    .is_empty());
+    assert!(!Error::TooManyDirectives { limit: 1 }.to_string().is_empty());
+    assert!(!Error::InvalidUtcOffset {
+        utc_offset: 100_000,
+        is_utc: false
+    }
+    .to_string()
+    .is_empty());
     assert!(!Error::WriteZero.to_string().is_empty());
 
     let fmt_error = fmt::Error;
@@ -23,6 +45,13 @@ fn test_error_display_is_non_empty() {
         let io_error = std::io::Write::write_all(&mut &mut [0u8; 0][..], b"1").unwrap_err();
         assert!(!Error::IoError(io_error).to_string().is_empty());
     }
+
+    let err = Error::Directive {
+        directive: "%d".to_string(),
+        position: 0,
+        source: alloc::boxed::Box::new(Error::InvalidTime),
+    };
+    assert!(!err.to_string().is_empty());
 }
 
 #[cfg(feature = "alloc")]
@@ -35,8 +64,36 @@ fn test_error_debug_is_non_empty() {
     use crate::Error;
 
     assert!(!format!("{:?}", Error::InvalidTime).is_empty());
+    assert!(!format!("{:?}", Error::MissingTimeZone).is_empty());
     assert!(!format!("{:?}", Error::InvalidFormatString).is_empty());
-    assert!(!format!("{:?}", Error::FormattedStringTooLarge).is_empty());
+    assert!(!format!(
+        "{:?}",
+        Error::UnterminatedDirective {
+            position: 0,
+            snippet: crate::Snippet::new(b"%"),
+        }
+    )
+    .is_empty());
+    assert!(!format!("{:?}", Error::UnknownDirective).is_empty());
+    assert!(!format!("{:?}", Error::WidthTooLarge { limit: 1 }).is_empty());
+    assert!(!format!("{:?}", Error::MissingWidthArgument).is_empty());
+    assert!(!format!(
+        "{:?}",
+        Error::FormattedStringTooLarge {
+            limit: 1,
+            attempted_size: 2,
+        }
+    )
+    .is_empty());
+    assert!(!format!("{:?}", Error::TooManyDirectives { limit: 1 }).is_empty());
+    assert!(!format!(
+        "{:?}",
+        Error::InvalidUtcOffset {
+            utc_offset: 3600,
+            is_utc: true
+        }
+    )
+    .is_empty());
     assert!(!format!("{:?}", Error::WriteZero).is_empty());
 
     let fmt_error = fmt::Error;
@@ -50,6 +107,13 @@ fn test_error_debug_is_non_empty() {
         let io_error = std::io::Write::write_all(&mut &mut [0u8; 0][..], b"1").unwrap_err();
         assert!(!format!("{:?}", Error::IoError(io_error)).is_empty());
     }
+
+    let err = Error::Directive {
+        directive: "%d".into(),
+        position: 0,
+        source: alloc::boxed::Box::new(Error::InvalidTime),
+    };
+    assert!(!format!("{err:?}").is_empty());
 }
 
 #[cfg(feature = "alloc")]
@@ -74,6 +138,48 @@ fn test_error_from_io_error() {
     assert!(matches!(io_error.into(), Error::IoError(_)));
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_io_flush_error() {
+    use std::io::{self, ErrorKind, Write};
+    use std::vec::Vec;
+
+    use crate::{DateTime, Error, FormatOptions};
+
+    /// A writer failing to flush.
+    struct Unflushable(Vec<u8>, usize);
+
+    impl Write for Unflushable {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.1 += 1;
+            Err(ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    let time = DateTime::from_timestamp(0, 0).unwrap();
+    let mut writer = Unflushable(Vec::new(), 0);
+
+    // The writer is not flushed by default
+    crate::io::strftime(&time, b"%Y", &mut writer).unwrap();
+    assert_eq!(writer.1, 0);
+
+    let options = FormatOptions::new().with_flush();
+    let result = crate::io::strftime_with_options(&time, b"%Y", &mut writer, &options);
+    assert!(matches!(result, Err(Error::IoError(err)) if err.kind() == ErrorKind::BrokenPipe));
+    assert_eq!(writer.0, b"19701970");
+    assert_eq!(writer.1, 1);
+
+    // The formatting error takes precedence
+    let result = crate::io::strftime_with_options(&time, b"%Y%", &mut writer, &options);
+    assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+    assert_eq!(writer.0, b"197019701970");
+    assert_eq!(writer.1, 2);
+}
+
 #[test]
 fn test_error_from_fmt_error() {
     use crate::Error;
@@ -84,6 +190,7 @@ fn test_error_from_fmt_error() {
 #[cfg(feature = "std")]
 #[test]
 fn test_error_source_returns_inner_error() {
+    use std::boxed::Box;
     use std::error::Error as _;
     use std::fmt;
     use std::io::Write;
@@ -97,8 +204,30 @@ fn test_error_source_returns_inner_error() {
 
     // Errors variants without inner error
     assert!(Error::InvalidTime.source().is_none());
+    assert!(Error::MissingTimeZone.source().is_none());
     assert!(Error::InvalidFormatString.source().is_none());
-    assert!(Error::FormattedStringTooLarge.source().is_none());
+    assert!(Error::UnterminatedDirective {
+        position: 0,
+        snippet: crate::Snippet::new(b"%"),
+    }
+    .source()
+    .is_none());
+    assert!(Error::UnknownDirective.source().is_none());
+    assert!(Error::WidthTooLarge { limit: 1 }.source().is_none());
+    assert!(Error::MissingWidthArgument.source().is_none());
+    assert!(Error::FormattedStringTooLarge {
+        limit: 1,
+        attempted_size: 2,
+    }
+    .source()
+    .is_none());
+    assert!(Error::TooManyDirectives { limit: 1 }.source().is_none());
+    assert!(Error::InvalidUtcOffset {
+        utc_offset: 3600,
+        is_utc: true
+    }
+    .source()
+    .is_none());
     assert!(Error::WriteZero.source().is_none());
 
     // Error variants with inner error
@@ -113,4 +242,37 @@ fn test_error_source_returns_inner_error() {
     let err = Error::IoError(io_error);
     let err_source: &std::io::Error = err.source().unwrap().downcast_ref().unwrap();
     assert_eq!(err_source.kind(), std::io::ErrorKind::WriteZero);
+
+    let err = Error::Directive {
+        directive: "%d".into(),
+        position: 0,
+        source: Box::new(Error::InvalidTime),
+    };
+    let err_source: &Error = err.source().unwrap().downcast_ref().unwrap();
+    assert!(matches!(err_source, Error::InvalidTime));
+}
+
+#[cfg(feature = "core-error")]
+#[test]
+fn test_error_implements_core_error() {
+    use core::error::Error as _;
+
+    use crate::Error;
+
+    let err = Error::FmtError(core::fmt::Error);
+    let err_source = err.source().unwrap().downcast_ref();
+    assert_eq!(err_source, Some(&core::fmt::Error));
+
+    assert!(Error::InvalidTime.source().is_none());
+}
+
+#[cfg(feature = "defmt")]
+#[test]
+fn test_error_implements_defmt_format() {
+    fn assert_format<T: defmt::Format>() {}
+
+    assert_format::<crate::Error>();
+    assert_format::<crate::strptime::ParseError>();
+    assert_format::<crate::strptime::ParseErrorKind>();
+    assert_format::<crate::strptime::Expected>();
 }
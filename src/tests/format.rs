@@ -1,7 +1,7 @@
 #![allow(clippy::should_panic_without_expect)]
 
 use crate::format::TimeFormatter;
-use crate::{Error, Time};
+use crate::{Error, FormatOptions, Time};
 
 include!("../mock.rs.in");
 
@@ -30,6 +30,37 @@ fn check_all(times: &[MockTime<'_>], format: &str, all_expected: &[&str]) {
     }
 }
 
+fn check_with_options(
+    time: &MockTime<'_>,
+    format: &str,
+    options: &FormatOptions<'_>,
+    expected: &str,
+) {
+    const SIZE: usize = 100;
+    let mut buf = [0u8; SIZE];
+    let mut cursor = &mut buf[..];
+
+    TimeFormatter::with_options(time, format, options)
+        .fmt(&mut cursor)
+        .unwrap();
+    let written = SIZE - cursor.len();
+    let data = core::str::from_utf8(&buf[..written]).unwrap();
+
+    assert_eq!(data, expected, "{format}");
+}
+
+fn check_all_with_options(
+    times: &[MockTime<'_>],
+    format: &str,
+    options: &FormatOptions<'_>,
+    all_expected: &[&str],
+) {
+    assert_eq!(times.len(), all_expected.len());
+    for (time, expected) in times.iter().zip(all_expected) {
+        check_with_options(time, format, options, expected);
+    }
+}
+
 #[test]
 #[should_panic]
 #[rustfmt::skip]
@@ -316,6 +347,53 @@ fn test_format_meridian_upper() {
     check_all(&times, "'%_04p'",   &["'00AM'", "'00PM'"]);
 }
 
+#[test]
+#[rustfmt::skip]
+fn test_format_day_period() {
+    use crate::{Dialect, Locale};
+
+    let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    let english = options.with_locale(Locale::English);
+    let german = options.with_locale(Locale::German);
+    let spanish = options.with_locale(Locale::Spanish);
+
+    let times = [
+        MockTime { hour: 0,                ..Default::default() },
+        MockTime { hour: 0,  second: 1,    ..Default::default() },
+        MockTime { hour: 5,  minute: 59,   ..Default::default() },
+        MockTime { hour: 6,                ..Default::default() },
+        MockTime { hour: 12,               ..Default::default() },
+        MockTime { hour: 12, minute: 30,   ..Default::default() },
+        MockTime { hour: 19,               ..Default::default() },
+        MockTime { hour: 22,               ..Default::default() },
+    ];
+
+    check_all_with_options(&times, "%:p", &english, &[
+        "midnight", "at night", "at night", "in the morning",
+        "noon", "in the afternoon", "in the evening", "at night",
+    ]);
+    check_all_with_options(&times, "%:p", &german, &[
+        "Mitternacht", "nachts", "morgens", "morgens",
+        "mittags", "mittags", "abends", "abends",
+    ]);
+    check_all_with_options(&times, "%:p", &spanish, &[
+        "de la madrugada", "de la madrugada", "de la madrugada", "de la mañana",
+        "del mediodía", "de la tarde", "de la tarde", "de la noche",
+    ]);
+
+    check_with_options(&times[4], "'%6:p'",    &english, "'  noon'");
+    check_with_options(&times[4], "'%-6:p'",   &english, "'noon'");
+    check_with_options(&times[4], "'%^:p'",    &english, "'NOON'");
+    check_with_options(&times[4], "'%.2:p'",   &english, "'no'");
+    check_with_options(&times[4], "'%^:p'",    &spanish, "'DEL MEDIODíA'");
+    check_with_options(&times[4], "'%14:p'",   &spanish, "'  del mediodía'");
+    check_with_options(&times[4], "'%.11:p'",  &spanish, "' del mediod'");
+    check_with_options(&times[4], "'%.12:p'",  &spanish, "' del mediodí'");
+
+    // Not a directive of the Ruby dialect
+    check_format(&times[0], "'%:p'", "'%:p'");
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_format_minute() {
@@ -350,6 +428,7 @@ fn test_format_second() {
     check_all(&times, "'%_0S'", &["'01'",   "'11'"]);
 }
 
+#[cfg(feature = "subsec")]
 #[test]
 #[rustfmt::skip]
 fn test_format_milli_second() {
@@ -376,6 +455,7 @@ fn test_format_milli_second() {
     check_all(&times, "'%012L'", &["'000000001000'", "'123456789000'"]);
 }
 
+#[cfg(feature = "subsec")]
 #[test]
 #[rustfmt::skip]
 fn test_format_fractional_second() {
@@ -483,6 +563,30 @@ fn test_format_time_zone_offset_colon_minimal() {
     check_all(&times, "'%_010:::z'", &["'+000000000'", "'+000000000'", "'+000000:09'", "'+000:09:21'", "'+000000001'"]);
 }
 
+#[test]
+#[rustfmt::skip]
+fn test_format_time_zone_offset_hour_minute_second() {
+    use crate::Dialect;
+
+    let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    let times = [
+        MockTime { is_utc: true,  utc_offset: 0,      ..Default::default() },
+        MockTime { is_utc: false, utc_offset: 0,      ..Default::default() },
+        MockTime { is_utc: false, utc_offset: 561,    ..Default::default() },
+        MockTime { is_utc: false, utc_offset: -19800, ..Default::default() },
+    ];
+
+    check_all_with_options(&times, "'%o'",    &options, &["'+000000'",  "'+000000'",  "'+000921'",  "'-053000'"]);
+    check_all_with_options(&times, "'%1o'",   &options, &["'+000000'",  "'+000000'",  "'+000921'",  "'-053000'"]);
+    check_all_with_options(&times, "'%8o'",   &options, &["'+0000000'", "'+0000000'", "'+0000921'", "'-0053000'"]);
+    check_all_with_options(&times, "'%-8o'",  &options, &["'-0000000'", "'+0000000'", "'+0000921'", "'-0053000'"]);
+    check_all_with_options(&times, "'%-_8o'", &options, &["'  -00000'", "'  +00000'", "'  +00921'", "'  -53000'"]);
+    check_all_with_options(&times, "'%_08o'", &options, &["'+0000000'", "'+0000000'", "'+0000921'", "'-0053000'"]);
+
+    // Not a directive of the Ruby dialect
+    check_format(&times[2], "'%o'", "'%o'");
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_format_time_zone_name() {
@@ -663,6 +767,218 @@ fn test_format_seconds_since_epoch() {
     check_all(&times, "'%_0s'", &["'1'",    "'11'"]);
 }
 
+#[cfg(feature = "subsec")]
+#[test]
+#[rustfmt::skip]
+fn test_format_seconds_since_epoch_fractional() {
+    use crate::Dialect;
+
+    let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    let time = MockTime { to_int: 11, nanoseconds: 123_456_789, ..Default::default() };
+    check_with_options(&time, "'%:s'",    &options, "'11.123456789'");
+    check_with_options(&time, "'%3:s'",   &options, "'11.123'");
+    check_with_options(&time, "'%-_3:s'", &options, "'11.123'");
+    check_with_options(&time, "'%12:s'",  &options, "'11.123456789000'");
+    check_with_options(&time, "'%::s'",   &options, "'%::s'");
+
+    let time = MockTime { to_int: -2, nanoseconds: 250_000_000, ..Default::default() };
+    check_with_options(&time, "'%:s'",  &options, "'-1.750000000'");
+
+    let time = MockTime { to_int: -1, nanoseconds: 500_000_000, ..Default::default() };
+    check_with_options(&time, "'%1:s'", &options, "'-0.5'");
+
+    let time = MockTime { to_int: i64::MIN, nanoseconds: 0, ..Default::default() };
+    check_with_options(&time, "'%:s'",  &options, "'-9223372036854775808.000000000'");
+
+    // Not supported by Ruby
+    check_format(&time, "'%:s'", "'%:s'");
+}
+
+#[cfg(not(feature = "subsec"))]
+#[test]
+fn test_format_without_subsec() {
+    use crate::Dialect;
+
+    let time = MockTime {
+        to_int: 11,
+        nanoseconds: 123_456_789,
+        ..Default::default()
+    };
+    let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+
+    for format in ["%L", "%3N", "%:s"] {
+        let mut buf = [0u8; 100];
+        let mut cursor = &mut buf[..];
+
+        TimeFormatter::with_options(&time, format, &options)
+            .fmt(&mut cursor)
+            .unwrap();
+        let written = 100 - cursor.len();
+        assert_eq!(&buf[..written], format.as_bytes());
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_format_truncate() {
+    use crate::Dialect;
+
+    let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    let time = MockTime { month: 9, day: 3, day_of_week: 1, time_zone: "CEST", ..Default::default() };
+    check_with_options(&time, "'%.2A'",   &options, "'Mo'");
+    check_with_options(&time, "'%.^2A'",  &options, "'MO'");
+    check_with_options(&time, "'%.3B'",   &options, "'Sep'");
+    check_with_options(&time, "'%.#3B'",  &options, "'SEP'");
+    check_with_options(&time, "'%.2Z'",   &options, "'CE'");
+    check_with_options(&time, "'%.#2Z'",  &options, "'ce'");
+    check_with_options(&time, "'%.12A'",  &options, "'      Monday'");
+    check_with_options(&time, "'%.1A'",   &options, "'M'");
+    check_with_options(&time, "'%.A'",    &options, "'Monday'");
+    check_with_options(&time, "'%.2d'",   &options, "'03'");
+
+    // Not supported by Ruby
+    check_format(&time, "'%.2A'", "'%.2A'");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_format_left_justify() {
+    use crate::Dialect;
+
+    let options = FormatOptions::new().with_dialect(Dialect::Extensions);
+    let time = MockTime { month: 7, day: 3, day_of_week: 1, time_zone: "CEST", ..Default::default() };
+    check_with_options(&time, "'%<9B'",   &options, "'July     '");
+    check_with_options(&time, "'%<^9B'",  &options, "'JULY     '");
+    check_with_options(&time, "'%<09B'",  &options, "'July     '");
+    check_with_options(&time, "'%<3B'",   &options, "'July'");
+    check_with_options(&time, "'%<B'",    &options, "'July'");
+    check_with_options(&time, "'%-<9B'",  &options, "'July'");
+    check_with_options(&time, "'%<6a'",   &options, "'Mon   '");
+    check_with_options(&time, "'%<#6Z'",  &options, "'cest  '");
+    check_with_options(&time, "'%<.4a'",  &options, "'Mon '");
+    check_with_options(&time, "'%<4d'",   &options, "'0003'");
+
+    // Not supported by Ruby
+    check_format(&time, "'%<9B'", "'%<9B'");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_format_grouping() {
+    use crate::Dialect;
+
+    let options = |separator| {
+        FormatOptions::new()
+            .with_dialect(Dialect::Extensions)
+            .with_digit_separator(separator)
+    };
+
+    let time = MockTime { to_int: 1_700_000_000, ..Default::default() };
+    check_with_options(&time, "'%'s'",     &options(","), "'1,700,000,000'");
+    check_with_options(&time, "'%'s'",     &options(" "), "'1 700 000 000'");
+    check_with_options(&time, "'%'s'",     &options(""),  "'1700000000'");
+    check_with_options(&time, "'%'s'",     &options("\u{202f}"), "'1\u{202f}700\u{202f}000\u{202f}000'");
+    check_with_options(&time, "'%'16s'",   &options(","), "'0001,700,000,000'");
+    check_with_options(&time, "'%_'16s'",  &options(","), "'   1,700,000,000'");
+    check_with_options(&time, "'%-'16s'",  &options(","), "'1,700,000,000'");
+    check_with_options(&time, "'%'3s'",    &options(","), "'1,700,000,000'");
+    check_with_options(&time, "'%s'",      &options(","), "'1700000000'");
+
+    let time = MockTime { to_int: -123_456, ..Default::default() };
+    check_with_options(&time, "'%'s'",     &options(","), "'-123,456'");
+    check_with_options(&time, "'%'10s'",   &options(","), "'-00123,456'");
+    check_with_options(&time, "'%_'10s'",  &options(","), "'  -123,456'");
+
+    let time = MockTime { to_int: 999, ..Default::default() };
+    check_with_options(&time, "'%'s'",     &options(","), "'999'");
+
+    let time = MockTime { to_int: i64::MIN, ..Default::default() };
+    check_with_options(&time, "'%'s'",     &options(","), "'-9,223,372,036,854,775,808'");
+
+    // Not supported by Ruby
+    check_format(&time, "'%'s'", "'%'s'");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_format_dynamic_width() {
+    use crate::Dialect;
+
+    fn options(width_args: &[usize]) -> FormatOptions<'_> {
+        FormatOptions::new()
+            .with_dialect(Dialect::Extensions)
+            .with_width_args(width_args)
+    }
+
+    fn format(time: &MockTime<'_>, format: &str, width_args: &[usize]) -> Result<usize, Error> {
+        let mut buf = [0u8; 100];
+        let mut cursor = &mut buf[..];
+
+        TimeFormatter::with_options(time, format, &options(width_args)).fmt(&mut cursor)?;
+        Ok(100 - cursor.len())
+    }
+
+    let time = MockTime { year: 1970, day: 8, hour: 1, ..Default::default() };
+
+    check_with_options(&time, "'%*d'",        &options(&[0]),       "'8'");
+    check_with_options(&time, "'%*d'",        &options(&[4]),       "'0008'");
+    check_with_options(&time, "'%_*d'",       &options(&[4]),       "'   8'");
+    check_with_options(&time, "'%-*d'",       &options(&[4]),       "'8'");
+    check_with_options(&time, "'%*d %*H'",    &options(&[3, 5, 9]), "'008 00001'");
+    check_with_options(&time, "'%*Y'",        &options(&[2_147_483_648]), "'%*Y'");
+    // The width arguments are consumed in order by invalid directives
+    check_with_options(&time, "'%*Y %*d'",    &options(&[2_147_483_648, 3]), "'%*Y 008'");
+
+    // Not a width placeholder in the Ruby dialect
+    check_format(&time, "'%*d'", "'%*d'");
+
+    assert!(matches!(format(&time, "%*d", &[]), Err(Error::MissingWidthArgument)));
+    assert!(matches!(format(&time, "%*d%*d", &[1]), Err(Error::MissingWidthArgument)));
+    assert_eq!(format(&time, "%*d%*d", &[1, 1]).unwrap(), 2);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_format_naive_policy() {
+    use crate::NaivePolicy;
+
+    let time = MockTime { hour: 12, utc_offset: 3600, time_zone: "CET", ..Default::default() };
+
+    let utc = FormatOptions::new().with_naive_policy(NaivePolicy::Utc);
+    let empty = FormatOptions::new().with_naive_policy(NaivePolicy::Empty);
+    let unknown = FormatOptions::new().with_naive_policy(NaivePolicy::Unknown);
+    let reject = FormatOptions::new().with_naive_policy(NaivePolicy::Reject);
+
+    check_with_options(&time, "'%z'",     &utc, "'+0000'");
+    check_with_options(&time, "'%-:z'",   &utc, "'-00:00'");
+    check_with_options(&time, "'%::z'",   &utc, "'+00:00:00'");
+    check_with_options(&time, "'%:::z'",  &utc, "'+00'");
+    check_with_options(&time, "'%Z'",     &utc, "'UTC'");
+    check_with_options(&time, "'%#5Z'",   &utc, "'  utc'");
+    check_with_options(&time, "'%H'",     &utc, "'12'");
+
+    check_with_options(&time, "'%z'",   &empty, "''");
+    check_with_options(&time, "'%8:z'", &empty, "''");
+    check_with_options(&time, "'%5Z'",  &empty, "''");
+    check_with_options(&time, "'%H'",   &empty, "'12'");
+
+    check_with_options(&time, "'%z'",     &unknown, "'-0000'");
+    check_with_options(&time, "'%:z'",    &unknown, "'-00:00'");
+    check_with_options(&time, "'%::z'",   &unknown, "'-00:00:00'");
+    check_with_options(&time, "'%:::z'",  &unknown, "'-00'");
+    check_with_options(&time, "'%_7:z'",  &unknown, "'  -0:00'");
+    check_with_options(&time, "'%5Z'",    &unknown, "''");
+    check_with_options(&time, "'%H'",     &unknown, "'12'");
+
+    check_with_options(&time, "'%H'",  &reject, "'12'");
+    for format in ["%z", "%:z", "%::z", "%:::z", "%Z"] {
+        let result = TimeFormatter::with_options(&time, format, &reject).fmt(&mut &mut [0u8; 100][..]);
+        assert!(matches!(result, Err(Error::MissingTimeZone)));
+    }
+
+    check_format(&time, "'%z %Z'", "'+0100 CET'");
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_format_newline() {
@@ -840,8 +1156,156 @@ fn test_format_invalid() {
 
     for format in ["%", "%-4", "%-", "%-_"] {
         let err = get_format_err(&time, format);
-        assert!(matches!(err, Error::InvalidFormatString));
+        assert!(matches!(
+            err,
+            Error::UnterminatedDirective { position: 0, snippet, .. }
+                if snippet.as_bytes() == format.as_bytes()
+        ));
+    }
+
+    let err = get_format_err(&time, "%Y %-_^#00000000000000000001");
+    assert!(matches!(
+        err,
+        Error::UnterminatedDirective { position: 3, snippet, .. }
+            if snippet.as_bytes() == b"%-_^#00000000000" && snippet.is_truncated()
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_lenient_trailing_percent() {
+    use alloc::vec::Vec;
+
+    let time = MockTime::default();
+    let options = FormatOptions::new().with_lenient_trailing_percent();
+
+    for (format, expected) in [
+        ("%", "%"),
+        ("%-4", "%-4"),
+        ("%-_", "%-_"),
+        ("%%%", "%%"),
+        ("abc%", "abc%"),
+        ("%n%:", "\n%:"),
+    ] {
+        let mut buf = Vec::new();
+        TimeFormatter::with_options(&time, format, &options)
+            .fmt(&mut buf)
+            .unwrap();
+        assert_eq!(buf, expected.as_bytes(), "{format}");
+    }
+}
+
+#[test]
+fn test_format_strict_directives() {
+    use crate::buffered::strftime_with_options;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        ..Default::default()
+    };
+    let options = FormatOptions::new().with_strict_directives();
+    let mut buf = [0u8; 32];
+
+    for format in ["%Q", "%-E", "%Y-%:q", "%:Y", "%:", "% "] {
+        let result = strftime_with_options(&time, format.as_bytes(), &mut buf, &options);
+        assert!(matches!(result, Err(Error::UnknownDirective)), "{format}");
     }
+
+    let result = strftime_with_options(&time, b"%Y %% %-m", &mut buf, &options);
+    assert_eq!(result.unwrap(), b"1970 % 1");
+
+    // Aliases and named formats are accepted
+    let options = options
+        .with_aliases(&[(b'Q', "%Y")])
+        .with_named_formats(&[("year", "%Y")]);
+    let result = strftime_with_options(&time, b"%Q %{year}", &mut buf, &options);
+    assert_eq!(result.unwrap(), b"1970 1970");
+
+    let result = strftime_with_options(&time, b"%{month}", &mut buf, &options);
+    assert!(matches!(result, Err(Error::UnknownDirective)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_strict_directives_error_context() {
+    use alloc::string::ToString;
+
+    let time = MockTime {
+        month: 1,
+        ..Default::default()
+    };
+    let options = FormatOptions::new()
+        .with_strict_directives()
+        .with_error_context();
+
+    let err = crate::string::strftime_with_options(&time, "%Y-%m-%Q", &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to format directive `%Q` at byte 6: unknown directive in the format string"
+    );
+}
+
+#[test]
+fn test_format_strict_widths() {
+    use crate::buffered::strftime_with_options;
+    use crate::Dialect;
+
+    let time = MockTime {
+        month: 1,
+        ..Default::default()
+    };
+    let options = FormatOptions::new().with_strict_widths();
+    let mut buf = [0u8; 32];
+
+    for format in [
+        "%2147483648m",
+        "%-99999999999999999999999d",
+        "%Y %2147483648",
+    ] {
+        let result = strftime_with_options(&time, format.as_bytes(), &mut buf, &options);
+        assert!(
+            matches!(
+                result,
+                Err(Error::WidthTooLarge {
+                    limit: 2_147_483_647
+                })
+            ),
+            "{format}"
+        );
+    }
+
+    let result = strftime_with_options(&time, b"%3m", &mut buf, &options);
+    assert_eq!(result.unwrap(), b"001");
+
+    // Dynamic widths are also checked
+    let width_args = [2_147_483_648];
+    let options = options
+        .with_dialect(Dialect::Extensions)
+        .with_width_args(&width_args);
+    let result = strftime_with_options(&time, b"%*m", &mut buf, &options);
+    assert!(matches!(result, Err(Error::WidthTooLarge { .. })));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_strict_widths_error_context() {
+    use alloc::string::ToString;
+
+    let time = MockTime {
+        month: 1,
+        ..Default::default()
+    };
+    let options = FormatOptions::new()
+        .with_strict_widths()
+        .with_error_context();
+
+    let err = crate::string::strftime_with_options(&time, "%m %2147483648m", &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to format directive `%2147483648` at byte 3: \
+         directive width exceeds the limit of 2147483647"
+    );
 }
 
 #[test]
@@ -878,10 +1342,69 @@ fn test_format_large_width() {
     assert!(matches!(err, Error::WriteZero));
 }
 
+#[test]
+fn test_format_large_width_buffered() {
+    let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, 0, "UTC");
+
+    let specs: [&[u8]; 44] = [
+        b"Y", b"C", b"y", b"m", b"B", b"b", b"d", b"e", b"j", b"H", b"k", b"I", b"l", b"P", b"p",
+        b"M", b"S", b"L", b"N", b"z", b":z", b"::z", b":::z", b"Z", b"A", b"a", b"u", b"w", b"G",
+        b"g", b"V", b"U", b"W", b"s", b"n", b"t", b"%", b"c", b"D", b"F", b"v", b"r", b"R", b"T",
+    ];
+
+    for spec in specs {
+        // Sub-second directives are written as is without the `subsec` feature
+        if !cfg!(feature = "subsec") && matches!(spec, b"L" | b"N") {
+            continue;
+        }
+
+        for flag in [&b""[..], b"-", b"_", b"0", b"^", b"#"] {
+            let mut format = [0u8; 32];
+            let mut len = 0;
+            for part in [&b"%"[..], flag, b"2147483647", spec] {
+                format[len..len + part.len()].copy_from_slice(part);
+                len += part.len();
+            }
+            let format = &format[..len];
+
+            let mut buf = [0u8; 100];
+            let result = TimeFormatter::new(&time, format).fmt(&mut &mut buf[..]);
+
+            // Width is ignored with left padding, except for the fractional
+            // seconds, time zone offset and combination specifiers
+            let width_is_ignored = flag == b"-"
+                && !matches!(
+                    spec,
+                    b"L" | b"N"
+                        | b"z"
+                        | b":z"
+                        | b"::z"
+                        | b":::z"
+                        | b"c"
+                        | b"D"
+                        | b"F"
+                        | b"v"
+                        | b"r"
+                        | b"R"
+                        | b"T"
+                );
+
+            if width_is_ignored {
+                assert!(result.is_ok(), "{format:?}");
+            } else {
+                assert!(matches!(result, Err(Error::WriteZero)), "{format:?}");
+            }
+        }
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn test_format_formatted_string_too_large() {
     use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    use crate::FormatStats;
 
     let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, 0, "");
 
@@ -889,7 +1412,303 @@ fn test_format_formatted_string_too_large() {
     let result = TimeFormatter::new(&time, "%4718593m").fmt(&mut buf);
 
     assert_eq!(buf.len(), 4_718_592);
-    assert!(matches!(result, Err(Error::FormattedStringTooLarge)));
+    assert!(matches!(
+        result,
+        Err(Error::FormattedStringTooLarge {
+            limit: 4_718_592,
+            attempted_size: 4_718_593,
+        })
+    ));
+
+    // The size limit and the attempted size are also reported in the
+    // statistics
+    let stats = Cell::new(FormatStats::new());
+    let options = FormatOptions::new().with_stats(&stats);
+    let result = TimeFormatter::with_options(&time, "%4718593m", &options).fmt(&mut Vec::new());
+    assert!(matches!(result, Err(Error::FormattedStringTooLarge { .. })));
+    assert_eq!(stats.get().size_limit(), 4_718_592);
+    assert_eq!(stats.get().attempted_size(), Some(4_718_593));
+
+    let result = TimeFormatter::with_options(&time, "%m", &options).fmt(&mut Vec::new());
+    assert!(result.is_ok());
+    assert_eq!(stats.get().size_limit(), 2 * 512 * 1024);
+    assert_eq!(stats.get().attempted_size(), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_too_many_directives() {
+    use alloc::vec::Vec;
+
+    let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, 0, "");
+
+    // No limit by default
+    let format = b"%Y".repeat(5000);
+    let mut buf = Vec::new();
+    TimeFormatter::new(&time, &format).fmt(&mut buf).unwrap();
+    assert_eq!(buf.len(), 4 * 5000);
+
+    let options = FormatOptions::new().with_max_directives(4096);
+    let format = b"%Y".repeat(4096);
+    let mut buf = Vec::new();
+    TimeFormatter::with_options(&time, &format, &options)
+        .fmt(&mut buf)
+        .unwrap();
+    assert_eq!(buf.len(), 4 * 4096);
+
+    let format = b"%Y".repeat(4097);
+    let result = TimeFormatter::with_options(&time, &format, &options).fmt(&mut Vec::new());
+    assert!(matches!(
+        result,
+        Err(Error::TooManyDirectives { limit: 4096 })
+    ));
+
+    // Unknown directives are counted
+    let options = FormatOptions::new().with_max_directives(2);
+    let mut buf = Vec::new();
+    TimeFormatter::with_options(&time, "%Q%Y", &options)
+        .fmt(&mut buf)
+        .unwrap();
+    assert_eq!(buf, b"%Q1970");
+
+    let result = TimeFormatter::with_options(&time, "%Q%Y%Q", &options).fmt(&mut Vec::new());
+    assert!(matches!(result, Err(Error::TooManyDirectives { limit: 2 })));
+
+    let options = FormatOptions::new().with_max_directives(0);
+    let result = TimeFormatter::with_options(&time, "%%", &options).fmt(&mut Vec::new());
+    assert!(matches!(result, Err(Error::TooManyDirectives { limit: 0 })));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_aliases() {
+    use alloc::vec::Vec;
+
+    let time = MockTime::new(1970, 1, 1, 12, 30, 0, 0, 4, 1, 0, false, 0, "");
+
+    let aliases = [
+        (b'f', "%H:%M"),
+        (b'i', "%Y-%m-%d"),
+        (b'q', "%f%i"),
+        (b'f', "%M"),
+    ];
+    let options = FormatOptions::new().with_aliases(&aliases);
+
+    check_with_options(&time, "%f", &options, "12:30");
+    check_with_options(&time, "[%i]", &options, "[1970-01-01]");
+    // Aliases are not expanded recursively
+    check_with_options(&time, "%q", &options, "%f%i");
+    // Aliases are only expanded for bare directives
+    check_with_options(&time, "%-f %5i %:f", &options, "%-f %5i %:f");
+    // Supported directives are not aliased
+    let options = FormatOptions::new().with_aliases(&[(b'Y', "%y"), (b'%', "%%%%")]);
+    check_with_options(&time, "%Y%%", &options, "1970%");
+
+    // Directives of the aliases are counted
+    let options = FormatOptions::new()
+        .with_aliases(&aliases)
+        .with_max_directives(3);
+    check_with_options(&time, "%i", &options, "1970-01-01");
+    let result = TimeFormatter::with_options(&time, "%i%f", &options).fmt(&mut Vec::new());
+    assert!(matches!(result, Err(Error::TooManyDirectives { limit: 3 })));
+
+    // An unterminated directive in an alias is an error
+    let options = FormatOptions::new().with_aliases(&[(b'f', "%")]);
+    let result = TimeFormatter::with_options(&time, "%f", &options).fmt(&mut Vec::new());
+    assert!(matches!(
+        result,
+        Err(Error::UnterminatedDirective { position: 0, .. })
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_named_formats() {
+    use alloc::vec::Vec;
+
+    let time = MockTime::new(1970, 1, 1, 12, 30, 0, 0, 4, 1, 0, true, 0, "UTC");
+
+    let named_formats = [
+        ("date", "%Y-%m-%d"),
+        ("time", "%H:%M"),
+        ("zone", "%Z"),
+        ("datetime", "%{date}T%{time}"),
+        ("alias", "%i"),
+        ("", "%%"),
+        ("date", "%D"),
+    ];
+    let options = FormatOptions::new().with_named_formats(&named_formats);
+
+    check_with_options(
+        &time,
+        "%{date} %{time} %{zone}",
+        &options,
+        "1970-01-01 12:30 UTC",
+    );
+    check_with_options(&time, "%{}", &options, "%");
+    // Named formats are not expanded recursively
+    check_with_options(&time, "%{datetime}", &options, "%{date}T%{time}");
+    // Unknown and unterminated references are copied verbatim
+    check_with_options(&time, "%{week} %{date", &options, "%{week} %{date");
+    check_with_options(&time, "%{da}te}", &options, "%{da}te}");
+    // References are only expanded for bare directives
+    check_with_options(&time, "%-{date} %5{date}", &options, "%-{date} %5{date}");
+    // Named formats are not expanded without options
+    check_with_options(&time, "%{date}", &FormatOptions::new(), "%{date}");
+
+    // Aliases are expanded in named formats
+    let aliases = [(b'i', "%j")];
+    let options = options.with_aliases(&aliases);
+    check_with_options(&time, "%{alias}", &options, "001");
+
+    // Directives of the named formats are counted
+    let options = options.with_max_directives(3);
+    check_with_options(&time, "%{date}", &options, "1970-01-01");
+    let result =
+        TimeFormatter::with_options(&time, "%{date}%{time}", &options).fmt(&mut Vec::new());
+    assert!(matches!(result, Err(Error::TooManyDirectives { limit: 3 })));
+}
+
+#[test]
+fn test_format_atomic() {
+    use core::cell::Cell;
+
+    use crate::FormatStats;
+
+    let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, 0, "UTC");
+    let options = FormatOptions::new().with_atomic();
+
+    let format = |format: &str, options: &FormatOptions<'_>, buf: &mut [u8]| {
+        TimeFormatter::with_options(&time, format, options).fmt(&mut &mut buf[..])
+    };
+
+    // The buffer is too small
+    let mut buf = [b'-'; 8];
+    let result = format("%F %T", &options, &mut buf);
+    assert!(matches!(result, Err(Error::WriteZero)));
+    assert_eq!(buf, [b'-'; 8]);
+
+    let result = format("%F %T", &FormatOptions::new(), &mut buf);
+    assert!(matches!(result, Err(Error::WriteZero)));
+    assert_eq!(&buf, b"1970-01-");
+
+    // The format string is invalid
+    let mut buf = [b'-'; 8];
+    let result = format("%F%", &options, &mut buf);
+    assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+    assert_eq!(buf, [b'-'; 8]);
+
+    // The buffer is large enough
+    let mut buf = [b'-'; 12];
+    format("%F", &options, &mut buf).unwrap();
+    assert_eq!(&buf, b"1970-01-01--");
+    let mut buf = [b'-'; 10];
+    format("%F", &options, &mut buf).unwrap();
+    assert_eq!(&buf, b"1970-01-01");
+
+    // Sinks without a capacity are left untouched too
+    #[cfg(feature = "alloc")]
+    {
+        let mut buf = alloc::vec::Vec::new();
+        let result = TimeFormatter::with_options(&time, "%F%", &options).fmt(&mut buf);
+        assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+        assert!(buf.is_empty());
+    }
+
+    // Statistics are recorded for the formatting of the output
+    let stats = Cell::new(FormatStats::new());
+    let options = FormatOptions::new().with_atomic().with_stats(&stats);
+    let mut buf = [b'-'; 20];
+    format("%F %T", &options, &mut buf).unwrap();
+    assert_eq!(&buf, b"1970-01-01 00:00:00-");
+    assert_eq!(stats.get().directives(), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_format_fast() {
+    use alloc::string::ToString;
+
+    use crate::fast::strftime;
+
+    let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, true, 0, "UTC");
+
+    let first = strftime(&time, "%F").unwrap();
+    // The buffer is lent to the first string, so another one is allocated
+    let second = strftime(&time, "%T %Z").unwrap();
+    assert_eq!(&*first, "1970-01-01");
+    assert_eq!(second.to_string(), "00:00:00 UTC");
+
+    drop((first, second));
+
+    let third = strftime(&time, "%Y").unwrap();
+    assert_eq!(third.as_ref(), "1970");
+    drop(third);
+
+    assert!(matches!(
+        strftime(&time, "%"),
+        Err(Error::UnterminatedDirective { .. })
+    ));
+    assert_eq!(&*strftime(&time, "%m/%d").unwrap(), "01/01");
+}
+
+#[test]
+fn test_format_stats() {
+    use core::cell::Cell;
+
+    use crate::FormatStats;
+
+    let time = MockTime::new(1970, 1, 1, 0, 0, 0, 0, 4, 1, 0, false, 0, "UTC");
+    let stats = Cell::new(FormatStats::new());
+
+    let format = |format: &str, options: &FormatOptions<'_>| {
+        let mut buf = [0u8; 100];
+        TimeFormatter::with_options(&time, format, options).fmt(&mut &mut buf[..])
+    };
+
+    let aliases = [(b'i', "[%Y-%m-%d]")];
+    let options = FormatOptions::new()
+        .with_aliases(&aliases)
+        .with_stats(&stats);
+
+    format("at %H:%M %q %i%", &options.with_lenient_trailing_percent()).unwrap();
+    let result = stats.get();
+    assert_eq!(result.directives(), 5);
+    assert_eq!(result.literal_bytes(), 13);
+    assert_eq!(result.bytes_written(), 25);
+    assert!(!result.limit_approached());
+
+    // The statistics are overwritten by each call
+    format("%%", &options).unwrap();
+    assert_eq!(stats.get().directives(), 1);
+    assert_eq!(stats.get().bytes_written(), 1);
+
+    // The statistics are filled on error
+    let options = options.with_max_directives(4);
+    let result = format("%H%M%S", &options);
+    assert!(result.is_ok());
+    assert!(stats.get().limit_approached());
+    let result = format("%H%M%S%i", &options);
+    assert!(matches!(result, Err(Error::TooManyDirectives { limit: 4 })));
+    assert_eq!(stats.get().directives(), 5);
+    assert_eq!(stats.get().bytes_written(), 12);
+    assert!(stats.get().limit_approached());
+
+    // The written bytes are counted up to the write error
+    let options = FormatOptions::new().with_stats(&stats);
+    let result = format("%1000Y", &options);
+    assert!(matches!(result, Err(Error::WriteZero)));
+    assert_eq!(stats.get().bytes_written(), 100);
+    assert!(!stats.get().limit_approached());
+    assert_eq!(stats.get().unterminated_directive(), None);
+
+    // The position of an unterminated directive is recorded
+    let result = format("%Y %-1", &options);
+    assert!(matches!(
+        result,
+        Err(Error::UnterminatedDirective { position: 3, .. })
+    ));
+    assert_eq!(stats.get().unterminated_directive(), Some(3));
 }
 
 #[test]
@@ -955,3 +1774,249 @@ fn test_chrono_pr_966_week_numbers() {
         ],
     );
 }
+
+#[test]
+fn test_format_utf16() {
+    let time = MockTime {
+        year: 1970,
+        ..Default::default()
+    };
+
+    let expected: ([u16; 7], [u16; 2]) = (
+        [0x31, 0x39, 0x37, 0x30, 0x20, 0xd83d, 0xde00],
+        [0xd83d, 0xde00],
+    );
+
+    let mut buf = [0u16; 7];
+    assert_eq!(
+        crate::utf16::strftime(&time, "%Y \u{1f600}", &mut buf).unwrap(),
+        expected.0
+    );
+
+    let mut buf = [0u16; 1];
+    let err = crate::utf16::strftime(&time, "\u{1f600}", &mut buf).unwrap_err();
+    assert!(matches!(err, Error::WriteZero));
+
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        crate::utf16::strftime_to_vec(&time, "\u{1f600}").unwrap(),
+        expected.1
+    );
+}
+
+#[test]
+fn test_format_len() {
+    use crate::buffered::strftime_len;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        ..Default::default()
+    };
+
+    let mut buf = [0u8; 8];
+    assert_eq!(strftime_len(&time, b"%Y", &mut buf).unwrap(), 4);
+    assert_eq!(strftime_len(&time, b"-%m", &mut buf[4..]).unwrap(), 3);
+    assert_eq!(strftime_len(&time, b"", &mut buf[7..]).unwrap(), 0);
+    assert_eq!(&buf[..7], b"1970-01");
+
+    let result = strftime_len(&time, b"%Y", &mut buf[6..]);
+    assert!(matches!(result, Err(Error::WriteZero)));
+}
+
+#[test]
+fn test_format_count_len() {
+    use crate::count::strftime_len;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        day: 1,
+        ..Default::default()
+    };
+
+    assert_eq!(strftime_len(&time, b"").unwrap(), 0);
+    assert_eq!(strftime_len(&time, b"%F").unwrap(), 10);
+    assert_eq!(strftime_len(&time, b"%1000Y\xff").unwrap(), 1001);
+
+    let result = strftime_len(&time, b"%Y %");
+    assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+    let invalid_time = MockTime { month: 13, ..time };
+    let result = strftime_len(&invalid_time, b"%B");
+    assert!(matches!(result, Err(Error::InvalidTime)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_cow() {
+    use alloc::borrow::Cow;
+
+    let time = MockTime {
+        year: 1970,
+        ..Default::default()
+    };
+
+    let result = crate::string::strftime_cow(&time, "no directives").unwrap();
+    assert!(matches!(result, Cow::Borrowed("no directives")));
+    let result = crate::string::strftime_cow(&time, "").unwrap();
+    assert!(matches!(result, Cow::Borrowed("")));
+    let result = crate::string::strftime_cow(&time, "%Y %%").unwrap();
+    assert!(matches!(result, Cow::Owned(ref s) if s == "1970 %"));
+    let result = crate::string::strftime_cow(&time, "100%");
+    assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+
+    let result = crate::bytes::strftime_cow(&time, b"\xff").unwrap();
+    assert!(matches!(result, Cow::Borrowed(b"\xff")));
+    let result = crate::bytes::strftime_cow(&time, b"\xff%Y").unwrap();
+    assert!(matches!(result, Cow::Owned(ref s) if s == b"\xff1970"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_into_vec() {
+    use crate::bytes::strftime_into;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        ..Default::default()
+    };
+
+    let mut buf = b"records:".to_vec();
+    strftime_into(&time, b" %Y", &mut buf).unwrap();
+    strftime_into(&time, b" %-m", &mut buf).unwrap();
+    strftime_into(&time, b"", &mut buf).unwrap();
+    assert_eq!(buf, b"records: 1970 1");
+
+    let invalid_time = MockTime { month: 13, ..time };
+    let result = strftime_into(&invalid_time, b" %Y %B", &mut buf);
+    assert!(matches!(result, Err(Error::InvalidTime)));
+    let result = strftime_into(&time, b" %Y %", &mut buf);
+    assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+    assert_eq!(buf, b"records: 1970 1");
+}
+
+#[cfg(all(feature = "alloc", feature = "bytes"))]
+#[test]
+fn test_format_into_bytes_mut() {
+    use crate::bytes::strftime_into_bytes_mut;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        ..Default::default()
+    };
+
+    let mut buf = ::bytes::BytesMut::from(&b"records:"[..]);
+    strftime_into_bytes_mut(&time, b" %Y", &mut buf).unwrap();
+    strftime_into_bytes_mut(&time, b" %-m", &mut buf).unwrap();
+    assert_eq!(buf, b"records: 1970 1"[..]);
+
+    let invalid_time = MockTime { month: 13, ..time };
+    let result = strftime_into_bytes_mut(&invalid_time, b" %Y %B", &mut buf);
+    assert!(matches!(result, Err(Error::InvalidTime)));
+    let result = strftime_into_bytes_mut(&time, b" %Y %", &mut buf);
+    assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+    assert_eq!(buf, b"records: 1970 1"[..]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_display() {
+    use alloc::format;
+    use alloc::string::{String, ToString};
+
+    use crate::fmt::display;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        day: 1,
+        ..Default::default()
+    };
+
+    let timestamp = display(&time, "%F");
+    assert_eq!(timestamp.to_string(), "1970-01-01");
+    assert_eq!(
+        format!("[{timestamp}] [{timestamp}]"),
+        "[1970-01-01] [1970-01-01]"
+    );
+    assert_eq!(display(&time, "").to_string(), "");
+
+    let mut buf = String::new();
+    assert!(core::fmt::write(&mut buf, format_args!("{}", display(&time, "%Y %"))).is_err());
+
+    let invalid_time = MockTime { month: 13, ..time };
+    assert!(core::fmt::write(&mut buf, format_args!("{}", display(&invalid_time, "%B"))).is_err());
+}
+
+#[test]
+fn test_format_dyn_time() {
+    use crate::buffered::strftime;
+    use crate::TimeExt;
+
+    let time = MockTime {
+        year: 1970,
+        month: 1,
+        day: 1,
+        ..Default::default()
+    };
+    let times: [&dyn Time; 2] = [&time, &MockTime { year: 1971, ..time }];
+
+    let mut buf = [0u8; 10];
+    assert_eq!(strftime(times[0], b"%F", &mut buf).unwrap(), b"1970-01-01");
+    assert_eq!(
+        times[1].strftime_into(&mut buf, b"%F").unwrap(),
+        b"1971-01-01"
+    );
+
+    let invalid_time = MockTime { month: 13, ..time };
+    let invalid_time: &dyn Time = &invalid_time;
+    assert!(matches!(
+        strftime(invalid_time, b"%B", &mut buf),
+        Err(Error::InvalidTime)
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_error_context() {
+    use alloc::string::ToString;
+
+    use crate::string::strftime_with_options;
+
+    let time = MockTime {
+        year: 1970,
+        month: 13,
+        day: 1,
+        ..Default::default()
+    };
+
+    let result = strftime_with_options(&time, "%Y %-m", &FormatOptions::new());
+    assert!(matches!(result, Err(Error::InvalidTime)));
+
+    let options = FormatOptions::new().with_error_context();
+    let err = strftime_with_options(&time, "%Y %-m", &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to format directive `%-m` at byte 3: invalid time"
+    );
+    assert!(matches!(
+        err,
+        Error::Directive { ref directive, position: 3, ref source }
+            if directive == "%-m" && matches!(**source, Error::InvalidTime)
+    ));
+
+    // Errors in the format string are not wrapped
+    let err = strftime_with_options(&time, "%Y %", &options).unwrap_err();
+    assert_eq!(err.to_string(), "unterminated directive `%` at byte 3");
+    assert!(matches!(
+        err,
+        Error::UnterminatedDirective { position: 3, .. }
+    ));
+
+    // Errors in aliases are reported at their position in the alias
+    let options = options.with_aliases(&[(b'i', "%Y-%m")]);
+    let err = strftime_with_options(&time, "%i", &options).unwrap_err();
+    assert!(matches!(err, Error::Directive { position: 3, .. }));
+}
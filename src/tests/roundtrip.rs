@@ -0,0 +1,286 @@
+// The assertion macros of proptest 1.0 expect `format!` in scope.
+extern crate std;
+
+use proptest::prelude::*;
+use std::format;
+
+use crate::buffered::strftime;
+use crate::strptime::{strptime, ParsedFields};
+use crate::{DateTime, Time};
+
+/// Range of timestamps of the years `1..=9998` for all the generated UTC
+/// offsets, so `%Y` and `%C%y` always format 4 digits.
+const TIMESTAMPS: core::ops::RangeInclusive<i64> = -62_135_596_800..=253_370_764_800;
+
+/// Check of the fields parsed by a directive against the formatted time.
+type Check = fn(&ParsedFields<'_>, &DateTime) -> bool;
+
+// Fields set by the directives.
+const YEAR: u16 = 1 << 0;
+const MONTH: u16 = 1 << 1;
+const DAY: u16 = 1 << 2;
+const DAY_OF_YEAR: u16 = 1 << 3;
+const HOUR: u16 = 1 << 4;
+const MINUTE: u16 = 1 << 5;
+const SECOND: u16 = 1 << 6;
+#[cfg(feature = "subsec")]
+const NANOSECONDS: u16 = 1 << 7;
+const DAY_OF_WEEK: u16 = 1 << 8;
+const DAY_OF_WEEK_FROM_MONDAY: u16 = 1 << 9;
+const ISO_8601_YEAR: u16 = 1 << 10;
+const ISO_8601_WEEK: u16 = 1 << 11;
+const WEEK_FROM_SUNDAY: u16 = 1 << 12;
+const WEEK_FROM_MONDAY: u16 = 1 << 13;
+const SECONDS_SINCE_EPOCH: u16 = 1 << 14;
+const UTC_OFFSET: u16 = 1 << 15;
+
+/// Directives parsing the value they format, with the fields they set and
+/// the check of these fields.
+///
+/// The directives are separated by literal text in the generated formats, so
+/// directives reading any number of digits like `%s` are not followed by a
+/// number.
+const DIRECTIVES: &[(&str, u16, Check)] = &[
+    ("%Y", YEAR, |f, t| f.year == Some(t.year())),
+    ("%C%y", YEAR, |f, t| f.year == Some(t.year())),
+    ("%m", MONTH, |f, t| f.month == Some(t.month())),
+    ("%-m", MONTH, |f, t| f.month == Some(t.month())),
+    ("%B", MONTH, |f, t| f.month == Some(t.month())),
+    ("%^b", MONTH, |f, t| f.month == Some(t.month())),
+    ("%d", DAY, |f, t| f.day == Some(t.day())),
+    ("%e", DAY, |f, t| f.day == Some(t.day())),
+    ("%j", DAY_OF_YEAR, |f, t| {
+        f.day_of_year == Some(t.day_of_year())
+    }),
+    ("%H", HOUR, |f, t| f.hour == Some(t.hour())),
+    ("%k", HOUR, |f, t| f.hour == Some(t.hour())),
+    ("%I %p", HOUR, |f, t| f.hour == Some(t.hour())),
+    ("%l%P", HOUR, |f, t| f.hour == Some(t.hour())),
+    ("%M", MINUTE, |f, t| f.minute == Some(t.minute())),
+    ("%S", SECOND, |f, t| f.second == Some(t.second())),
+    ("%s", SECONDS_SINCE_EPOCH, |f, t| {
+        f.seconds_since_epoch == Some(t.to_int())
+    }),
+    ("%A", DAY_OF_WEEK, |f, t| {
+        f.day_of_week == Some(t.day_of_week())
+    }),
+    ("%#a", DAY_OF_WEEK, |f, t| {
+        f.day_of_week == Some(t.day_of_week())
+    }),
+    ("%w", DAY_OF_WEEK, |f, t| {
+        f.day_of_week == Some(t.day_of_week())
+    }),
+    ("%u", DAY_OF_WEEK_FROM_MONDAY, |f, t| {
+        f.day_of_week_from_monday == Some((t.day_of_week() + 6) % 7 + 1)
+    }),
+    ("%G", ISO_8601_YEAR, |f, t| {
+        f.iso_8601_year == formatted(t, "%G")
+    }),
+    ("%V", ISO_8601_WEEK, |f, t| {
+        f.iso_8601_week_number == formatted(t, "%V")
+    }),
+    ("%U", WEEK_FROM_SUNDAY, |f, t| {
+        f.week_number_from_sunday == formatted(t, "%U")
+    }),
+    ("%W", WEEK_FROM_MONDAY, |f, t| {
+        f.week_number_from_monday == formatted(t, "%W")
+    }),
+    ("%z", UTC_OFFSET, |f, t| {
+        f.utc_offset == Some(t.utc_offset())
+    }),
+    ("%:z", UTC_OFFSET, |f, t| {
+        f.utc_offset == Some(t.utc_offset())
+    }),
+    ("%::z", UTC_OFFSET, |f, t| {
+        f.utc_offset == Some(t.utc_offset())
+    }),
+    ("%F", YEAR | MONTH | DAY, |f, t| {
+        (f.year, f.month, f.day) == (Some(t.year()), Some(t.month()), Some(t.day()))
+    }),
+    ("%T", HOUR | MINUTE | SECOND, |f, t| {
+        (f.hour, f.minute, f.second) == (Some(t.hour()), Some(t.minute()), Some(t.second()))
+    }),
+    (
+        "%c",
+        YEAR | MONTH | DAY | DAY_OF_WEEK | HOUR | MINUTE | SECOND,
+        |f, t| {
+            let date = (f.year, f.month, f.day, f.day_of_week);
+            let time = (f.hour, f.minute, f.second);
+            date == (
+                Some(t.year()),
+                Some(t.month()),
+                Some(t.day()),
+                Some(t.day_of_week()),
+            ) && time == (Some(t.hour()), Some(t.minute()), Some(t.second()))
+        },
+    ),
+    #[cfg(feature = "subsec")]
+    ("%N", NANOSECONDS, |f, t| {
+        f.nanoseconds == Some(t.nanoseconds())
+    }),
+    #[cfg(feature = "subsec")]
+    ("%3N", NANOSECONDS, |f, t| {
+        f.nanoseconds == Some(t.nanoseconds() / 1_000_000 * 1_000_000)
+    }),
+    #[cfg(feature = "subsec")]
+    ("%L", NANOSECONDS, |f, t| {
+        f.nanoseconds == Some(t.nanoseconds() / 1_000_000 * 1_000_000)
+    }),
+];
+
+/// Literal text separating the directives.
+const SEPARATORS: &[&str] = &[" ", "-", "/", ":", "T", ", ", " at "];
+
+/// Format a time with a directive and parse the result as a number.
+fn formatted<T: core::str::FromStr>(time: &DateTime, format: &str) -> Option<T> {
+    let mut buf = [0u8; 16];
+    let bytes = strftime(time, format.as_bytes(), &mut buf).ok()?;
+    core::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Generate a time with a fixed UTC offset in minutes, or a UTC time.
+fn time() -> impl Strategy<Value = DateTime> {
+    let offset = prop_oneof![Just(None), (-1439..=1439).prop_map(|x: i32| Some(x * 60))];
+
+    (TIMESTAMPS, 0..1_000_000_000_u32, offset).prop_map(|(seconds, nanoseconds, offset)| {
+        match offset {
+            Some(offset) => DateTime::from_timestamp_with_offset(seconds, nanoseconds, offset),
+            None => DateTime::from_timestamp(seconds, nanoseconds),
+        }
+        .unwrap()
+    })
+}
+
+/// Generate a format string, as the indices of its directives in
+/// [`DIRECTIVES`] and of the separators following them.
+fn format() -> impl Strategy<Value = [(usize, usize); 6]> {
+    let item = (0..DIRECTIVES.len(), 0..SEPARATORS.len());
+    [
+        item.clone(),
+        item.clone(),
+        item.clone(),
+        item.clone(),
+        item.clone(),
+        item,
+    ]
+}
+
+/// Format a time, parse the result with the same format string and check
+/// the parsed fields.
+fn check_roundtrip(time: &DateTime, items: &[(usize, usize)]) -> Result<(), TestCaseError> {
+    let mut format = [0u8; 128];
+    let mut len = 0;
+    for &(directive, separator) in items {
+        for text in [DIRECTIVES[directive].0, SEPARATORS[separator]] {
+            format[len..len + text.len()].copy_from_slice(text.as_bytes());
+            len += text.len();
+        }
+    }
+    let format = core::str::from_utf8(&format[..len]).unwrap();
+
+    let mut buf = [0u8; 512];
+    let formatted = strftime(time, format.as_bytes(), &mut buf).unwrap();
+    let formatted = core::str::from_utf8(formatted).unwrap();
+
+    let fields = strptime(formatted, format);
+    prop_assert!(fields.is_ok(), "{format:?} {formatted:?}: {fields:?}");
+    let fields = fields.unwrap();
+
+    // Fields are set by the last directive parsing them, like `%N` after
+    // `%3N`, so only the directives without a later directive setting one of
+    // their fields are checked
+    let mut later_fields = 0;
+    for &(directive, _) in items.iter().rev() {
+        let (directive, fields_mask, check) = DIRECTIVES[directive];
+        let is_overridden = fields_mask & later_fields != 0;
+        later_fields |= fields_mask;
+
+        prop_assert!(
+            is_overridden || check(&fields, time),
+            "{directive} in {format:?} {formatted:?}: {fields:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Format strings with a complete date, time and UTC offset.
+const COMPLETE_FORMATS: &[&str] = &[
+    "%FT%T%z",
+    "%s %z",
+    "%Y-%j %T %:z",
+    "%G-W%V-%u %T %z",
+    "%Y %W %a %T %z",
+    "%Y %U %w %T %z",
+    "%c %::z",
+];
+
+/// Format a time, parse the result with the same format string and check
+/// the parsed fields as a [`Time`].
+fn check_roundtrip_time(time: &DateTime, format: &str) -> Result<(), TestCaseError> {
+    let mut buf = [0u8; 512];
+    let formatted = strftime(time, format.as_bytes(), &mut buf).unwrap();
+    let formatted = core::str::from_utf8(formatted).unwrap();
+
+    let fields = strptime(formatted, format).unwrap();
+    let date = |t: &dyn Time| {
+        (
+            t.year(),
+            t.month(),
+            t.day(),
+            t.day_of_week(),
+            t.day_of_year(),
+        )
+    };
+    let time_of_day = |t: &dyn Time| (t.hour(), t.minute(), t.second());
+    let instant = |t: &dyn Time| (t.to_int(), t.utc_offset());
+    prop_assert_eq!(date(&fields), date(time), "{:?} {:?}", format, formatted);
+    prop_assert_eq!(time_of_day(&fields), time_of_day(time));
+    prop_assert_eq!(instant(&fields), instant(time));
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_roundtrip(time in time(), items in format()) {
+        check_roundtrip(&time, &items)?;
+    }
+
+    #[test]
+    fn test_roundtrip_time(time in time(), format in 0..COMPLETE_FORMATS.len()) {
+        check_roundtrip_time(&time, COMPLETE_FORMATS[format])?;
+    }
+
+    #[test]
+    fn test_roundtrip_single_directive(time in time(), directive in 0..DIRECTIVES.len()) {
+        check_roundtrip(&time, &[(directive, 0)])?;
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_complete_formats_are_round_trippable() {
+    use crate::Format;
+
+    for format in COMPLETE_FORMATS {
+        assert!(
+            Format::parse(format).unwrap().is_round_trippable(),
+            "{format}"
+        );
+    }
+}
+
+#[test]
+fn test_roundtrip_all_directives() {
+    let times = [
+        DateTime::from_timestamp(0, 0).unwrap(),
+        DateTime::from_timestamp(-62_135_596_800, 999_999_999).unwrap(),
+        DateTime::from_timestamp_with_offset(1_720_432_800, 123_456_789, -34_200).unwrap(),
+        DateTime::from_timestamp_with_offset(253_370_764_800, 1, 86_340).unwrap(),
+    ];
+
+    for time in &times {
+        for directive in 0..DIRECTIVES.len() {
+            check_roundtrip(time, &[(directive, 0)]).unwrap();
+        }
+    }
+}
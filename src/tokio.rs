@@ -0,0 +1,156 @@
+//! Provides a `strftime` implementation writing to a [`tokio`] asynchronous
+//! writer.
+//!
+//! [`tokio`]: https://docs.rs/tokio
+
+use alloc::vec::Vec;
+use core::future::Future;
+
+use ::tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{Error, Time};
+
+/// Size of the stack buffer used for the formatted strings.
+const STACK_BUFFER_SIZE: usize = 256;
+
+/// Formatted string, stored on the stack if it is small enough.
+// The size difference is the point of the stack variant, which avoids an
+// allocation for common formats.
+#[allow(variant_size_differences)]
+#[allow(clippy::large_enum_variant)]
+enum Formatted {
+    /// Formatted string stored in the first `len` bytes of a stack buffer.
+    Stack([u8; STACK_BUFFER_SIZE], usize),
+    /// Formatted string too large for the stack buffer.
+    Heap(Vec<u8>),
+}
+
+impl Formatted {
+    /// Returns the formatted bytes.
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Stack(buf, len) => &buf[..*len],
+            Self::Heap(buf) => buf,
+        }
+    }
+}
+
+/// Format a _time_ implementation with the specified format byte string,
+/// writing to the provided [`AsyncWrite`] object.
+///
+/// The _time_ is formatted when this function is called, into a stack buffer
+/// or a heap-allocated [`Vec`] for long outputs, and the returned future only
+/// writes the formatted string. The future does not borrow the _time_, so it
+/// is [`Send`] if the writer is.
+///
+/// See the [crate-level documentation](crate) for a complete description of
+/// possible format specifiers.
+///
+/// # Allocations
+///
+/// This `strftime` implementation only allocates if the formatted string is
+/// longer than 256 bytes. The implementation exclusively uses fallible
+/// allocation APIs like [`Vec::try_reserve`]. The future will return
+/// [`Error::OutOfMemory`] if there is an allocation failure.
+///
+/// # Examples
+///
+/// ```
+/// use strftime::Time;
+///
+/// // Not shown: create a time implementation with the year 1970
+/// // let time = ...;
+/// # include!("mock.rs.in");
+/// # async fn run() -> Result<(), strftime::Error> {
+/// # let time = MockTime { year: 1970, ..Default::default() };
+/// assert_eq!(time.year(), 1970);
+///
+/// let mut log = Vec::new();
+/// strftime::tokio::strftime(&time, b"%Y", &mut log).await?;
+/// assert_eq!(log, b"1970");
+/// # Ok(())
+/// # }
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Can produce an [`Error`] when the formatting fails, or
+/// [`Error::IoError`] when writing fails.
+pub fn strftime<'a, W>(
+    time: &(impl Time + ?Sized),
+    format: &[u8],
+    buf: &'a mut W,
+) -> impl Future<Output = Result<(), Error>> + 'a
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut stack = [0; STACK_BUFFER_SIZE];
+    let formatted = match crate::buffered::strftime_len(time, format, &mut stack) {
+        Ok(len) => Ok(Formatted::Stack(stack, len)),
+        Err(Error::WriteZero) => crate::bytes::strftime(time, format).map(Formatted::Heap),
+        Err(err) => Err(err),
+    };
+
+    async move {
+        buf.write_all(formatted?.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use std::task::Wake;
+
+    use super::*;
+    use crate::DateTime;
+
+    /// Run a future which is always ready after a finite number of polls.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut future: Pin<Box<F>> = Box::pin(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    #[test]
+    fn test_tokio() {
+        let time = DateTime::from_timestamp(0, 0).unwrap();
+
+        let mut buf = Vec::new();
+        block_on(assert_send(strftime(&time, b"%F %T", &mut buf))).unwrap();
+        block_on(strftime(&time, b"", &mut buf)).unwrap();
+        assert_eq!(buf, b"1970-01-01 00:00:00");
+
+        let mut buf = Vec::new();
+        block_on(strftime(&time, b"%1000Y", &mut buf)).unwrap();
+        assert_eq!(buf.len(), 1000);
+        assert!(buf.ends_with(b"01970"));
+
+        let result = block_on(strftime(&time, b"%Y %", &mut Vec::new()));
+        assert!(matches!(result, Err(Error::UnterminatedDirective { .. })));
+
+        let mut buf = [0u8; 2];
+        let mut buf = std::io::Cursor::new(&mut buf[..]);
+        let result = block_on(strftime(&time, b"%Y", &mut buf));
+        assert!(matches!(result, Err(Error::IoError(_))));
+    }
+}